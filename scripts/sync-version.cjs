@@ -14,4 +14,10 @@ const tauri = JSON.parse(fs.readFileSync(tauriPath, "utf8"));
 tauri.version = version;
 fs.writeFileSync(tauriPath, JSON.stringify(tauri, null, 2) + "\n");
 
-console.log(`Synced version ${version} → Cargo.toml, tauri.conf.json`);
+// Sync the hardware monitor helper (reported in its daemon handshake)
+const lhmPath = "src-lhm/OndoHardwareMonitor.csproj";
+let lhm = fs.readFileSync(lhmPath, "utf8");
+lhm = lhm.replace(/<Version>.*<\/Version>/, `<Version>${version}</Version>`);
+fs.writeFileSync(lhmPath, lhm);
+
+console.log(`Synced version ${version} → Cargo.toml, tauri.conf.json, OndoHardwareMonitor.csproj`);
@@ -1,14 +1,45 @@
 using System.Text.Json;
 using System.Text.Json.Serialization;
 using LibreHardwareMonitor.Hardware;
+using LibreHardwareMonitor.Hardware.Storage;
 
 namespace OndoHardwareMonitor;
 
 class Program
 {
+    // Version of the daemon's JSON line format. Must match LHM_PROTOCOL in
+    // src-tauri/src/hardware.rs; bump both when a field is renamed or removed.
+    const int ProtocolVersion = 1;
+
+    // Fan sensors that have reported a speed since the daemon started. Every
+    // header has a sensor, so 0 RPM only means "stopped" for these; for the
+    // others it means nothing is plugged in.
+    static readonly HashSet<string> SpunFans = new();
+
     static Computer? _computer;
     static UpdateVisitor? _visitor;
 
+    // How often storage and motherboard hardware is updated. Changed by a
+    // "slow <interval_ms>" line on stdin.
+    static volatile int _slowIntervalMs;
+
+    // Hardware of the sections Ondo has turned off, which is neither updated
+    // nor read. Set by a "sections <name>..." line on stdin listing the ones
+    // to keep; everything is read until then.
+    static volatile HashSet<HardwareType> _skipped = new();
+
+    // A section turned back on reads its slow hardware on the next tick
+    // instead of after the slow interval
+    static volatile bool _slowUpdateNow;
+
+    static readonly Dictionary<string, HardwareType[]> Sections = new()
+    {
+        ["cpu"] = new[] { HardwareType.Cpu },
+        ["gpu"] = new[] { HardwareType.GpuNvidia, HardwareType.GpuAmd, HardwareType.GpuIntel },
+        ["storage"] = new[] { HardwareType.Storage },
+        ["motherboard"] = new[] { HardwareType.Motherboard },
+    };
+
     static void Main(string[] args)
     {
         // Debug mode: --debug (list all sensors)
@@ -16,14 +47,17 @@ class Program
         {
             RunDebug();
         }
-        // Daemon mode: --daemon [interval_ms]
+        // Daemon mode: --daemon [interval_ms] [slow_interval_ms]
         else if (args.Length > 0 && args[0] == "--daemon")
         {
             int intervalMs = 1000;
             if (args.Length > 1 && int.TryParse(args[1], out int parsed))
                 intervalMs = Math.Max(100, parsed);
+            int slowIntervalMs = intervalMs;
+            if (args.Length > 2 && int.TryParse(args[2], out int parsedSlow))
+                slowIntervalMs = Math.Max(intervalMs, parsedSlow);
 
-            RunDaemon(intervalMs);
+            RunDaemon(intervalMs, slowIntervalMs);
         }
         else
         {
@@ -99,8 +133,13 @@ class Program
         }
     }
 
-    static void RunDaemon(int intervalMs)
+    static void RunDaemon(int intervalMs, int slowIntervalMs)
     {
+        _slowIntervalMs = slowIntervalMs;
+
+        // Handshake first, before the (slow) hardware enumeration
+        OutputHandshake();
+
         try
         {
             _computer = CreateComputer();
@@ -115,30 +154,60 @@ class Program
                 Environment.Exit(0);
             };
 
-            // Also handle stdin close (parent process terminated)
+            // Read commands from stdin; it closing means the parent process
+            // terminated
             _ = Task.Run(() =>
             {
                 try
                 {
-                    while (Console.Read() != -1) { }
+                    string? line;
+                    while ((line = Console.ReadLine()) != null)
+                    {
+                        var parts = line.Split(' ', StringSplitOptions.RemoveEmptyEntries);
+                        if (parts.Length == 2 && parts[0] == "slow" && int.TryParse(parts[1], out int slow))
+                            _slowIntervalMs = Math.Max(intervalMs, slow);
+                        else if (parts.Length > 0 && parts[0] == "sections")
+                        {
+                            var enabled = parts.Skip(1).ToHashSet();
+                            _skipped = Sections
+                                .Where(section => !enabled.Contains(section.Key))
+                                .SelectMany(section => section.Value)
+                                .ToHashSet();
+                            _slowUpdateNow = true;
+                        }
+                    }
                 }
                 catch { }
                 _computer?.Close();
                 Environment.Exit(0);
             });
 
+            var lastSlowUpdate = DateTime.MinValue;
             while (true)
             {
                 try
                 {
-                    // Explicitly update all hardware and sub-hardware
+                    // Storage and the motherboard keep their last readings
+                    // between slow updates; polling SMART every tick keeps
+                    // drives from spinning down
+                    var now = DateTime.UtcNow;
+                    bool slowDue = _slowUpdateNow || (now - lastSlowUpdate).TotalMilliseconds >= _slowIntervalMs;
+                    if (slowDue)
+                    {
+                        lastSlowUpdate = now;
+                        _slowUpdateNow = false;
+                    }
+
+                    // Update each hardware item with its whole sub-hardware
+                    // tree; the Extract* functions only read sensors
+                    var skipped = _skipped;
                     foreach (var hardware in _computer.Hardware)
                     {
-                        hardware.Update();
-                        foreach (var subHardware in hardware.SubHardware)
-                        {
-                            subHardware.Update();
-                        }
+                        if (skipped.Contains(hardware.HardwareType))
+                            continue;
+                        if (!slowDue && IsSlowHardware(hardware.HardwareType))
+                            continue;
+                        hardware.Accept(_visitor);
                     }
 
                     var data = ExtractData(_computer);
@@ -159,6 +228,9 @@ class Program
         }
     }
 
+    static bool IsSlowHardware(HardwareType type) =>
+        type == HardwareType.Storage || type == HardwareType.Motherboard;
+
     static Computer CreateComputer()
     {
         return new Computer
@@ -183,6 +255,13 @@ class Program
         Console.WriteLine(JsonSerializer.Serialize(data, options));
     }
 
+    static void OutputHandshake()
+    {
+        var version = typeof(Program).Assembly.GetName().Version?.ToString(3) ?? "unknown";
+        var handshake = new { protocol = ProtocolVersion, version };
+        Console.WriteLine(JsonSerializer.Serialize(handshake));
+    }
+
     static void OutputError(string message)
     {
         var error = new { error = message };
@@ -197,13 +276,21 @@ class Program
     static HardwareData ExtractData(Computer computer)
     {
         var data = new HardwareData();
+        var skipped = _skipped;
 
         foreach (var hardware in computer.Hardware)
         {
+            if (skipped.Contains(hardware.HardwareType))
+                continue;
+
             switch (hardware.HardwareType)
             {
                 case HardwareType.Cpu:
-                    data.Cpu = ExtractCpuData(hardware);
+                    // One entry per socket; Cpu stays the first for older Ondo builds
+                    var cpuData = ExtractCpuData(hardware);
+                    data.Cpu ??= cpuData;
+                    data.CpuPackages ??= new List<CpuData>();
+                    data.CpuPackages.Add(cpuData);
                     break;
                 case HardwareType.GpuNvidia:
                 case HardwareType.GpuAmd:
@@ -223,6 +310,22 @@ class Program
             }
         }
 
+        // Only multi-socket machines send the list
+        if (data.CpuPackages?.Count < 2)
+            data.CpuPackages = null;
+
+        // Why a section is missing or incomplete, shown as cpuError/gpuError.
+        // Sections that are turned off aren't missing.
+        if (!skipped.Contains(HardwareType.Cpu))
+        {
+            if (data.Cpu == null)
+                data.CpuError = new SensorError { Code = "lhm_no_cpu", Message = "No CPU found by LibreHardwareMonitor" };
+            else if (data.Cpu.Temperature <= 0)
+                data.CpuError = new SensorError { Code = "lhm_no_temperature", Message = "CPU temperature sensors unavailable: PawnIO driver not loaded" };
+        }
+        if (data.Gpu == null && !skipped.Contains(HardwareType.GpuNvidia))
+            data.GpuError = new SensorError { Code = "lhm_no_gpu", Message = "GPU sensors unavailable: driver not loaded" };
+
         return data;
     }
 
@@ -231,7 +334,7 @@ class Program
         var cpu = new CpuData { Name = hardware.Name };
         var coreTemps = new Dictionary<int, float>();
         var coreLoads = new Dictionary<int, float>();
-        var coreClocks = new List<float>();
+        var coreClocks = new Dictionary<int, float>();
         float tjMax = 0f;
 
         foreach (var sensor in hardware.Sensors)
@@ -276,12 +379,24 @@ class Program
                     }
                     break;
                 case SensorType.Clock:
-                    // Collect all core clocks for averaging
-                    if (sensor.Name.Contains("Core #"))
+                    // Per-core clocks: "Core #1" or "CPU Core #1", averaged for the package
+                    var coreAt = sensor.Name.IndexOf("Core #");
+                    if (coreAt >= 0 &&
+                        int.TryParse(sensor.Name.Substring(coreAt + "Core #".Length).Split(' ')[0], out int clockIndex))
                     {
-                        coreClocks.Add(value);
+                        coreClocks[clockIndex] = value;
                     }
                     break;
+                case SensorType.Power:
+                    // Intel: "CPU Package", AMD: "Package"
+                    if (sensor.Name.Contains("Package") && value > 0)
+                        cpu.Power = value;
+                    break;
+                case SensorType.Voltage:
+                    // Vcore. Intel: "CPU Core", AMD: "Core (SVI2 TFN)"/"Core (SVI3 TFN)"
+                    if (value > 0 && (sensor.Name == "CPU Core" || sensor.Name.StartsWith("Core (SVI")))
+                        cpu.Voltage = value;
+                    break;
             }
         }
 
@@ -294,7 +409,7 @@ class Program
         // Calculate average frequency from all cores
         if (coreClocks.Count > 0)
         {
-            cpu.Frequency = coreClocks.Average() / 1000f; // MHz to GHz
+            cpu.Frequency = coreClocks.Values.Average() / 1000f; // MHz to GHz
         }
 
         // Build core data
@@ -302,6 +417,7 @@ class Program
             coreTemps.Count > 0 ? coreTemps.Keys.Max() + 1 : 0,
             coreLoads.Count > 0 ? coreLoads.Keys.Max() + 1 : 0
         );
+        maxCores = Math.Max(maxCores, coreClocks.Count > 0 ? coreClocks.Keys.Max() + 1 : 0);
 
         if (maxCores > 0)
         {
@@ -312,7 +428,8 @@ class Program
                 {
                     Index = (uint)i,
                     Temperature = coreTemps.GetValueOrDefault(i, cpu.Temperature),
-                    Load = coreLoads.GetValueOrDefault(i, cpu.Load)
+                    Load = coreLoads.GetValueOrDefault(i, cpu.Load),
+                    Frequency = coreClocks.TryGetValue(i, out float clock) ? clock / 1000f : null // MHz to GHz
                 });
             }
         }
@@ -348,6 +465,20 @@ class Program
                 case SensorType.Temperature:
                     if (sensor.Name.Contains("GPU Core") || sensor.Name == "GPU" || sensor.Name == "Temperature")
                         gpu.Temperature = value;
+                    else if (sensor.Name == "GPU Hot Spot")
+                        gpu.HotspotTemperature = value;
+                    else if (sensor.Name == "GPU Memory Junction" || sensor.Name == "GPU Memory")
+                        // NVIDIA: "GPU Memory Junction", AMD: "GPU Memory"
+                        gpu.MemoryTemperature = value;
+                    break;
+                case SensorType.Fan:
+                    // "GPU Fan", "GPU Fan 1", ...; 0 RPM is a stopped fan in zero-RPM mode
+                    gpu.Fans ??= new List<FanData>();
+                    gpu.Fans.Add(new FanData
+                    {
+                        Name = sensor.Name,
+                        Speed = (uint)value
+                    });
                     break;
                 case SensorType.Load:
                     if (sensor.Name == "GPU Core" || sensor.Name == "GPU")
@@ -357,6 +488,16 @@ class Program
                     if (sensor.Name == "GPU Core" || sensor.Name.Contains("GPU Core"))
                         gpu.Frequency = value / 1000f; // MHz to GHz
                     break;
+                case SensorType.Power:
+                    // Prefer "GPU Package" (board power); otherwise take "GPU Core"/"GPU Power"
+                    if (value <= 0) break;
+                    if (sensor.Name.Contains("Limit"))
+                        gpu.PowerLimit = value;
+                    else if (sensor.Name.Contains("Package"))
+                        gpu.Power = value;
+                    else if (gpu.Power == null && (sensor.Name.Contains("GPU Core") || sensor.Name == "GPU Power"))
+                        gpu.Power = value;
+                    break;
                 case SensorType.SmallData:
                     if (sensor.Name == "GPU Memory Used" || sensor.Name == "D3D Dedicated Memory Used")
                         gpu.MemoryUsed = value / 1024f; // MB to GB
@@ -372,12 +513,24 @@ class Program
 
     static MotherboardData ExtractMotherboardData(IHardware hardware)
     {
-        var mb = new MotherboardData { Name = hardware.Name, Fans = new List<FanData>() };
+        var mb = new MotherboardData
+        {
+            Name = hardware.Name,
+            Fans = new List<FanData>(),
+            Voltages = new List<VoltageData>(),
+            Temperatures = new List<TemperatureData>()
+        };
         var temps = new List<float>();
 
         // Helper to process sensors from any hardware
         void ProcessSensors(IHardware hw)
         {
+            // Headers with a tachometer; the others only report their duty cycle
+            var tachometers = hw.Sensors
+                .Where(s => s.SensorType == SensorType.Fan)
+                .Select(s => s.Index)
+                .ToHashSet();
+
             foreach (var sensor in hw.Sensors)
             {
                 if (sensor.Value == null) continue;
@@ -390,6 +543,11 @@ class Program
                         if (!sensor.Name.Contains("CPU") && !sensor.Name.Contains("Core") && value > 0 && value < 150)
                         {
                             temps.Add(value);
+                            mb.Temperatures.Add(new TemperatureData
+                            {
+                                Name = sensor.Name,
+                                Temperature = value
+                            });
                             // Prefer specific motherboard temps (expanded list)
                             var lowerName = sensor.Name.ToLower();
                             if (lowerName.Contains("system") || lowerName.Contains("motherboard") ||
@@ -409,6 +567,8 @@ class Program
                         break;
                     case SensorType.Fan:
                         if (value > 0)
+                            SpunFans.Add(sensor.Identifier.ToString());
+                        if (SpunFans.Contains(sensor.Identifier.ToString()))
                         {
                             mb.Fans.Add(new FanData
                             {
@@ -417,6 +577,28 @@ class Program
                             });
                         }
                         break;
+                    case SensorType.Control:
+                        if (value > 0 && !tachometers.Contains(sensor.Index))
+                        {
+                            mb.Fans.Add(new FanData
+                            {
+                                Name = sensor.Name,
+                                Speed = (uint)Math.Round(value),
+                                Unit = "percent"
+                            });
+                        }
+                        break;
+                    case SensorType.Voltage:
+                        // Super I/O inputs: Vcore, the +12V/+5V/+3.3V rails, DRAM, etc.
+                        if (value > 0)
+                        {
+                            mb.Voltages.Add(new VoltageData
+                            {
+                                Name = sensor.Name,
+                                Volts = value
+                            });
+                        }
+                        break;
                 }
             }
         }
@@ -427,13 +609,11 @@ class Program
         // Check sub-hardware for sensors (motherboard sensors are usually in sub-hardware like SuperIO chips)
         foreach (var subHardware in hardware.SubHardware)
         {
-            subHardware.Update();
             ProcessSensors(subHardware);
 
             // Also check nested sub-hardware
             foreach (var nestedSub in subHardware.SubHardware)
             {
-                nestedSub.Update();
                 ProcessSensors(nestedSub);
             }
         }
@@ -483,9 +663,29 @@ class Program
                         {
                             storage.Temperature = value;
                         }
+                        // SATA SSDs report the life left, NVMe drives the life used
+                        else if (sensor.Name == "Remaining Life")
+                        {
+                            storage.HealthPercent = value;
+                        }
+                        else if (sensor.Name == "Percentage Used" && storage.HealthPercent == null)
+                        {
+                            storage.HealthPercent = Math.Max(0f, 100f - value);
+                        }
                         break;
                     case SensorType.Data:
-                        // Total Bytes Written/Read - can use to estimate drive health
+                        // "Data Written" (NVMe) or "Total Bytes Written" (SATA), in GB
+                        if (sensor.Name == "Data Written" || sensor.Name == "Total Bytes Written")
+                        {
+                            storage.DataWrittenTb = value / 1024f; // GB to TB
+                        }
+                        break;
+                    case SensorType.Throughput:
+                        // Bytes per second
+                        if (sensor.Name == "Read Rate")
+                            storage.ReadRate = value;
+                        else if (sensor.Name == "Write Rate")
+                            storage.WriteRate = value;
                         break;
                 }
             }
@@ -497,7 +697,6 @@ class Program
         // Also check sub-hardware (some drives have sensors in sub-hardware)
         foreach (var subHardware in hardware.SubHardware)
         {
-            subHardware.Update();
             ExtractSensors(subHardware);
         }
 
@@ -520,9 +719,48 @@ class Program
             }
         }
 
+        storage.Partitions = ExtractPartitions(hardware);
+
         // Return storage data even if we only have the name (temperature/usage may be unavailable)
         return storage;
     }
+
+    const double BytesPerGb = 1024.0 * 1024 * 1024;
+
+    // Volumes on a physical drive. LHM maps drive letters to the disk through
+    // the Win32_LogicalDiskToPartition / Win32_DiskDriveToDiskPartition
+    // associations. Null when that mapping isn't available; empty when the
+    // drive has no readable volume (raw, BitLocker-locked)
+    static List<PartitionData>? ExtractPartitions(IHardware hardware)
+    {
+        if (hardware is not AbstractStorage disk || disk.DriveInfos == null)
+        {
+            return null;
+        }
+
+        var partitions = new List<PartitionData>();
+        foreach (var drive in disk.DriveInfos)
+        {
+            try
+            {
+                if (drive.DriveType == DriveType.Network || !drive.IsReady || drive.TotalSize <= 0)
+                {
+                    continue;
+                }
+                partitions.Add(new PartitionData
+                {
+                    MountPoint = drive.Name.TrimEnd('\\'),
+                    UsedSpace = (float)((drive.TotalSize - drive.TotalFreeSpace) / BytesPerGb),
+                    TotalSpace = (float)(drive.TotalSize / BytesPerGb),
+                });
+            }
+            catch (Exception e) when (e is IOException or UnauthorizedAccessException)
+            {
+                // Ejected or locked between the check and the read
+            }
+        }
+        return partitions;
+    }
 }
 
 class UpdateVisitor : IVisitor
@@ -547,9 +785,18 @@ class UpdateVisitor : IVisitor
 class HardwareData
 {
     public CpuData? Cpu { get; set; }
+    public List<CpuData>? CpuPackages { get; set; } // more than one socket only
     public GpuData? Gpu { get; set; }
     public List<StorageData>? Storage { get; set; }
     public MotherboardData? Motherboard { get; set; }
+    public SensorError? CpuError { get; set; }
+    public SensorError? GpuError { get; set; }
+}
+
+class SensorError
+{
+    public string Code { get; set; } = ""; // e.g. "lhm_no_gpu"
+    public string Message { get; set; } = "";
 }
 
 class CpuData
@@ -560,6 +807,8 @@ class CpuData
     public float Load { get; set; }
     public float Frequency { get; set; }
     public List<CpuCoreData>? Cores { get; set; }
+    public float? Power { get; set; } // W, omitted when no sensor
+    public float? Voltage { get; set; } // Vcore in V, omitted when no sensor
 }
 
 class CpuCoreData
@@ -567,6 +816,7 @@ class CpuCoreData
     public uint Index { get; set; }
     public float Temperature { get; set; }
     public float Load { get; set; }
+    public float? Frequency { get; set; } // GHz, omitted when the core has no clock sensor
 }
 
 class GpuData
@@ -578,6 +828,11 @@ class GpuData
     public float Frequency { get; set; }
     public float MemoryUsed { get; set; }
     public float MemoryTotal { get; set; }
+    public float? Power { get; set; } // W, omitted when no sensor
+    public float? PowerLimit { get; set; } // W, omitted when the driver doesn't report it
+    public float? HotspotTemperature { get; set; } // omitted when no sensor
+    public float? MemoryTemperature { get; set; } // memory junction, omitted when no sensor
+    public List<FanData>? Fans { get; set; }
 }
 
 class StorageData
@@ -586,6 +841,18 @@ class StorageData
     public float Temperature { get; set; }
     public float UsedPercent { get; set; }
     public float TotalSpace { get; set; }
+    public float? HealthPercent { get; set; } // life left, omitted without SMART data
+    public float? DataWrittenTb { get; set; } // TB, omitted without SMART data
+    public double? ReadRate { get; set; } // bytes/s
+    public double? WriteRate { get; set; } // bytes/s
+    public List<PartitionData>? Partitions { get; set; }
+}
+
+class PartitionData
+{
+    public string MountPoint { get; set; } = ""; // "C:"
+    public float UsedSpace { get; set; } // GB
+    public float TotalSpace { get; set; } // GB
 }
 
 class MotherboardData
@@ -593,10 +860,25 @@ class MotherboardData
     public string Name { get; set; } = "";
     public float Temperature { get; set; }
     public List<FanData>? Fans { get; set; }
+    public List<VoltageData>? Voltages { get; set; }
+    public List<TemperatureData>? Temperatures { get; set; }
 }
 
 class FanData
 {
     public string Name { get; set; } = "";
     public uint Speed { get; set; }
+    public string Unit { get; set; } = "rpm"; // or "percent" for a duty cycle
+}
+
+class VoltageData
+{
+    public string Name { get; set; } = "";
+    public float Volts { get; set; }
+}
+
+class TemperatureData
+{
+    public string Name { get; set; } = "";
+    public float Temperature { get; set; }
 }
@@ -1,4 +1,40 @@
+#[path = "src/changelog.rs"]
+#[allow(dead_code)]
+mod changelog;
+
+/// Parse ../CHANGELOG.md into OUT_DIR/release-notes.json, which the app
+/// embeds. Fails the build if the changelog is malformed, or if a release
+/// build has no entry for its version. Debug builds of the next release are
+/// let through: its changes are still under `[Unreleased]`.
+fn bundle_release_notes() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../CHANGELOG.md");
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let text = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+    let notes = changelog::parse_changelog(&text).unwrap_or_else(|e| panic!("{}", e));
+
+    let version = env!("CARGO_PKG_VERSION");
+    let current = changelog::Version::parse(version).unwrap_or_else(|e| panic!("{}", e));
+    let has_entry = notes
+        .iter()
+        .any(|note| changelog::Version::parse(&note.version).as_ref() == Ok(&current));
+    let release = std::env::var("PROFILE").as_deref() == Ok("release");
+    if !has_entry && release {
+        panic!(
+            "CHANGELOG.md has no entry for {}; add a \"## [{}] - YYYY-MM-DD\" section",
+            version, version
+        );
+    }
+
+    let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("release-notes.json");
+    std::fs::write(&out, serde_json::to_string(&notes).unwrap())
+        .unwrap_or_else(|e| panic!("failed to write {}: {}", out.display(), e));
+}
+
 fn main() {
+    bundle_release_notes();
+
     // Embed Windows application manifest requesting admin privileges
     // Required for LibreHardwareMonitor to access CPU temperature (Ring 0/MSR),
     // storage S.M.A.R.T. data, and motherboard SuperIO sensors.
@@ -0,0 +1,575 @@
+//! Temperature alerts: a `temperature-alert` event, shown as a toast, when
+//! the CPU or GPU gets close to its maximum temperature. Each alert fires
+//! once when the temperature crosses a threshold and is resolved, with an
+//! `alert-resolved` event, once it has dropped `HYSTERESIS` below it, so a
+//! sensor hovering at the threshold doesn't repeat it every sample.
+//!
+//! Alerts are kept in `AlertLog`, persisted to `alerts.jsonl` next to
+//! settings.json, so what happened while nobody was looking can be read
+//! back with `get_alert_history`. Inside quiet hours (see `QuietHours`)
+//! alerts are still logged but not shown. Active alerts that haven't been
+//! acknowledged are listed in the tray tooltip.
+
+use crate::settings::{self, ComponentThresholds, QuietHours, Thresholds};
+use crate::{hardware, AppState, HardwareData};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How far (°C) below a threshold the temperature must drop before the
+/// alert is resolved and can fire again.
+const HYSTERESIS: f32 = 3.0;
+/// Alerts kept in memory and in alerts.jsonl; older ones are dropped.
+const MAX_HISTORY: usize = 500;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    pub id: u64,
+    /// `cpu` or `gpu`.
+    pub component: String,
+    /// What was measured; `temperature` for now.
+    pub metric: String,
+    pub severity: Severity,
+    /// °C when the alert fired.
+    pub value: f32,
+    /// °C threshold that was crossed.
+    pub threshold: f32,
+    /// ms since epoch.
+    pub fired_at: u64,
+    /// ms since epoch; `None` while the alert is active.
+    pub resolved_at: Option<u64>,
+    pub acknowledged: bool,
+    /// False when quiet hours kept it from being shown.
+    pub notified: bool,
+}
+
+impl Alert {
+    fn is_active(&self) -> bool {
+        self.resolved_at.is_none()
+    }
+}
+
+/// Warning and critical thresholds for a sensor reaching `max_temperature`,
+/// the same ones its `status` in samples is decided by.
+fn thresholds(limits: &ComponentThresholds, max_temperature: f32) -> Vec<(Severity, f32)> {
+    let (warning, critical) = hardware::temperature_limits(limits, max_temperature);
+    [(Severity::Warning, warning), (Severity::Critical, critical)]
+        .into_iter()
+        .filter_map(|(severity, threshold)| Some((severity, threshold?)))
+        .collect()
+}
+
+/// The severity `temperature` is at, given the one it was at before. A
+/// level is kept until the temperature drops `HYSTERESIS` below it.
+fn level(
+    temperature: f32,
+    max_temperature: f32,
+    limits: &ComponentThresholds,
+    previous: Option<Severity>,
+) -> Option<Severity> {
+    if temperature <= 0.0 {
+        return None;
+    }
+    thresholds(limits, max_temperature)
+        .into_iter()
+        .rev()
+        .find(|&(severity, threshold)| {
+            let held = previous.is_some_and(|p| p >= severity);
+            temperature >= threshold || (held && temperature > threshold - HYSTERESIS)
+        })
+        .map(|(severity, _)| severity)
+}
+
+/// Whether `minute` (since local midnight) is inside `quiet`'s window,
+/// which may run past midnight.
+fn in_quiet_hours(quiet: &QuietHours, minute: u16) -> bool {
+    let Some((start, end)) = quiet.window().filter(|_| quiet.enabled) else {
+        return false;
+    };
+    if start <= end {
+        (start..end).contains(&minute)
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+fn silenced(quiet: &QuietHours, severity: Severity, minute: u16) -> bool {
+    in_quiet_hours(quiet, minute) && !(severity == Severity::Critical && quiet.allow_critical)
+}
+
+/// Minutes since local midnight. Read from the clock every time, so a
+/// time zone change mid-session applies from the next sample.
+fn local_minute_of_day() -> u16 {
+    use chrono::Timelike;
+    let now = chrono::Local::now();
+    (now.hour() * 60 + now.minute()) as u16
+}
+
+/// Alerts fired and resolved by one sample.
+#[derive(Debug, Default)]
+struct Changes {
+    fired: Vec<Alert>,
+    resolved: Vec<Alert>,
+}
+
+/// Alert state per sensor and the alert history, persisted to
+/// `alerts.jsonl`.
+#[derive(Debug, Default)]
+pub struct AlertLog {
+    cpu: Option<Severity>,
+    gpu: Option<Severity>,
+    history: VecDeque<Alert>,
+    next_id: u64,
+    /// Never written to disk; used by the soak test.
+    in_memory: bool,
+}
+
+impl AlertLog {
+    /// The history from alerts.jsonl. Alerts still active when Ondo last
+    /// exited are resolved now, since their sensor state is gone.
+    pub fn load() -> Self {
+        let content = fs::read_to_string(history_path()).unwrap_or_default();
+        let mut log = Self::with_history(parse_history(&content));
+        let now = crate::now_millis();
+        for alert in log.history.iter_mut().filter(|a| a.is_active()) {
+            alert.resolved_at = Some(now);
+        }
+        log
+    }
+
+    /// A log that starts empty and is never persisted.
+    pub fn in_memory() -> Self {
+        Self {
+            in_memory: true,
+            ..Self::with_history(VecDeque::new())
+        }
+    }
+
+    fn with_history(history: VecDeque<Alert>) -> Self {
+        let next_id = history.iter().map(|a| a.id + 1).max().unwrap_or(1);
+        Self {
+            history,
+            next_id,
+            ..Self::default()
+        }
+    }
+
+    /// Fire and resolve alerts for `data`. `minute` is the local time of
+    /// day for quiet hours.
+    fn evaluate(
+        &mut self,
+        data: &HardwareData,
+        limits: &Thresholds,
+        quiet: &QuietHours,
+        minute: u16,
+        now: u64,
+    ) -> Changes {
+        let readings = [
+            (
+                "cpu",
+                self.cpu,
+                &limits.cpu,
+                data.cpu
+                    .as_ref()
+                    .map(|c| (c.temperature, c.max_temperature)),
+            ),
+            (
+                "gpu",
+                self.gpu,
+                &limits.gpu,
+                data.gpu
+                    .as_ref()
+                    .map(|g| (g.temperature, g.max_temperature)),
+            ),
+        ];
+        let mut changes = Changes::default();
+        for (component, previous, component_limits, reading) in readings {
+            let current = reading.and_then(|(temperature, max_temperature)| {
+                level(temperature, max_temperature, component_limits, previous)
+            });
+            match component {
+                "cpu" => self.cpu = current,
+                _ => self.gpu = current,
+            }
+            // Dropping a level resolves the alerts above it
+            for alert in self
+                .history
+                .iter_mut()
+                .filter(|a| a.component == component && a.is_active() && Some(a.severity) > current)
+            {
+                alert.resolved_at = Some(now);
+                changes.resolved.push(alert.clone());
+            }
+            let (Some(severity), Some((temperature, max_temperature))) = (current, reading) else {
+                continue;
+            };
+            if current <= previous {
+                continue;
+            }
+            let threshold = thresholds(component_limits, max_temperature)
+                .into_iter()
+                .find(|&(s, _)| s == severity)
+                .map_or(max_temperature, |(_, t)| t);
+            let alert = Alert {
+                id: self.next_id,
+                component: component.to_string(),
+                metric: "temperature".to_string(),
+                severity,
+                value: temperature,
+                threshold,
+                fired_at: now,
+                resolved_at: None,
+                acknowledged: false,
+                notified: !silenced(quiet, severity, minute),
+            };
+            self.next_id += 1;
+            if self.history.len() >= MAX_HISTORY {
+                self.history.pop_front();
+            }
+            self.history.push_back(alert.clone());
+            changes.fired.push(alert);
+        }
+        changes
+    }
+
+    /// The latest `limit` alerts (all without a limit), oldest first.
+    pub fn history(&self, limit: Option<usize>) -> Vec<Alert> {
+        let skip = limit.map_or(0, |limit| self.history.len().saturating_sub(limit));
+        self.history.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn acknowledge(&mut self, id: u64) -> Result<Alert, String> {
+        let alert = self
+            .history
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| format!("No alert with id {}", id))?;
+        alert.acknowledged = true;
+        let alert = alert.clone();
+        self.persist();
+        Ok(alert)
+    }
+
+    /// Forget the history. Sensors that are still hot don't alert again
+    /// until they have cooled down.
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.persist();
+    }
+
+    /// Tray tooltip lines for the active alerts nobody has acknowledged,
+    /// e.g. "⚠ CPU over 90°C".
+    pub fn tray_warning(&self, fahrenheit: bool) -> Option<String> {
+        let lines: Vec<String> = self
+            .history
+            .iter()
+            .filter(|a| a.is_active() && !a.acknowledged)
+            .map(|a| {
+                let threshold = if fahrenheit {
+                    format!("{:.0}°F", a.threshold * 9.0 / 5.0 + 32.0)
+                } else {
+                    format!("{:.0}°C", a.threshold)
+                };
+                format!("⚠ {} over {}", a.component.to_uppercase(), threshold)
+            })
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Rewrite alerts.jsonl with the current history.
+    fn persist(&self) {
+        if self.in_memory {
+            return;
+        }
+        let content: String = self
+            .history
+            .iter()
+            .filter_map(|alert| serde_json::to_string(alert).ok())
+            .map(|line| line + "\n")
+            .collect();
+        if let Err(e) = fs::write(history_path(), content) {
+            crate::log_warn!("Alerts", "Failed to save alert history: {}", e);
+        }
+    }
+}
+
+/// The last `MAX_HISTORY` readable lines of alerts.jsonl.
+fn parse_history(content: &str) -> VecDeque<Alert> {
+    let mut history: VecDeque<Alert> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    while history.len() > MAX_HISTORY {
+        history.pop_front();
+    }
+    history
+}
+
+fn history_path() -> PathBuf {
+    settings::data_dir().join("alerts.jsonl")
+}
+
+/// Refresh the tray tooltip's alert lines from `log`.
+pub fn update_tray(app: &AppHandle, log: &AlertLog) {
+    let state = app.state::<AppState>();
+    let fahrenheit = state.settings.lock().temperature_unit == "fahrenheit";
+    state.tray_readout.set_alert(log.tray_warning(fahrenheit));
+}
+
+/// Check `data` for alerts, emitting `temperature-alert` for those outside
+/// quiet hours and `alert-resolved` for those that cleared.
+pub fn check(app: &AppHandle, data: &HardwareData) {
+    let state = app.state::<AppState>();
+    let (enabled, limits, quiet) = {
+        let settings = state.settings.lock();
+        (
+            settings.alerts_enabled,
+            settings.thresholds,
+            settings.quiet_hours.clone(),
+        )
+    };
+    if !enabled {
+        return;
+    }
+    let mut log = state.alerts.lock().unwrap_or_else(|e| e.into_inner());
+    let changes = log.evaluate(
+        data,
+        &limits,
+        &quiet,
+        local_minute_of_day(),
+        crate::now_millis(),
+    );
+    if changes.fired.is_empty() && changes.resolved.is_empty() {
+        return;
+    }
+    log.persist();
+    update_tray(app, &log);
+    drop(log);
+
+    for alert in changes.fired {
+        crate::log_info!(
+            "Alerts",
+            "{} {:?} at {:.0}°C{}",
+            alert.component,
+            alert.severity,
+            alert.value,
+            if alert.notified { "" } else { " (quiet hours)" }
+        );
+        if alert.notified {
+            let _ = app.emit("temperature-alert", &alert);
+        }
+    }
+    for alert in changes.resolved {
+        crate::log_info!(
+            "Alerts",
+            "{} {:?} resolved",
+            alert.component,
+            alert.severity
+        );
+        let _ = app.emit("alert-resolved", &alert);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockHardware;
+
+    fn sample(cpu_temperature: f32) -> HardwareData {
+        let mut data = MockHardware::new(0, 1000).sample_at(0);
+        let cpu = data.cpu.as_mut().unwrap();
+        cpu.temperature = cpu_temperature;
+        cpu.max_temperature = 100.0;
+        data.gpu = None;
+        data
+    }
+
+    fn quiet(start: &str, end: &str, allow_critical: bool) -> QuietHours {
+        QuietHours {
+            enabled: true,
+            start: start.to_string(),
+            end: end.to_string(),
+            allow_critical,
+        }
+    }
+
+    fn fired(log: &mut AlertLog, temperature: f32) -> Vec<Severity> {
+        log.evaluate(
+            &sample(temperature),
+            &Thresholds::default(),
+            &QuietHours::default(),
+            0,
+            0,
+        )
+        .fired
+        .iter()
+        .map(|a| a.severity)
+        .collect()
+    }
+
+    #[test]
+    fn an_alert_fires_once_per_crossing() {
+        let mut log = AlertLog::in_memory();
+        assert!(fired(&mut log, 80.0).is_empty());
+        assert_eq!(fired(&mut log, 86.0), vec![Severity::Warning]);
+        // Hovering around the threshold doesn't repeat it
+        assert!(fired(&mut log, 84.0).is_empty());
+        assert!(fired(&mut log, 85.5).is_empty());
+        assert_eq!(fired(&mut log, 96.0), vec![Severity::Critical]);
+        assert!(fired(&mut log, 93.0).is_empty());
+        // Cooled down: armed again
+        assert!(fired(&mut log, 70.0).is_empty());
+        assert_eq!(fired(&mut log, 86.0), vec![Severity::Warning]);
+        assert_eq!(log.history(None).len(), 3);
+        assert_eq!(log.history(Some(1))[0].id, 3);
+    }
+
+    #[test]
+    fn custom_thresholds_replace_the_margins() {
+        let mut limits = Thresholds::default();
+        limits.cpu.warning_temperature = Some(70.0);
+        let mut log = AlertLog::in_memory();
+        let fired = log
+            .evaluate(&sample(72.0), &limits, &QuietHours::default(), 0, 0)
+            .fired;
+        assert_eq!(fired[0].severity, Severity::Warning);
+        assert_eq!(fired[0].threshold, 70.0);
+        // Critical still follows maxTemperature
+        let fired = log
+            .evaluate(&sample(96.0), &limits, &QuietHours::default(), 0, 1)
+            .fired;
+        assert_eq!(fired[0].threshold, 95.0);
+    }
+
+    #[test]
+    fn alerts_resolve_below_the_hysteresis_point() {
+        let mut log = AlertLog::in_memory();
+        assert_eq!(fired(&mut log, 88.0), vec![Severity::Warning]);
+        assert_eq!(fired(&mut log, 97.0), vec![Severity::Critical]);
+
+        // Critical clears first, the warning stays active
+        let changes = log.evaluate(
+            &sample(91.0),
+            &Thresholds::default(),
+            &QuietHours::default(),
+            0,
+            2,
+        );
+        assert_eq!(changes.resolved.len(), 1);
+        assert_eq!(changes.resolved[0].severity, Severity::Critical);
+        assert_eq!(changes.resolved[0].resolved_at, Some(2));
+        assert!(log
+            .evaluate(
+                &sample(83.0),
+                &Thresholds::default(),
+                &QuietHours::default(),
+                0,
+                3
+            )
+            .resolved
+            .is_empty());
+
+        let changes = log.evaluate(
+            &sample(81.0),
+            &Thresholds::default(),
+            &QuietHours::default(),
+            0,
+            4,
+        );
+        assert_eq!(changes.resolved.len(), 1);
+        assert!(log.history(None).iter().all(|a| !a.is_active()));
+    }
+
+    #[test]
+    fn acknowledged_alerts_leave_the_tray() {
+        let mut log = AlertLog::in_memory();
+        log.evaluate(
+            &sample(88.0),
+            &Thresholds::default(),
+            &QuietHours::default(),
+            0,
+            1,
+        );
+        assert_eq!(log.tray_warning(false).as_deref(), Some("⚠ CPU over 85°C"));
+        assert_eq!(log.tray_warning(true).as_deref(), Some("⚠ CPU over 185°F"));
+
+        assert!(log.acknowledge(1).unwrap().acknowledged);
+        assert!(log.tray_warning(false).is_none());
+        assert!(log.acknowledge(7).is_err());
+
+        log.clear();
+        assert!(log.history(None).is_empty());
+        // Still hot: cleared, but not fired again
+        assert!(fired(&mut log, 88.0).is_empty());
+    }
+
+    #[test]
+    fn history_survives_unreadable_lines() {
+        let mut log = AlertLog::in_memory();
+        fired(&mut log, 88.0);
+        fired(&mut log, 97.0);
+        let lines: Vec<String> = log
+            .history(None)
+            .iter()
+            .map(|a| serde_json::to_string(a).unwrap())
+            .collect();
+        let content = format!("{}\n{{truncated\n{}\n", lines[0], lines[1]);
+
+        let restored = AlertLog::with_history(parse_history(&content));
+        assert_eq!(restored.history(None), log.history(None));
+        assert_eq!(restored.next_id, 3);
+    }
+
+    #[test]
+    fn quiet_hours_can_run_past_midnight() {
+        let night = quiet("22:00", "07:00", false);
+        assert!(in_quiet_hours(&night, 23 * 60));
+        assert!(in_quiet_hours(&night, 2 * 60));
+        assert!(!in_quiet_hours(&night, 7 * 60));
+        assert!(!in_quiet_hours(&night, 12 * 60));
+
+        let lunch = quiet("12:00", "13:00", false);
+        assert!(in_quiet_hours(&lunch, 12 * 60 + 30));
+        assert!(!in_quiet_hours(&lunch, 13 * 60));
+        assert!(!in_quiet_hours(
+            &QuietHours {
+                enabled: false,
+                ..night
+            },
+            23 * 60
+        ));
+    }
+
+    #[test]
+    fn quiet_hours_record_but_silence_alerts() {
+        let mut log = AlertLog::in_memory();
+        let night = quiet("22:00", "07:00", true);
+        let fired = log
+            .evaluate(&sample(88.0), &Thresholds::default(), &night, 2 * 60, 5)
+            .fired;
+        assert_eq!(fired.len(), 1);
+        assert!(!fired[0].notified);
+        // Critical alerts still get through when allowed
+        let fired = log
+            .evaluate(&sample(97.0), &Thresholds::default(), &night, 2 * 60, 6)
+            .fired;
+        assert!(fired[0].notified);
+        assert_eq!(log.history(None).len(), 2);
+
+        let strict = quiet("22:00", "07:00", false);
+        assert!(silenced(&strict, Severity::Critical, 2 * 60));
+    }
+}
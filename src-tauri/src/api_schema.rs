@@ -0,0 +1,464 @@
+//! JSON Schema for the data the app exposes: Tauri command payloads and the
+//! debug server's JSON endpoints. Derived from the Rust types with schemars,
+//! so a type that can't be represented fails to compile, and serde renames
+//! are reflected automatically.
+//!
+//! The generated document is checked in as `resources/api-schema.json` and
+//! bundled with the app; that file is what gets served. A test regenerates it
+//! from the types and fails when the two disagree.
+
+use crate::commands::{self, pawnio::PawnIOStatus, window::WindowStateData};
+use crate::{
+    alerts, app_metrics, audio, changelog, error_reporting, hardware, hardware_static, metrics,
+    monitoring_mode, power, release_notes, session_stats, settings, HardwareData,
+};
+use once_cell::sync::Lazy;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+const BUNDLED_SCHEMA: &str = include_str!("../resources/api-schema.json");
+
+/// The version is stamped on at load time so a release bump doesn't
+/// invalidate the checked-in file.
+static API_SCHEMA: Lazy<Value> = Lazy::new(|| {
+    let mut schema: Value =
+        serde_json::from_str(BUNDLED_SCHEMA).expect("resources/api-schema.json is valid JSON");
+    schema["version"] = Value::from(env!("CARGO_PKG_VERSION"));
+    schema
+});
+
+/// The full schema document, as bundled. Parsed once on first use.
+pub fn api_schema() -> &'static Value {
+    &API_SCHEMA
+}
+
+/// Describe one command. Argument names are camelCase, matching how Tauri
+/// maps them from the Rust parameter names.
+fn command(args: Vec<(&str, Schema)>, result: Schema) -> Value {
+    let args: Map<String, Value> = args
+        .into_iter()
+        .map(|(name, schema)| (name.to_string(), json!(schema)))
+        .collect();
+    json!({ "args": args, "result": result })
+}
+
+fn schema_for<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    gen.subschema_for::<T>()
+}
+
+fn getter<T: JsonSchema>(gen: &mut SchemaGenerator) -> Value {
+    command(vec![], schema_for::<T>(gen))
+}
+
+fn setter<T: JsonSchema>(gen: &mut SchemaGenerator, args: &[&str]) -> Value {
+    let args = args
+        .iter()
+        .map(|name| (*name, schema_for::<T>(gen)))
+        .collect();
+    command(args, schema_for::<()>(gen))
+}
+
+/// Only the drift test calls this, but it's compiled into every build so a
+/// type schemars can't represent still breaks `cargo build`.
+#[cfg_attr(not(test), allow(dead_code))]
+fn build_api_schema() -> Value {
+    let mut gen = SchemaSettings::draft07().into_generator();
+    let gen = &mut gen;
+
+    let types = json!({
+        "HardwareData": schema_for::<HardwareData>(gen),
+        "HardwareStatic": schema_for::<hardware_static::HardwareStatic>(gen),
+        "AppSettings": schema_for::<settings::AppSettings>(gen),
+        "PawnIOStatus": schema_for::<PawnIOStatus>(gen),
+        "PawnIOInstallResult": schema_for::<commands::pawnio::PawnIOInstallResult>(gen),
+        "WindowStateData": schema_for::<WindowStateData>(gen),
+        "AudioDevice": schema_for::<audio::AudioDevice>(gen),
+        "LhmDiagnostics": schema_for::<hardware::LhmDiagnostics>(gen),
+        "TelemetryStatus": schema_for::<error_reporting::TelemetryStatus>(gen),
+        "AutoStartStatus": schema_for::<commands::settings::AutoStartStatus>(gen),
+        "SettingsUpdate": schema_for::<commands::settings::SettingsUpdate>(gen),
+        "SettingsPatchError": schema_for::<commands::settings::SettingsPatchError>(gen),
+        "SettingsImport": schema_for::<commands::settings::SettingsImport>(gen),
+        "SettingsExport": schema_for::<settings::SettingsExport>(gen),
+        "ErrorLogEntry": schema_for::<error_reporting::ErrorLogEntry>(gen),
+        "EnergyReport": schema_for::<power::EnergyReport>(gen),
+        "SessionStatsSnapshot": schema_for::<session_stats::SessionStatsSnapshot>(gen),
+        "ReleaseNote": schema_for::<changelog::ReleaseNote>(gen),
+        "UpdateNotice": schema_for::<release_notes::UpdateNotice>(gen),
+        "InternalMetrics": schema_for::<metrics::InternalMetrics>(gen),
+        "DetectedSensor": schema_for::<hardware::DetectedSensor>(gen),
+        "UpdateCheck": schema_for::<commands::updater::UpdateCheck>(gen),
+        "PrivilegeStatus": schema_for::<commands::privileges::PrivilegeStatus>(gen),
+        "Capabilities": schema_for::<commands::capabilities::Capabilities>(gen),
+        "SystemInfo": schema_for::<commands::system_info::SystemInfo>(gen),
+        "SelfUsage": schema_for::<app_metrics::SelfUsage>(gen),
+        "DataSourceChange": schema_for::<commands::hardware::DataSourceChange>(gen),
+        "Alert": schema_for::<alerts::Alert>(gen),
+        "MonitoringModeStatus": schema_for::<monitoring_mode::MonitoringModeStatus>(gen),
+        "UpdateProgress": schema_for::<commands::updater::UpdateProgress>(gen),
+    });
+
+    let commands = json!({
+        "get_hardware_data": getter::<HardwareData>(gen),
+        "get_hardware_static": getter::<hardware_static::HardwareStatic>(gen),
+        "get_capabilities": getter::<commands::capabilities::Capabilities>(gen),
+        "get_system_info": getter::<commands::system_info::SystemInfo>(gen),
+        "get_self_usage": getter::<app_metrics::SelfUsage>(gen),
+        "copy_snapshot_to_clipboard": command(
+            vec![("format", schema_for::<String>(gen))],
+            schema_for::<String>(gen),
+        ),
+        "set_monitoring_paused": setter::<bool>(gen, &["paused"]),
+        "set_monitoring_mode": command(
+            vec![("mode", schema_for::<String>(gen))],
+            schema_for::<monitoring_mode::MonitoringModeStatus>(gen)
+        ),
+        "get_monitoring_mode": getter::<monitoring_mode::MonitoringModeStatus>(gen),
+        "get_alert_history": command(
+            vec![("limit", schema_for::<Option<usize>>(gen))],
+            schema_for::<Vec<alerts::Alert>>(gen)
+        ),
+        "acknowledge_alert": command(
+            vec![("id", schema_for::<u64>(gen))],
+            schema_for::<alerts::Alert>(gen)
+        ),
+        "clear_alert_history": getter::<()>(gen),
+        "simulate_reading": command(
+            vec![
+                ("cpuTemp", schema_for::<f32>(gen)),
+                ("gpuTemp", schema_for::<f32>(gen)),
+                ("durationSecs", schema_for::<Option<u32>>(gen)),
+            ],
+            schema_for::<()>(gen),
+        ),
+        "get_settings": getter::<settings::AppSettings>(gen),
+        "save_settings": setter::<settings::AppSettings>(gen, &["settings"]),
+        "update_settings": command(
+            vec![("patch", schema_for::<Value>(gen))],
+            schema_for::<commands::settings::SettingsUpdate>(gen),
+        ),
+        "export_settings": setter::<String>(gen, &["path"]),
+        "export_diagnostics": setter::<String>(gen, &["path"]),
+        "import_settings": command(
+            vec![
+                ("path", schema_for::<String>(gen)),
+                ("includeWindowState", schema_for::<Option<bool>>(gen)),
+            ],
+            schema_for::<commands::settings::SettingsImport>(gen),
+        ),
+        "list_profiles": getter::<Vec<String>>(gen),
+        "save_profile": command(
+            vec![("name", schema_for::<String>(gen))],
+            schema_for::<Vec<String>>(gen),
+        ),
+        "apply_profile": command(
+            vec![("name", schema_for::<String>(gen))],
+            schema_for::<settings::AppSettings>(gen),
+        ),
+        "delete_profile": command(
+            vec![("name", schema_for::<String>(gen))],
+            schema_for::<Vec<String>>(gen),
+        ),
+        "set_always_on_top": setter::<bool>(gen, &["enabled"]),
+        "set_always_on_back": setter::<bool>(gen, &["enabled"]),
+        "set_click_through": setter::<bool>(gen, &["enabled"]),
+        "set_auto_start": setter::<bool>(gen, &["enabled"]),
+        "get_auto_start_status": getter::<commands::settings::AutoStartStatus>(gen),
+        "set_window_position": setter::<String>(gen, &["position"]),
+        "get_window_state": getter::<WindowStateData>(gen),
+        "restore_window_state": setter::<WindowStateData>(gen, &["state"]),
+        "set_window_min_size": setter::<Option<u32>>(gen, &["width", "height"]),
+        "set_window_shadow": setter::<bool>(gen, &["enable"]),
+        "set_window_opacity": setter::<u32>(gen, &["opacity"]),
+        "check_pawnio_status": getter::<PawnIOStatus>(gen),
+        "download_and_install_pawnio": getter::<commands::pawnio::PawnIOInstallResult>(gen),
+        "uninstall_pawnio": getter::<String>(gen),
+        "get_audio_devices": getter::<Vec<audio::AudioDevice>>(gen),
+        "set_default_audio_device": setter::<String>(gen, &["deviceId", "deviceType"]),
+        "toggle_debug_server": setter::<bool>(gen, &["enabled"]),
+        "test_influxdb_connection": setter::<settings::InfluxDbSettings>(gen, &["settings"]),
+        "get_lhm_diagnostics": getter::<hardware::LhmDiagnostics>(gen),
+        "get_api_schema": getter::<Value>(gen),
+        "get_internal_metrics": getter::<metrics::InternalMetrics>(gen),
+        "get_energy_report": getter::<power::EnergyReport>(gen),
+        "get_session_stats": getter::<session_stats::SessionStatsSnapshot>(gen),
+        "reset_session_stats": getter::<()>(gen),
+        "list_detected_sensors": getter::<Vec<hardware::DetectedSensor>>(gen),
+        "check_for_update": getter::<commands::updater::UpdateCheck>(gen),
+        "install_update": getter::<()>(gen),
+        "get_privilege_status": getter::<commands::privileges::PrivilegeStatus>(gen),
+        "restart_as_admin": getter::<()>(gen),
+        "get_telemetry_status": getter::<error_reporting::TelemetryStatus>(gen),
+        "set_telemetry_enabled": command(
+            vec![("enabled", schema_for::<bool>(gen))],
+            schema_for::<error_reporting::TelemetryStatus>(gen),
+        ),
+        "get_release_notes": command(
+            vec![("sinceVersion", schema_for::<Option<String>>(gen))],
+            schema_for::<Vec<changelog::ReleaseNote>>(gen),
+        ),
+        "open_config_dir": getter::<()>(gen),
+        "reveal_settings_file": getter::<()>(gen),
+        "open_data_file": setter::<String>(gen, &["kind"]),
+        "get_recent_errors": command(
+            vec![("count", schema_for::<usize>(gen))],
+            schema_for::<Vec<error_reporting::ErrorLogEntry>>(gen),
+        ),
+    });
+
+    let endpoints = json!({
+        "/api/hardware": schema_for::<HardwareData>(gen),
+        "/api/pawnio": schema_for::<PawnIOStatus>(gen),
+        "/api/schema": schema_for::<Value>(gen),
+    });
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Ondo API",
+        "types": types,
+        "commands": commands,
+        "endpoints": endpoints,
+        "definitions": gen.definitions(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{api_schema, build_api_schema, BUNDLED_SCHEMA};
+    use crate::power::SystemPowerEstimate;
+    use crate::{
+        settings, CpuCoreData, CpuData, DisplayData, FanData, FanUnit, GpuData, HardwareData,
+        MotherboardData, NamedTemperature, NetworkInterfaceData, StorageData, VoltageData,
+    };
+    use serde_json::Value;
+
+    /// Minimal draft-07 subset: `$ref`, `anyOf`, `type`, `properties`,
+    /// `required`, `items`. Enough to catch a serde rename or new field that
+    /// the schema doesn't know about.
+    fn validate(value: &Value, schema: &Value, root: &Value, path: &str) -> Result<(), String> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.trim_start_matches("#/definitions/");
+            let target = &root["definitions"][name];
+            if target.is_null() {
+                return Err(format!("{}: unresolved $ref {}", path, reference));
+            }
+            return validate(value, target, root, path);
+        }
+
+        if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+            if !options
+                .iter()
+                .any(|option| validate(value, option, root, path).is_ok())
+            {
+                return Err(format!("{}: no anyOf branch matches {}", path, value));
+            }
+            return Ok(());
+        }
+
+        if let Some(types) = schema.get("type") {
+            let allowed: Vec<&str> = match types {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            let matches = allowed.iter().any(|t| match *t {
+                "null" => value.is_null(),
+                "boolean" => value.is_boolean(),
+                "string" => value.is_string(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "number" => value.is_number(),
+                "array" => value.is_array(),
+                "object" => value.is_object(),
+                _ => false,
+            });
+            if !matches {
+                return Err(format!("{}: {} is not of type {:?}", path, value, allowed));
+            }
+        }
+
+        if let (Some(object), Some(properties)) = (
+            value.as_object(),
+            schema.get("properties").and_then(Value::as_object),
+        ) {
+            for (key, field) in object {
+                let field_schema = properties
+                    .get(key)
+                    .ok_or_else(|| format!("{}: property {} missing from schema", path, key))?;
+                validate(field, field_schema, root, &format!("{}.{}", path, key))?;
+            }
+            for required in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !object.contains_key(required) {
+                    return Err(format!("{}: required property {} absent", path, required));
+                }
+            }
+        }
+
+        if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+            for (i, item) in items.iter().enumerate() {
+                validate(item, item_schema, root, &format!("{}[{}]", path, i))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sample_hardware_data() -> HardwareData {
+        HardwareData {
+            cpu: Some(CpuData {
+                name: "CPU".to_string(),
+                temperature: 55.0,
+                max_temperature: 70.0,
+                load: 12.5,
+                frequency: 4.2,
+                cores: vec![CpuCoreData {
+                    index: 0,
+                    temperature: 54.0,
+                    load: 10.0,
+                    frequency: Some(4.1),
+                    estimated: false,
+                    package: None,
+                }],
+                power: Some(65.0),
+                voltage: Some(1.25),
+                is_throttling: false,
+                base_frequency: 0.0,
+                max_frequency: 0.0,
+                packages: None,
+                status: Default::default(),
+            }),
+            gpu: Some(GpuData {
+                name: "GPU".to_string(),
+                temperature: 48.0,
+                max_temperature: 60.0,
+                load: 3.0,
+                frequency: 1.8,
+                memory_used: 1024.0,
+                memory_total: 8192.0,
+                power: Some(120.0),
+                power_limit: Some(200.0),
+                hotspot_temperature: Some(61.0),
+                memory_temperature: Some(54.0),
+                fans: vec![FanData {
+                    name: "GPU Fan 1".to_string(),
+                    unit: FanUnit::Rpm,
+                    speed: 0,
+                    stopped: true,
+                }],
+                status: Default::default(),
+            }),
+            storage: Some(vec![StorageData {
+                name: "SSD".to_string(),
+                temperature: 38.0,
+                used_space: 200.0,
+                used_percent: 20.0,
+                total_space: 1000.0,
+                health_percent: None,
+                data_written_tb: None,
+                read_rate: None,
+                write_rate: None,
+                partitions: None,
+                status: Default::default(),
+            }]),
+            motherboard: Some(MotherboardData {
+                name: "Board".to_string(),
+                temperature: 35.0,
+                temperatures: vec![NamedTemperature {
+                    name: "System".to_string(),
+                    temperature: 35.0,
+                }],
+                fans: vec![FanData {
+                    name: "CPU Fan".to_string(),
+                    unit: FanUnit::Rpm,
+                    speed: 900,
+                    stopped: false,
+                }],
+                voltages: vec![VoltageData {
+                    name: "+12V".to_string(),
+                    volts: 12.05,
+                }],
+                status: Default::default(),
+            }),
+            network: Some(vec![NetworkInterfaceData {
+                name: "Ethernet".to_string(),
+                received_per_sec: 1024.0,
+                sent_per_sec: 512.0,
+            }]),
+            display: Some(DisplayData {
+                name: Some("Monitor".to_string()),
+                refresh_rate: 144,
+                fps: Some(120),
+                fps_process_name: Some("game.exe".to_string()),
+            }),
+            timestamp: 1_700_000_000_000,
+            cpu_error: Some("cpu".to_string()),
+            system_power: Some(SystemPowerEstimate {
+                system_power_estimate_watts: 225.0,
+                is_estimated: true,
+            }),
+            data_age_ms: Some(250),
+            data_source: "lhm".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hardware_sample_matches_schema() {
+        let root = api_schema();
+        let sample = serde_json::to_value(sample_hardware_data()).unwrap();
+        validate(
+            &sample,
+            &root["types"]["HardwareData"],
+            root,
+            "HardwareData",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn settings_sample_matches_schema() {
+        let root = api_schema();
+        let mut settings = settings::AppSettings::default();
+        settings.window_state = Some(settings::WindowState::default());
+        let sample = serde_json::to_value(settings).unwrap();
+        validate(&sample, &root["types"]["AppSettings"], root, "AppSettings").unwrap();
+    }
+
+    #[test]
+    fn validator_rejects_unknown_properties() {
+        let root = api_schema();
+        let mut sample = serde_json::to_value(sample_hardware_data()).unwrap();
+        sample["cpu"]["max_temperature"] = Value::from(70.0);
+        assert!(validate(
+            &sample,
+            &root["types"]["HardwareData"],
+            root,
+            "HardwareData"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn bundled_schema_matches_the_types() {
+        let generated = build_api_schema();
+        if std::env::var_os("UPDATE_API_SCHEMA").is_some() {
+            let path = concat!(env!("CARGO_MANIFEST_DIR"), "/resources/api-schema.json");
+            let json = serde_json::to_string_pretty(&generated).unwrap() + "\n";
+            std::fs::write(path, json).unwrap();
+            return;
+        }
+        let bundled: Value = serde_json::from_str(BUNDLED_SCHEMA).unwrap();
+        assert!(
+            bundled == generated,
+            "resources/api-schema.json is out of date; regenerate it with \
+             `UPDATE_API_SCHEMA=1 cargo test bundled_schema`"
+        );
+    }
+}
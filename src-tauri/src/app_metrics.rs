@@ -1,7 +1,9 @@
 use once_cell::sync::Lazy;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::BTreeSet;
 use std::sync::Mutex;
+use std::time::Instant;
 use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
 
 #[derive(Debug, Clone, Serialize)]
@@ -30,13 +32,29 @@ pub struct ProcessMetrics {
     pub run_time_seconds: u64,
 }
 
+/// Ondo's own footprint: this process, its children (the webview) and the
+/// LHM daemon.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfUsage {
+    /// Share of the whole machine's CPU time, 0-100.
+    pub cpu_percent: f32,
+    /// Resident memory (RSS / working set).
+    pub memory_bytes: u64,
+    pub memory_mb: f64,
+    pub process_count: usize,
+}
+
 struct MetricsState {
     system: System,
+    /// When processes were last refreshed; CPU usage is measured since.
+    refreshed_at: Option<Instant>,
 }
 
 static METRICS_STATE: Lazy<Mutex<MetricsState>> = Lazy::new(|| {
     Mutex::new(MetricsState {
         system: System::new(),
+        refreshed_at: None,
     })
 });
 
@@ -61,6 +79,7 @@ pub fn snapshot(extra_pids: &[u32]) -> Result<RuntimeMetrics, String> {
             .with_memory()
             .without_tasks(),
     );
+    state.refreshed_at = Some(Instant::now());
 
     let processes: Vec<ProcessMetrics> = app_pid_vec
         .iter()
@@ -83,6 +102,38 @@ pub fn snapshot(extra_pids: &[u32]) -> Result<RuntimeMetrics, String> {
     })
 }
 
+/// `snapshot` summed up, with CPU usage measured over at least
+/// `sysinfo::MINIMUM_CPU_UPDATE_INTERVAL`: a first call takes a baseline
+/// and waits, so blocks for up to that long.
+pub fn self_usage(extra_pids: &[u32]) -> Result<SelfUsage, String> {
+    let refreshed_at = METRICS_STATE
+        .lock()
+        .map_err(|e| e.to_string())?
+        .refreshed_at;
+    let wait = match refreshed_at {
+        Some(at) => sysinfo::MINIMUM_CPU_UPDATE_INTERVAL.saturating_sub(at.elapsed()),
+        None => {
+            snapshot(extra_pids)?;
+            sysinfo::MINIMUM_CPU_UPDATE_INTERVAL
+        }
+    };
+    std::thread::sleep(wait);
+
+    let metrics = snapshot(extra_pids)?;
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    Ok(SelfUsage {
+        cpu_percent: machine_share(metrics.app_cpu_percent, cpus),
+        memory_bytes: metrics.app_memory_bytes,
+        memory_mb: metrics.app_memory_mb,
+        process_count: metrics.app_process_count,
+    })
+}
+
+/// sysinfo counts 100% per core; scale to the whole machine.
+fn machine_share(cpu_percent: f32, cpus: usize) -> f32 {
+    (cpu_percent / cpus.max(1) as f32).clamp(0.0, 100.0)
+}
+
 fn collect_app_pids(system: &System, current_pid: Pid, extra_pids: &[u32]) -> BTreeSet<Pid> {
     let mut pids = BTreeSet::from([current_pid]);
 
@@ -131,7 +182,7 @@ fn bytes_to_mb(bytes: u64) -> f64 {
 
 #[cfg(test)]
 mod tests {
-    use super::bytes_to_mb;
+    use super::{bytes_to_mb, machine_share};
 
     #[test]
     fn converts_bytes_to_mebibytes() {
@@ -139,4 +190,11 @@ mod tests {
         assert_eq!(bytes_to_mb(1024 * 1024), 1.0);
         assert_eq!(bytes_to_mb(1536 * 1024), 1.5);
     }
+
+    #[test]
+    fn cpu_usage_is_a_share_of_all_cores() {
+        assert_eq!(machine_share(200.0, 8), 25.0);
+        assert_eq!(machine_share(50.0, 0), 50.0);
+        assert_eq!(machine_share(900.0, 8), 100.0);
+    }
 }
@@ -0,0 +1,420 @@
+//! Release notes parsed from CHANGELOG.md. Also compiled into build.rs, which
+//! turns the changelog into the JSON bundled with the app, so this file only
+//! depends on std, serde and schemars.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A semantic version, `MAJOR.MINOR.PATCH[-PRERELEASE][+BUILD]`. Build
+/// metadata is ignored for ordering, as semver requires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Vec<Identifier>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Identifier {
+    Numeric(u64),
+    Alpha(String),
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            // Numeric identifiers sort before alphanumeric ones
+            (Identifier::Numeric(_), Identifier::Alpha(_)) => Ordering::Less,
+            (Identifier::Alpha(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::Alpha(a), Identifier::Alpha(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Version {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid version \"{}\"", text);
+        let text_core = text.trim().trim_start_matches('v');
+        let without_build = text_core.split('+').next().unwrap_or_default();
+        let (core, pre) = match without_build.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (without_build, None),
+        };
+
+        let numbers: Vec<u64> = core
+            .split('.')
+            .map(parse_number)
+            .collect::<Option<_>>()
+            .ok_or_else(invalid)?;
+        let [major, minor, patch] = numbers[..] else {
+            return Err(invalid());
+        };
+
+        let pre = match pre {
+            None => Vec::new(),
+            Some(pre) => pre
+                .split('.')
+                .map(|id| {
+                    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                        None
+                    } else if id.chars().all(|c| c.is_ascii_digit()) {
+                        parse_number(id).map(Identifier::Numeric)
+                    } else {
+                        Some(Identifier::Alpha(id.to_string()))
+                    }
+                })
+                .collect::<Option<_>>()
+                .ok_or_else(invalid)?,
+        };
+
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+}
+
+/// Digits only, without leading zeros.
+fn parse_number(text: &str) -> Option<u64> {
+    if text.is_empty() || !text.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if text.len() > 1 && text.starts_with('0') {
+        return None;
+    }
+    text.parse().ok()
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                // A prerelease sorts before its release
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        for (i, id) in self.pre.iter().enumerate() {
+            f.write_str(if i == 0 { "-" } else { "." })?;
+            match id {
+                Identifier::Numeric(n) => write!(f, "{}", n)?,
+                Identifier::Alpha(s) => f.write_str(s)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseSection {
+    /// e.g. "Added", "Fixed".
+    pub title: String,
+    pub items: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseNote {
+    pub version: String,
+    /// Release date as written in the changelog (YYYY-MM-DD).
+    pub date: Option<String>,
+    pub sections: Vec<ReleaseSection>,
+}
+
+/// Parse a Keep a Changelog style file:
+///
+/// ```text
+/// ## [1.2.0] - 2024-05-01
+/// ### Added
+/// - Something
+/// ```
+///
+/// An `[Unreleased]` section is skipped, so a changelog with nothing
+/// released yet parses to no notes. Releases must be listed newest first,
+/// each version once, and every release needs at least one item.
+/// The app itself only reads the JSON build.rs produces from this.
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn parse_changelog(text: &str) -> Result<Vec<ReleaseNote>, String> {
+    let mut notes: Vec<ReleaseNote> = Vec::new();
+    let mut versions: Vec<Version> = Vec::new();
+    let mut in_unreleased = false;
+    let mut has_unreleased = false;
+
+    for (index, raw) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.trim_end();
+        let error = |msg: &str| format!("CHANGELOG.md line {}: {}", line_no, msg);
+
+        if let Some(heading) = line.strip_prefix("## ") {
+            let heading = heading.trim();
+            let (label, date) = match heading.split_once(" - ") {
+                Some((label, date)) => (label.trim(), Some(date.trim())),
+                None => (heading, None),
+            };
+            let label = label
+                .strip_prefix('[')
+                .and_then(|l| l.strip_suffix(']'))
+                .unwrap_or(label);
+            if label.eq_ignore_ascii_case("unreleased") {
+                in_unreleased = true;
+                has_unreleased = true;
+                continue;
+            }
+            in_unreleased = false;
+
+            let version = Version::parse(label).map_err(|e| error(&e))?;
+            if let Some(date) = date {
+                if !is_iso_date(date) {
+                    return Err(error(&format!(
+                        "release date \"{}\" is not YYYY-MM-DD",
+                        date
+                    )));
+                }
+            }
+            if let Some(previous) = versions.last() {
+                if version >= *previous {
+                    return Err(error(&format!(
+                        "{} must be listed below {} (newest first, no duplicates)",
+                        version, previous
+                    )));
+                }
+            }
+            if let Some(last) = notes.last() {
+                if last.sections.is_empty() {
+                    return Err(error(&format!("release {} has no entries", last.version)));
+                }
+            }
+            notes.push(ReleaseNote {
+                version: version.to_string(),
+                date: date.map(str::to_string),
+                sections: Vec::new(),
+            });
+            versions.push(version);
+        } else if in_unreleased {
+            continue;
+        } else if let Some(title) = line.strip_prefix("### ") {
+            let release = notes
+                .last_mut()
+                .ok_or_else(|| error("section before any release heading"))?;
+            release.sections.push(ReleaseSection {
+                title: title.trim().to_string(),
+                items: Vec::new(),
+            });
+        } else if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            let section = notes
+                .last_mut()
+                .and_then(|release| release.sections.last_mut())
+                .ok_or_else(|| error("list item outside a ### section"))?;
+            section.items.push(item.trim().to_string());
+        } else if line.starts_with("  ") && !line.trim().is_empty() {
+            // Continuation of a wrapped list item
+            let item = notes
+                .last_mut()
+                .and_then(|release| release.sections.last_mut())
+                .and_then(|section| section.items.last_mut())
+                .ok_or_else(|| error("indented text outside a list item"))?;
+            item.push(' ');
+            item.push_str(line.trim());
+        }
+    }
+
+    match notes.last() {
+        None if has_unreleased => Ok(notes),
+        None => Err("CHANGELOG.md has no releases".to_string()),
+        Some(last) if last.sections.is_empty() => Err(format!(
+            "CHANGELOG.md: release {} has no entries",
+            last.version
+        )),
+        Some(_) => Ok(notes),
+    }
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+fn is_iso_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+/// Notes for releases newer than `since` up to and including `current`,
+/// newest first.
+pub fn notes_between<'a>(
+    notes: &'a [ReleaseNote],
+    since: Option<&Version>,
+    current: &Version,
+) -> Vec<&'a ReleaseNote> {
+    notes
+        .iter()
+        .filter(|note| {
+            Version::parse(&note.version)
+                .map(|v| v <= *current && since.is_none_or(|since| v > *since))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(text: &str) -> Version {
+        Version::parse(text).unwrap()
+    }
+
+    #[test]
+    fn versions_order_numerically() {
+        assert!(v("0.1.10") > v("0.1.9"));
+        assert!(v("1.0.0") > v("0.99.99"));
+        assert_eq!(v("v1.2.3"), v("1.2.3"));
+    }
+
+    #[test]
+    fn prereleases_follow_semver_precedence() {
+        // Example chain from the semver spec
+        let chain = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+        for pair in chain.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn build_metadata_is_ignored() {
+        assert_eq!(v("1.0.0+build.5"), v("1.0.0"));
+        assert_eq!(v("1.0.0-rc.1+abc").to_string(), "1.0.0-rc.1");
+    }
+
+    #[test]
+    fn malformed_versions_are_rejected() {
+        for bad in [
+            "",
+            "1",
+            "1.2",
+            "1.2.3.4",
+            "01.2.3",
+            "1.2.x",
+            "1.2.3-",
+            "1.2.3-a..b",
+        ] {
+            assert!(Version::parse(bad).is_err(), "{:?} parsed", bad);
+        }
+    }
+
+    const CHANGELOG: &str = "# Changelog
+
+## [Unreleased]
+### Added
+- Not shipped yet
+
+## [0.2.0] - 2026-01-10
+### Added
+- Tray tooltip with live
+  temperatures
+### Fixed
+- Crash on resume
+
+## [0.2.0-beta.1] - 2026-01-02
+### Changed
+- Faster startup
+
+## [0.1.25] - 2025-12-01
+### Fixed
+- Settings migration
+";
+
+    #[test]
+    fn parses_releases_newest_first() {
+        let notes = parse_changelog(CHANGELOG).unwrap();
+        let versions: Vec<_> = notes.iter().map(|n| n.version.as_str()).collect();
+        assert_eq!(versions, vec!["0.2.0", "0.2.0-beta.1", "0.1.25"]);
+        assert_eq!(notes[0].date.as_deref(), Some("2026-01-10"));
+        assert_eq!(
+            notes[0].sections[0].items,
+            vec!["Tray tooltip with live temperatures"]
+        );
+        assert_eq!(notes[0].sections[1].title, "Fixed");
+    }
+
+    #[test]
+    fn nothing_released_yet_is_no_notes() {
+        let notes = parse_changelog("## [Unreleased]\n### Added\n- Not shipped yet\n");
+        assert_eq!(notes, Ok(Vec::new()));
+    }
+
+    #[test]
+    fn notes_between_filters_by_version() {
+        let notes = parse_changelog(CHANGELOG).unwrap();
+        let pick = |since: Option<&str>, current: &str| -> Vec<String> {
+            notes_between(&notes, since.map(v).as_ref(), &v(current))
+                .into_iter()
+                .map(|n| n.version.clone())
+                .collect()
+        };
+        assert_eq!(pick(Some("0.1.25"), "0.2.0"), vec!["0.2.0", "0.2.0-beta.1"]);
+        assert_eq!(pick(Some("0.2.0-beta.1"), "0.2.0"), vec!["0.2.0"]);
+        assert_eq!(pick(None, "0.2.0-beta.1"), vec!["0.2.0-beta.1", "0.1.25"]);
+        assert!(pick(Some("0.2.0"), "0.2.0").is_empty());
+    }
+
+    #[test]
+    fn malformed_changelogs_are_rejected() {
+        let cases = [
+            ("", "no releases"),
+            ("## [0.1.0] - 2025-01-01\n", "no entries"),
+            (
+                "## [0.1.0]\n### Added\n- a\n## [0.2.0]\n### Added\n- b\n",
+                "newest first",
+            ),
+            (
+                "## [0.1.0]\n### Added\n- a\n## [0.1.0]\n### Added\n- b\n",
+                "no duplicates",
+            ),
+            ("## [banana]\n### Added\n- a\n", "invalid version"),
+            ("## [0.1.0] - 1st May\n### Added\n- a\n", "YYYY-MM-DD"),
+            ("## [0.1.0]\n- a\n", "outside a ### section"),
+            ("### Added\n- a\n", "before any release"),
+        ];
+        for (text, expected) in cases {
+            let err = parse_changelog(text).unwrap_err();
+            assert!(err.contains(expected), "{:?}: {}", text, err);
+        }
+    }
+}
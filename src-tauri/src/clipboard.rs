@@ -0,0 +1,93 @@
+//! Writing text to the system clipboard: the Win32 clipboard on Windows,
+//! `pbcopy` on macOS and `wl-copy` or `xclip` on Linux.
+
+/// Program and arguments that read the clipboard text from stdin.
+#[cfg(any(not(target_os = "windows"), test))]
+fn copy_command(platform: &str, wayland: bool) -> (&'static str, &'static [&'static str]) {
+    match platform {
+        "macos" => ("pbcopy", &[]),
+        _ if wayland => ("wl-copy", &[]),
+        _ => ("xclip", &["-selection", "clipboard"]),
+    }
+}
+
+/// Replace the clipboard contents with `text`. Blocks; call it off the
+/// async runtime.
+#[cfg(target_os = "windows")]
+pub fn write_text(text: &str) -> Result<(), String> {
+    use windows::Win32::Foundation::{GlobalFree, HANDLE};
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData,
+    };
+    use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+
+    /// Standard clipboard format for UTF-16 text.
+    const CF_UNICODETEXT: u32 = 13;
+
+    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        OpenClipboard(None).map_err(|e| format!("Failed to open the clipboard: {}", e))?;
+        let result = (|| -> windows_core::Result<()> {
+            EmptyClipboard()?;
+            let memory = GlobalAlloc(GMEM_MOVEABLE, wide.len() * std::mem::size_of::<u16>())?;
+            let target = GlobalLock(memory) as *mut u16;
+            if target.is_null() {
+                let _ = GlobalFree(Some(memory));
+                return Err(windows_core::Error::from_thread());
+            }
+            std::ptr::copy_nonoverlapping(wide.as_ptr(), target, wide.len());
+            // Reports an error once the lock count reaches zero, which is expected
+            let _ = GlobalUnlock(memory);
+            // The clipboard owns the memory once this succeeds
+            if let Err(e) = SetClipboardData(CF_UNICODETEXT, Some(HANDLE(memory.0))) {
+                let _ = GlobalFree(Some(memory));
+                return Err(e);
+            }
+            Ok(())
+        })();
+        let _ = CloseClipboard();
+        result.map_err(|e| format!("Failed to copy to the clipboard: {}", e))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn write_text(text: &str) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let (program, args) = copy_command(std::env::consts::OS, wayland);
+    let mut child = Command::new(program)
+        .args(args)
+        // pbcopy decodes stdin by locale
+        .env("LANG", "en_US.UTF-8")
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to write to {}: {}", program, e))?;
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linux_picks_the_tool_for_the_session() {
+        assert_eq!(copy_command("macos", false).0, "pbcopy");
+        assert_eq!(copy_command("linux", true).0, "wl-copy");
+        assert_eq!(
+            copy_command("linux", false),
+            ("xclip", &["-selection", "clipboard"][..])
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Alert history from `alerts::AlertLog`: listing it, including alerts
+//! quiet hours kept from being shown, acknowledging and clearing it.
+
+use crate::alerts::{self, Alert};
+use crate::AppState;
+use tauri::{AppHandle, State};
+
+/// The latest `limit` alerts, or all of them, oldest first.
+#[tauri::command]
+pub async fn get_alert_history(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<Alert>, String> {
+    let log = state.alerts.lock().map_err(|e| e.to_string())?;
+    Ok(log.history(limit))
+}
+
+/// Mark alert `id` as seen, which takes it out of the tray tooltip.
+#[tauri::command]
+pub async fn acknowledge_alert(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: u64,
+) -> Result<Alert, String> {
+    let mut log = state.alerts.lock().map_err(|e| e.to_string())?;
+    let alert = log.acknowledge(id)?;
+    alerts::update_tray(&app, &log);
+    Ok(alert)
+}
+
+#[tauri::command]
+pub async fn clear_alert_history(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let mut log = state.alerts.lock().map_err(|e| e.to_string())?;
+    log.clear();
+    alerts::update_tray(&app, &log);
+    Ok(())
+}
@@ -0,0 +1,245 @@
+//! What Ondo can measure on this machine: which drivers and tools are
+//! present, and which readings a probe sample actually has, with plain
+//! explanations for the missing ones.
+
+use crate::hardware::{self, MonitoredSections};
+use crate::{elevation, monitor_pause, now_millis, AppState, HardwareData};
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::State;
+
+/// Readings with a non-zero value in the probe sample.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorAvailability {
+    pub cpu_temperature: bool,
+    pub cpu_load: bool,
+    pub cpu_power: bool,
+    pub gpu_temperature: bool,
+    pub gpu_load: bool,
+    pub gpu_power: bool,
+    pub storage_temperature: bool,
+    pub motherboard_temperature: bool,
+    pub fan_speeds: bool,
+}
+
+impl SensorAvailability {
+    fn from_sample(data: &HardwareData) -> Self {
+        let cpu = data.cpu.as_ref();
+        let gpu = data.gpu.as_ref();
+        let board = data.motherboard.as_ref();
+        Self {
+            cpu_temperature: cpu.is_some_and(|c| c.temperature > 0.0),
+            cpu_load: cpu.is_some_and(|c| c.load > 0.0),
+            cpu_power: cpu.and_then(|c| c.power).is_some_and(|w| w > 0.0),
+            gpu_temperature: gpu.is_some_and(|g| g.temperature > 0.0),
+            gpu_load: gpu.is_some_and(|g| g.load > 0.0),
+            gpu_power: gpu.and_then(|g| g.power).is_some_and(|w| w > 0.0),
+            storage_temperature: data
+                .storage
+                .iter()
+                .flatten()
+                .any(|drive| drive.temperature > 0.0),
+            motherboard_temperature: board.is_some_and(|b| b.temperature > 0.0),
+            fan_speeds: board
+                .map(|b| &b.fans)
+                .into_iter()
+                .chain(gpu.map(|g| &g.fans))
+                .flatten()
+                .any(|fan| fan.speed > 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    /// `windows`, `macos` or `linux`.
+    pub platform: String,
+    /// Always true outside Windows, where sensors don't need it.
+    pub elevated: bool,
+    /// Always true outside Windows, where no driver is needed.
+    pub pawnio_installed: bool,
+    /// `ondo-hwmon.exe` is installed and compatible (Windows only).
+    pub lhm_daemon_available: bool,
+    /// Used for the GPU when the LHM helper isn't (Windows only).
+    pub nvidia_smi_available: bool,
+    pub rocm_smi_available: bool,
+    /// `dataSource` of the probe sample.
+    pub data_source: String,
+    pub sensors: SensorAvailability,
+    /// Why readings are missing, one sentence each.
+    pub notes: Vec<String>,
+}
+
+/// Explain the readings missing from `caps.sensors`, from the most to the
+/// least likely cause.
+fn explain(caps: &Capabilities, sections: MonitoredSections) -> Vec<String> {
+    let windows = caps.platform == "windows";
+    let sensors = &caps.sensors;
+    let mut notes = Vec::new();
+
+    if windows && !caps.lhm_daemon_available {
+        notes.push(
+            "Sensor helper (ondo-hwmon.exe) not found, so only basic readings are available"
+                .to_string(),
+        );
+    }
+    if !sections.cpu {
+        notes.push("CPU not sampled: turned off in the settings".to_string());
+    } else if !sensors.cpu_temperature && windows {
+        if !caps.pawnio_installed {
+            notes.push(
+                "CPU temperature unavailable because the PawnIO driver is not installed"
+                    .to_string(),
+            );
+        } else if !caps.elevated {
+            notes.push(
+                "CPU temperature unavailable because Ondo is not running as administrator"
+                    .to_string(),
+            );
+        }
+    }
+    if !sections.gpu {
+        notes.push("GPU not sampled: turned off in the settings".to_string());
+    } else if !sensors.gpu_temperature
+        && windows
+        && !caps.lhm_daemon_available
+        && !caps.nvidia_smi_available
+        && !caps.rocm_smi_available
+    {
+        notes.push(
+            "GPU temperature unavailable because neither nvidia-smi nor rocm-smi was found"
+                .to_string(),
+        );
+    }
+    if !sections.storage {
+        notes.push("Storage not sampled: turned off in the settings".to_string());
+    } else if !sensors.storage_temperature {
+        notes.push("No drive reports a temperature".to_string());
+    }
+    if !sections.motherboard {
+        notes.push("Motherboard not sampled: turned off in the settings".to_string());
+    } else if !sensors.motherboard_temperature && !windows {
+        notes.push("Motherboard sensors are only read on Windows".to_string());
+    }
+    notes
+}
+
+/// Probe the drivers and tools, and take a sample to see which readings
+/// come through. While monitoring is paused the last sample is used
+/// instead.
+#[tauri::command]
+pub async fn get_capabilities(state: State<'_, AppState>) -> Result<Capabilities, String> {
+    let (pawnio_installed, nvidia_smi_available, rocm_smi_available) =
+        tauri::async_runtime::spawn_blocking(|| {
+            (
+                super::pawnio::get_pawnio_detailed_status().installed,
+                hardware::nvidia_smi_available(),
+                hardware::rocm_smi_available(),
+            )
+        })
+        .await
+        .map_err(|e| format!("Task failed: {:?}", e))?;
+
+    let sample = match monitor_pause::cached_sample(now_millis()) {
+        Some(cached) => cached,
+        None => hardware::get_hardware_info().await?,
+    };
+    let sections = state.settings.lock().monitored_sections();
+
+    let mut caps = Capabilities {
+        platform: std::env::consts::OS.to_string(),
+        elevated: elevation::is_elevated(),
+        pawnio_installed,
+        lhm_daemon_available: hardware::lhm_helper_available(),
+        nvidia_smi_available,
+        rocm_smi_available,
+        data_source: sample.data_source.clone(),
+        sensors: SensorAvailability::from_sample(&sample),
+        notes: Vec::new(),
+    };
+    caps.notes = explain(&caps, sections);
+    Ok(caps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows_caps() -> Capabilities {
+        Capabilities {
+            platform: "windows".to_string(),
+            elevated: true,
+            pawnio_installed: true,
+            lhm_daemon_available: true,
+            nvidia_smi_available: false,
+            rocm_smi_available: false,
+            data_source: "lhm".to_string(),
+            sensors: SensorAvailability::from_sample(
+                &crate::mock::MockHardware::new(0, 1000).sample_at(0),
+            ),
+            notes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn mock_sample_has_every_reading() {
+        let sensors = windows_caps().sensors;
+        assert!(sensors.cpu_temperature && sensors.cpu_power && sensors.gpu_power);
+        assert!(sensors.storage_temperature && sensors.motherboard_temperature);
+        assert!(sensors.fan_speeds);
+    }
+
+    #[test]
+    fn nothing_to_explain_when_everything_reads() {
+        assert!(explain(&windows_caps(), MonitoredSections::ALL).is_empty());
+    }
+
+    #[test]
+    fn missing_gpu_temperature_names_the_missing_tools() {
+        let mut caps = windows_caps();
+        caps.lhm_daemon_available = false;
+        caps.sensors.gpu_temperature = false;
+        let notes = explain(&caps, MonitoredSections::ALL);
+        assert!(notes.iter().any(|n| n.contains("ondo-hwmon.exe")));
+        assert!(notes.iter().any(|n| n.contains("nvidia-smi")));
+
+        // nvidia-smi present: the reading is missing for another reason
+        caps.nvidia_smi_available = true;
+        let notes = explain(&caps, MonitoredSections::ALL);
+        assert!(!notes.iter().any(|n| n.contains("nvidia-smi")));
+    }
+
+    #[test]
+    fn missing_cpu_temperature_points_at_the_driver_first() {
+        let mut caps = windows_caps();
+        caps.sensors.cpu_temperature = false;
+        caps.pawnio_installed = false;
+        caps.elevated = false;
+        let notes = explain(&caps, MonitoredSections::ALL);
+        assert!(notes.iter().any(|n| n.contains("PawnIO")));
+        assert!(!notes.iter().any(|n| n.contains("administrator")));
+
+        caps.pawnio_installed = true;
+        let notes = explain(&caps, MonitoredSections::ALL);
+        assert!(notes.iter().any(|n| n.contains("administrator")));
+    }
+
+    #[test]
+    fn disabled_sections_are_explained_as_settings() {
+        let mut caps = windows_caps();
+        caps.sensors = SensorAvailability::default();
+        let sections = MonitoredSections {
+            cpu: false,
+            gpu: false,
+            storage: false,
+            motherboard: false,
+        };
+        let notes = explain(&caps, sections);
+        assert_eq!(notes.len(), 4);
+        assert!(notes
+            .iter()
+            .all(|n| n.contains("turned off in the settings")));
+    }
+}
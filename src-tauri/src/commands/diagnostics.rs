@@ -0,0 +1,123 @@
+//! A folder with everything a bug report needs: settings, the error log,
+//! capabilities, a sample and OS details, all passed through the same
+//! scrubber as Sentry events so it can be attached to a public issue.
+
+use crate::settings::AppSettings;
+use crate::{error_reporting, hardware, monitor_pause, now_millis, AppState};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tauri::State;
+
+/// Error log entries included, newest last.
+const ERROR_LOG_ENTRIES: usize = 200;
+
+/// Scrub every string in `value`, see `error_reporting::scrub`.
+fn scrub_json(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = error_reporting::scrub(s),
+        Value::Array(items) => items.iter_mut().for_each(scrub_json),
+        Value::Object(map) => map.values_mut().for_each(scrub_json),
+        _ => {}
+    }
+}
+
+/// `data` as scrubbed, pretty-printed JSON.
+fn redacted_json(data: &impl Serialize) -> Result<String, String> {
+    let mut value = serde_json::to_value(data).map_err(|e| e.to_string())?;
+    scrub_json(&mut value);
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+/// Blank out settings that grant access to something: a self-hosted DSN
+/// carries its project key, and the tokens authenticate WebSocket clients
+/// and InfluxDB writes.
+fn redact_secrets(settings: &mut AppSettings) {
+    for secret in [
+        &mut settings.sentry_dsn,
+        &mut settings.websocket_token,
+        &mut settings.influxdb.token,
+    ] {
+        if secret.is_some() {
+            *secret = Some("<redacted>".to_string());
+        }
+    }
+}
+
+fn write_file(dir: &Path, name: &str, content: &str) -> Result<(), String> {
+    let path = dir.join(name);
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Write a diagnostics bundle into the folder `path`, created if needed:
+/// settings.json, errors.json, capabilities.json, sample.json and
+/// system.json.
+#[tauri::command]
+pub async fn export_diagnostics(state: State<'_, AppState>, path: String) -> Result<(), String> {
+    let mut current = state.settings.lock().clone();
+    redact_secrets(&mut current);
+    let capabilities = super::capabilities::get_capabilities(state.clone()).await?;
+    let sample = match monitor_pause::cached_sample(now_millis()) {
+        Some(cached) => cached,
+        None => hardware::get_hardware_info().await?,
+    };
+    let system = super::system_info::get_system_info().await?;
+    let errors =
+        tauri::async_runtime::spawn_blocking(|| error_reporting::recent_errors(ERROR_LOG_ENTRIES))
+            .await
+            .map_err(|e| format!("Task failed: {:?}", e))?;
+
+    let dir = Path::new(&path);
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    for (name, content) in [
+        ("settings.json", redacted_json(&current)?),
+        ("errors.json", redacted_json(&errors)?),
+        ("capabilities.json", redacted_json(&capabilities)?),
+        ("sample.json", redacted_json(&sample)?),
+        ("system.json", redacted_json(&system)?),
+    ] {
+        write_file(dir, name, &content)?;
+    }
+    crate::log_info!("Diagnostics", "Exported diagnostics to {}", path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_string_is_scrubbed() {
+        let value = serde_json::json!({
+            "settingsPath": "/home/jane/.config/ondo/settings.json",
+            "recentErrors": ["[hardware] Serial: WD-WX1234 missing"],
+            "nested": { "drive": "/Users/jane/Volumes" },
+            "count": 3,
+        });
+        let text = redacted_json(&value).unwrap();
+        assert!(!text.contains("jane"));
+        assert!(!text.contains("WX1234"));
+        assert!(text.contains("/home/<user>/.config"));
+        assert!(text.contains("\"count\": 3"));
+    }
+
+    #[test]
+    fn no_secret_survives_in_settings() {
+        let mut settings = AppSettings {
+            sentry_dsn: Some("https://secret-key@sentry.example.com/1".to_string()),
+            websocket_token: Some("secret-token".to_string()),
+            ..AppSettings::default()
+        };
+        settings.influxdb.token = Some("secret-influx".to_string());
+        redact_secrets(&mut settings);
+
+        let text = redacted_json(&settings).unwrap();
+        assert!(!text.contains("secret"));
+        assert_eq!(settings.websocket_token.as_deref(), Some("<redacted>"));
+        // Unset stays unset, so the bundle still shows what is configured
+        let mut settings = AppSettings::default();
+        redact_secrets(&mut settings);
+        assert_eq!(settings.websocket_token, None);
+    }
+}
@@ -0,0 +1,189 @@
+//! Showing Ondo's data files (settings.json, the error log, ...) in the
+//! system file manager, so users can find them for support.
+
+use crate::{error_reporting, log_buffer, settings};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Snapshot of the in-memory app log, written by `open_data_file("log")`.
+const LOG_SNAPSHOT_FILE: &str = "ondo.log";
+
+/// Files `open_data_file` can show, by `kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DataFile {
+    Settings,
+    Errors,
+    Log,
+}
+
+impl DataFile {
+    fn parse(kind: &str) -> Result<Self, String> {
+        match kind {
+            "settings" => Ok(Self::Settings),
+            "errors" => Ok(Self::Errors),
+            "log" => Ok(Self::Log),
+            other => Err(format!(
+                "Unknown data file \"{}\"; expected settings, errors or log",
+                other
+            )),
+        }
+    }
+
+    fn path(self) -> PathBuf {
+        match self {
+            Self::Settings => settings::get_settings_path(),
+            Self::Errors => error_reporting::error_log_path(),
+            Self::Log => settings::data_dir().join(LOG_SNAPSHOT_FILE),
+        }
+    }
+}
+
+/// Program and arguments that show `target` on `platform` (as in
+/// `std::env::consts::OS`). With `select`, the folder is opened with the
+/// file highlighted where the file manager can; xdg-open can't, so Linux
+/// gets the folder.
+///
+/// Explorer wants `/select,"path"` with the quotes inside the argument, so
+/// the Windows arguments are quoted here and passed raw.
+fn reveal_command(platform: &str, target: &Path, select: bool) -> (&'static str, Vec<OsString>) {
+    let quoted = |path: &Path| {
+        let mut arg = OsString::from("\"");
+        arg.push(path.as_os_str());
+        arg.push("\"");
+        arg
+    };
+    match (platform, select) {
+        ("windows", true) => {
+            let mut arg = OsString::from("/select,");
+            arg.push(quoted(target));
+            ("explorer", vec![arg])
+        }
+        ("windows", false) => ("explorer", vec![quoted(target)]),
+        ("macos", true) => ("open", vec!["-R".into(), target.into()]),
+        ("macos", false) => ("open", vec![target.into()]),
+        (_, true) => ("xdg-open", vec![target.parent().unwrap_or(target).into()]),
+        (_, false) => ("xdg-open", vec![target.into()]),
+    }
+}
+
+/// Show `target` in the file manager, highlighted if `select`. Errors name
+/// the path.
+async fn reveal(target: PathBuf, select: bool) -> Result<(), String> {
+    let (program, args) = reveal_command(std::env::consts::OS, &target, select);
+    let shown = target.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut command = std::process::Command::new(program);
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            for arg in &args {
+                command.raw_arg(arg);
+            }
+        }
+        #[cfg(not(target_os = "windows"))]
+        command.args(&args);
+        let status = command.status().map_err(|e| e.to_string())?;
+        // Explorer exits with 1 even when the window opened
+        if status.success() || cfg!(target_os = "windows") {
+            Ok(())
+        } else {
+            Err(format!("{} exited with {}", program, status))
+        }
+    })
+    .await
+    .map_err(|e| format!("Task failed: {:?}", e))?
+    .map_err(|e| format!("Failed to open {}: {}", shown.display(), e))
+    .inspect_err(|e| crate::log_warn!("Files", "{}", e))
+}
+
+/// Highlight `path` in its folder, or open the folder while the file
+/// doesn't exist yet.
+async fn reveal_file(path: PathBuf) -> Result<(), String> {
+    if path.exists() {
+        reveal(path, true).await
+    } else {
+        reveal(path.parent().map(Path::to_path_buf).unwrap_or(path), false).await
+    }
+}
+
+fn write_log_snapshot(path: &Path) -> Result<(), String> {
+    let text: String = log_buffer::get_all()
+        .iter()
+        .map(|e| {
+            format!(
+                "[{}] [{}] [{}] {}\n",
+                e.timestamp, e.level, e.tag, e.message
+            )
+        })
+        .collect();
+    std::fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Open the folder holding settings.json and the other data files.
+#[tauri::command]
+pub async fn open_config_dir() -> Result<(), String> {
+    reveal(settings::data_dir(), false).await
+}
+
+#[tauri::command]
+pub async fn reveal_settings_file() -> Result<(), String> {
+    reveal_file(settings::get_settings_path()).await
+}
+
+/// Show one of Ondo's files: `settings`, `errors` (the local error log) or
+/// `log` (the app log so far, written out to ondo.log first).
+#[tauri::command]
+pub async fn open_data_file(kind: String) -> Result<(), String> {
+    let file = DataFile::parse(&kind)?;
+    let path = file.path();
+    if file == DataFile::Log {
+        write_log_snapshot(&path)?;
+    }
+    reveal_file(path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_are_parsed() {
+        assert_eq!(DataFile::parse("settings"), Ok(DataFile::Settings));
+        assert_eq!(DataFile::parse("errors"), Ok(DataFile::Errors));
+        assert_eq!(DataFile::parse("log"), Ok(DataFile::Log));
+        assert!(DataFile::parse("passwords")
+            .unwrap_err()
+            .contains("passwords"));
+    }
+
+    #[test]
+    fn paths_with_spaces_and_non_ascii_names_are_passed_whole() {
+        let file = Path::new("/Users/Zoë Müller/Application Support/Ondo/settings.json");
+        let (program, args) = reveal_command("macos", file, true);
+        assert_eq!(program, "open");
+        assert_eq!(args, vec![OsString::from("-R"), file.into()]);
+
+        let (program, args) = reveal_command("linux", file, true);
+        assert_eq!(program, "xdg-open");
+        assert_eq!(
+            args,
+            vec![OsString::from("/Users/Zoë Müller/Application Support/Ondo")]
+        );
+    }
+
+    #[test]
+    fn explorer_gets_the_path_quoted_after_select() {
+        let file = Path::new(r"C:\Users\José Núñez\AppData\Roaming\Ondo\settings.json");
+        let (program, args) = reveal_command("windows", file, true);
+        assert_eq!(program, "explorer");
+        assert_eq!(
+            args,
+            vec![OsString::from(
+                r#"/select,"C:\Users\José Núñez\AppData\Roaming\Ondo\settings.json""#
+            )]
+        );
+
+        let (_, args) = reveal_command("windows", Path::new(r"C:\Data Dir"), false);
+        assert_eq!(args, vec![OsString::from(r#""C:\Data Dir""#)]);
+    }
+}
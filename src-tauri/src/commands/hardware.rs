@@ -0,0 +1,388 @@
+//! Sensor data, derived statistics and device commands.
+
+use crate::{
+    api_schema, audio, error_reporting, hardware, hardware_static, influxdb, metrics,
+    monitor_pause, monitoring_mode, power, session_stats, simulation, ws_server,
+};
+use crate::{now_millis, AppState, HardwareData};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Where samples come from. The live implementation is the hardware module;
+/// tests supply canned results.
+pub trait HardwareSource {
+    fn sample(&self) -> impl Future<Output = Result<HardwareData, String>> + Send;
+}
+
+pub struct LiveHardware;
+
+impl HardwareSource for LiveHardware {
+    fn sample(&self) -> impl Future<Output = Result<HardwareData, String>> + Send {
+        hardware::get_hardware_info()
+    }
+}
+
+/// Describe why a sample is unusable, if it has neither CPU nor GPU data.
+pub fn missing_data_error(data: &HardwareData) -> Option<String> {
+    if data.cpu.is_some() || data.gpu.is_some() {
+        return None;
+    }
+    let detail = data
+        .cpu_error
+        .as_deref()
+        .or(data.gpu_error.as_deref())
+        .unwrap_or("Unknown error");
+    Some(format!("Both CPU and GPU data unavailable: {}", detail))
+}
+
+/// Take one sample, report failures, and hand it to `process` for the
+/// per-sample bookkeeping.
+pub async fn collect_sample(
+    source: &impl HardwareSource,
+    process: impl FnOnce(&mut HardwareData),
+) -> Result<HardwareData, String> {
+    match source.sample().await {
+        Ok(mut data) => {
+            metrics::SAMPLES_COLLECTED.inc();
+            // Both missing indicates a problem worth reporting
+            if let Some(error) = missing_data_error(&data) {
+                error_reporting::capture_hardware_error(&error, "both");
+            }
+            process(&mut data);
+            Ok(data)
+        }
+        Err(e) => {
+            metrics::SAMPLE_ERRORS.inc();
+            error_reporting::capture_hardware_error(&e, "get_hardware_info");
+            Err(e)
+        }
+    }
+}
+
+/// Payload of `data-source-changed`, emitted when samples start coming
+/// from another source, e.g. the sysinfo fallback after the LHM daemon died.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DataSourceChange {
+    /// `None` for the first sample.
+    pub previous: Option<String>,
+    pub source: String,
+    pub degraded: bool,
+}
+
+/// `dataSource` and `degraded` of the last sample.
+static LAST_SOURCE: Mutex<Option<(String, bool)>> = Mutex::new(None);
+
+fn source_change(
+    last: &mut Option<(String, bool)>,
+    data: &HardwareData,
+) -> Option<DataSourceChange> {
+    let current = (data.data_source.clone(), data.degraded);
+    if last.as_ref() == Some(&current) {
+        return None;
+    }
+    let previous = last.replace(current).map(|(source, _)| source);
+    Some(DataSourceChange {
+        previous,
+        source: data.data_source.clone(),
+        degraded: data.degraded,
+    })
+}
+
+/// Emit `data-source-changed` when `data` comes from another source than
+/// the last sample. A switch is a breadcrumb for later errors rather than
+/// an error itself; the fallback reports its own failures.
+fn report_source_change(app: &AppHandle, data: &HardwareData) {
+    let change = source_change(
+        &mut LAST_SOURCE.lock().unwrap_or_else(|e| e.into_inner()),
+        data,
+    );
+    let Some(change) = change else {
+        return;
+    };
+    if let Some(previous) = &change.previous {
+        let message = format!(
+            "Data source changed from {} to {}{}",
+            previous,
+            change.source,
+            if change.degraded { " (degraded)" } else { "" }
+        );
+        crate::log_info!("Hardware", "{}", message);
+        error_reporting::add_breadcrumb("hardware", &message);
+    }
+    let _ = app.emit("data-source-changed", &change);
+}
+
+/// Read the hardware and pass the sample on to everything that follows
+/// one: statistics, the tray, alerts, the WebSocket feed and the InfluxDB
+/// push.
+pub async fn take_sample(app: &AppHandle) -> Result<HardwareData, String> {
+    let state = app.state::<AppState>();
+    let data = collect_sample(&LiveHardware, |data| state.process_sample(data)).await?;
+    monitor_pause::remember_sample(now_millis(), &data);
+    report_source_change(app, &data);
+    crate::alerts::check(app, &data);
+    ws_server::publish(&data);
+    influxdb::publish(&data);
+    Ok(data)
+}
+
+/// One sample on demand; the widget follows `hardware-update` from the
+/// background sampler (see `sampler`) instead of polling this.
+///
+/// While sampling is paused (see `monitor_pause`) this returns the last
+/// sample until it is `KEEP_ALIVE_MS` old, and while monitoring is paused
+/// from the tray, the last sample whatever its age.
+#[tauri::command]
+pub async fn get_hardware_data(app: AppHandle) -> Result<HardwareData, String> {
+    let mut data = match monitor_pause::cached_sample(now_millis()) {
+        Some(cached) => cached,
+        None => take_sample(&app).await?,
+    };
+    hardware_static::cached(&data).join(&mut data);
+    Ok(data)
+}
+
+/// Report `cpu_temp` and `gpu_temp` (°C) instead of the measured
+/// temperatures for `duration_secs` (10 by default), to test alerts and
+/// color thresholds. Refused outside debug builds.
+#[tauri::command]
+pub async fn simulate_reading(
+    cpu_temp: f32,
+    gpu_temp: f32,
+    duration_secs: Option<u32>,
+) -> Result<(), String> {
+    simulation::start(
+        cpu_temp,
+        gpu_temp,
+        duration_secs.unwrap_or(simulation::DEFAULT_DURATION_SECS),
+        now_millis(),
+    )
+}
+
+/// Switch between "normal" and "performance" (fast CPU and GPU sampling
+/// for benchmark runs, see `monitoring_mode`).
+#[tauri::command]
+pub async fn set_monitoring_mode(
+    app: AppHandle,
+    mode: String,
+) -> Result<monitoring_mode::MonitoringModeStatus, String> {
+    monitoring_mode::set(&app, &mode)
+}
+
+#[tauri::command]
+pub async fn get_monitoring_mode(
+    app: AppHandle,
+) -> Result<monitoring_mode::MonitoringModeStatus, String> {
+    monitoring_mode::status(&app)
+}
+
+/// Stop or resume sampling and the LHM daemon, then store the flag so it
+/// can be restored at startup (see `remember_monitoring_pause`).
+pub fn apply_monitoring_paused(app: &AppHandle, paused: bool) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let settings_to_save = super::settings::set_monitoring_paused(&state.settings, paused)?;
+    monitor_pause::set_monitoring_paused(app, paused);
+
+    let task = metrics::task_started();
+    tauri::async_runtime::spawn(async move {
+        let _task = task;
+        if let Err(e) = crate::settings::save_settings_to_file(&settings_to_save).await {
+            error_reporting::capture_settings_error(&e, "set_monitoring_paused");
+        }
+    });
+
+    crate::log_info!("Monitor", "Monitoring paused set to {}", paused);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_monitoring_paused(app: AppHandle, paused: bool) -> Result<(), String> {
+    apply_monitoring_paused(&app, paused)
+}
+
+/// Model names, core count and capacities, which don't change between
+/// samples. Cached, see `hardware_static`; the first call after a restart
+/// of the LHM daemon takes them from the latest sample, or samples once if
+/// there is none yet.
+#[tauri::command]
+pub async fn get_hardware_static() -> Result<hardware_static::HardwareStatic, String> {
+    let data = match monitor_pause::last_sample() {
+        Some(data) => data,
+        None => hardware::get_hardware_info().await?,
+    };
+    Ok(hardware_static::cached(&data))
+}
+
+/// Estimated energy use per day, with cost at the configured price.
+#[tauri::command]
+pub async fn get_energy_report(state: State<'_, AppState>) -> Result<power::EnergyReport, String> {
+    let price = state.settings.lock().electricity_price_per_kwh;
+    let energy = state.energy.lock().map_err(|e| e.to_string())?;
+    Ok(energy.report(price))
+}
+
+#[tauri::command]
+pub async fn get_session_stats(
+    state: State<'_, AppState>,
+) -> Result<session_stats::SessionStatsSnapshot, String> {
+    let stats = state.session_stats.lock().map_err(|e| e.to_string())?;
+    Ok(stats.snapshot())
+}
+
+/// Every device seen this session under the name its sensors report, for
+/// choosing `sensor_overrides`. Includes hidden devices.
+#[tauri::command]
+pub async fn list_detected_sensors() -> Result<Vec<hardware::DetectedSensor>, String> {
+    Ok(hardware::detected_sensors())
+}
+
+#[tauri::command]
+pub async fn reset_session_stats(state: State<'_, AppState>) -> Result<(), String> {
+    let mut stats = state.session_stats.lock().map_err(|e| e.to_string())?;
+    stats.reset(now_millis());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_audio_devices() -> Result<Vec<audio::AudioDevice>, String> {
+    audio::get_audio_devices()
+}
+
+#[tauri::command]
+pub async fn set_default_audio_device(
+    device_id: String,
+    device_type: String,
+) -> Result<(), String> {
+    audio::set_default_audio_device(&device_id, &device_type)
+}
+
+#[tauri::command]
+pub async fn get_api_schema() -> Result<serde_json::Value, String> {
+    Ok(api_schema::api_schema().clone())
+}
+
+/// Bundled release notes newer than `since_version`, newest first.
+#[tauri::command]
+pub async fn get_release_notes(
+    since_version: Option<String>,
+) -> Result<Vec<crate::changelog::ReleaseNote>, String> {
+    crate::release_notes::notes_since(since_version.as_deref())
+}
+
+/// Internal resource counters, for spotting leaks in long sessions.
+#[tauri::command]
+pub async fn get_internal_metrics() -> Result<metrics::InternalMetrics, String> {
+    Ok(metrics::snapshot())
+}
+
+#[tauri::command]
+pub async fn get_lhm_diagnostics() -> Result<hardware::LhmDiagnostics, String> {
+    Ok(hardware::lhm_diagnostics())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CpuData;
+
+    struct CannedSource(Result<HardwareData, String>);
+
+    impl HardwareSource for CannedSource {
+        fn sample(&self) -> impl Future<Output = Result<HardwareData, String>> + Send {
+            std::future::ready(self.0.clone())
+        }
+    }
+
+    fn empty_sample() -> HardwareData {
+        HardwareData {
+            timestamp: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn missing_data_prefers_cpu_error() {
+        let mut data = empty_sample();
+        data.cpu_error = Some("PawnIO not running".to_string());
+        data.gpu_error = Some("no GPU".to_string());
+        assert_eq!(
+            missing_data_error(&data).as_deref(),
+            Some("Both CPU and GPU data unavailable: PawnIO not running")
+        );
+
+        data.cpu_error = None;
+        assert_eq!(
+            missing_data_error(&data).as_deref(),
+            Some("Both CPU and GPU data unavailable: no GPU")
+        );
+    }
+
+    #[test]
+    fn sample_with_cpu_is_not_an_error() {
+        let mut data = empty_sample();
+        data.cpu = Some(CpuData {
+            name: "CPU".to_string(),
+            temperature: 50.0,
+            max_temperature: 60.0,
+            load: 1.0,
+            frequency: 4.0,
+            cores: Vec::new(),
+            power: None,
+            voltage: None,
+            is_throttling: false,
+            base_frequency: 0.0,
+            max_frequency: 0.0,
+            packages: None,
+            status: Default::default(),
+        });
+        assert_eq!(missing_data_error(&data), None);
+    }
+
+    #[tokio::test]
+    async fn successful_sample_is_processed() {
+        let source = CannedSource(Ok(empty_sample()));
+        let data = collect_sample(&source, |data| data.timestamp = 42)
+            .await
+            .unwrap();
+        assert_eq!(data.timestamp, 42);
+    }
+
+    #[test]
+    fn only_a_different_source_is_a_change() {
+        let mut last = None;
+        let mut data = empty_sample();
+        data.data_source = "lhm".to_string();
+        assert_eq!(
+            source_change(&mut last, &data),
+            Some(DataSourceChange {
+                previous: None,
+                source: "lhm".to_string(),
+                degraded: false,
+            })
+        );
+        assert_eq!(source_change(&mut last, &data), None);
+
+        data.data_source = "sysinfo".to_string();
+        data.degraded = true;
+        assert_eq!(
+            source_change(&mut last, &data),
+            Some(DataSourceChange {
+                previous: Some("lhm".to_string()),
+                source: "sysinfo".to_string(),
+                degraded: true,
+            })
+        );
+        assert_eq!(source_change(&mut last, &data), None);
+    }
+
+    #[tokio::test]
+    async fn failed_sample_skips_processing() {
+        let source = CannedSource(Err("daemon gone".to_string()));
+        let result = collect_sample(&source, |_| panic!("processed a failed sample")).await;
+        assert_eq!(result.unwrap_err(), "daemon gone");
+    }
+}
@@ -0,0 +1,161 @@
+//! Tauri commands, one file per domain. Each command is a thin wrapper that
+//! resolves the window or state and delegates to a plain function taking a
+//! trait, so the logic can be tested without a running app.
+
+pub mod alerts;
+pub mod capabilities;
+pub mod diagnostics;
+pub mod files;
+pub mod hardware;
+pub mod pawnio;
+pub mod privileges;
+pub mod profiles;
+pub mod settings;
+pub mod snapshot;
+pub mod system_info;
+pub mod updater;
+pub mod window;
+
+/// Registers the command list with Tauri and keeps the names around for the
+/// registration test.
+macro_rules! commands {
+    ($($module:ident :: $name:ident),* $(,)?) => {
+        /// Every command name the frontend can invoke.
+        #[cfg_attr(not(test), allow(dead_code))]
+        pub const COMMAND_NAMES: &[&str] = &[$(stringify!($name)),*];
+
+        pub fn invoke_handler(
+        ) -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static {
+            tauri::generate_handler![$($module::$name),*]
+        }
+    };
+}
+
+commands![
+    hardware::get_hardware_data,
+    hardware::get_hardware_static,
+    capabilities::get_capabilities,
+    system_info::get_system_info,
+    system_info::get_self_usage,
+    snapshot::copy_snapshot_to_clipboard,
+    diagnostics::export_diagnostics,
+    hardware::set_monitoring_paused,
+    hardware::set_monitoring_mode,
+    hardware::get_monitoring_mode,
+    hardware::simulate_reading,
+    alerts::get_alert_history,
+    alerts::acknowledge_alert,
+    alerts::clear_alert_history,
+    settings::get_settings,
+    settings::save_settings,
+    settings::update_settings,
+    settings::export_settings,
+    settings::import_settings,
+    profiles::list_profiles,
+    profiles::save_profile,
+    profiles::apply_profile,
+    profiles::delete_profile,
+    window::set_always_on_top,
+    window::set_always_on_back,
+    window::set_click_through,
+    window::set_window_position,
+    settings::set_auto_start,
+    settings::get_auto_start_status,
+    window::get_window_state,
+    window::restore_window_state,
+    window::set_window_min_size,
+    window::set_window_shadow,
+    window::set_window_opacity,
+    pawnio::check_pawnio_status,
+    pawnio::download_and_install_pawnio,
+    pawnio::uninstall_pawnio,
+    hardware::get_audio_devices,
+    hardware::set_default_audio_device,
+    settings::toggle_debug_server,
+    settings::test_influxdb_connection,
+    hardware::get_lhm_diagnostics,
+    hardware::get_internal_metrics,
+    hardware::get_api_schema,
+    hardware::get_release_notes,
+    settings::get_telemetry_status,
+    settings::set_telemetry_enabled,
+    settings::get_recent_errors,
+    files::open_config_dir,
+    files::reveal_settings_file,
+    files::open_data_file,
+    hardware::get_energy_report,
+    hardware::get_session_stats,
+    hardware::reset_session_stats,
+    hardware::list_detected_sensors,
+    updater::check_for_update,
+    updater::install_update,
+    privileges::get_privilege_status,
+    privileges::restart_as_admin,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::COMMAND_NAMES;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    fn registered() -> BTreeSet<&'static str> {
+        COMMAND_NAMES.iter().copied().collect()
+    }
+
+    /// Names passed to `invoke("...")` anywhere in the frontend sources.
+    fn frontend_invokes(dir: &Path, pattern: &Regex, found: &mut BTreeSet<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                frontend_invokes(&path, pattern, found);
+            } else if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("ts" | "tsx")
+            ) {
+                let source = std::fs::read_to_string(&path).unwrap();
+                for capture in pattern.captures_iter(&source) {
+                    found.insert(capture[1].to_string());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn command_names_are_unique() {
+        assert_eq!(registered().len(), COMMAND_NAMES.len());
+    }
+
+    #[test]
+    fn every_frontend_invoke_is_registered() {
+        let pattern = Regex::new(r#"invoke(?:<[^>]*>)?\(\s*"([a-z_]+)""#).unwrap();
+        let mut invoked = BTreeSet::new();
+        let frontend = Path::new(env!("CARGO_MANIFEST_DIR")).join("../src");
+        frontend_invokes(&frontend, &pattern, &mut invoked);
+
+        assert!(
+            !invoked.is_empty(),
+            "no invoke calls found in {:?}",
+            frontend
+        );
+        let registered = registered();
+        let missing: Vec<_> = invoked
+            .iter()
+            .filter(|name| !registered.contains(name.as_str()))
+            .collect();
+        assert!(missing.is_empty(), "not registered: {:?}", missing);
+    }
+
+    #[test]
+    fn every_command_is_in_the_api_schema() {
+        let schema = crate::api_schema::api_schema();
+        let documented: BTreeSet<&str> = schema["commands"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(registered(), documented);
+    }
+}
@@ -0,0 +1,705 @@
+//! PawnIO driver check and installation. LibreHardwareMonitor needs the
+//! driver for CPU temperatures on Windows.
+
+#[cfg(target_os = "windows")]
+use crate::elevation::{run_elevated, wide};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PawnIOStatus {
+    pub installed: bool,
+    pub checking: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver_file_exists: Option<bool>,
+    /// Driver binary from the service configuration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver_path: Option<String>,
+    /// Installed version, from PawnIO's uninstall registry entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The bundled installer is newer than the installed version.
+    #[serde(default)]
+    pub needs_update: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of running the PawnIO installer. Not being able to run it at all
+/// (installer missing, UAC prompt declined) is an error instead.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct PawnIOInstallResult {
+    /// The installer succeeded and the driver is running.
+    pub success: bool,
+    pub exit_code: u32,
+    /// Driver status after the installer exited.
+    pub status: PawnIOStatus,
+    /// The LHM daemon was restarted to pick up the driver, so Ondo doesn't
+    /// need a restart.
+    pub daemon_restarted: bool,
+    pub message: String,
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn install_result(
+    exit_code: u32,
+    status: PawnIOStatus,
+    daemon_restarted: bool,
+) -> PawnIOInstallResult {
+    let (success, message) = if exit_code != 0 {
+        (
+            false,
+            format!("PawnIO installer exited with code {}", exit_code),
+        )
+    } else if !status.installed {
+        (
+            false,
+            format!(
+                "PawnIO installer finished but the driver is not running ({})",
+                status.service_state.as_deref().unwrap_or("unknown state")
+            ),
+        )
+    } else if daemon_restarted {
+        (
+            true,
+            "PawnIO driver is installed and sensors were reloaded.".to_string(),
+        )
+    } else {
+        (
+            true,
+            "PawnIO driver is installed. Please restart Ondo to use it.".to_string(),
+        )
+    };
+    PawnIOInstallResult {
+        success,
+        exit_code,
+        status,
+        daemon_restarted,
+        message,
+    }
+}
+
+/// Access to the service controller. Implemented with `sc.exe` in
+/// production and by canned output in tests.
+#[cfg(any(target_os = "windows", test))]
+pub trait ServiceControl {
+    /// stdout of `sc qc PawnIO`.
+    fn query_config(&self) -> std::io::Result<String>;
+    /// stdout and stderr of `sc query PawnIO`.
+    fn query_state(&self) -> std::io::Result<(String, String)>;
+    fn file_exists(&self, path: &str) -> bool;
+    /// `DisplayVersion` of the installed PawnIO.
+    fn installed_version(&self) -> Option<String>;
+    /// File version of the installer bundled with Ondo.
+    fn bundled_version(&self) -> Option<String>;
+}
+
+/// Numeric components of a version like `2.0.1.0`, or `None` if any
+/// component isn't a number.
+#[cfg(any(target_os = "windows", test))]
+fn version_parts(version: &str) -> Option<Vec<u64>> {
+    version
+        .trim()
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Whether `installed` is older than `bundled`. Missing trailing components
+/// count as zero, so `2.0.1` and `2.0.1.0` are the same version; versions
+/// that don't parse are never reported as outdated.
+#[cfg(any(target_os = "windows", test))]
+fn is_older(installed: &str, bundled: &str) -> bool {
+    let (Some(mut installed), Some(mut bundled)) =
+        (version_parts(installed), version_parts(bundled))
+    else {
+        return false;
+    };
+    let len = installed.len().max(bundled.len());
+    installed.resize(len, 0);
+    bundled.resize(len, 0);
+    installed < bundled
+}
+
+/// Program and arguments of a registry `UninstallString`. The program may
+/// be quoted, or an unquoted path with spaces ending in `.exe`.
+#[cfg(any(target_os = "windows", test))]
+fn split_command_line(command: &str) -> Option<(String, String)> {
+    let command = command.trim();
+    if let Some(quoted) = command.strip_prefix('"') {
+        let end = quoted.find('"')?;
+        return Some((
+            quoted[..end].to_string(),
+            quoted[end + 1..].trim().to_string(),
+        ));
+    }
+    let end = command
+        .to_ascii_lowercase()
+        .find(".exe")
+        .map_or(command.len(), |i| i + ".exe".len());
+    let program = command[..end].trim();
+    (!program.is_empty()).then(|| (program.to_string(), command[end..].trim().to_string()))
+}
+
+/// Uninstaller arguments with `-silent` added, as for the install.
+#[cfg(any(target_os = "windows", test))]
+fn silent_uninstall_params(args: &str) -> String {
+    if args
+        .split_whitespace()
+        .any(|arg| arg.eq_ignore_ascii_case("-silent"))
+    {
+        args.to_string()
+    } else {
+        format!("{} -silent", args).trim().to_string()
+    }
+}
+
+/// Driver binary path from `sc qc` output, without the `\??\` prefix.
+#[cfg(any(target_os = "windows", test))]
+fn driver_path_from_config(qc_stdout: &str) -> Option<String> {
+    let line = qc_stdout.lines().find(|l| l.contains("BINARY_PATH_NAME"))?;
+    // The path itself contains ':' after the drive letter
+    let path = line.split(':').skip(1).collect::<Vec<_>>().join(":");
+    Some(path.trim().trim_start_matches(r"\??\").to_string())
+}
+
+/// Service state name from `sc query` output.
+#[cfg(any(target_os = "windows", test))]
+fn service_state_from_query(stdout: &str, stderr: &str) -> String {
+    if stdout.contains("RUNNING") {
+        "RUNNING".to_string()
+    } else if stdout.contains("STOPPED") {
+        "STOPPED".to_string()
+    } else if stdout.contains("START_PENDING") {
+        "START_PENDING".to_string()
+    } else if stdout.contains("STOP_PENDING") {
+        "STOP_PENDING".to_string()
+    } else if !stderr.is_empty() || stdout.contains("1060") {
+        // 1060: the service does not exist
+        "NOT_FOUND".to_string()
+    } else {
+        format!("UNKNOWN: {}", stdout.trim())
+    }
+}
+
+#[cfg(any(target_os = "windows", test))]
+pub fn detailed_status(sc: &impl ServiceControl) -> PawnIOStatus {
+    // Get driver binary path from service config (sc qc)
+    let driver_path = sc
+        .query_config()
+        .ok()
+        .as_deref()
+        .and_then(driver_path_from_config);
+    let driver_file_exists = match &driver_path {
+        Some(path) => {
+            let exists = sc.file_exists(path);
+            crate::log_debug!("PawnIO", "Driver binary path: {} (exists={})", path, exists);
+            exists
+        }
+        None => false,
+    };
+    let version = sc.installed_version();
+    let needs_update = match (&version, sc.bundled_version()) {
+        (Some(installed), Some(bundled)) => is_older(installed, &bundled),
+        _ => false,
+    };
+
+    // Check if PawnIO service/driver is registered and its state
+    match sc.query_state() {
+        Ok((stdout, stderr)) => {
+            let service_state = service_state_from_query(&stdout, &stderr);
+            let installed = service_state == "RUNNING";
+
+            crate::log_info!(
+                "PawnIO",
+                "Driver status: service={}, driver_file={}",
+                service_state,
+                driver_file_exists
+            );
+            if !installed {
+                crate::log_warn!(
+                    "PawnIO",
+                    "Driver is not running. CPU temperature may not be available. sc output: {}",
+                    stdout.trim()
+                );
+            }
+
+            PawnIOStatus {
+                installed,
+                checking: false,
+                service_state: Some(service_state),
+                driver_file_exists: Some(driver_file_exists),
+                driver_path,
+                version,
+                needs_update,
+                error: None,
+            }
+        }
+        Err(e) => {
+            let error_msg = format!("Failed to query PawnIO service: {}", e);
+            crate::log_error!("PawnIO", "{}", error_msg);
+
+            PawnIOStatus {
+                installed: false,
+                checking: false,
+                service_state: None,
+                driver_file_exists: Some(driver_file_exists),
+                driver_path,
+                version,
+                needs_update,
+                error: Some(error_msg),
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+struct ScExe;
+
+#[cfg(target_os = "windows")]
+impl ScExe {
+    fn run(&self, verb: &str) -> std::io::Result<std::process::Output> {
+        use std::os::windows::process::CommandExt;
+        use std::process::Command;
+
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+        Command::new("sc")
+            .args([verb, "PawnIO"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl ServiceControl for ScExe {
+    fn query_config(&self) -> std::io::Result<String> {
+        let output = self.run("qc")?;
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn query_state(&self) -> std::io::Result<(String, String)> {
+        let output = self.run("query")?;
+        Ok((
+            String::from_utf8_lossy(&output.stdout).into_owned(),
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ))
+    }
+
+    fn file_exists(&self, path: &str) -> bool {
+        std::path::Path::new(path).exists()
+    }
+
+    fn installed_version(&self) -> Option<String> {
+        uninstall_entry_value("DisplayVersion")
+    }
+
+    fn bundled_version(&self) -> Option<String> {
+        file_version(&bundled_installer_path().ok()?)
+    }
+}
+
+/// Uninstall registry key written by the PawnIO installer.
+#[cfg(target_os = "windows")]
+const PAWNIO_UNINSTALL_KEY: &str = r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall\PawnIO";
+
+/// A string value from PawnIO's uninstall registry entry.
+#[cfg(target_os = "windows")]
+fn uninstall_entry_value(name: &str) -> Option<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    let key = wide(PAWNIO_UNINSTALL_KEY);
+    let value = wide(name);
+    let mut buffer = [0u16; 1024];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            PCWSTR(key.as_ptr()),
+            PCWSTR(value.as_ptr()),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+    };
+    if status.is_err() {
+        return None;
+    }
+    let len = (size as usize / 2).min(buffer.len());
+    let value = String::from_utf16_lossy(&buffer[..len]);
+    let value = value.trim_end_matches('\0').trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// File version from an executable's version resource, as `a.b.c.d`.
+#[cfg(target_os = "windows")]
+fn file_version(path: &std::path::Path) -> Option<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::{
+        GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW, VS_FIXEDFILEINFO,
+    };
+
+    let path = wide(path.to_str()?);
+    unsafe {
+        let size = GetFileVersionInfoSizeW(PCWSTR(path.as_ptr()), None);
+        if size == 0 {
+            return None;
+        }
+        let mut data = vec![0u8; size as usize];
+        GetFileVersionInfoW(PCWSTR(path.as_ptr()), None, size, data.as_mut_ptr().cast()).ok()?;
+
+        let root = wide("\\");
+        let mut info: *mut std::ffi::c_void = std::ptr::null_mut();
+        let mut len = 0u32;
+        if !VerQueryValueW(
+            data.as_ptr().cast(),
+            PCWSTR(root.as_ptr()),
+            &mut info,
+            &mut len,
+        )
+        .as_bool()
+            || info.is_null()
+            || (len as usize) < std::mem::size_of::<VS_FIXEDFILEINFO>()
+        {
+            return None;
+        }
+        let info = &*(info as *const VS_FIXEDFILEINFO);
+        Some(format!(
+            "{}.{}.{}.{}",
+            info.dwFileVersionMS >> 16,
+            info.dwFileVersionMS & 0xffff,
+            info.dwFileVersionLS >> 16,
+            info.dwFileVersionLS & 0xffff
+        ))
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_pawnio_detailed_status() -> PawnIOStatus {
+    detailed_status(&ScExe)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn get_pawnio_detailed_status() -> PawnIOStatus {
+    PawnIOStatus {
+        installed: true,
+        checking: false,
+        service_state: Some("N/A (non-Windows)".to_string()),
+        driver_file_exists: None,
+        driver_path: None,
+        version: None,
+        needs_update: false,
+        error: None,
+    }
+}
+
+#[tauri::command]
+pub async fn check_pawnio_status() -> Result<PawnIOStatus, String> {
+    Ok(get_pawnio_detailed_status())
+}
+
+/// The PawnIO installer bundled with Ondo.
+#[cfg(target_os = "windows")]
+fn bundled_installer_path() -> Result<std::path::PathBuf, String> {
+    let exe_path = std::env::current_exe().map_err(|e| format!("Failed to get exe path: {}", e))?;
+    let exe_dir = exe_path.parent().ok_or("Failed to get exe directory")?;
+
+    // In development, the resources are in src-tauri/resources
+    // In production, they're next to the exe
+    let installer_path = exe_dir.join("resources").join("PawnIO_setup.exe");
+    let installer_path = if installer_path.exists() {
+        installer_path
+    } else {
+        // Fallback to direct path (production build)
+        exe_dir.join("PawnIO_setup.exe")
+    };
+
+    if !installer_path.exists() {
+        return Err(format!(
+            "PawnIO installer not found at {:?}",
+            installer_path
+        ));
+    }
+    Ok(installer_path)
+}
+
+/// Install the bundled PawnIO silently, wait for the installer and check
+/// the driver service. On success the LHM daemon is restarted to load the
+/// driver and `pawnio-installed` is emitted with the result.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn download_and_install_pawnio(
+    app: tauri::AppHandle,
+) -> Result<PawnIOInstallResult, String> {
+    use tauri::Emitter;
+
+    let installer_path = bundled_installer_path()?;
+    let program = installer_path
+        .to_str()
+        .ok_or("Installer path is not valid Unicode")?
+        .to_string();
+
+    let result = tokio::task::spawn_blocking(move || {
+        // PawnIO uses -install -silent, not NSIS /S
+        let exit_code = run_elevated(&program, "-install -silent")?;
+        let status = get_pawnio_detailed_status();
+        let daemon_restarted = exit_code == 0
+            && status.installed
+            && crate::hardware::restart_lhm_daemon()
+                .inspect_err(|e| crate::log_warn!("PawnIO", "Daemon not restarted: {}", e))
+                .is_ok();
+        Ok::<_, String>(install_result(exit_code, status, daemon_restarted))
+    })
+    .await
+    .map_err(|e| format!("Install task failed: {}", e))??;
+
+    crate::log_info!("PawnIO", "{}", result.message);
+    if result.success {
+        if let Err(e) = app.emit("pawnio-installed", &result) {
+            crate::log_warn!("PawnIO", "Failed to emit pawnio-installed: {}", e);
+        }
+    }
+    Ok(result)
+}
+
+/// Remove PawnIO silently with its registered uninstaller.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn uninstall_pawnio() -> Result<String, String> {
+    tokio::task::spawn_blocking(|| {
+        let command = uninstall_entry_value("UninstallString").ok_or("PawnIO is not installed")?;
+        let (program, args) = split_command_line(&command)
+            .ok_or_else(|| format!("Unrecognized uninstall command: {}", command))?;
+        let exit_code = run_elevated(&program, &silent_uninstall_params(&args))?;
+        if exit_code != 0 {
+            return Err(format!("PawnIO uninstaller exited with code {}", exit_code));
+        }
+        let status = get_pawnio_detailed_status();
+        if status.installed {
+            return Err("PawnIO uninstaller finished but the driver is still running".to_string());
+        }
+        Ok("PawnIO driver was removed. Restart Windows to finish if it was in use.".to_string())
+    })
+    .await
+    .map_err(|e| format!("Uninstall task failed: {}", e))?
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn download_and_install_pawnio() -> Result<PawnIOInstallResult, String> {
+    Err("PawnIO is only required on Windows.".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn uninstall_pawnio() -> Result<String, String> {
+    Ok("PawnIO is only required on Windows.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    const QC_OUTPUT: &str = "[SC] QueryServiceConfig SUCCESS\n\nSERVICE_NAME: PawnIO\n        TYPE               : 1  KERNEL_DRIVER\n        BINARY_PATH_NAME   : \\??\\C:\\Program Files\\PawnIO\\PawnIO.sys\n";
+    const QUERY_RUNNING: &str = "SERVICE_NAME: PawnIO\n        TYPE               : 1  KERNEL_DRIVER\n        STATE              : 4  RUNNING\n";
+    const QUERY_MISSING: &str =
+        "[SC] EnumQueryServicesStatus:OpenService FAILED 1060:\n\nThe specified service does not exist as an installed service.\n";
+
+    struct FakeSc {
+        config: Option<&'static str>,
+        state: Option<(&'static str, &'static str)>,
+        driver_present: bool,
+        versions: (Option<&'static str>, Option<&'static str>),
+    }
+
+    impl ServiceControl for FakeSc {
+        fn query_config(&self) -> io::Result<String> {
+            self.config
+                .map(str::to_string)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "sc missing"))
+        }
+
+        fn query_state(&self) -> io::Result<(String, String)> {
+            self.state
+                .map(|(out, err)| (out.to_string(), err.to_string()))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "sc missing"))
+        }
+
+        fn file_exists(&self, path: &str) -> bool {
+            assert_eq!(path, r"C:\Program Files\PawnIO\PawnIO.sys");
+            self.driver_present
+        }
+
+        fn installed_version(&self) -> Option<String> {
+            self.versions.0.map(str::to_string)
+        }
+
+        fn bundled_version(&self) -> Option<String> {
+            self.versions.1.map(str::to_string)
+        }
+    }
+
+    #[test]
+    fn driver_path_keeps_drive_colon_and_drops_prefix() {
+        assert_eq!(
+            driver_path_from_config(QC_OUTPUT).as_deref(),
+            Some(r"C:\Program Files\PawnIO\PawnIO.sys")
+        );
+        assert_eq!(driver_path_from_config("SERVICE_NAME: PawnIO"), None);
+    }
+
+    #[test]
+    fn service_states_are_mapped() {
+        assert_eq!(service_state_from_query(QUERY_RUNNING, ""), "RUNNING");
+        assert_eq!(
+            service_state_from_query("STATE : 1  STOPPED", ""),
+            "STOPPED"
+        );
+        assert_eq!(
+            service_state_from_query("STATE : 2  START_PENDING", ""),
+            "START_PENDING"
+        );
+        assert_eq!(service_state_from_query(QUERY_MISSING, ""), "NOT_FOUND");
+        assert_eq!(service_state_from_query("", "Access denied"), "NOT_FOUND");
+        assert_eq!(service_state_from_query(" odd ", ""), "UNKNOWN: odd");
+    }
+
+    #[test]
+    fn running_driver_is_installed() {
+        let status = detailed_status(&FakeSc {
+            config: Some(QC_OUTPUT),
+            state: Some((QUERY_RUNNING, "")),
+            driver_present: true,
+            versions: (Some("2.0.1"), Some("2.0.1.0")),
+        });
+        assert_eq!(
+            status,
+            PawnIOStatus {
+                installed: true,
+                checking: false,
+                service_state: Some("RUNNING".to_string()),
+                driver_file_exists: Some(true),
+                driver_path: Some(r"C:\Program Files\PawnIO\PawnIO.sys".to_string()),
+                version: Some("2.0.1".to_string()),
+                needs_update: false,
+                error: None,
+            }
+        );
+    }
+
+    #[test]
+    fn stopped_driver_with_missing_file_is_not_installed() {
+        let status = detailed_status(&FakeSc {
+            config: Some(QC_OUTPUT),
+            state: Some(("STATE : 1  STOPPED", "")),
+            driver_present: false,
+            versions: (None, Some("2.1.0.0")),
+        });
+        assert!(!status.installed);
+        assert_eq!(status.service_state.as_deref(), Some("STOPPED"));
+        assert_eq!(status.driver_file_exists, Some(false));
+    }
+
+    #[test]
+    fn sc_failure_is_reported_as_error() {
+        let status = detailed_status(&FakeSc {
+            config: None,
+            state: None,
+            driver_present: false,
+            versions: (None, None),
+        });
+        assert!(!status.installed);
+        assert_eq!(status.service_state, None);
+        assert_eq!(status.driver_file_exists, Some(false));
+        assert_eq!(
+            status.error.as_deref(),
+            Some("Failed to query PawnIO service: sc missing")
+        );
+    }
+
+    #[test]
+    fn outdated_install_needs_update() {
+        let status = detailed_status(&FakeSc {
+            config: Some(QC_OUTPUT),
+            state: Some((QUERY_RUNNING, "")),
+            driver_present: true,
+            versions: (Some("1.0.3"), Some("2.0.1.0")),
+        });
+        assert!(status.installed);
+        assert!(status.needs_update);
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(is_older("1.9", "1.10"));
+        assert!(is_older("2.0.1", "2.0.1.1"));
+        assert!(!is_older("2.0.1", "2.0.1.0"));
+        assert!(!is_older("2.1.0.0", "2.0.9"));
+        // Unparseable versions are never reported as outdated
+        assert!(!is_older("2.0-beta", "3.0"));
+    }
+
+    #[test]
+    fn uninstall_command_is_split_and_made_silent() {
+        assert_eq!(
+            split_command_line(r#""C:\Program Files\PawnIO\PawnIO_setup.exe" -uninstall"#),
+            Some((
+                r"C:\Program Files\PawnIO\PawnIO_setup.exe".to_string(),
+                "-uninstall".to_string()
+            ))
+        );
+        assert_eq!(
+            split_command_line(r"C:\Program Files\PawnIO\uninstall.exe"),
+            Some((
+                r"C:\Program Files\PawnIO\uninstall.exe".to_string(),
+                String::new()
+            ))
+        );
+        assert_eq!(split_command_line(r#""C:\unterminated"#), None);
+        assert_eq!(split_command_line("  "), None);
+
+        assert_eq!(silent_uninstall_params("-uninstall"), "-uninstall -silent");
+        assert_eq!(
+            silent_uninstall_params("-uninstall -SILENT"),
+            "-uninstall -SILENT"
+        );
+        assert_eq!(silent_uninstall_params(""), "-silent");
+    }
+
+    #[test]
+    fn install_result_reflects_exit_code_and_driver_state() {
+        let running = || {
+            detailed_status(&FakeSc {
+                config: Some(QC_OUTPUT),
+                state: Some((QUERY_RUNNING, "")),
+                driver_present: true,
+                versions: (Some("2.0.1"), Some("2.0.1.0")),
+            })
+        };
+
+        let result = install_result(0, running(), true);
+        assert!(result.success);
+        assert!(result.message.contains("sensors were reloaded"));
+        assert!(install_result(0, running(), false)
+            .message
+            .contains("restart Ondo"));
+
+        let failed = install_result(1603, running(), false);
+        assert!(!failed.success);
+        assert_eq!(failed.message, "PawnIO installer exited with code 1603");
+
+        let stopped = PawnIOStatus {
+            installed: false,
+            service_state: Some("STOPPED".to_string()),
+            ..running()
+        };
+        let result = install_result(0, stopped, false);
+        assert!(!result.success);
+        assert!(result.message.contains("(STOPPED)"));
+    }
+}
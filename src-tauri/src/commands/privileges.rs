@@ -0,0 +1,77 @@
+//! Administrator status, and restarting Ondo elevated when sensors were
+//! refused for lack of it.
+
+use crate::elevation;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivilegeStatus {
+    /// Always true outside Windows, where sensors don't need it.
+    pub elevated: bool,
+    /// `restart_as_admin` can raise the privileges (Windows only).
+    pub can_restart_elevated: bool,
+}
+
+#[tauri::command]
+pub async fn get_privilege_status() -> Result<PrivilegeStatus, String> {
+    Ok(PrivilegeStatus {
+        elevated: elevation::is_elevated(),
+        can_restart_elevated: cfg!(target_os = "windows"),
+    })
+}
+
+/// Store the current window placement, so the elevated instance opens
+/// where this one was.
+#[cfg(target_os = "windows")]
+async fn hand_off_window_state(app: &AppHandle) -> Result<(), String> {
+    match super::window::remember_window_state(app)? {
+        Some(settings) => crate::settings::save_settings_to_file(&settings).await,
+        None => Ok(()),
+    }
+}
+
+/// Relaunch Ondo through the UAC prompt and exit this instance. Nothing
+/// changes when the prompt is declined.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn restart_as_admin(app: AppHandle) -> Result<(), String> {
+    if elevation::is_elevated() {
+        return Err("Ondo is already running as administrator".to_string());
+    }
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let program = exe
+        .to_str()
+        .ok_or("Executable path is not valid Unicode")?
+        .to_string();
+    let params = elevation::relaunch_params(std::env::args().skip(1));
+
+    if let Err(e) = hand_off_window_state(&app).await {
+        crate::log_warn!("Privileges", "Failed to save window state: {}", e);
+    }
+    tokio::task::spawn_blocking(move || elevation::spawn_elevated(&program, &params))
+        .await
+        .map_err(|e| format!("Restart task failed: {}", e))?
+        .inspect_err(|e| {
+            if e != elevation::UAC_DECLINED {
+                crate::error_reporting::capture_error(
+                    &format!("[Privileges] {}", e),
+                    "privileges",
+                    Some(&[("operation", "restart_as_admin")]),
+                );
+            }
+        })?;
+
+    crate::error_reporting::add_breadcrumb("privileges", "Restarting as administrator");
+    crate::error_reporting::flush(crate::error_reporting::SHUTDOWN_FLUSH_TIMEOUT);
+    app.exit(0);
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn restart_as_admin(_app: AppHandle) -> Result<(), String> {
+    Err("Administrator restart is only needed on Windows".to_string())
+}
@@ -0,0 +1,277 @@
+//! Named settings profiles (e.g. "Desk" and "Streaming") saved from the
+//! current settings and applied in one go, from the settings panel or the
+//! tray "Profiles" submenu. Stored in `profiles.json`, see
+//! `settings::Profiles`.
+
+use crate::settings::{self, AppSettings, Profiles};
+use crate::{error_reporting, tray, AppState};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Serializes read-modify-write cycles on profiles.json.
+static PROFILES_LOCK: Mutex<()> = Mutex::new(());
+
+/// Store `current` as the profile `name`, replacing a profile of the same
+/// name (ignoring case). Returns the name it was stored under.
+fn store_profile(
+    profiles: &mut Profiles,
+    name: &str,
+    mut current: AppSettings,
+) -> Result<String, String> {
+    let name = settings::validate_profile_name(name)?;
+    // Not part of a configuration: owned by the backend
+    current.monitoring_paused = false;
+    current.last_run_version = None;
+
+    let key = match settings::find_profile(profiles, &name) {
+        Some(existing) => existing.clone(),
+        None if profiles.len() >= settings::MAX_PROFILES => {
+            return Err(format!(
+                "At most {} profiles can be saved",
+                settings::MAX_PROFILES
+            ));
+        }
+        None => name,
+    };
+    profiles.insert(key.clone(), current);
+    Ok(key)
+}
+
+/// Add or replace `incoming` in `profiles`, as if each was saved in turn.
+/// Returns the names they were stored under; on error nothing is stored.
+pub fn merge_profiles(profiles: &mut Profiles, incoming: Profiles) -> Result<Vec<String>, String> {
+    let mut merged = profiles.clone();
+    let names = incoming
+        .into_iter()
+        .map(|(name, settings)| store_profile(&mut merged, &name, settings))
+        .collect::<Result<Vec<_>, _>>()?;
+    *profiles = merged;
+    Ok(names)
+}
+
+fn missing_profile(name: &str) -> String {
+    format!("No profile named \"{}\"", name.trim())
+}
+
+fn remove_profile(profiles: &mut Profiles, name: &str) -> Result<(), String> {
+    let key = settings::find_profile(profiles, name)
+        .cloned()
+        .ok_or_else(|| missing_profile(name))?;
+    profiles.remove(&key);
+    Ok(())
+}
+
+fn profile_settings(profiles: &Profiles, name: &str) -> Result<AppSettings, String> {
+    settings::find_profile(profiles, name)
+        .and_then(|key| profiles.get(key))
+        .cloned()
+        .ok_or_else(|| missing_profile(name))
+}
+
+fn profile_names(profiles: &Profiles) -> Vec<String> {
+    profiles.keys().cloned().collect()
+}
+
+/// Load profiles.json, let `change` edit it and write it back, then refresh
+/// the tray submenu. Returns the profile names afterwards.
+fn update_profiles(
+    app: &AppHandle,
+    operation: &'static str,
+    change: impl FnOnce(&mut Profiles) -> Result<(), String>,
+) -> Result<Vec<String>, String> {
+    let _guard = PROFILES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut profiles = settings::load_profiles_from_file()
+        .inspect_err(|e| error_reporting::capture_settings_error(e, operation))?;
+    change(&mut profiles)?;
+    settings::save_profiles_to_file(&profiles)
+        .inspect_err(|e| error_reporting::capture_settings_error(e, operation))?;
+    let names = profile_names(&profiles);
+    tray::set_profiles(app, &names);
+    Ok(names)
+}
+
+/// Merge `incoming` into the saved profiles, see `merge_profiles`. Returns
+/// the names of the imported profiles.
+pub fn import_profiles(app: &AppHandle, incoming: Profiles) -> Result<Vec<String>, String> {
+    if incoming.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut imported = Vec::new();
+    update_profiles(app, "import_settings", |profiles| {
+        imported = merge_profiles(profiles, incoming)?;
+        Ok(())
+    })?;
+    Ok(imported)
+}
+
+/// Names of the saved profiles, sorted.
+pub fn saved_profile_names() -> Vec<String> {
+    let _guard = PROFILES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    match settings::load_profiles_from_file() {
+        Ok(profiles) => profile_names(&profiles),
+        Err(e) => {
+            crate::log_warn!("Profiles", "{}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// The current settings with the window's placement right now, unless mini
+/// mode has shrunk it.
+fn current_settings(app: &AppHandle) -> Result<AppSettings, String> {
+    if !crate::window_monitor::is_mini_mode() {
+        if let Some(settings) = super::window::remember_window_state(app)? {
+            return Ok(settings);
+        }
+    }
+    let state = app.state::<AppState>();
+    let settings = state.settings.lock();
+    Ok(settings.clone())
+}
+
+/// Put the window where `settings` says and pin it accordingly. The rest
+/// of the settings only take effect through the UI or `apply_changes`.
+fn apply_window_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    let Some(window) = super::window::main_window(app) else {
+        return Ok(());
+    };
+    if let Some(window_state) = &settings.window_state {
+        super::window::apply_window_state(&window, &window_state.into())?;
+    }
+    super::window::move_to_anchor(&window, &settings.position, settings.position_margin)?;
+    super::window::pin_on_back(&window, settings.always_on_back)?;
+    if !settings.always_on_back {
+        super::window::pin_on_top(&window, settings.always_on_top)?;
+    }
+    super::window::apply_opacity(&window, settings.opacity)
+}
+
+/// Apply the profile `name` like a save from the settings panel, then
+/// move, pin and fade the window and set click-through to match. Fails
+/// without changing anything when there is no such profile.
+pub async fn apply_profile_by_name(app: &AppHandle, name: &str) -> Result<AppSettings, String> {
+    let profile = {
+        let _guard = PROFILES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        profile_settings(&settings::load_profiles_from_file()?, name)?
+    };
+    let state = app.state::<AppState>();
+    let before = state.settings.lock().clone();
+
+    let saved = super::settings::save_settings(app.clone(), state.clone(), profile.clone()).await;
+    if let Err(e) = apply_window_settings(app, &profile) {
+        crate::log_warn!("Profiles", "Window not fully updated: {}", e);
+    }
+    if profile.click_through != before.click_through {
+        super::window::apply_click_through(app, profile.click_through)?;
+    }
+
+    let after = state.settings.lock().clone();
+    let changed = super::settings::changed_settings(&before, &after);
+    if !changed.is_empty() {
+        let _ = app.emit("settings-changed", &changed);
+    }
+    crate::log_info!("Profiles", "Applied profile {}", name.trim());
+    saved.map(|_| after)
+}
+
+#[tauri::command]
+pub async fn list_profiles() -> Result<Vec<String>, String> {
+    Ok(saved_profile_names())
+}
+
+/// Save the current settings, window placement included, as the profile
+/// `name`. Returns the profile names.
+#[tauri::command]
+pub async fn save_profile(app: AppHandle, name: String) -> Result<Vec<String>, String> {
+    let current = current_settings(&app)?;
+    update_profiles(&app, "save_profile", |profiles| {
+        store_profile(profiles, &name, current).map(|_| ())
+    })
+}
+
+#[tauri::command]
+pub async fn apply_profile(app: AppHandle, name: String) -> Result<AppSettings, String> {
+    apply_profile_by_name(&app, &name).await
+}
+
+/// Returns the remaining profile names.
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, name: String) -> Result<Vec<String>, String> {
+    update_profiles(&app, "delete_profile", |profiles| {
+        remove_profile(profiles, &name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saving_under_an_existing_name_replaces_it() {
+        let mut profiles = Profiles::new();
+        let desk = AppSettings {
+            position: "bottom-right".to_string(),
+            ..AppSettings::default()
+        };
+        assert_eq!(
+            store_profile(&mut profiles, " Desk ", desk).unwrap(),
+            "Desk"
+        );
+
+        let moved = AppSettings {
+            position: "top-left".to_string(),
+            monitoring_paused: true,
+            ..AppSettings::default()
+        };
+        assert_eq!(store_profile(&mut profiles, "desk", moved).unwrap(), "Desk");
+        assert_eq!(profile_names(&profiles), vec!["Desk"]);
+        let stored = profile_settings(&profiles, "DESK").unwrap();
+        assert_eq!(stored.position, "top-left");
+        assert!(!stored.monitoring_paused);
+    }
+
+    #[test]
+    fn invalid_names_and_too_many_profiles_are_refused() {
+        let mut profiles = Profiles::new();
+        assert!(store_profile(&mut profiles, "", AppSettings::default()).is_err());
+        for i in 0..settings::MAX_PROFILES {
+            store_profile(&mut profiles, &format!("P{}", i), AppSettings::default()).unwrap();
+        }
+        assert!(store_profile(&mut profiles, "One more", AppSettings::default()).is_err());
+        // Replacing one still works at the limit
+        assert!(store_profile(&mut profiles, "p0", AppSettings::default()).is_ok());
+    }
+
+    #[test]
+    fn merging_is_all_or_nothing() {
+        let mut profiles = Profiles::new();
+        store_profile(&mut profiles, "Desk", AppSettings::default()).unwrap();
+
+        let mut incoming = Profiles::new();
+        incoming.insert("desk".to_string(), AppSettings::default());
+        incoming.insert("Streaming".to_string(), AppSettings::default());
+        let names = merge_profiles(&mut profiles, incoming).unwrap();
+        assert_eq!(names, vec!["Streaming", "Desk"]);
+        assert_eq!(profile_names(&profiles), vec!["Desk", "Streaming"]);
+
+        let too_many: Profiles = (0..settings::MAX_PROFILES)
+            .map(|i| (format!("New {}", i), AppSettings::default()))
+            .collect();
+        assert!(merge_profiles(&mut profiles, too_many).is_err());
+        assert_eq!(profiles.len(), 2);
+    }
+
+    #[test]
+    fn missing_profiles_are_errors() {
+        let mut profiles = Profiles::new();
+        assert_eq!(
+            profile_settings(&profiles, "Streaming").unwrap_err(),
+            "No profile named \"Streaming\""
+        );
+        assert!(remove_profile(&mut profiles, "Streaming").is_err());
+
+        store_profile(&mut profiles, "Streaming", AppSettings::default()).unwrap();
+        remove_profile(&mut profiles, "streaming").unwrap();
+        assert!(profiles.is_empty());
+    }
+}
@@ -0,0 +1,1022 @@
+//! Settings, telemetry opt-in, debug server and InfluxDB commands.
+
+use crate::hotkeys::{self, HotkeyAction};
+use crate::settings::{
+    self, AppSettings, InfluxDbSettings, SensorOverride, SettingsExport, SharedSettings,
+};
+use crate::{
+    error_reporting, hardware, influxdb, metrics, monitor_pause, tray, ws_server, AppState,
+};
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Where the live settings are kept. Implemented by `SharedSettings` in
+/// `AppState`, so the flows below can be tested without a Tauri app.
+pub trait SettingsStore {
+    fn with_settings<T>(&self, f: impl FnOnce(&mut AppSettings) -> T) -> Result<T, String>;
+}
+
+impl SettingsStore for SharedSettings {
+    fn with_settings<T>(&self, f: impl FnOnce(&mut AppSettings) -> T) -> Result<T, String> {
+        Ok(f(&mut self.lock()))
+    }
+}
+
+/// Side effects of a settings save that need more than writing the file.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SettingsChanges {
+    pub position: Option<String>,
+    pub position_margin: Option<u32>,
+    pub opacity: Option<u32>,
+    pub always_on_top: Option<bool>,
+    pub always_on_back: Option<bool>,
+    pub auto_start: Option<bool>,
+    pub compact_mode: Option<bool>,
+    pub telemetry_enabled: Option<bool>,
+    pub sentry_dsn: Option<Option<String>>,
+    pub update_interval_fast: Option<u32>,
+    pub update_interval_slow: Option<u32>,
+    pub pause_when_hidden: Option<bool>,
+    pub tray_show_temperature: Option<bool>,
+    pub sensor_overrides: Option<BTreeMap<String, SensorOverride>>,
+    pub monitored_sections: Option<hardware::MonitoredSections>,
+    pub mock_data: Option<bool>,
+    pub debug_server: Option<bool>,
+    /// Either global shortcut changed and must be re-registered.
+    pub hotkeys: bool,
+    /// Any WebSocket server setting changed and the server must be
+    /// reconfigured.
+    pub websocket: bool,
+    /// The InfluxDB push settings changed and the writer must be restarted.
+    pub influxdb: bool,
+}
+
+impl SettingsChanges {
+    fn between(previous: &AppSettings, current: &AppSettings) -> Self {
+        Self {
+            position: (previous.position != current.position).then(|| current.position.clone()),
+            position_margin: (previous.position_margin != current.position_margin)
+                .then_some(current.position_margin),
+            opacity: (previous.opacity != current.opacity).then_some(current.opacity),
+            always_on_top: (previous.always_on_top != current.always_on_top)
+                .then_some(current.always_on_top),
+            always_on_back: (previous.always_on_back != current.always_on_back)
+                .then_some(current.always_on_back),
+            auto_start: (previous.auto_start != current.auto_start).then_some(current.auto_start),
+            compact_mode: (previous.compact_mode != current.compact_mode)
+                .then_some(current.compact_mode),
+            telemetry_enabled: (previous.telemetry_enabled != current.telemetry_enabled)
+                .then_some(current.telemetry_enabled),
+            sentry_dsn: (previous.sentry_dsn != current.sentry_dsn)
+                .then(|| current.sentry_dsn.clone()),
+            update_interval_fast: (previous.update_interval_fast != current.update_interval_fast)
+                .then_some(current.update_interval_fast),
+            update_interval_slow: (previous.update_interval_slow != current.update_interval_slow)
+                .then_some(current.update_interval_slow),
+            pause_when_hidden: (previous.pause_when_hidden != current.pause_when_hidden)
+                .then_some(current.pause_when_hidden),
+            tray_show_temperature: (previous.tray_show_temperature
+                != current.tray_show_temperature)
+                .then_some(current.tray_show_temperature),
+            sensor_overrides: (previous.sensor_overrides != current.sensor_overrides)
+                .then(|| current.sensor_overrides.clone()),
+            monitored_sections: (previous.monitored_sections() != current.monitored_sections())
+                .then(|| current.monitored_sections()),
+            mock_data: (previous.mock_data != current.mock_data).then_some(current.mock_data),
+            debug_server: (previous.debug_server != current.debug_server)
+                .then_some(current.debug_server),
+            hotkeys: previous.toggle_hotkey != current.toggle_hotkey
+                || previous.settings_hotkey != current.settings_hotkey,
+            websocket: previous.websocket_server != current.websocket_server
+                || previous.websocket_port != current.websocket_port
+                || previous.websocket_allow_lan != current.websocket_allow_lan
+                || previous.websocket_token != current.websocket_token,
+            influxdb: previous.influxdb != current.influxdb,
+        }
+    }
+}
+
+/// Sanitize and store settings coming from the frontend. Returns what was
+/// stored and which changes must be applied.
+pub fn replace_settings(
+    store: &impl SettingsStore,
+    mut incoming: AppSettings,
+) -> Result<(AppSettings, SettingsChanges), String> {
+    incoming.sanitize();
+    let previous = store.with_settings(|current| {
+        // The UI can't be clicked while click-through is on, so the tray owns
+        // this flag; a stale copy from the frontend must not undo a toggle.
+        incoming.click_through = current.click_through;
+        incoming.monitoring_paused = current.monitoring_paused;
+        // Maintained by the backend for update detection
+        incoming.last_run_version = current.last_run_version.clone();
+        std::mem::replace(current, incoming.clone())
+    })?;
+
+    let changes = SettingsChanges::between(&previous, &incoming);
+    Ok((incoming, changes))
+}
+
+/// Top-level `AppSettings` keys as they appear in JSON.
+static SETTINGS_FIELDS: Lazy<BTreeSet<String>> = Lazy::new(|| {
+    schemars::schema_for!(AppSettings)
+        .schema
+        .object
+        .map(|object| object.properties.into_keys().collect())
+        .unwrap_or_default()
+});
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SettingsPatchError {
+    /// The patch is not a JSON object.
+    NotAnObject,
+    /// The patch names fields `AppSettings` doesn't have.
+    UnknownFields {
+        fields: Vec<String>,
+    },
+    /// The patched settings don't deserialize, e.g. a string for a number.
+    Invalid {
+        message: String,
+    },
+    /// A global shortcut couldn't be registered. The rest of the patch was
+    /// applied; the shortcut keeps its previous value.
+    Shortcut {
+        message: String,
+    },
+    Internal {
+        message: String,
+    },
+}
+
+impl std::fmt::Display for SettingsPatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotAnObject => write!(f, "settings patch must be a JSON object"),
+            Self::UnknownFields { fields } => {
+                write!(f, "unknown settings fields: {}", fields.join(", "))
+            }
+            Self::Invalid { message } | Self::Shortcut { message } | Self::Internal { message } => {
+                f.write_str(message)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsUpdate {
+    pub settings: AppSettings,
+    /// Top-level fields whose stored value changed, in JSON naming.
+    pub changed: Vec<String>,
+}
+
+/// RFC 7396 JSON merge patch: objects merge recursively, `null` removes.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let target = target.as_object_mut().expect("just made an object");
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Top-level fields that differ between two serialized `AppSettings`.
+fn changed_fields(before: &Value, after: &Value) -> Vec<String> {
+    SETTINGS_FIELDS
+        .iter()
+        .filter(|key| before.get(key.as_str()) != after.get(key.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Fields that differ between two settings, in JSON naming, as carried by
+/// `settings-changed`.
+pub fn changed_settings(before: &AppSettings, after: &AppSettings) -> Vec<String> {
+    match (serde_json::to_value(before), serde_json::to_value(after)) {
+        (Ok(before), Ok(after)) => changed_fields(&before, &after),
+        _ => Vec::new(),
+    }
+}
+
+/// Apply a partial update atomically under the store's lock. Fields absent
+/// from the patch keep their current value, so concurrent patches to
+/// different fields never overwrite each other.
+pub fn patch_settings(
+    store: &impl SettingsStore,
+    patch: &Value,
+) -> Result<(SettingsUpdate, SettingsChanges), SettingsPatchError> {
+    let fields = patch.as_object().ok_or(SettingsPatchError::NotAnObject)?;
+    let unknown: Vec<String> = fields
+        .keys()
+        .filter(|key| !SETTINGS_FIELDS.contains(*key))
+        .cloned()
+        .collect();
+    if !unknown.is_empty() {
+        return Err(SettingsPatchError::UnknownFields { fields: unknown });
+    }
+
+    let internal = |e: serde_json::Error| SettingsPatchError::Internal {
+        message: e.to_string(),
+    };
+    store
+        .with_settings(|current| {
+            let before = serde_json::to_value(&*current).map_err(internal)?;
+            let mut merged = before.clone();
+            merge_patch(&mut merged, patch);
+
+            let mut updated: AppSettings =
+                serde_json::from_value(merged).map_err(|e| SettingsPatchError::Invalid {
+                    message: e.to_string(),
+                })?;
+            updated.sanitize();
+            // Owned by the tray, see `replace_settings`
+            updated.click_through = current.click_through;
+            updated.monitoring_paused = current.monitoring_paused;
+            updated.last_run_version = current.last_run_version.clone();
+
+            let after = serde_json::to_value(&updated).map_err(internal)?;
+            let changed = changed_fields(&before, &after);
+            let changes = SettingsChanges::between(current, &updated);
+            *current = updated.clone();
+            Ok((
+                SettingsUpdate {
+                    settings: updated,
+                    changed,
+                },
+                changes,
+            ))
+        })
+        .map_err(|message| SettingsPatchError::Internal { message })?
+}
+
+/// Apply the side effects of a settings change that go beyond storing it:
+/// window placement, tray check marks, sampling and background services.
+/// Saving is enough; the UI doesn't follow up with the matching commands.
+fn apply_changes(app: &AppHandle, changes: &SettingsChanges) {
+    if let Some(position) = &changes.position {
+        tray::set_position_checked(app, position);
+    }
+    if changes.position.is_some() || changes.position_margin.is_some() {
+        // Re-dock from the stored values; a no-op for a custom position
+        let (position, margin) = {
+            let state = app.state::<AppState>();
+            let settings = state.settings.lock();
+            (settings.position.clone(), settings.position_margin)
+        };
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = super::window::move_to_anchor(&window, &position, margin);
+        }
+    }
+    if let Some(opacity) = changes.opacity {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = super::window::apply_opacity(&window, opacity);
+        }
+    }
+    if let Some(enabled) = changes.always_on_top {
+        tray::set_always_on_top_checked(app, enabled);
+    }
+    if let Some(enabled) = changes.always_on_back {
+        tray::set_always_on_back_checked(app, enabled);
+    }
+    if changes.always_on_top.is_some() || changes.always_on_back.is_some() {
+        // The two are exclusive; pinning to the back wins if both are set
+        let (top, back) = {
+            let state = app.state::<AppState>();
+            let settings = state.settings.lock();
+            (settings.always_on_top, settings.always_on_back)
+        };
+        if let Some(window) = app.get_webview_window("main") {
+            let result = if back {
+                super::window::pin_on_back(&window, true)
+            } else {
+                super::window::pin_on_back(&window, false)
+                    .and_then(|()| super::window::pin_on_top(&window, top))
+            };
+            if let Err(e) = result {
+                crate::log_warn!("Settings", "Failed to pin the window: {}", e);
+            }
+        }
+    }
+    // Only the check mark: the login entry is changed by `set_auto_start`
+    if let Some(enabled) = changes.auto_start {
+        tray::set_auto_start_checked(app, enabled);
+    }
+    if let Some(enabled) = changes.compact_mode {
+        tray::set_compact_checked(app, enabled);
+    }
+    // Before the opt-in, so enabling starts with the new DSN
+    if let Some(dsn) = &changes.sentry_dsn {
+        error_reporting::set_dsn_setting(dsn.as_deref());
+    }
+    if let Some(enabled) = changes.telemetry_enabled {
+        error_reporting::set_telemetry_enabled(enabled);
+    }
+    if let Some(enabled) = changes.pause_when_hidden {
+        monitor_pause::set_pause_when_hidden(app, enabled);
+    }
+    if changes.tray_show_temperature == Some(false) {
+        // Turning it on waits for the next sample
+        app.state::<AppState>().tray_readout.restore_logo();
+    }
+    if let Some(overrides) = &changes.sensor_overrides {
+        hardware::set_sensor_overrides(overrides);
+    }
+    if let Some(sections) = changes.monitored_sections {
+        hardware::set_monitored_sections(sections);
+    }
+    if let Some(enabled) = changes.mock_data {
+        let served = crate::mock::set_enabled(enabled);
+        // Starts the slow sections and static info over from the new source
+        if let Ok(sections) = app
+            .state::<AppState>()
+            .settings
+            .with_settings(|s| s.monitored_sections())
+        {
+            hardware::set_monitored_sections(sections);
+        }
+        if served {
+            // Not read while mock samples are served; started again on
+            // the first real sample
+            let task = metrics::task_started();
+            tauri::async_runtime::spawn_blocking(move || {
+                let _task = task;
+                hardware::shutdown_lhm_daemon();
+            });
+        }
+    }
+    if let Some(enabled) = changes.debug_server {
+        let state = app.state::<AppState>();
+        if let Err(e) = state.set_debug_server(enabled) {
+            crate::log_warn!("Settings", "Failed to toggle the debug server: {}", e);
+        }
+        tray::set_debug_server_checked(app, state.debug_server_running.load(Ordering::SeqCst));
+    }
+    if let Some(interval_ms) = changes.update_interval_slow {
+        // Picked up by the running daemon, no restart
+        hardware::set_slow_interval(crate::monitoring_mode::slow_interval(interval_ms));
+    }
+    if changes.websocket {
+        let state = app.state::<AppState>();
+        let result = state
+            .settings
+            .with_settings(|s| ws_server::configure(s))
+            .and_then(|configured| configured);
+        if let Err(e) = result {
+            crate::log_warn!("WsServer", "Not reconfigured: {}", e);
+        }
+    }
+    if changes.influxdb {
+        let state = app.state::<AppState>();
+        let result = influxdb::configure(&state.settings.lock());
+        if let Err(e) = result {
+            crate::log_warn!("InfluxDB", "Not reconfigured: {}", e);
+        }
+    }
+    if changes.update_interval_fast.is_some() {
+        // Restarting the daemon blocks until it delivers its first sample
+        let app = app.clone();
+        let task = metrics::task_started();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _task = task;
+            monitor_pause::reconcile_lhm_interval(&app)
+        });
+    }
+}
+
+/// Register the stored global shortcuts. A shortcut that can't be bound is
+/// reverted in the store to whatever is still registered for it, and the
+/// failure is returned for the UI to show.
+pub fn sync_hotkeys(app: &AppHandle, store: &impl SettingsStore) -> Result<(), String> {
+    let requested = store
+        .with_settings(|s| HotkeyAction::ALL.map(|action| (action, action.setting(s).clone())))?;
+    let mut errors = Vec::new();
+    for (action, accelerator) in requested {
+        if let Err(e) = hotkeys::bind(app, action, accelerator.as_deref()) {
+            crate::log_warn!("Hotkeys", "{}", e);
+            let bound = hotkeys::bound_accelerator(action);
+            store.with_settings(|s| *action.setting(s) = bound)?;
+            errors.push(e);
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+/// Delay before a debounced save hits the disk.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+static SAVE_SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+/// Write settings to disk shortly, coalescing bursts of updates (a dragged
+/// slider) into one write. Saves whatever is current when the timer fires.
+pub fn save_settings_debounced(app: &AppHandle) {
+    if SAVE_SCHEDULED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    let task = metrics::task_started();
+    tauri::async_runtime::spawn(async move {
+        let _task = task;
+        tokio::time::sleep(SAVE_DEBOUNCE).await;
+        SAVE_SCHEDULED.store(false, Ordering::SeqCst);
+        let state = app.state::<AppState>();
+        let Ok(settings) = state.settings.with_settings(|s| s.clone()) else {
+            return;
+        };
+        if let Err(e) = settings::save_settings_to_file(&settings).await {
+            error_reporting::capture_settings_error(&e, "save_settings_debounced");
+        }
+    });
+}
+
+/// Store the telemetry opt-in and return the settings to persist.
+pub fn set_telemetry_flag(
+    store: &impl SettingsStore,
+    enabled: bool,
+) -> Result<AppSettings, String> {
+    store.with_settings(|current| {
+        current.telemetry_enabled = enabled;
+        current.clone()
+    })
+}
+
+/// Store the click-through flag and return the settings to persist.
+pub fn set_click_through(store: &impl SettingsStore, enabled: bool) -> Result<AppSettings, String> {
+    store.with_settings(|current| {
+        current.click_through = enabled;
+        current.clone()
+    })
+}
+
+/// Store the monitoring pause flag and return the settings to persist.
+pub fn set_monitoring_paused(
+    store: &impl SettingsStore,
+    paused: bool,
+) -> Result<AppSettings, String> {
+    store.with_settings(|current| {
+        current.monitoring_paused = paused;
+        current.clone()
+    })
+}
+
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+    state
+        .settings
+        .with_settings(|s| s.clone())
+        .inspect_err(|e| error_reporting::capture_settings_error(e, "get_settings"))
+}
+
+#[tauri::command]
+pub async fn save_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    settings: AppSettings,
+) -> Result<(), String> {
+    // Update state first; the lock is released before the await below
+    let (mut settings, changes) = replace_settings(&state.settings, settings)
+        .inspect_err(|e| error_reporting::capture_settings_error(e, "save_settings_lock"))?;
+    apply_changes(&app, &changes);
+    // A rejected shortcut is reverted and the rest of the settings still saved
+    let hotkeys = if changes.hotkeys {
+        sync_hotkeys(&app, &state.settings)
+    } else {
+        Ok(())
+    };
+    if hotkeys.is_err() {
+        settings = state.settings.with_settings(|s| s.clone())?;
+    }
+    settings::save_settings_to_file(&settings)
+        .await
+        .inspect_err(|e| error_reporting::capture_settings_error(e, "save_settings_file"))?;
+    hotkeys
+}
+
+/// Apply a JSON merge patch to the stored settings, apply its side effects,
+/// schedule a save and emit `settings-changed` with the changed field names.
+/// Shared by `update_settings` and the tray toggles.
+pub fn apply_settings_patch(
+    app: &AppHandle,
+    patch: &Value,
+) -> Result<SettingsUpdate, SettingsPatchError> {
+    let state = app.state::<AppState>();
+    let (update, changes) = patch_settings(&state.settings, patch).inspect_err(|e| {
+        if let SettingsPatchError::Internal { message } = e {
+            error_reporting::capture_settings_error(message, "update_settings");
+        }
+    })?;
+    apply_changes(app, &changes);
+    let hotkeys = if changes.hotkeys {
+        sync_hotkeys(app, &state.settings)
+    } else {
+        Ok(())
+    };
+    if !update.changed.is_empty() {
+        save_settings_debounced(app);
+        let _ = app.emit("settings-changed", &update.changed);
+    }
+    hotkeys.map_err(|message| SettingsPatchError::Shortcut { message })?;
+    Ok(update)
+}
+
+/// Partially update settings with a JSON merge patch. Emits
+/// `settings-changed` with the changed field names. Fails with `Shortcut`
+/// if a hotkey in the patch can't be registered.
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    patch: Value,
+) -> Result<SettingsUpdate, SettingsPatchError> {
+    apply_settings_patch(&app, &patch)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoStartStatus {
+    /// The `auto_start` setting.
+    pub enabled: bool,
+    /// `task-scheduler`, `xdg-autostart`, `launch-agent` or `none`.
+    pub mechanism: String,
+    /// The login entry exists and starts this executable.
+    pub registered: bool,
+}
+
+/// Fails with `settings::AUTO_START_NEEDS_ADMIN` when Ondo isn't elevated
+/// and Windows refuses to create the task.
+#[tauri::command]
+pub async fn set_auto_start(enabled: bool) -> Result<(), String> {
+    settings::set_auto_start(enabled)
+        .await
+        .inspect_err(|e| error_reporting::capture_settings_error(e, "set_auto_start"))
+}
+
+/// Whether auto-start is on and how it is registered.
+#[tauri::command]
+pub async fn get_auto_start_status(state: State<'_, AppState>) -> Result<AutoStartStatus, String> {
+    let enabled = state.settings.with_settings(|s| s.auto_start)?;
+    // Queries schtasks on Windows
+    let registered = tauri::async_runtime::spawn_blocking(settings::auto_start_is_current)
+        .await
+        .map_err(|e| format!("Task failed: {:?}", e))??;
+    Ok(AutoStartStatus {
+        enabled,
+        mechanism: settings::AUTO_START_MECHANISM.to_string(),
+        registered,
+    })
+}
+
+#[tauri::command]
+pub async fn get_telemetry_status(
+    state: State<'_, AppState>,
+) -> Result<error_reporting::TelemetryStatus, String> {
+    let enabled = state.settings.with_settings(|s| s.telemetry_enabled)?;
+    Ok(error_reporting::telemetry_status(enabled))
+}
+
+/// Opt in or out of error reporting. Takes effect immediately: enabling
+/// initializes Sentry, disabling flushes pending events and closes it.
+#[tauri::command]
+pub async fn set_telemetry_enabled(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<error_reporting::TelemetryStatus, String> {
+    let settings = set_telemetry_flag(&state.settings, enabled)?;
+    error_reporting::set_telemetry_enabled(enabled);
+    settings::save_settings_to_file(&settings)
+        .await
+        .inspect_err(|e| error_reporting::capture_settings_error(e, "set_telemetry_enabled"))?;
+    Ok(error_reporting::telemetry_status(enabled))
+}
+
+/// Recent entries from the local error log, oldest first.
+#[tauri::command]
+pub async fn get_recent_errors(
+    count: usize,
+) -> Result<Vec<error_reporting::ErrorLogEntry>, String> {
+    Ok(error_reporting::recent_errors(count))
+}
+
+/// What `import_settings` changed.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImport {
+    /// Ondo version that exported the file.
+    pub exported_by: String,
+    /// Settings that changed, by their JSON names.
+    pub changed: Vec<String>,
+    /// Profiles added or replaced.
+    pub profiles: Vec<String>,
+}
+
+/// Imported settings minus what only fits the machine they came from:
+/// unless `include_window_state`, the window keeps its placement here, and
+/// profiles carry none.
+fn settings_to_import(
+    current: &AppSettings,
+    mut export: SettingsExport,
+    include_window_state: bool,
+) -> SettingsExport {
+    if !include_window_state {
+        export.settings.window_state = current.window_state.clone();
+        // Dragged coordinates don't carry over either
+        if export.settings.position == super::window::CUSTOM_POSITION {
+            export.settings.position = current.position.clone();
+        }
+        for profile in export.profiles.values_mut() {
+            profile.window_state = None;
+        }
+    }
+    export
+}
+
+/// Write the settings and saved profiles to `path` as one JSON document,
+/// for `import_settings` on another machine or after a reinstall.
+#[tauri::command]
+pub async fn export_settings(state: State<'_, AppState>, path: String) -> Result<(), String> {
+    let current = state.settings.with_settings(|s| s.clone())?;
+    let profiles = settings::load_profiles_from_file()?;
+    let content = serde_json::to_string_pretty(&SettingsExport::new(current, profiles))
+        .map_err(|e| e.to_string())?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+        .inspect_err(|e| error_reporting::capture_settings_error(e, "export_settings"))?;
+    crate::log_info!("Settings", "Exported settings to {}", path);
+    Ok(())
+}
+
+/// Replace the settings with those exported to `path` and add its
+/// profiles. The window position and size are only taken along when
+/// `include_window_state` is set. The file is checked in full first, so an
+/// unreadable file, a newer format or too many profiles change nothing.
+#[tauri::command]
+pub async fn import_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    include_window_state: Option<bool>,
+) -> Result<SettingsImport, String> {
+    let include_window_state = include_window_state.unwrap_or(false);
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let before = state.settings.with_settings(|s| s.clone())?;
+    let export = settings_to_import(
+        &before,
+        SettingsExport::parse(&content)?,
+        include_window_state,
+    );
+    // Fails here on too many profiles, before anything is changed
+    super::profiles::merge_profiles(
+        &mut settings::load_profiles_from_file()?,
+        export.profiles.clone(),
+    )?;
+
+    let profiles = super::profiles::import_profiles(&app, export.profiles)?;
+    let saved = save_settings(app.clone(), state.clone(), export.settings.clone()).await;
+    if include_window_state {
+        if let (Some(window), Some(window_state)) = (
+            super::window::main_window(&app),
+            export.settings.window_state.as_ref(),
+        ) {
+            super::window::apply_window_state(&window, &window_state.into())?;
+        }
+    }
+
+    let after = state.settings.with_settings(|s| s.clone())?;
+    let changed = changed_settings(&before, &after);
+    if !changed.is_empty() {
+        let _ = app.emit("settings-changed", &changed);
+    }
+    crate::log_info!(
+        "Settings",
+        "Imported settings from {} ({} changed, {} profiles)",
+        path,
+        changed.len(),
+        profiles.len()
+    );
+    saved.map(|_| SettingsImport {
+        exported_by: export.app_version,
+        changed,
+        profiles,
+    })
+}
+
+#[tauri::command]
+pub async fn toggle_debug_server(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state.set_debug_server(enabled)
+}
+
+/// Try InfluxDB settings from the settings panel before they are saved.
+#[tauri::command]
+pub async fn test_influxdb_connection(mut settings: InfluxDbSettings) -> Result<(), String> {
+    settings.sanitize();
+    influxdb::test_connection(&settings).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn store() -> SharedSettings {
+        SharedSettings::default()
+    }
+
+    #[test]
+    fn window_placement_is_only_imported_when_asked() {
+        let here = AppSettings {
+            position: "left".to_string(),
+            window_state: Some(settings::WindowState {
+                x: 10,
+                ..Default::default()
+            }),
+            ..AppSettings::default()
+        };
+        let elsewhere = AppSettings {
+            position: super::super::window::CUSTOM_POSITION.to_string(),
+            window_state: Some(settings::WindowState {
+                x: 3000,
+                monitor_name: Some("DELL U2720Q".to_string()),
+                ..Default::default()
+            }),
+            ..AppSettings::default()
+        };
+        let mut profiles = settings::Profiles::new();
+        profiles.insert("Desk".to_string(), elsewhere.clone());
+        let export = SettingsExport::new(elsewhere, profiles);
+
+        let imported = settings_to_import(&here, export.clone(), false);
+        assert_eq!(imported.settings.position, "left");
+        assert_eq!(imported.settings.window_state.unwrap().x, 10);
+        assert!(imported.profiles["Desk"].window_state.is_none());
+
+        let imported = settings_to_import(&here, export, true);
+        assert_eq!(imported.settings.position, "custom");
+        assert_eq!(imported.settings.window_state.unwrap().x, 3000);
+        assert!(imported.profiles["Desk"].window_state.is_some());
+    }
+
+    #[test]
+    fn unchanged_save_has_no_side_effects() {
+        let store = store();
+        let (_, changes) = replace_settings(&store, AppSettings::default()).unwrap();
+        assert_eq!(changes, SettingsChanges::default());
+    }
+
+    #[test]
+    fn save_reports_interval_and_telemetry_changes() {
+        let store = store();
+        let incoming = AppSettings {
+            update_interval_fast: 2000,
+            telemetry_enabled: true,
+            ..AppSettings::default()
+        };
+
+        let (saved, changes) = replace_settings(&store, incoming).unwrap();
+        assert_eq!(saved.update_interval_fast, 2000);
+        assert_eq!(
+            changes,
+            SettingsChanges {
+                position: None,
+                position_margin: None,
+                opacity: None,
+                always_on_top: None,
+                always_on_back: None,
+                auto_start: None,
+                compact_mode: None,
+                telemetry_enabled: Some(true),
+                sentry_dsn: None,
+                update_interval_fast: Some(2000),
+                update_interval_slow: None,
+                pause_when_hidden: None,
+                tray_show_temperature: None,
+                sensor_overrides: None,
+                monitored_sections: None,
+                mock_data: None,
+                debug_server: None,
+                hotkeys: false,
+                websocket: false,
+                influxdb: false,
+            }
+        );
+        assert_eq!(store.lock().update_interval_fast, 2000);
+    }
+
+    #[test]
+    fn save_sanitizes_before_storing() {
+        let store = store();
+        let incoming = AppSettings {
+            update_interval_fast: 1,
+            ..AppSettings::default()
+        };
+
+        let (saved, changes) = replace_settings(&store, incoming).unwrap();
+        assert_eq!(saved.update_interval_fast, 250);
+        assert_eq!(changes.update_interval_fast, Some(250));
+        assert_eq!(store.lock().update_interval_fast, 250);
+    }
+
+    #[test]
+    fn save_keeps_click_through_from_the_tray() {
+        let store = store();
+        set_click_through(&store, true).unwrap();
+
+        // Frontend copy predates the tray toggle
+        let (saved, _) = replace_settings(&store, AppSettings::default()).unwrap();
+        assert!(saved.click_through);
+        assert!(store.lock().click_through);
+    }
+
+    #[test]
+    fn save_and_patch_keep_monitoring_paused_from_the_tray() {
+        let store = store();
+        set_monitoring_paused(&store, true).unwrap();
+
+        let (saved, _) = replace_settings(&store, AppSettings::default()).unwrap();
+        assert!(saved.monitoring_paused);
+        let (update, _) = patch_settings(&store, &json!({ "monitoringPaused": false })).unwrap();
+        assert!(update.settings.monitoring_paused);
+        assert!(update.changed.is_empty());
+    }
+
+    #[test]
+    fn save_keeps_last_run_version() {
+        let store = store();
+        store.lock().last_run_version = Some("0.1.25".to_string());
+
+        let (saved, _) = replace_settings(&store, AppSettings::default()).unwrap();
+        assert_eq!(saved.last_run_version.as_deref(), Some("0.1.25"));
+    }
+
+    #[test]
+    fn patch_changes_only_named_fields() {
+        let store = store();
+        let (update, changes) =
+            patch_settings(&store, &json!({ "opacity": 80, "theme": "auto" })).unwrap();
+        // theme already was "auto"
+        assert_eq!(update.changed, vec!["opacity"]);
+        assert_eq!(update.settings.opacity, 80);
+        assert_eq!(changes, SettingsChanges::default());
+        assert_eq!(store.lock().position, "right");
+    }
+
+    #[test]
+    fn patch_reports_sanitized_values_and_side_effects() {
+        let store = store();
+        let (update, changes) =
+            patch_settings(&store, &json!({ "updateIntervalFast": 5 })).unwrap();
+        assert_eq!(update.settings.update_interval_fast, 250);
+        assert_eq!(changes.update_interval_fast, Some(250));
+    }
+
+    #[test]
+    fn patch_merges_nested_objects_and_null_resets() {
+        let store = store();
+        patch_settings(
+            &store,
+            &json!({ "windowState": { "x": 1, "y": 2, "width": 300, "height": 600 } }),
+        )
+        .unwrap();
+        let (update, _) = patch_settings(&store, &json!({ "windowState": { "x": 50 } })).unwrap();
+        let window = update.settings.window_state.unwrap();
+        assert_eq!((window.x, window.y, window.width), (50, 2, 300));
+
+        let (update, _) = patch_settings(&store, &json!({ "windowState": null })).unwrap();
+        assert!(update.settings.window_state.is_none());
+        assert_eq!(update.changed, vec!["windowState"]);
+    }
+
+    #[test]
+    fn patch_rejects_unknown_fields_by_name() {
+        let store = store();
+        let err =
+            patch_settings(&store, &json!({ "opacity": 50, "alertThreshold": 90 })).unwrap_err();
+        assert_eq!(
+            err,
+            SettingsPatchError::UnknownFields {
+                fields: vec!["alertThreshold".to_string()],
+            }
+        );
+        // Nothing was applied
+        assert_eq!(store.lock().opacity, 95);
+    }
+
+    #[test]
+    fn patch_rejects_wrong_types_and_non_objects() {
+        let store = store();
+        assert!(matches!(
+            patch_settings(&store, &json!({ "opacity": "high" })),
+            Err(SettingsPatchError::Invalid { .. })
+        ));
+        assert_eq!(
+            patch_settings(&store, &json!([1, 2])).unwrap_err(),
+            SettingsPatchError::NotAnObject
+        );
+    }
+
+    #[test]
+    fn hotkey_edits_request_re_registration() {
+        let store = store();
+        let (_, changes) =
+            patch_settings(&store, &json!({ "toggleHotkey": "Ctrl+Shift+O" })).unwrap();
+        assert!(changes.hotkeys);
+        let (_, changes) =
+            patch_settings(&store, &json!({ "toggleHotkey": " Ctrl+Shift+O " })).unwrap();
+        assert!(!changes.hotkeys, "trimmed value is unchanged");
+    }
+
+    #[test]
+    fn patch_cannot_change_click_through() {
+        let store = store();
+        let (update, _) = patch_settings(&store, &json!({ "clickThrough": true })).unwrap();
+        assert!(!update.settings.click_through);
+        assert!(update.changed.is_empty());
+    }
+
+    #[test]
+    fn concurrent_patches_to_different_fields_are_not_lost() {
+        let store = Arc::new(store());
+        let writers: Vec<_> = [
+            ("opacity", 0u32),
+            ("updateIntervalFast", 1000),
+            ("powerBaseWatts", 0),
+        ]
+        .into_iter()
+        .map(|(field, base)| {
+            let store = Arc::clone(&store);
+            std::thread::spawn(move || {
+                for i in 1..=200u32 {
+                    patch_settings(&*store, &json!({ field: base + i })).unwrap();
+                }
+            })
+        })
+        .collect();
+        // A stale full-settings writer would clobber the others; toggles
+        // through patches must not
+        let toggler = {
+            let store = Arc::clone(&store);
+            std::thread::spawn(move || {
+                for i in 0..200 {
+                    patch_settings(&*store, &json!({ "compactMode": i % 2 == 0 })).unwrap();
+                }
+            })
+        };
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        toggler.join().unwrap();
+
+        let settings = store.lock();
+        assert_eq!(settings.opacity, 200);
+        assert_eq!(settings.update_interval_fast, 1200);
+        assert_eq!(settings.power_base_watts, 200.0);
+        assert!(!settings.compact_mode);
+    }
+
+    #[test]
+    fn telemetry_flag_is_stored_and_returned() {
+        let store = store();
+        let saved = set_telemetry_flag(&store, true).unwrap();
+        assert!(saved.telemetry_enabled);
+        assert!(store.lock().telemetry_enabled);
+    }
+
+    #[test]
+    fn settings_stay_usable_after_a_panic_while_locked() {
+        let store = store();
+        set_telemetry_flag(&store, true).unwrap();
+        let panicked = std::thread::scope(|s| {
+            s.spawn(|| {
+                let _settings = store.lock();
+                panic!("boom");
+            })
+            .join()
+        });
+        assert!(panicked.is_err());
+
+        assert!(store.with_settings(|s| s.telemetry_enabled).unwrap());
+        patch_settings(&store, &json!({ "opacity": 80 })).unwrap();
+        let (saved, _) = replace_settings(&store, store.lock().clone()).unwrap();
+        assert_eq!(saved.opacity, 80);
+        assert!(saved.telemetry_enabled);
+    }
+}
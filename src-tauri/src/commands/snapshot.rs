@@ -0,0 +1,258 @@
+//! Copying the current readings to the clipboard, for pasting into a chat:
+//! a one-line summary, a markdown table or the raw sample as JSON.
+
+use super::hardware::{collect_sample, LiveHardware};
+use crate::{clipboard, monitor_pause, AppState, HardwareData};
+use std::time::Duration;
+use tauri::State;
+
+/// How long to wait for a sample when there is none to copy yet.
+const FRESH_SAMPLE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SnapshotFormat {
+    /// "CPU 62°C 31% 4.6GHz | GPU 55°C 18% 6.2/12GB | NVMe 41°C"
+    Summary,
+    /// A table in a code fence, so chat apps keep the columns.
+    Markdown,
+    Json,
+}
+
+impl SnapshotFormat {
+    fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "summary" => Ok(Self::Summary),
+            "markdown" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "Unknown snapshot format \"{}\"; expected summary, markdown or json",
+                other
+            )),
+        }
+    }
+}
+
+/// `None` for a missing reading (0 or less), so it's left out rather than
+/// shown as 0°.
+fn temperature(celsius: f32, fahrenheit: bool) -> Option<String> {
+    if !(celsius.is_finite() && celsius > 0.0) {
+        return None;
+    }
+    Some(if fahrenheit {
+        format!("{:.0}°F", celsius * 9.0 / 5.0 + 32.0)
+    } else {
+        format!("{:.0}°C", celsius)
+    })
+}
+
+/// "12" rather than "12.0", "6.2" otherwise.
+fn gigabytes(gb: f32) -> String {
+    let text = format!("{:.1}", gb);
+    text.strip_suffix(".0").map(str::to_string).unwrap_or(text)
+}
+
+/// One row per device with a reading: name, temperature, load, and clock,
+/// memory or fill level.
+struct Row {
+    label: &'static str,
+    name: String,
+    temperature: Option<String>,
+    load: Option<String>,
+    detail: Option<String>,
+}
+
+fn rows(data: &HardwareData, fahrenheit: bool) -> Vec<Row> {
+    let mut rows = Vec::new();
+    if let Some(cpu) = &data.cpu {
+        rows.push(Row {
+            label: "CPU",
+            name: cpu.name.clone(),
+            temperature: temperature(cpu.temperature, fahrenheit),
+            load: Some(format!("{:.0}%", cpu.load)),
+            detail: (cpu.frequency > 0.0).then(|| format!("{:.1}GHz", cpu.frequency)),
+        });
+    }
+    if let Some(gpu) = &data.gpu {
+        rows.push(Row {
+            label: "GPU",
+            name: gpu.name.clone(),
+            temperature: temperature(gpu.temperature, fahrenheit),
+            load: Some(format!("{:.0}%", gpu.load)),
+            detail: (gpu.memory_used > 0.0 && gpu.memory_total > 0.0).then(|| {
+                format!(
+                    "{}/{}GB",
+                    gigabytes(gpu.memory_used),
+                    gigabytes(gpu.memory_total)
+                )
+            }),
+        });
+    }
+    for drive in data.storage.iter().flatten() {
+        let Some(temperature) = temperature(drive.temperature, fahrenheit) else {
+            continue;
+        };
+        rows.push(Row {
+            label: "",
+            name: drive.name.clone(),
+            temperature: Some(temperature),
+            load: None,
+            detail: (drive.total_space > 0.0).then(|| format!("{:.0}% full", drive.used_percent)),
+        });
+    }
+    rows
+}
+
+fn summary(rows: &[Row]) -> String {
+    rows.iter()
+        .map(|row| {
+            // Drives go by name, the rest by kind
+            let label = if row.label.is_empty() {
+                row.name.as_str()
+            } else {
+                row.label
+            };
+            let readings = [&row.temperature, &row.load];
+            let mut parts = vec![label];
+            parts.extend(readings.into_iter().flatten().map(String::as_str));
+            if !row.label.is_empty() {
+                parts.extend(row.detail.as_deref());
+            }
+            parts.join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn markdown(rows: &[Row]) -> String {
+    let cell = |value: &Option<String>| value.clone().unwrap_or_else(|| "–".to_string());
+    let mut lines = vec![
+        "```".to_string(),
+        "| Device | Temperature | Load | Details |".to_string(),
+        "|---|---|---|---|".to_string(),
+    ];
+    lines.extend(rows.iter().map(|row| {
+        format!(
+            "| {} | {} | {} | {} |",
+            row.name.replace('|', "/"),
+            cell(&row.temperature),
+            cell(&row.load),
+            cell(&row.detail)
+        )
+    }));
+    lines.push("```".to_string());
+    lines.join("\n")
+}
+
+fn render(format: SnapshotFormat, data: &HardwareData, fahrenheit: bool) -> Result<String, String> {
+    if format == SnapshotFormat::Json {
+        return serde_json::to_string_pretty(data).map_err(|e| e.to_string());
+    }
+    let rows = rows(data, fahrenheit);
+    if rows.is_empty() {
+        return Err("No readings to copy".to_string());
+    }
+    Ok(match format {
+        SnapshotFormat::Summary => summary(&rows),
+        _ => markdown(&rows),
+    })
+}
+
+/// Copy the latest sample as `format` (`summary`, `markdown` or `json`),
+/// taking a sample first if there is none. Returns the copied text.
+#[tauri::command]
+pub async fn copy_snapshot_to_clipboard(
+    state: State<'_, AppState>,
+    format: String,
+) -> Result<String, String> {
+    let format = SnapshotFormat::parse(&format)?;
+    let data = match monitor_pause::last_sample() {
+        Some(data) => data,
+        None => tokio::time::timeout(
+            FRESH_SAMPLE_TIMEOUT,
+            collect_sample(&LiveHardware, |data| state.process_sample(data)),
+        )
+        .await
+        .map_err(|_| "No sample yet; try again in a moment".to_string())??,
+    };
+    let fahrenheit = state.settings.lock().temperature_unit == "fahrenheit";
+
+    let text = render(format, &data, fahrenheit)?;
+    let copied = text.clone();
+    tauri::async_runtime::spawn_blocking(move || clipboard::write_text(&copied))
+        .await
+        .map_err(|e| format!("Task failed: {:?}", e))??;
+    crate::log_info!("Snapshot", "Copied readings to the clipboard");
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockHardware;
+
+    fn sample() -> HardwareData {
+        let mut data = MockHardware::new(0, 1000).sample_at(0);
+        let cpu = data.cpu.as_mut().unwrap();
+        cpu.temperature = 62.0;
+        cpu.load = 31.0;
+        cpu.frequency = 4.6;
+        let gpu = data.gpu.as_mut().unwrap();
+        gpu.temperature = 55.0;
+        gpu.load = 18.0;
+        gpu.memory_used = 6.2;
+        gpu.memory_total = 12.0;
+        let storage = data.storage.as_mut().unwrap();
+        storage[0].name = "NVMe".to_string();
+        storage[0].temperature = 41.0;
+        // No temperature sensor: left out
+        storage[1].temperature = 0.0;
+        data
+    }
+
+    #[test]
+    fn summary_is_one_line() {
+        assert_eq!(
+            render(SnapshotFormat::Summary, &sample(), false).unwrap(),
+            "CPU 62°C 31% 4.6GHz | GPU 55°C 18% 6.2/12GB | NVMe 41°C"
+        );
+        assert_eq!(
+            render(SnapshotFormat::Summary, &sample(), true).unwrap(),
+            "CPU 144°F 31% 4.6GHz | GPU 131°F 18% 6.2/12GB | NVMe 106°F"
+        );
+    }
+
+    #[test]
+    fn missing_readings_are_left_out() {
+        let mut data = sample();
+        data.gpu = None;
+        data.cpu.as_mut().unwrap().temperature = 0.0;
+        assert_eq!(
+            render(SnapshotFormat::Summary, &data, false).unwrap(),
+            "CPU 31% 4.6GHz | NVMe 41°C"
+        );
+
+        data.cpu = None;
+        data.storage = None;
+        assert!(render(SnapshotFormat::Summary, &data, false).is_err());
+    }
+
+    #[test]
+    fn markdown_is_a_fenced_table() {
+        let text = render(SnapshotFormat::Markdown, &sample(), false).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.first(), Some(&"```"));
+        assert_eq!(lines.last(), Some(&"```"));
+        assert_eq!(lines[1], "| Device | Temperature | Load | Details |");
+        assert_eq!(lines[3], "| Mock CPU 8-Core | 62°C | 31% | 4.6GHz |");
+        assert_eq!(lines[5], "| NVMe | 41°C | – | 50% full |");
+        assert_eq!(lines.len(), 7);
+    }
+
+    #[test]
+    fn formats_are_parsed() {
+        assert_eq!(SnapshotFormat::parse("json"), Ok(SnapshotFormat::Json));
+        assert!(SnapshotFormat::parse("csv").unwrap_err().contains("csv"));
+        let json = render(SnapshotFormat::Json, &sample(), false).unwrap();
+        assert!(json.contains("\"dataSource\": \"mock\""));
+    }
+}
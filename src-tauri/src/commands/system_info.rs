@@ -0,0 +1,92 @@
+//! Facts about this installation gathered in one place, for the "Copy
+//! Diagnostics" button and bug reports.
+
+use crate::app_metrics::{self, SelfUsage};
+use crate::commands::pawnio;
+use crate::error_reporting::{self, ErrorLogEntry};
+use crate::{elevation, hardware, monitor_pause, settings};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Locally logged errors included in `SystemInfo`.
+const RECENT_ERROR_COUNT: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfo {
+    /// e.g. "Windows 11 Pro 24H2 (26100)".
+    pub os: Option<String>,
+    /// Kernel version, the build number on Windows.
+    pub os_build: Option<String>,
+    pub app_version: String,
+    /// Always true outside Windows, where no driver is needed.
+    pub pawnio_installed: bool,
+    pub lhm_daemon_running: bool,
+    /// Seconds since the running LHM daemon was (re)started.
+    pub lhm_daemon_uptime_secs: Option<u64>,
+    /// `dataSource` of the last sample (`lhm`, `sysinfo` or `mock`), if one
+    /// was taken yet.
+    pub data_source: Option<String>,
+    /// Always true outside Windows, where sensors don't need it.
+    pub elevated: bool,
+    pub settings_path: String,
+    /// Most recent locally logged errors, oldest first.
+    pub recent_errors: Vec<String>,
+}
+
+fn error_line(entry: &ErrorLogEntry) -> String {
+    format!("[{}] {}", entry.source, entry.message)
+}
+
+#[tauri::command]
+pub async fn get_system_info() -> Result<SystemInfo, String> {
+    // Querying the PawnIO service and reading the error log both block
+    tauri::async_runtime::spawn_blocking(|| {
+        let lhm = hardware::lhm_daemon_status();
+        SystemInfo {
+            os: error_reporting::os_version(),
+            os_build: sysinfo::System::kernel_version(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            pawnio_installed: pawnio::get_pawnio_detailed_status().installed,
+            lhm_daemon_running: lhm.running,
+            lhm_daemon_uptime_secs: hardware::lhm_daemon_uptime().map(|d| d.as_secs()),
+            data_source: monitor_pause::last_data_source(),
+            elevated: elevation::is_elevated(),
+            settings_path: settings::get_settings_path().display().to_string(),
+            recent_errors: error_reporting::recent_errors(RECENT_ERROR_COUNT)
+                .iter()
+                .map(error_line)
+                .collect(),
+        }
+    })
+    .await
+    .map_err(|e| format!("Task failed: {:?}", e))
+}
+
+/// CPU and memory used by Ondo itself, its webview and the LHM daemon.
+/// The first call takes a second or so to measure CPU usage.
+#[tauri::command]
+pub async fn get_self_usage() -> Result<SelfUsage, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let extra_pids: Vec<u32> = hardware::lhm_daemon_status().pid.into_iter().collect();
+        app_metrics::self_usage(&extra_pids)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {:?}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_are_listed_with_their_source() {
+        let entry = ErrorLogEntry {
+            timestamp: 1,
+            source: "hardware".to_string(),
+            message: "LHM daemon exited".to_string(),
+            suppressed: Some(4),
+        };
+        assert_eq!(error_line(&entry), "[hardware] LHM daemon exited");
+    }
+}
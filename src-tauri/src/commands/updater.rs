@@ -0,0 +1,192 @@
+//! Update check and install on top of the updater plugin. The endpoint
+//! follows the `updateChannel` setting, and a version the user skipped is
+//! left out of the startup check.
+
+use crate::{error_reporting, metrics, AppState};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/yossyl3oy/Ondo/releases/latest/download/latest.json";
+/// Rolling `beta` release, re-pointed at each pre-release.
+const BETA_ENDPOINT: &str = "https://github.com/yossyl3oy/Ondo/releases/download/beta/latest.json";
+
+/// Long enough after launch not to compete with startup sampling.
+const STARTUP_CHECK_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheck {
+    pub current_version: String,
+    /// `None` when this is the latest version on the channel.
+    pub available_version: Option<String>,
+    pub release_notes: Option<String>,
+    /// Download size in bytes, when the server reports it.
+    pub download_size: Option<u64>,
+    /// The available version is the one in `skipVersion`.
+    pub skipped: bool,
+}
+
+/// Payload of the `update-progress` event.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+fn endpoint_for_channel(channel: &str) -> &'static str {
+    match channel {
+        "beta" => BETA_ENDPOINT,
+        _ => STABLE_ENDPOINT,
+    }
+}
+
+fn is_skipped(available: Option<&str>, skip_version: Option<&str>) -> bool {
+    available.is_some() && available == skip_version
+}
+
+/// Report an updater error under `operation` and pass it on.
+fn report(operation: &'static str) -> impl Fn(String) -> String {
+    move |err| {
+        error_reporting::capture_updater_error(&err, operation);
+        err
+    }
+}
+
+fn settings_snapshot(app: &AppHandle) -> Result<(String, Option<String>), String> {
+    let state = app.state::<AppState>();
+    let settings = state.settings.lock();
+    Ok((
+        settings.update_channel.clone(),
+        settings.skip_version.clone(),
+    ))
+}
+
+async fn fetch_update(app: &AppHandle, channel: &str) -> Result<Option<Update>, String> {
+    let endpoint = Url::parse(endpoint_for_channel(channel)).map_err(|e| e.to_string())?;
+    app.updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Content-Length of the update package, from a HEAD request. The updater
+/// only learns it once the download starts.
+async fn download_size(update: &Update) -> Option<u64> {
+    let response = reqwest::Client::new()
+        .head(update.download_url.clone())
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+        .filter(|size| *size > 0)
+}
+
+async fn check(app: &AppHandle) -> Result<UpdateCheck, String> {
+    let (channel, skip_version) = settings_snapshot(app)?;
+    let Some(update) = fetch_update(app, &channel).await? else {
+        return Ok(UpdateCheck {
+            current_version: app.package_info().version.to_string(),
+            available_version: None,
+            release_notes: None,
+            download_size: None,
+            skipped: false,
+        });
+    };
+    Ok(UpdateCheck {
+        skipped: is_skipped(Some(&update.version), skip_version.as_deref()),
+        download_size: download_size(&update).await,
+        current_version: update.current_version,
+        available_version: Some(update.version),
+        release_notes: update.body,
+    })
+}
+
+#[tauri::command]
+pub async fn check_for_update(app: AppHandle) -> Result<UpdateCheck, String> {
+    check(&app).await.map_err(report("check"))
+}
+
+/// Download and install the latest version on the channel, emitting
+/// `update-progress` as it downloads, then restart into it.
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), String> {
+    let (channel, _) = settings_snapshot(&app)?;
+    let update = fetch_update(&app, &channel)
+        .await
+        .map_err(report("install_check"))?
+        .ok_or_else(|| "No update available".to_string())?;
+
+    crate::log_info!("Updater", "Installing {}", update.version);
+    let mut downloaded = 0u64;
+    update
+        .download_and_install(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = app.emit("update-progress", UpdateProgress { downloaded, total });
+            },
+            || crate::log_info!("Updater", "Download complete"),
+        )
+        .await
+        .map_err(|e| e.to_string())
+        .map_err(report("install"))?;
+
+    // Same as the process plugin's `restart`
+    app.restart()
+}
+
+/// Check once, `STARTUP_CHECK_DELAY` after launch, and emit
+/// `update-available` unless the version was skipped.
+pub fn spawn_startup_check(app: AppHandle) {
+    let task = metrics::task_started();
+    tauri::async_runtime::spawn(async move {
+        let _task = task;
+        tokio::time::sleep(STARTUP_CHECK_DELAY).await;
+        match check(&app).await {
+            Ok(result) if result.available_version.is_some() && !result.skipped => {
+                if let Err(e) = app.emit("update-available", &result) {
+                    crate::log_warn!("Updater", "Failed to emit update-available: {}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => error_reporting::capture_updater_error(&e, "startup_check"),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_selects_the_endpoint() {
+        assert_eq!(endpoint_for_channel("stable"), STABLE_ENDPOINT);
+        assert_eq!(endpoint_for_channel("beta"), BETA_ENDPOINT);
+        // Anything unknown stays on stable
+        assert_eq!(endpoint_for_channel("nightly"), STABLE_ENDPOINT);
+    }
+
+    #[test]
+    fn only_the_skipped_version_is_skipped() {
+        assert!(is_skipped(Some("0.1.26"), Some("0.1.26")));
+        assert!(!is_skipped(Some("0.1.27"), Some("0.1.26")));
+        assert!(!is_skipped(Some("0.1.26"), None));
+        assert!(!is_skipped(None, None));
+    }
+}
@@ -0,0 +1,1280 @@
+//! Window placement and z-order commands for the main widget window.
+
+use crate::{error_reporting, tray, AppState};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowStateData {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    /// Monitor the window was on, to tell whether `x`/`y` still exist.
+    #[serde(default)]
+    monitor_name: Option<String>,
+}
+
+impl From<&crate::settings::WindowState> for WindowStateData {
+    fn from(state: &crate::settings::WindowState) -> Self {
+        Self {
+            x: state.x,
+            y: state.y,
+            width: state.width,
+            height: state.height,
+            monitor_name: state.monitor_name.clone(),
+        }
+    }
+}
+
+impl From<WindowStateData> for crate::settings::WindowState {
+    fn from(state: WindowStateData) -> Self {
+        Self {
+            x: state.x,
+            y: state.y,
+            width: state.width,
+            height: state.height,
+            monitor_name: state.monitor_name,
+        }
+    }
+}
+
+/// Origin and size of a monitor.
+pub type MonitorGeometry = (PhysicalPosition<i32>, PhysicalSize<u32>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedMonitor {
+    pub name: Option<String>,
+    pub geometry: MonitorGeometry,
+}
+
+/// The window operations the commands need. Implemented by the Tauri window
+/// and by a recording fake in tests.
+pub trait WindowOps {
+    fn set_always_on_top(&self, enabled: bool) -> Result<(), String>;
+    fn set_always_on_bottom(&self, enabled: bool) -> Result<(), String>;
+    fn set_ignore_cursor_events(&self, enabled: bool) -> Result<(), String>;
+    /// Position and size of the monitor the window is on, if any.
+    fn current_monitor(&self) -> Result<Option<MonitorGeometry>, String>;
+    /// The current monitor without the taskbar and docked toolbars.
+    fn current_work_area(&self) -> Result<Option<MonitorGeometry>, String>;
+    fn current_monitor_name(&self) -> Result<Option<String>, String>;
+    fn available_monitors(&self) -> Result<Vec<NamedMonitor>, String>;
+    fn primary_monitor(&self) -> Result<Option<NamedMonitor>, String>;
+    fn outer_position(&self) -> Result<PhysicalPosition<i32>, String>;
+    fn outer_size(&self) -> Result<PhysicalSize<u32>, String>;
+    fn inner_size(&self) -> Result<PhysicalSize<u32>, String>;
+    fn set_position(&self, position: PhysicalPosition<i32>) -> Result<(), String>;
+    fn set_size(&self, size: PhysicalSize<u32>) -> Result<(), String>;
+    fn set_min_size(&self, size: Option<PhysicalSize<u32>>) -> Result<(), String>;
+    fn set_shadow(&self, enable: bool) -> Result<(), String>;
+    /// Window-level alpha, 0 (invisible) to 255 (opaque).
+    fn set_alpha(&self, alpha: u8) -> Result<(), String>;
+}
+
+impl WindowOps for tauri::WebviewWindow {
+    fn set_always_on_top(&self, enabled: bool) -> Result<(), String> {
+        tauri::WebviewWindow::set_always_on_top(self, enabled).map_err(|e| e.to_string())
+    }
+
+    fn set_always_on_bottom(&self, enabled: bool) -> Result<(), String> {
+        tauri::WebviewWindow::set_always_on_bottom(self, enabled).map_err(|e| e.to_string())
+    }
+
+    fn set_ignore_cursor_events(&self, enabled: bool) -> Result<(), String> {
+        tauri::WebviewWindow::set_ignore_cursor_events(self, enabled).map_err(|e| e.to_string())
+    }
+
+    fn current_monitor(&self) -> Result<Option<MonitorGeometry>, String> {
+        tauri::WebviewWindow::current_monitor(self)
+            .map(|monitor| monitor.map(|m| (*m.position(), *m.size())))
+            .map_err(|e| e.to_string())
+    }
+
+    fn current_work_area(&self) -> Result<Option<MonitorGeometry>, String> {
+        tauri::WebviewWindow::current_monitor(self)
+            .map(|monitor| monitor.map(|m| (m.work_area().position, m.work_area().size)))
+            .map_err(|e| e.to_string())
+    }
+
+    fn current_monitor_name(&self) -> Result<Option<String>, String> {
+        tauri::WebviewWindow::current_monitor(self)
+            .map(|monitor| monitor.and_then(|m| m.name().cloned()))
+            .map_err(|e| e.to_string())
+    }
+
+    fn available_monitors(&self) -> Result<Vec<NamedMonitor>, String> {
+        tauri::WebviewWindow::available_monitors(self)
+            .map(|monitors| monitors.iter().map(named_monitor).collect())
+            .map_err(|e| e.to_string())
+    }
+
+    fn primary_monitor(&self) -> Result<Option<NamedMonitor>, String> {
+        tauri::WebviewWindow::primary_monitor(self)
+            .map(|monitor| monitor.as_ref().map(named_monitor))
+            .map_err(|e| e.to_string())
+    }
+
+    fn outer_position(&self) -> Result<PhysicalPosition<i32>, String> {
+        tauri::WebviewWindow::outer_position(self).map_err(|e| e.to_string())
+    }
+
+    fn outer_size(&self) -> Result<PhysicalSize<u32>, String> {
+        tauri::WebviewWindow::outer_size(self).map_err(|e| e.to_string())
+    }
+
+    fn inner_size(&self) -> Result<PhysicalSize<u32>, String> {
+        tauri::WebviewWindow::inner_size(self).map_err(|e| e.to_string())
+    }
+
+    fn set_position(&self, position: PhysicalPosition<i32>) -> Result<(), String> {
+        tauri::WebviewWindow::set_position(self, position).map_err(|e| e.to_string())
+    }
+
+    fn set_size(&self, size: PhysicalSize<u32>) -> Result<(), String> {
+        tauri::WebviewWindow::set_size(self, size).map_err(|e| e.to_string())
+    }
+
+    fn set_min_size(&self, size: Option<PhysicalSize<u32>>) -> Result<(), String> {
+        tauri::WebviewWindow::set_min_size(self, size).map_err(|e| e.to_string())
+    }
+
+    fn set_shadow(&self, enable: bool) -> Result<(), String> {
+        tauri::WebviewWindow::set_shadow(self, enable).map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    fn set_alpha(&self, alpha: u8) -> Result<(), String> {
+        use windows::Win32::Foundation::{COLORREF, HWND};
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetWindowLongW, SetLayeredWindowAttributes, SetWindowLongW, GWL_EXSTYLE, LWA_ALPHA,
+            WS_EX_LAYERED,
+        };
+
+        let hwnd = HWND(self.hwnd().map_err(|e| e.to_string())?.0);
+        unsafe {
+            let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE);
+            if ex_style & WS_EX_LAYERED.0 as i32 == 0 {
+                SetWindowLongW(hwnd, GWL_EXSTYLE, ex_style | WS_EX_LAYERED.0 as i32);
+            }
+            SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA)
+                .map_err(|e| e.to_string())
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn set_alpha(&self, _alpha: u8) -> Result<(), String> {
+        crate::log_warn!("Window", "Window opacity is only applied on Windows");
+        Ok(())
+    }
+}
+
+fn named_monitor(monitor: &tauri::Monitor) -> NamedMonitor {
+    NamedMonitor {
+        name: monitor.name().cloned(),
+        geometry: (*monitor.position(), *monitor.size()),
+    }
+}
+
+/// Report a window error under `context` and pass it on.
+fn report(context: &'static str) -> impl Fn(String) -> String {
+    move |err| {
+        error_reporting::capture_window_error(&err, context);
+        err
+    }
+}
+
+pub(crate) fn main_window(app: &AppHandle) -> Option<tauri::WebviewWindow> {
+    app.get_webview_window("main")
+}
+
+/// The stored `position_margin`.
+pub fn position_margin(app: &AppHandle) -> u32 {
+    app.state::<AppState>().settings.lock().position_margin
+}
+
+#[derive(Clone, Copy)]
+enum AxisAnchor {
+    Start,
+    Center,
+    End,
+}
+
+/// Offset of the window along one axis of the monitor, `margin` pixels in
+/// from the edge it is anchored to. The margin shrinks when the window
+/// wouldn't fit, so it never pushes the window off the monitor.
+fn anchored_axis(monitor_len: u32, window_len: u32, anchor: AxisAnchor, margin: u32) -> i32 {
+    let available = monitor_len.saturating_sub(window_len);
+    let margin = margin.min(available / 2);
+    let offset = match anchor {
+        AxisAnchor::Start => margin,
+        AxisAnchor::Center => available / 2,
+        AxisAnchor::End => available - margin,
+    };
+    i32::try_from(offset).unwrap_or(i32::MAX)
+}
+
+/// `(x, y)` of a `window` for the named position, relative to the origin of
+/// a `monitor` of the given size, `margin` pixels in from the edges it
+/// touches. Unknown names fall back to "right". The single place positions
+/// are computed, for `set_window_position`, startup placement and the tray.
+pub fn compute_window_position(
+    position: &str,
+    monitor: PhysicalSize<u32>,
+    window: PhysicalSize<u32>,
+    margin: u32,
+) -> (i32, i32) {
+    let (horizontal, vertical) = match position {
+        "left" => (AxisAnchor::Start, AxisAnchor::Center),
+        "right" => (AxisAnchor::End, AxisAnchor::Center),
+        "top-left" => (AxisAnchor::Start, AxisAnchor::Start),
+        "top-right" => (AxisAnchor::End, AxisAnchor::Start),
+        "bottom-left" => (AxisAnchor::Start, AxisAnchor::End),
+        "bottom-right" => (AxisAnchor::End, AxisAnchor::End),
+        _ => (AxisAnchor::End, AxisAnchor::Center),
+    };
+
+    (
+        anchored_axis(monitor.width, window.width, horizontal, margin),
+        anchored_axis(monitor.height, window.height, vertical, margin),
+    )
+}
+
+pub fn anchored_window_position(
+    monitor_position: PhysicalPosition<i32>,
+    monitor_size: PhysicalSize<u32>,
+    window_size: PhysicalSize<u32>,
+    position: &str,
+    margin: u32,
+) -> PhysicalPosition<i32> {
+    let (x, y) = compute_window_position(position, monitor_size, window_size, margin);
+    PhysicalPosition::new(
+        monitor_position.x.saturating_add(x),
+        monitor_position.y.saturating_add(y),
+    )
+}
+
+pub fn pin_on_top(window: &impl WindowOps, enabled: bool) -> Result<(), String> {
+    window
+        .set_always_on_top(enabled)
+        .map_err(report("set_always_on_top"))
+}
+
+/// Pin the window below others. Always-on-top is cleared first; the two are
+/// mutually exclusive.
+pub fn pin_on_back(window: &impl WindowOps, enabled: bool) -> Result<(), String> {
+    if enabled {
+        window
+            .set_always_on_top(false)
+            .map_err(report("set_always_on_back_top"))?;
+        window
+            .set_always_on_bottom(true)
+            .map_err(report("set_always_on_back_bottom"))
+    } else {
+        window
+            .set_always_on_bottom(false)
+            .map_err(report("set_always_on_back_disable"))
+    }
+}
+
+/// `position` after the user dragged the widget somewhere no preset puts
+/// it. Anchoring is skipped for it, so the window stays where it was left.
+pub const CUSTOM_POSITION: &str = "custom";
+
+/// Where the named anchor puts the window on its current monitor.
+fn anchor_target(
+    window: &impl WindowOps,
+    position: &str,
+    margin: u32,
+) -> Result<PhysicalPosition<i32>, String> {
+    let (monitor_position, monitor_size) = window
+        .current_monitor()
+        .map_err(report("set_window_position_monitor"))?
+        .ok_or_else(|| report("set_window_position")("No monitor found".to_string()))?;
+    let window_size = window
+        .outer_size()
+        .map_err(report("set_window_position_size"))?;
+    Ok(anchored_window_position(
+        monitor_position,
+        monitor_size,
+        window_size,
+        position,
+        margin,
+    ))
+}
+
+/// Move the window to the named anchor on its current monitor, `margin`
+/// pixels from the edges. Used by `set_window_position`, the tray and
+/// startup placement. A `CUSTOM_POSITION` window is left where it is.
+pub fn move_to_anchor(window: &impl WindowOps, position: &str, margin: u32) -> Result<(), String> {
+    if position == CUSTOM_POSITION {
+        return Ok(());
+    }
+    let target = anchor_target(window, position, margin)?;
+    window
+        .set_position(target)
+        .map_err(report("set_window_position_set"))?;
+    error_reporting::add_breadcrumb("window", &format!("Docked {}", position));
+    Ok(())
+}
+
+pub fn read_window_state(window: &impl WindowOps) -> Result<WindowStateData, String> {
+    let position = window
+        .outer_position()
+        .map_err(report("get_window_state_position"))?;
+    let size = window
+        .inner_size()
+        .map_err(report("get_window_state_size"))?;
+    Ok(WindowStateData {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        // Only used to validate a restore, so a failure here isn't fatal
+        monitor_name: window.current_monitor_name().ok().flatten(),
+    })
+}
+
+/// Store the main window's placement in `window_state`, so the next start
+/// opens it where it is now. Returns the settings to persist, or `None`
+/// when there is no main window.
+pub fn remember_window_state(
+    app: &AppHandle,
+) -> Result<Option<crate::settings::AppSettings>, String> {
+    let Some(window) = main_window(app) else {
+        return Ok(None);
+    };
+    let window_state = read_window_state(&window)?;
+    let state = app.state::<AppState>();
+    let mut settings = state.settings.lock();
+    settings.window_state = Some(window_state.into());
+    Ok(Some(settings.clone()))
+}
+
+/// Edges of `monitor` that another monitor continues past, as
+/// `[left, right, top, bottom]`. The pointer crosses these, so they are not
+/// screen edges to snap to.
+fn shared_edges(monitor: MonitorGeometry, monitors: &[MonitorGeometry]) -> [bool; 4] {
+    let (position, size) = monitor;
+    let right = position.x.saturating_add(size.width as i32);
+    let bottom = position.y.saturating_add(size.height as i32);
+    let mut shared = [false; 4];
+    for &(other_position, other_size) in monitors {
+        let other_right = other_position.x.saturating_add(other_size.width as i32);
+        let other_bottom = other_position.y.saturating_add(other_size.height as i32);
+        let overlaps_vertically = other_position.y < bottom && other_bottom > position.y;
+        let overlaps_horizontally = other_position.x < right && other_right > position.x;
+        shared[0] |= overlaps_vertically && other_right == position.x;
+        shared[1] |= overlaps_vertically && other_position.x == right;
+        shared[2] |= overlaps_horizontally && other_bottom == position.y;
+        shared[3] |= overlaps_horizontally && other_position.y == bottom;
+    }
+    shared
+}
+
+/// Lowest and highest start of a `len` long window docked inside
+/// `area_start`..`area_start + area_len`, `margin` in from the ends.
+fn docked_range(len: u32, area_start: i32, area_len: u32, margin: u32) -> (i32, i32) {
+    let low = area_start.saturating_add(margin as i32);
+    let high = area_start
+        .saturating_add(area_len as i32)
+        .saturating_sub(len as i32)
+        .saturating_sub(margin as i32);
+    (low, high)
+}
+
+/// Snap one axis: pull `start` to whichever allowed end of the docked
+/// range is within `threshold`, the nearer one if both are.
+fn snap_axis(
+    start: i32,
+    (low, high): (i32, i32),
+    [snap_low, snap_high]: [bool; 2],
+    threshold: u32,
+) -> i32 {
+    let low_distance = start.abs_diff(low);
+    let high_distance = start.abs_diff(high);
+    if snap_low && low_distance <= threshold && (!snap_high || low_distance <= high_distance) {
+        low
+    } else if snap_high && high_distance <= threshold {
+        high
+    } else {
+        start
+    }
+}
+
+/// Where a window at `position` snaps to in `work_area`: flush with each
+/// real screen edge within `threshold` (`margin` in from it). Unchanged
+/// when no edge is close enough.
+fn snap_position(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    (area_position, area_size): MonitorGeometry,
+    shared: [bool; 4],
+    margin: u32,
+    threshold: u32,
+) -> PhysicalPosition<i32> {
+    PhysicalPosition::new(
+        snap_axis(
+            position.x,
+            docked_range(size.width, area_position.x, area_size.width, margin),
+            [!shared[0], !shared[1]],
+            threshold,
+        ),
+        snap_axis(
+            position.y,
+            docked_range(size.height, area_position.y, area_size.height, margin),
+            [!shared[2], !shared[3]],
+            threshold,
+        ),
+    )
+}
+
+/// The corner preset a window at `position` sits in, flush with the work
+/// area (`margin` in from it) on both axes.
+fn corner_preset(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    (area_position, area_size): MonitorGeometry,
+    margin: u32,
+) -> Option<&'static str> {
+    let side = |start: i32, (low, high): (i32, i32)| match start {
+        _ if start == low => Some(false),
+        _ if start == high => Some(true),
+        _ => None,
+    };
+    let right = side(
+        position.x,
+        docked_range(size.width, area_position.x, area_size.width, margin),
+    )?;
+    let bottom = side(
+        position.y,
+        docked_range(size.height, area_position.y, area_size.height, margin),
+    )?;
+    Some(match (right, bottom) {
+        (false, false) => "top-left",
+        (true, false) => "top-right",
+        (false, true) => "bottom-left",
+        (true, true) => "bottom-right",
+    })
+}
+
+/// Position the last snap moved the window to. Its own `Moved` event is
+/// skipped so the snap doesn't feed back into itself.
+static SNAP_TARGET: Mutex<Option<PhysicalPosition<i32>>> = Mutex::new(None);
+
+/// Call on every `Moved` of the main window. Pulls the window flush with a
+/// screen edge of its monitor's work area when it comes within the
+/// `snapThreshold` setting. Edges shared with a neighbouring monitor are
+/// left alone so the widget can still be dragged across.
+pub fn snap_to_edges(app: &AppHandle, position: PhysicalPosition<i32>) {
+    {
+        let mut target = SNAP_TARGET.lock().unwrap_or_else(|e| e.into_inner());
+        if target.take() == Some(position) {
+            return;
+        }
+    }
+    let (threshold, margin) = {
+        let state = app.state::<AppState>();
+        let settings = state.settings.lock();
+        (settings.snap_threshold, settings.position_margin)
+    };
+    if threshold == 0 || crate::window_monitor::is_mini_mode() {
+        return;
+    }
+    let Some(window) = main_window(app) else {
+        return;
+    };
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    if let Err(e) = snap_window(&window, position, threshold, margin) {
+        crate::log_debug!("Window", "Snap skipped: {}", e);
+    }
+}
+
+fn snap_window(
+    window: &impl WindowOps,
+    position: PhysicalPosition<i32>,
+    threshold: u32,
+    margin: u32,
+) -> Result<(), String> {
+    let (Some(monitor), Some(work_area)) = (window.current_monitor()?, window.current_work_area()?)
+    else {
+        return Ok(());
+    };
+    let others: Vec<MonitorGeometry> = window
+        .available_monitors()?
+        .into_iter()
+        .map(|m| m.geometry)
+        .filter(|geometry| *geometry != monitor)
+        .collect();
+    let snapped = snap_position(
+        position,
+        window.outer_size()?,
+        work_area,
+        shared_edges(monitor, &others),
+        margin,
+        threshold,
+    );
+    if snapped == position {
+        return Ok(());
+    }
+    *SNAP_TARGET.lock().unwrap_or_else(|e| e.into_inner()) = Some(snapped);
+    window.set_position(snapped)
+}
+
+/// The `position` setting that describes where the window is now: the
+/// stored one while the window is still there, a corner preset when it was
+/// snapped into one, otherwise `CUSTOM_POSITION`.
+fn placed_position<'a>(
+    window: &impl WindowOps,
+    position: &'a str,
+    margin: u32,
+) -> Result<&'a str, String> {
+    if is_docked(window, position, margin)? {
+        return Ok(position);
+    }
+    let corner = match window.current_work_area()? {
+        Some(work_area) => corner_preset(
+            window.outer_position()?,
+            window.outer_size()?,
+            work_area,
+            margin,
+        ),
+        None => None,
+    };
+    Ok(corner.unwrap_or(CUSTOM_POSITION))
+}
+
+/// Quiet time after the last move or resize before the placement is stored.
+const WINDOW_STATE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Bumped on every move or resize; a pending store only runs if no newer
+/// event arrived while it waited.
+static GEOMETRY_EVENTS: AtomicU64 = AtomicU64::new(0);
+
+/// Windows parks minimized windows at (-32000, -32000).
+fn is_minimized_placement(state: &WindowStateData) -> bool {
+    state.x <= -32000 && state.y <= -32000
+}
+
+/// Whether the window sits exactly where its stored preset puts it. Moves
+/// made by `move_to_anchor` pass; a drag doesn't.
+fn is_docked(window: &impl WindowOps, position: &str, margin: u32) -> Result<bool, String> {
+    if position == CUSTOM_POSITION {
+        return Ok(false);
+    }
+    let target = anchor_target(window, position, margin)?;
+    Ok(window.outer_position()? == target)
+}
+
+/// Call on every `Moved`/`Resized` of the main window. Once the window has
+/// been still for `WINDOW_STATE_DEBOUNCE`, stores its placement, switching
+/// `position` to `CUSTOM_POSITION` if it was dragged off its preset.
+pub fn window_geometry_changed(app: &AppHandle) {
+    let event = GEOMETRY_EVENTS.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    let task = crate::metrics::task_started();
+    tauri::async_runtime::spawn(async move {
+        let _task = task;
+        tokio::time::sleep(WINDOW_STATE_DEBOUNCE).await;
+        if GEOMETRY_EVENTS.load(Ordering::SeqCst) != event {
+            return;
+        }
+        if let Err(e) = store_moved_window_state(&app) {
+            crate::log_warn!("Window", "Failed to store window state: {}", e);
+        }
+    });
+}
+
+fn store_moved_window_state(app: &AppHandle) -> Result<(), String> {
+    let Some(window) = main_window(app) else {
+        return Ok(());
+    };
+    // Mini mode shrinks the window temporarily; keep the full-size state
+    if window.is_minimized().unwrap_or(false) || crate::window_monitor::is_mini_mode() {
+        return Ok(());
+    }
+    let window_state = read_window_state(&window)?;
+    if is_minimized_placement(&window_state) {
+        return Ok(());
+    }
+    let (position, margin) = {
+        let state = app.state::<AppState>();
+        let settings = state.settings.lock();
+        (settings.position.clone(), settings.position_margin)
+    };
+
+    let mut patch =
+        serde_json::json!({ "windowState": crate::settings::WindowState::from(window_state) });
+    let placed = placed_position(&window, &position, margin)?;
+    if placed != position {
+        patch["position"] = placed.into();
+    }
+    super::settings::apply_settings_patch(app, &patch).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// `remember_window_state` and write the settings before returning, for
+/// the paths that end the process right after.
+pub fn save_window_state_on_exit(app: &AppHandle) {
+    let result = remember_window_state(app).and_then(|settings| match settings {
+        Some(settings) => {
+            tauri::async_runtime::block_on(crate::settings::save_settings_to_file(&settings))
+        }
+        None => Ok(()),
+    });
+    if let Err(e) = result {
+        crate::log_warn!("Window", "Failed to save window state on exit: {}", e);
+    }
+}
+
+/// Where to restore a saved window. The saved coordinates, unless the
+/// monitor they were on is gone; then the window is centered on the primary
+/// monitor (or any, if there is no primary). States saved without a monitor
+/// name can't be checked and are restored as saved, as are all states when
+/// no monitors are known.
+fn restore_position(
+    state: &WindowStateData,
+    monitors: &[NamedMonitor],
+    primary: Option<&NamedMonitor>,
+) -> PhysicalPosition<i32> {
+    let saved = PhysicalPosition::new(state.x, state.y);
+    let Some(name) = state.monitor_name.as_deref() else {
+        return saved;
+    };
+    if monitors.iter().any(|m| m.name.as_deref() == Some(name)) {
+        return saved;
+    }
+    let Some((origin, size)) = primary.or(monitors.first()).map(|m| m.geometry) else {
+        return saved;
+    };
+    let centered = |monitor_len: u32, window_len: u32| {
+        i32::try_from(monitor_len.saturating_sub(window_len) / 2).unwrap_or(0)
+    };
+    PhysicalPosition::new(
+        origin.x.saturating_add(centered(size.width, state.width)),
+        origin.y.saturating_add(centered(size.height, state.height)),
+    )
+}
+
+pub fn apply_window_state(window: &impl WindowOps, state: &WindowStateData) -> Result<(), String> {
+    // If the monitors can't be listed, the saved coordinates are used as is
+    let monitors = window.available_monitors().unwrap_or_default();
+    let primary = window.primary_monitor().ok().flatten();
+    let position = restore_position(state, &monitors, primary.as_ref());
+    if position != PhysicalPosition::new(state.x, state.y) {
+        crate::log_info!(
+            "Window",
+            "Monitor {:?} is gone; centering on the primary monitor",
+            state.monitor_name
+        );
+        error_reporting::add_breadcrumb("window", "Saved monitor gone, centered on primary");
+    }
+    window
+        .set_position(position)
+        .map_err(report("restore_window_state_position"))?;
+    window
+        .set_size(PhysicalSize::new(state.width, state.height))
+        .map_err(report("restore_window_state_size"))?;
+    error_reporting::add_breadcrumb("window", "Restored window state");
+    Ok(())
+}
+
+/// A minimum size needs both dimensions; anything else clears it.
+pub fn apply_min_size(
+    window: &impl WindowOps,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<(), String> {
+    let min_size = match (width, height) {
+        (Some(w), Some(h)) => Some(PhysicalSize::new(w, h)),
+        _ => None,
+    };
+    window
+        .set_min_size(min_size)
+        .map_err(report("set_window_min_size"))
+}
+
+/// `opacity` range applied to the window, in percent. Any lower and the
+/// widget could become impossible to find and turn back up.
+const MIN_OPACITY: u32 = 10;
+const MAX_OPACITY: u32 = 100;
+
+fn opacity_alpha(opacity: u32) -> u8 {
+    (opacity.clamp(MIN_OPACITY, MAX_OPACITY) * 255 / 100) as u8
+}
+
+/// Make the whole window translucent, `opacity` percent (clamped to
+/// `MIN_OPACITY`..=`MAX_OPACITY`). Only implemented on Windows.
+pub fn apply_opacity(window: &impl WindowOps, opacity: u32) -> Result<(), String> {
+    window
+        .set_alpha(opacity_alpha(opacity))
+        .map_err(report("set_window_opacity"))
+}
+
+/// Apply the stored `opacity` to the main window. Called at startup and
+/// after changes that rewrite the window's extended style, which drops the
+/// layered alpha.
+pub fn reapply_opacity(app: &AppHandle) {
+    let Some(window) = main_window(app) else {
+        return;
+    };
+    let opacity = {
+        let state = app.state::<AppState>();
+        let settings = state.settings.lock();
+        settings.opacity
+    };
+    let _ = apply_opacity(&window, opacity);
+}
+
+/// Make the main window ignore or accept mouse input, then sync the stored
+/// setting and the tray check mark. Independent of always-on-top.
+pub fn apply_click_through(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    if let Some(window) = main_window(app) {
+        WindowOps::set_ignore_cursor_events(&window, enabled)
+            .map_err(report("set_click_through"))?;
+        reapply_opacity(app);
+    }
+    tray::set_click_through_checked(app, enabled);
+
+    let state = app.state::<AppState>();
+    let settings_to_save = super::settings::set_click_through(&state.settings, enabled)?;
+    let task = crate::metrics::task_started();
+    tauri::async_runtime::spawn(async move {
+        let _task = task;
+        if let Err(e) = crate::settings::save_settings_to_file(&settings_to_save).await {
+            error_reporting::capture_settings_error(&e, "set_click_through");
+        }
+    });
+
+    crate::log_info!("Window", "Click-through set to {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_always_on_top(app: AppHandle, enabled: bool) -> Result<(), String> {
+    match main_window(&app) {
+        Some(window) => pin_on_top(&window, enabled),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn set_always_on_back(app: AppHandle, enabled: bool) -> Result<(), String> {
+    match main_window(&app) {
+        Some(window) => pin_on_back(&window, enabled),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn set_click_through(app: AppHandle, enabled: bool) -> Result<(), String> {
+    apply_click_through(&app, enabled)
+}
+
+#[tauri::command]
+pub async fn set_window_position(app: AppHandle, position: String) -> Result<(), String> {
+    match main_window(&app) {
+        Some(window) => move_to_anchor(&window, &position, position_margin(&app)),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_window_state(app: AppHandle) -> Result<WindowStateData, String> {
+    match main_window(&app) {
+        Some(window) => read_window_state(&window),
+        None => Err(report("get_window_state")("Window not found".to_string())),
+    }
+}
+
+#[tauri::command]
+pub async fn restore_window_state(app: AppHandle, state: WindowStateData) -> Result<(), String> {
+    match main_window(&app) {
+        Some(window) => apply_window_state(&window, &state),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn set_window_min_size(
+    app: AppHandle,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<(), String> {
+    match main_window(&app) {
+        Some(window) => apply_min_size(&window, width, height),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn set_window_shadow(app: AppHandle, enable: bool) -> Result<(), String> {
+    match main_window(&app) {
+        Some(window) => WindowOps::set_shadow(&window, enable).map_err(report("set_window_shadow")),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn set_window_opacity(app: AppHandle, opacity: u32) -> Result<(), String> {
+    match main_window(&app) {
+        Some(window) => apply_opacity(&window, opacity),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Records every call; geometry getters return fixed values.
+    #[derive(Default)]
+    struct FakeWindow {
+        calls: RefCell<Vec<String>>,
+        monitor: Option<MonitorGeometry>,
+        monitor_name: Option<String>,
+        /// Connected monitors, primary first.
+        monitors: Vec<NamedMonitor>,
+        size: PhysicalSize<u32>,
+        fail_on: Option<&'static str>,
+    }
+
+    impl FakeWindow {
+        fn on_monitor(x: i32, y: i32, width: u32, height: u32) -> Self {
+            Self {
+                monitor: Some((
+                    PhysicalPosition::new(x, y),
+                    PhysicalSize::new(width, height),
+                )),
+                size: PhysicalSize::new(300, 600),
+                ..Default::default()
+            }
+        }
+
+        fn record(&self, call: String) -> Result<(), String> {
+            let failed = self.fail_on.is_some_and(|name| call.starts_with(name));
+            self.calls.borrow_mut().push(call);
+            if failed {
+                Err("boom".to_string())
+            } else {
+                Ok(())
+            }
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.borrow().clone()
+        }
+    }
+
+    impl WindowOps for FakeWindow {
+        fn set_always_on_top(&self, enabled: bool) -> Result<(), String> {
+            self.record(format!("top={}", enabled))
+        }
+        fn set_always_on_bottom(&self, enabled: bool) -> Result<(), String> {
+            self.record(format!("bottom={}", enabled))
+        }
+        fn set_ignore_cursor_events(&self, enabled: bool) -> Result<(), String> {
+            self.record(format!("ignore_cursor={}", enabled))
+        }
+        fn current_monitor(&self) -> Result<Option<MonitorGeometry>, String> {
+            Ok(self.monitor)
+        }
+        fn current_work_area(&self) -> Result<Option<MonitorGeometry>, String> {
+            Ok(self.monitor)
+        }
+        fn current_monitor_name(&self) -> Result<Option<String>, String> {
+            Ok(self.monitor_name.clone())
+        }
+        fn available_monitors(&self) -> Result<Vec<NamedMonitor>, String> {
+            Ok(self.monitors.clone())
+        }
+        fn primary_monitor(&self) -> Result<Option<NamedMonitor>, String> {
+            Ok(self.monitors.first().cloned())
+        }
+        fn outer_position(&self) -> Result<PhysicalPosition<i32>, String> {
+            Ok(PhysicalPosition::new(10, 20))
+        }
+        fn outer_size(&self) -> Result<PhysicalSize<u32>, String> {
+            Ok(self.size)
+        }
+        fn inner_size(&self) -> Result<PhysicalSize<u32>, String> {
+            Ok(PhysicalSize::new(self.size.width - 2, self.size.height - 2))
+        }
+        fn set_position(&self, position: PhysicalPosition<i32>) -> Result<(), String> {
+            self.record(format!("position={},{}", position.x, position.y))
+        }
+        fn set_size(&self, size: PhysicalSize<u32>) -> Result<(), String> {
+            self.record(format!("size={}x{}", size.width, size.height))
+        }
+        fn set_min_size(&self, size: Option<PhysicalSize<u32>>) -> Result<(), String> {
+            self.record(match size {
+                Some(size) => format!("min_size={}x{}", size.width, size.height),
+                None => "min_size=none".to_string(),
+            })
+        }
+        fn set_shadow(&self, enable: bool) -> Result<(), String> {
+            self.record(format!("shadow={}", enable))
+        }
+        fn set_alpha(&self, alpha: u8) -> Result<(), String> {
+            self.record(format!("alpha={}", alpha))
+        }
+    }
+
+    fn position_on_1080p(anchor: &str) -> (i32, i32) {
+        compute_window_position(
+            anchor,
+            PhysicalSize::new(1920, 1080),
+            PhysicalSize::new(300, 600),
+            0,
+        )
+    }
+
+    #[test]
+    fn each_named_position_maps_to_monitor_edges() {
+        assert_eq!(position_on_1080p("left"), (0, 240));
+        assert_eq!(position_on_1080p("right"), (1620, 240));
+        assert_eq!(position_on_1080p("top-left"), (0, 0));
+        assert_eq!(position_on_1080p("top-right"), (1620, 0));
+        assert_eq!(position_on_1080p("bottom-left"), (0, 480));
+        assert_eq!(position_on_1080p("bottom-right"), (1620, 480));
+    }
+
+    #[test]
+    fn unknown_position_falls_back_to_right() {
+        assert_eq!(position_on_1080p("middle"), (1620, 240));
+        assert_eq!(position_on_1080p(""), (1620, 240));
+    }
+
+    fn position_with_margin(anchor: &str, monitor: (u32, u32), margin: u32) -> (i32, i32) {
+        compute_window_position(
+            anchor,
+            PhysicalSize::new(monitor.0, monitor.1),
+            PhysicalSize::new(300, 600),
+            margin,
+        )
+    }
+
+    #[test]
+    fn margin_moves_the_window_in_from_the_edges_it_touches() {
+        let on_1080p = |anchor| position_with_margin(anchor, (1920, 1080), 16);
+        assert_eq!(on_1080p("right"), (1920 - 300 - 16, 240));
+        assert_eq!(on_1080p("left"), (16, 240));
+        assert_eq!(on_1080p("top-left"), (16, 16));
+        assert_eq!(on_1080p("top-right"), (1604, 16));
+        assert_eq!(on_1080p("bottom-left"), (16, 1080 - 600 - 16));
+        assert_eq!(on_1080p("bottom-right"), (1604, 464));
+    }
+
+    #[test]
+    fn margin_never_pushes_the_window_off_a_small_monitor() {
+        // 20px to spare horizontally, 40px vertically: the margin is cut to
+        // half of that so both edges stay on screen
+        assert_eq!(
+            position_with_margin("bottom-right", (320, 640), 100),
+            (10, 20)
+        );
+        assert_eq!(position_with_margin("top-left", (320, 640), 100), (10, 20));
+        // No room at all
+        assert_eq!(position_with_margin("right", (300, 600), 50), (0, 0));
+    }
+
+    #[test]
+    fn secondary_monitor_offsets_are_respected() {
+        // Monitor left of the primary has a negative origin
+        let p = anchored_window_position(
+            PhysicalPosition::new(-2560, -200),
+            PhysicalSize::new(2560, 1440),
+            PhysicalSize::new(300, 600),
+            "top-right",
+            0,
+        );
+        assert_eq!((p.x, p.y), (-300, -200));
+    }
+
+    #[test]
+    fn computed_position_is_relative_to_the_monitor() {
+        let offset = compute_window_position(
+            "bottom-left",
+            PhysicalSize::new(2560, 1440),
+            PhysicalSize::new(300, 600),
+            0,
+        );
+        assert_eq!(offset, (0, 840));
+        let p = anchored_window_position(
+            PhysicalPosition::new(1920, -100),
+            PhysicalSize::new(2560, 1440),
+            PhysicalSize::new(300, 600),
+            "bottom-left",
+            0,
+        );
+        assert_eq!((p.x, p.y), (1920, 740));
+    }
+
+    #[test]
+    fn window_larger_than_monitor_sticks_to_origin() {
+        let p = anchored_window_position(
+            PhysicalPosition::new(100, 100),
+            PhysicalSize::new(800, 600),
+            PhysicalSize::new(1000, 1000),
+            "bottom-right",
+            0,
+        );
+        assert_eq!((p.x, p.y), (100, 100));
+    }
+
+    #[test]
+    fn move_to_anchor_uses_current_monitor() {
+        let window = FakeWindow::on_monitor(1920, 0, 1920, 1080);
+        move_to_anchor(&window, "top-left", 0).unwrap();
+        assert_eq!(window.calls(), vec!["position=1920,0"]);
+    }
+
+    #[test]
+    fn opacity_is_clamped_so_the_window_stays_visible() {
+        let window = FakeWindow::default();
+        apply_opacity(&window, 100).unwrap();
+        apply_opacity(&window, 50).unwrap();
+        apply_opacity(&window, 0).unwrap();
+        apply_opacity(&window, 250).unwrap();
+        assert_eq!(
+            window.calls(),
+            vec!["alpha=255", "alpha=127", "alpha=25", "alpha=255"]
+        );
+    }
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32) -> MonitorGeometry {
+        (
+            PhysicalPosition::new(x, y),
+            PhysicalSize::new(width, height),
+        )
+    }
+
+    #[test]
+    fn snaps_to_nearby_edges_only() {
+        // 1920x1040 work area above a 40px taskbar
+        let area = geometry(0, 0, 1920, 1040);
+        let size = PhysicalSize::new(300, 600);
+        let snap = |x, y, margin| {
+            snap_position(
+                PhysicalPosition::new(x, y),
+                size,
+                area,
+                [false; 4],
+                margin,
+                20,
+            )
+        };
+        assert_eq!(snap(12, 15, 0), PhysicalPosition::new(0, 0));
+        assert_eq!(snap(1605, 300, 0), PhysicalPosition::new(1620, 300));
+        assert_eq!(snap(800, 425, 0), PhysicalPosition::new(800, 440));
+        // Too far from every edge
+        assert_eq!(snap(500, 200, 0), PhysicalPosition::new(500, 200));
+        // The margin moves the magnet in from the edge
+        assert_eq!(snap(3, 500, 8), PhysicalPosition::new(8, 500));
+        // Already flush: nothing to do, so no feedback loop
+        assert_eq!(snap(0, 0, 0), PhysicalPosition::new(0, 0));
+    }
+
+    #[test]
+    fn edges_shared_with_another_monitor_do_not_snap() {
+        let left = geometry(0, 0, 1920, 1080);
+        let right = geometry(1920, 0, 2560, 1440);
+        let shared = shared_edges(left, &[right]);
+        assert_eq!(shared, [false, true, false, false]);
+        assert_eq!(shared_edges(right, &[left]), [true, false, false, false]);
+
+        let snapped = snap_position(
+            PhysicalPosition::new(1610, 5),
+            PhysicalSize::new(300, 600),
+            left,
+            shared,
+            0,
+            20,
+        );
+        assert_eq!(snapped, PhysicalPosition::new(1610, 0));
+        // A monitor above only shares the top edge
+        let above = geometry(500, -1080, 1920, 1080);
+        assert_eq!(shared_edges(left, &[above]), [false, false, true, false]);
+    }
+
+    #[test]
+    fn corners_map_to_presets() {
+        let area = geometry(0, 0, 1920, 1040);
+        let size = PhysicalSize::new(300, 600);
+        let at = |x, y| corner_preset(PhysicalPosition::new(x, y), size, area, 0);
+        assert_eq!(at(0, 0), Some("top-left"));
+        assert_eq!(at(1620, 0), Some("top-right"));
+        assert_eq!(at(0, 440), Some("bottom-left"));
+        assert_eq!(at(1620, 440), Some("bottom-right"));
+        assert_eq!(at(1620, 200), None);
+        assert_eq!(
+            corner_preset(PhysicalPosition::new(8, 8), size, area, 8),
+            Some("top-left")
+        );
+    }
+
+    #[test]
+    fn placement_is_described_by_a_preset_when_possible() {
+        // The fake window sits at (10, 20)
+        let window = FakeWindow::on_monitor(10, 20, 1920, 1080);
+        assert_eq!(placed_position(&window, "top-left", 0).unwrap(), "top-left");
+        assert_eq!(placed_position(&window, "right", 0).unwrap(), "top-left");
+        assert_eq!(
+            placed_position(&window, "right", 8).unwrap(),
+            CUSTOM_POSITION
+        );
+    }
+
+    #[test]
+    fn custom_position_is_left_alone() {
+        let window = FakeWindow::on_monitor(0, 0, 1920, 1080);
+        move_to_anchor(&window, CUSTOM_POSITION, 0).unwrap();
+        assert!(window.calls().is_empty());
+    }
+
+    #[test]
+    fn docked_only_where_the_preset_puts_the_window() {
+        // The fake window sits at (10, 20)
+        let window = FakeWindow::on_monitor(10, 20, 1920, 1080);
+        assert!(is_docked(&window, "top-left", 0).unwrap());
+        assert!(!is_docked(&window, "top-left", 8).unwrap());
+        assert!(!is_docked(&window, "right", 0).unwrap());
+        assert!(!is_docked(&window, CUSTOM_POSITION, 0).unwrap());
+    }
+
+    #[test]
+    fn minimized_placement_is_recognized() {
+        let state = |x, y| WindowStateData {
+            x,
+            y,
+            width: 300,
+            height: 600,
+            monitor_name: None,
+        };
+        assert!(is_minimized_placement(&state(-32000, -32000)));
+        assert!(!is_minimized_placement(&state(-1920, 0)));
+        assert!(!is_minimized_placement(&state(100, 100)));
+    }
+
+    #[test]
+    fn move_to_anchor_without_monitor_fails_without_moving() {
+        let window = FakeWindow::default();
+        assert_eq!(
+            move_to_anchor(&window, "left", 0).unwrap_err(),
+            "No monitor found"
+        );
+        assert!(window.calls().is_empty());
+    }
+
+    #[test]
+    fn pin_on_back_clears_top_first() {
+        let window = FakeWindow::default();
+        pin_on_back(&window, true).unwrap();
+        assert_eq!(window.calls(), vec!["top=false", "bottom=true"]);
+
+        let window = FakeWindow::default();
+        pin_on_back(&window, false).unwrap();
+        assert_eq!(window.calls(), vec!["bottom=false"]);
+    }
+
+    #[test]
+    fn pin_on_back_stops_when_clearing_top_fails() {
+        let window = FakeWindow {
+            fail_on: Some("top"),
+            ..Default::default()
+        };
+        assert!(pin_on_back(&window, true).is_err());
+        assert_eq!(window.calls(), vec!["top=false"]);
+    }
+
+    fn monitor(name: &str, x: i32, width: u32, height: u32) -> NamedMonitor {
+        NamedMonitor {
+            name: Some(name.to_string()),
+            geometry: (
+                PhysicalPosition::new(x, 0),
+                PhysicalSize::new(width, height),
+            ),
+        }
+    }
+
+    #[test]
+    fn window_state_round_trips() {
+        let window = FakeWindow {
+            monitor_name: Some("DELL U2720Q".to_string()),
+            monitors: vec![monitor("DELL U2720Q", 0, 1920, 1080)],
+            ..FakeWindow::on_monitor(0, 0, 1920, 1080)
+        };
+        let state = read_window_state(&window).unwrap();
+        assert_eq!(
+            state,
+            WindowStateData {
+                x: 10,
+                y: 20,
+                width: 298,
+                height: 598,
+                monitor_name: Some("DELL U2720Q".to_string()),
+            }
+        );
+        apply_window_state(&window, &state).unwrap();
+        assert_eq!(window.calls(), vec!["position=10,20", "size=298x598"]);
+    }
+
+    #[test]
+    fn restore_on_an_unplugged_monitor_centers_on_the_primary() {
+        let window = FakeWindow {
+            monitors: vec![monitor("Built-in", 0, 1920, 1080)],
+            ..Default::default()
+        };
+        let state = WindowStateData {
+            x: 3000,
+            y: 100,
+            width: 300,
+            height: 600,
+            monitor_name: Some("External".to_string()),
+        };
+        apply_window_state(&window, &state).unwrap();
+        assert_eq!(window.calls(), vec!["position=810,240", "size=300x600"]);
+    }
+
+    #[test]
+    fn restore_without_a_monitor_name_keeps_the_saved_coordinates() {
+        // Saved by a version that didn't record the monitor
+        let state: WindowStateData =
+            serde_json::from_str(r#"{"x":3000,"y":100,"width":300,"height":600}"#).unwrap();
+        let monitors = [monitor("Built-in", 0, 1920, 1080)];
+        assert_eq!(
+            restore_position(&state, &monitors, monitors.first()),
+            PhysicalPosition::new(3000, 100)
+        );
+
+        // Nothing to validate against either
+        let state = WindowStateData {
+            monitor_name: Some("External".to_string()),
+            ..state
+        };
+        assert_eq!(
+            restore_position(&state, &[], None),
+            PhysicalPosition::new(3000, 100)
+        );
+    }
+
+    #[test]
+    fn min_size_needs_both_dimensions() {
+        let window = FakeWindow::default();
+        apply_min_size(&window, Some(200), Some(100)).unwrap();
+        apply_min_size(&window, Some(200), None).unwrap();
+        apply_min_size(&window, None, None).unwrap();
+        assert_eq!(
+            window.calls(),
+            vec!["min_size=200x100", "min_size=none", "min_size=none"]
+        );
+    }
+}
@@ -1,19 +1,29 @@
+use crate::api_schema;
 use crate::app_metrics;
 use crate::hardware;
 use crate::log_buffer;
 use crate::window_debug;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
 use tokio::sync::oneshot;
 
-const PORT: u16 = 19210;
+pub const PORT: u16 = 19210;
+
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+/// Whether this process currently holds the debug server port.
+pub fn is_listening() -> bool {
+    LISTENING.load(Ordering::SeqCst)
+}
 
 pub async fn start_debug_server(mut shutdown: oneshot::Receiver<()>) {
     let addr = format!("0.0.0.0:{}", PORT);
     let listener = match TcpListener::bind(&addr).await {
         Ok(l) => {
             crate::log_info!("DebugServer", "Listening on http://{}", addr);
+            LISTENING.store(true, Ordering::SeqCst);
             l
         }
         Err(e) => {
@@ -59,6 +69,7 @@ pub async fn start_debug_server(mut shutdown: oneshot::Receiver<()>) {
                 (_, "/api/sensors") => handle_sensors().await,
                 (_, "/api/pawnio") => handle_pawnio(),
                 (_, "/api/window") => handle_window_info(),
+                (_, "/api/schema") => handle_schema(),
                 (_, "/help") => handle_help(&query),
                 (_, "/status") => handle_status(&query),
                 (_, "/logs") => handle_logs(&query),
@@ -71,6 +82,7 @@ pub async fn start_debug_server(mut shutdown: oneshot::Receiver<()>) {
             let _ = stream.write_all(response.as_bytes()).await;
         });
     }
+    LISTENING.store(false, Ordering::SeqCst);
 }
 
 fn parse_request(request: &str) -> (String, String, HashMap<String, String>) {
@@ -138,6 +150,7 @@ fn handle_help(query: &HashMap<String, String>) -> String {
     {"method": "GET", "path": "/api/sensors", "description": "Raw sensor list (text)"},
     {"method": "GET", "path": "/api/pawnio", "description": "PawnIO driver status (JSON)"},
     {"method": "GET", "path": "/api/window", "description": "Main window state: HWND, class, styles, DWM attributes, injected DLLs (Windows only)"},
+    {"method": "GET", "path": "/api/schema", "description": "JSON Schema for API payloads and Tauri commands"},
     {"method": "GET", "path": "/logs", "description": "All logs. Filters: ?since=<epoch_ms>&limit=N&level=info&tag=Hardware"},
     {"method": "GET", "path": "/logs/tail", "description": "Latest N lines. ?n=100 (default)"},
     {"method": "GET", "path": "/logs/search", "description": "Regex search. ?q=<pattern>&limit=200"}
@@ -161,6 +174,7 @@ Ondo Debug Server - API Reference
   GET  /api/sensors     Raw sensor list (text)
   GET  /api/pawnio      PawnIO driver status (JSON)
   GET  /api/window      Main window state: HWND, class, styles, DWM attributes, injected DLLs (Windows only)
+  GET  /api/schema      JSON Schema for API payloads and Tauri commands
   GET  /logs            All logs. Filters: ?since=<epoch_ms>&limit=N&level=info&tag=Hardware
   GET  /logs/tail       Latest N lines. ?n=100 (default)
   GET  /logs/search     Regex search. ?q=<pattern>&limit=200
@@ -179,10 +193,11 @@ fn handle_status(query: &HashMap<String, String>) -> String {
     let log_count = log_buffer::count();
     let pid = std::process::id();
     let version = env!("CARGO_PKG_VERSION");
-    let pawnio = crate::get_pawnio_detailed_status();
+    let pawnio = crate::commands::pawnio::get_pawnio_detailed_status();
     let lhm = hardware::lhm_daemon_status();
     let extra_pids: Vec<u32> = lhm.pid.iter().copied().collect();
     let metrics = app_metrics::snapshot(&extra_pids);
+    let internal = crate::metrics::snapshot();
 
     if wants_json(query) {
         let mut body = serde_json::json!({
@@ -192,6 +207,7 @@ fn handle_status(query: &HashMap<String, String>) -> String {
             "logCount": log_count,
             "pawnio": pawnio,
             "lhm": lhm,
+            "internal": internal,
         });
         match metrics {
             Ok(metrics) => body["metrics"] = serde_json::json!(metrics),
@@ -219,7 +235,7 @@ fn handle_status(query: &HashMap<String, String>) -> String {
             Err(e) => format!("Metrics error: {}", e),
         };
         let text = format!(
-            "Status: running\nPID: {}\nVersion: {}\nLog lines: {}\n{}\nLHM: supported={}, running={}, pid={}, cached_data={}\nPawnIO: service={}, driver_file={}",
+            "Status: running\nPID: {}\nVersion: {}\nLog lines: {}\n{}\nLHM: supported={}, running={}, pid={}, cached_data={}\nPawnIO: service={}, driver_file={}\nInternal: samples={}, sample_errors={}, daemon_spawns={}, tasks_running={}, session_sensors={}, energy_days={}",
             pid,
             version,
             log_count,
@@ -229,19 +245,31 @@ fn handle_status(query: &HashMap<String, String>) -> String {
             lhm_pid,
             lhm.has_cached_data,
             pawnio_state,
-            pawnio_driver
+            pawnio_driver,
+            internal.samples_collected,
+            internal.sample_errors,
+            internal.lhm_daemon_spawns,
+            internal.tasks_running,
+            internal.session_sensors,
+            internal.energy_days
         );
         http_response(200, "text/plain", &text)
     }
 }
 
 fn handle_pawnio() -> String {
-    let status = crate::get_pawnio_detailed_status();
+    let status = crate::commands::pawnio::get_pawnio_detailed_status();
     let json = serde_json::to_string_pretty(&status)
         .unwrap_or_else(|_| r#"{"error":"serialization failed"}"#.to_string());
     http_response(200, "application/json", &json)
 }
 
+fn handle_schema() -> String {
+    let json = serde_json::to_string_pretty(api_schema::api_schema())
+        .unwrap_or_else(|_| r#"{"error":"serialization failed"}"#.to_string());
+    http_response(200, "application/json", &json)
+}
+
 // --- Window debug endpoints ---
 
 fn handle_window_info() -> String {
@@ -543,7 +571,7 @@ function renderHardware(d) {
     d.storage.forEach(s => {
       h += '<h2>Storage: ' + s.name + '</h2><div class="section"><div class="grid">';
       h += '<div><div class="label">Temperature</div><div class="value ' + (s.temperature > 0 ? tempClass(s.temperature, 70) : '') + '">' + (s.temperature > 0 ? s.temperature.toFixed(1) + '°C' : 'N/A') + '</div></div>';
-      h += '<div><div class="label">Used</div><div class="value">' + s.usedSpace.toFixed(1) + '%</div></div>';
+      h += '<div><div class="label">Used</div><div class="value">' + s.usedPercent.toFixed(1) + '%</div></div>';
       h += '<div><div class="label">Total</div><div class="value">' + s.totalSpace.toFixed(0) + ' GB</div></div>';
       h += '</div></div>';
     });
@@ -554,14 +582,14 @@ function renderHardware(d) {
     h += '<div><div class="label">Temperature</div><div class="value ' + (d.motherboard.temperature > 0 ? tempClass(d.motherboard.temperature, 80) : '') + '">' + (d.motherboard.temperature > 0 ? d.motherboard.temperature.toFixed(1) + '°C' : 'N/A') + '</div></div>';
     if (d.motherboard.fans) {
       d.motherboard.fans.forEach(f => {
-        h += '<div><div class="label">' + f.name + '</div><div class="value">' + f.speed + ' RPM</div></div>';
+        h += '<div><div class="label">' + f.name + '</div><div class="value">' + (f.stopped ? 'STOPPED' : f.speed + (f.unit === 'percent' ? '%' : ' RPM')) + '</div></div>';
       });
     }
     h += '</div></div>';
   }
 
-  if (d.cpuError) h += '<div class="error">CPU Error: ' + d.cpuError + '</div>';
-  if (d.gpuError) h += '<div class="error">GPU Error: ' + d.gpuError + '</div>';
+  if (d.cpuError) h += '<div class="error">CPU Error: ' + d.cpuError + (d.cpuErrorDetail ? ' (' + d.cpuErrorDetail.code + ')' : '') + '</div>';
+  if (d.gpuError) h += '<div class="error">GPU Error: ' + d.gpuError + (d.gpuErrorDetail ? ' (' + d.gpuErrorDetail.code + ')' : '') + '</div>';
 
   return h;
 }
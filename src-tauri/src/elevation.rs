@@ -0,0 +1,193 @@
+//! Administrator rights on Windows: whether Ondo is elevated, and launching
+//! programs through the UAC prompt. The manifest asks for elevation, but
+//! the app can still end up limited (e.g. started by a scheduled task
+//! without highest privileges), and LHM then can't read most sensors.
+
+/// Shown when a UAC prompt is declined.
+#[cfg(target_os = "windows")]
+pub const UAC_DECLINED: &str = "Administrator permission was declined";
+
+/// Launch flag kept across a restart as administrator.
+#[cfg(any(target_os = "windows", test))]
+const FORWARDED_ARGS: &[&str] = &["--minimized"];
+
+/// Parameters for the elevated relaunch, from this process's arguments
+/// (without the program name).
+#[cfg(any(target_os = "windows", test))]
+pub fn relaunch_params(args: impl IntoIterator<Item = String>) -> String {
+    args.into_iter()
+        .filter(|arg| FORWARDED_ARGS.contains(&arg.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether a daemon error line means it was refused access to a sensor or
+/// driver, rather than failing for some other reason.
+#[cfg(any(target_os = "windows", test))]
+pub fn is_permission_error(line: &str) -> bool {
+    let line = line.to_ascii_lowercase();
+    [
+        "access is denied",
+        "access denied",
+        "unauthorizedaccess",
+        "requires elevation",
+        "administrator",
+    ]
+    .iter()
+    .any(|needle| line.contains(needle))
+}
+
+#[cfg(target_os = "windows")]
+pub fn wide(s: &str) -> Vec<u16> {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+
+    OsStr::new(s)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect()
+}
+
+/// Whether the process token is elevated. Checked once; it can't change
+/// while the process runs.
+#[cfg(target_os = "windows")]
+pub fn is_elevated() -> bool {
+    use once_cell::sync::Lazy;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    static ELEVATED: Lazy<bool> = Lazy::new(|| unsafe {
+        let mut token = HANDLE::default();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut returned = 0u32;
+        let result = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut TOKEN_ELEVATION as *mut _),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        );
+        let _ = CloseHandle(token);
+        result.is_ok() && elevation.TokenIsElevated != 0
+    });
+    *ELEVATED
+}
+
+/// Sensors don't need administrator rights outside Windows.
+#[cfg(not(target_os = "windows"))]
+pub fn is_elevated() -> bool {
+    true
+}
+
+/// Start `program` through the UAC prompt (ShellExecuteExW with "runas").
+/// Returns the process handle when `wait` is set, so the caller can wait
+/// for it; otherwise the process is left to run on its own.
+#[cfg(target_os = "windows")]
+fn shell_execute_runas(
+    program: &str,
+    params: &str,
+    wait: bool,
+) -> Result<Option<windows::Win32::Foundation::HANDLE>, String> {
+    use windows::core::{HRESULT, PCWSTR};
+    use windows::Win32::Foundation::ERROR_CANCELLED;
+    use windows::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_DEFAULT, SEE_MASK_NOASYNC, SEE_MASK_NOCLOSEPROCESS,
+        SHELLEXECUTEINFOW,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let verb = wide("runas");
+    let file = wide(program);
+    let params = wide(params);
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: if wait {
+            SEE_MASK_NOCLOSEPROCESS | SEE_MASK_NOASYNC
+        } else {
+            SEE_MASK_DEFAULT | SEE_MASK_NOASYNC
+        },
+        lpVerb: PCWSTR(verb.as_ptr()),
+        lpFile: PCWSTR(file.as_ptr()),
+        lpParameters: PCWSTR(params.as_ptr()),
+        nShow: SW_SHOWNORMAL.0,
+        ..Default::default()
+    };
+
+    unsafe {
+        if let Err(e) = ShellExecuteExW(&mut info) {
+            return Err(if e.code() == HRESULT::from_win32(ERROR_CANCELLED.0) {
+                UAC_DECLINED.to_string()
+            } else {
+                format!("Failed to launch {}: {}", program, e)
+            });
+        }
+    }
+    if !wait {
+        return Ok(None);
+    }
+    if info.hProcess.is_invalid() {
+        return Err(format!("No process handle for {}", program));
+    }
+    Ok(Some(info.hProcess))
+}
+
+/// Run `program` elevated and wait for it to exit. Returns its exit code.
+#[cfg(target_os = "windows")]
+pub fn run_elevated(program: &str, params: &str) -> Result<u32, String> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject, INFINITE};
+
+    let process = shell_execute_runas(program, params, true)?
+        .ok_or_else(|| format!("No process handle for {}", program))?;
+    unsafe {
+        WaitForSingleObject(process, INFINITE);
+        let mut exit_code = 0u32;
+        let result = GetExitCodeProcess(process, &mut exit_code);
+        let _ = CloseHandle(process);
+        result.map_err(|e| format!("Failed to read exit code: {}", e))?;
+        Ok(exit_code)
+    }
+}
+
+/// Start `program` elevated without waiting for it.
+#[cfg(target_os = "windows")]
+pub fn spawn_elevated(program: &str, params: &str) -> Result<(), String> {
+    shell_execute_runas(program, params, false).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn relaunch_keeps_only_forwarded_flags() {
+        assert_eq!(relaunch_params(args(&["--minimized"])), "--minimized");
+        assert_eq!(
+            relaunch_params(args(&["--soak", "--minimized", "60"])),
+            "--minimized"
+        );
+        assert_eq!(relaunch_params(args(&[])), "");
+    }
+
+    #[test]
+    fn permission_errors_are_recognized() {
+        assert!(is_permission_error(
+            "System.UnauthorizedAccessException: Access to the path is denied."
+        ));
+        assert!(is_permission_error("Ring0: Access is denied"));
+        assert!(is_permission_error("PawnIO requires administrator rights"));
+        assert!(!is_permission_error(
+            "Failed to open SMBus: device not found"
+        ));
+    }
+}
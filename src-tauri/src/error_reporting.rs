@@ -1,19 +1,548 @@
-use once_cell::sync::OnceCell;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use schemars::JsonSchema;
+use sentry::protocol::{Breadcrumb, Event};
 use sentry::ClientInitGuard;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-static SENTRY_GUARD: OnceCell<ClientInitGuard> = OnceCell::new();
+static SENTRY_GUARD: Mutex<Option<ClientInitGuard>> = Mutex::new(None);
 
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Initialize Sentry for error reporting
-pub fn init_sentry() {
-    // Get DSN from environment variable (set during build)
-    let dsn = option_env!("SENTRY_DSN").unwrap_or("");
+/// Identical errors from the same source are forwarded at most once per
+/// window. Polling loops can otherwise report the same failure every second,
+/// which adds up to tens of thousands of events a day.
+const CAPTURE_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+static CAPTURE_RATE_LIMITER: Lazy<Mutex<RateLimiter>> =
+    Lazy::new(|| Mutex::new(RateLimiter::new(CAPTURE_RATE_LIMIT_WINDOW)));
+
+#[derive(Debug)]
+struct RateLimitEntry {
+    last_sent: Instant,
+    /// Duplicates dropped since `last_sent`.
+    suppressed: u32,
+}
+
+/// Suppresses duplicate captures keyed by `(source, message hash)`.
+#[derive(Debug)]
+struct RateLimiter {
+    window: Duration,
+    entries: HashMap<(String, u64), RateLimitEntry>,
+}
+
+impl RateLimiter {
+    /// Entries past this count trigger a sweep of expired keys, then
+    /// eviction of the least recently sent ones.
+    const MAX_ENTRIES: usize = 256;
+
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns `None` if the capture should be dropped, otherwise the number
+    /// of duplicates suppressed since this key was last sent.
+    fn check(&mut self, source: &str, message: &str, now: Instant) -> Option<u32> {
+        let mut hasher = DefaultHasher::new();
+        message.hash(&mut hasher);
+        let key = (source.to_string(), hasher.finish());
+
+        if let Some(entry) = self.entries.get_mut(&key) {
+            if now.duration_since(entry.last_sent) < self.window {
+                entry.suppressed = entry.suppressed.saturating_add(1);
+                return None;
+            }
+            let suppressed = entry.suppressed;
+            entry.last_sent = now;
+            entry.suppressed = 0;
+            return Some(suppressed);
+        }
+
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            let window = self.window;
+            self.entries
+                .retain(|_, entry| now.duration_since(entry.last_sent) < window);
+        }
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_sent)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(
+            key,
+            RateLimitEntry {
+                last_sent: now,
+                suppressed: 0,
+            },
+        );
+        Some(0)
+    }
+}
+
+/// Telemetry state reported to the UI.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryStatus {
+    /// The user's `telemetry_enabled` setting.
+    pub enabled: bool,
+    /// Whether error reports are actually being sent right now.
+    pub active: bool,
+    /// Whether this build has a Sentry DSN. Without one nothing is ever sent.
+    pub dsn_configured: bool,
+    /// Human-readable explanation of the above.
+    pub description: String,
+}
+
+/// Redaction rules applied to every outgoing event: `(pattern, replacement)`.
+/// Error strings from the OS and the LHM helper routinely embed profile paths
+/// and drive serials.
+pub const SCRUB_PATTERNS: &[(&str, &str)] = &[
+    // C:\Users\<name>\... (either slash style, any drive letter)
+    (
+        r#"(?i)\b([a-z]:[\\/]+users[\\/]+)[^\\/\s"':]+"#,
+        "${1}<user>",
+    ),
+    // macOS and Linux home directories
+    (r#"/Users/[^/\s"':]+"#, "/Users/<user>"),
+    (r#"/home/[^/\s"':]+"#, "/home/<user>"),
+    // "Serial: WD-WX12...", "serial number = 1234"
+    (
+        r#"(?i)\b(serial(?:[ _]?number)?\s*[:=]\s*)[^\s,;"']+"#,
+        "${1}<serial>",
+    ),
+    // Volume serial numbers as printed by Windows (1A2B-3C4D)
+    (r"\b[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}\b", "<serial>"),
+];
+
+static SCRUB_REGEXES: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    SCRUB_PATTERNS
+        .iter()
+        .map(|(pattern, replacement)| {
+            (
+                Regex::new(pattern).expect("invalid scrub pattern"),
+                *replacement,
+            )
+        })
+        .collect()
+});
+
+/// Machine-specific names that must never leave the PC. Looked up once.
+static LOCAL_IDENTIFIERS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    [
+        ("COMPUTERNAME", "<computer>"),
+        ("HOSTNAME", "<computer>"),
+        ("USERNAME", "<user>"),
+        ("USER", "<user>"),
+    ]
+    .iter()
+    .filter_map(|(var, replacement)| {
+        let value = std::env::var(var).ok()?;
+        local_identifier_regex(&value).map(|re| (re, *replacement))
+    })
+    .collect()
+});
+
+/// Case-insensitive whole-word matcher for a literal name. Very short names
+/// are skipped since they would redact ordinary words.
+fn local_identifier_regex(name: &str) -> Option<Regex> {
+    let name = name.trim();
+    if name.len() < 3 {
+        return None;
+    }
+    Regex::new(&format!(r"(?i)\b{}\b", regex::escape(name))).ok()
+}
+
+/// Redact home directories, serials and local machine/user names.
+pub fn scrub(text: &str) -> String {
+    let mut scrubbed = text.to_string();
+    for (re, replacement) in SCRUB_REGEXES.iter().chain(LOCAL_IDENTIFIERS.iter()) {
+        scrubbed = re.replace_all(&scrubbed, *replacement).into_owned();
+    }
+    scrubbed
+}
+
+fn scrub_value(value: &mut sentry::protocol::Value) {
+    use sentry::protocol::Value;
+    match value {
+        Value::String(s) => *s = scrub(s),
+        Value::Array(items) => items.iter_mut().for_each(scrub_value),
+        Value::Object(map) => map.values_mut().for_each(scrub_value),
+        _ => {}
+    }
+}
+
+/// `before_send` hook: scrub every free-text field of an event.
+fn scrub_event(mut event: Event<'static>) -> Option<Event<'static>> {
+    event.server_name = None;
+    event.user = None;
+    if let Some(message) = event.message.as_mut() {
+        *message = scrub(message);
+    }
+    if let Some(logentry) = event.logentry.as_mut() {
+        logentry.message = scrub(&logentry.message);
+        logentry.params.iter_mut().for_each(scrub_value);
+    }
+    for value in event.tags.values_mut() {
+        *value = scrub(value);
+    }
+    event.extra.values_mut().for_each(scrub_value);
+    for exception in event.exception.values.iter_mut() {
+        if let Some(value) = exception.value.as_mut() {
+            *value = scrub(value);
+        }
+    }
+    for breadcrumb in event.breadcrumbs.values.iter_mut() {
+        if let Some(message) = breadcrumb.message.as_mut() {
+            *message = scrub(message);
+        }
+        breadcrumb.data.values_mut().for_each(scrub_value);
+    }
+    Some(event)
+}
+
+/// Read at runtime too, so self-built copies can report to their own
+/// collector.
+const DSN_ENV_VAR: &str = "SENTRY_DSN";
+
+/// The `sentry_dsn` setting, see `set_dsn_setting`.
+static DSN_SETTING: Mutex<Option<String>> = Mutex::new(None);
+
+/// The first non-empty DSN of the setting, the environment and the one
+/// compiled in. Empty when there is none, which keeps reporting off.
+fn resolve_dsn(setting: Option<&str>, env: Option<&str>, compiled: &str) -> String {
+    [setting, env, Some(compiled)]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|dsn| !dsn.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn dsn() -> String {
+    let setting = DSN_SETTING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    resolve_dsn(
+        setting.as_deref(),
+        std::env::var(DSN_ENV_VAR).ok().as_deref(),
+        // Set during build
+        option_env!("SENTRY_DSN").unwrap_or(""),
+    )
+}
+
+/// Report to `dsn` (the `sentry_dsn` setting) instead of the DSN from the
+/// environment or the build. A running client is restarted to use it.
+pub fn set_dsn_setting(dsn: Option<&str>) {
+    let dsn = dsn.map(str::to_string);
+    {
+        let mut setting = DSN_SETTING.lock().unwrap_or_else(|e| e.into_inner());
+        if *setting == dsn {
+            return;
+        }
+        *setting = dsn;
+    }
+    if sentry_active() {
+        shutdown_sentry();
+        init_sentry();
+    }
+}
+
+/// Whether a Sentry client is running, i.e. the user opted in and the build
+/// has a DSN.
+fn sentry_active() -> bool {
+    SENTRY_GUARD
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some()
+}
+
+/// Current telemetry state for the given `telemetry_enabled` setting.
+pub fn telemetry_status(enabled: bool) -> TelemetryStatus {
+    let dsn_configured = !dsn().is_empty();
+    let active = sentry_active();
+    let description = match (enabled, dsn_configured) {
+        (false, _) => "Error reporting is off. No crash or error data leaves this computer.",
+        (true, false) => {
+            "Error reporting is on, but this build has no reporting endpoint, so nothing is sent."
+        }
+        (true, true) => {
+            "Error reports (error messages, app version, OS) are sent to Sentry to help fix bugs."
+        }
+    };
+    TelemetryStatus {
+        enabled,
+        active,
+        dsn_configured,
+        description: description.to_string(),
+    }
+}
+
+/// Apply the user's opt-in choice: initialize Sentry when enabled, flush
+/// and shut it down when disabled. Safe to call repeatedly.
+pub fn set_telemetry_enabled(enabled: bool) {
+    if enabled {
+        init_sentry();
+    } else {
+        shutdown_sentry();
+    }
+}
+
+/// How long quitting waits for queued error reports. Short, so quitting
+/// stays snappy when the network is slow or down.
+pub const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Send queued events, waiting at most `timeout`. Returns whether all were
+/// sent; true straight away when reporting is off.
+pub fn flush(timeout: Duration) -> bool {
+    let Some(client) = sentry::Hub::main().client() else {
+        return true;
+    };
+    let flushed = client.flush(Some(timeout));
+    if !flushed {
+        crate::log_warn!(
+            "Sentry",
+            "Some error reports were not sent within {:?}",
+            timeout
+        );
+    }
+    flushed
+}
+
+/// Flush pending events and close the client.
+fn shutdown_sentry() {
+    let guard = SENTRY_GUARD
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+    if let Some(guard) = guard {
+        // Dropping the guard flushes and closes the transport
+        drop(guard);
+        sentry::Hub::main().bind_client(None);
+        crate::log_info!("Sentry", "Error reporting disabled");
+    }
+}
+
+/// Local error log, written whether or not Sentry is enabled. There is no
+/// console in release builds, so this is the only place errors end up.
+const ERROR_LOG_FILE: &str = "ondo-errors.log";
+/// Past this size the log is rotated to `ondo-errors.log.1`.
+pub const ERROR_LOG_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Serializes appends and rotation across threads.
+static ERROR_LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// One line of the local error log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorLogEntry {
+    /// ms since epoch.
+    pub timestamp: u64,
+    pub source: String,
+    pub message: String,
+    /// Identical errors dropped by the rate limiter before this one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppressed: Option<u32>,
+}
+
+pub fn error_log_path() -> PathBuf {
+    crate::settings::data_dir().join(ERROR_LOG_FILE)
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+/// Append one JSON line, rotating first if the file is already too large.
+fn append_error_log(path: &Path, entry: &ErrorLogEntry, max_bytes: u64) -> std::io::Result<()> {
+    if fs::metadata(path)
+        .map(|m| m.len() >= max_bytes)
+        .unwrap_or(false)
+    {
+        fs::rename(path, rotated_path(path))?;
+    }
+    let line = serde_json::to_string(entry)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// The last `count` entries across the rotated and current file, oldest
+/// first. Lines that don't parse are skipped.
+fn read_error_log(path: &Path, count: usize) -> Vec<ErrorLogEntry> {
+    let mut entries = VecDeque::with_capacity(count);
+    for file in [rotated_path(path), path.to_path_buf()] {
+        let Ok(file) = fs::File::open(file) else {
+            continue;
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if let Ok(entry) = serde_json::from_str(&line) {
+                if entries.len() == count {
+                    entries.pop_front();
+                }
+                if count > 0 {
+                    entries.push_back(entry);
+                }
+            }
+        }
+    }
+    entries.into()
+}
+
+/// Write a panic to the local log. Skipped if the panic happened while the
+/// log was being written, which would otherwise deadlock.
+fn log_panic_locally(message: &str) {
+    let entry = ErrorLogEntry {
+        timestamp: crate::now_millis(),
+        source: "panic".to_string(),
+        message: message.to_string(),
+        suppressed: None,
+    };
+    if let Ok(_lock) = ERROR_LOG_LOCK.try_lock() {
+        let _ = append_error_log(&error_log_path(), &entry, ERROR_LOG_MAX_BYTES);
+    }
+}
+
+/// The most recent panic as logged, e.g. to say what poisoned a lock.
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn last_panic() -> Option<String> {
+    LAST_PANIC.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Log panics locally, then run the previous hook. Call once at startup,
+/// before Sentry: its panic integration chains onto this hook when the user
+/// opts in, and sends the panic with a backtrace.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info
+            .payload()
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        let location = info
+            .location()
+            .map(|l| format!(" at {}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+        let message = scrub(&format!("{}{}", payload, location));
+        log_panic_locally(&message);
+        *LAST_PANIC.lock().unwrap_or_else(|e| e.into_inner()) = Some(message);
+        previous(info);
+    }));
+}
+
+/// Recent actions attached to error reports, so a report shows what the app
+/// was doing before it.
+const MAX_BREADCRUMBS: usize = 20;
+
+static BREADCRUMBS: Mutex<VecDeque<Breadcrumb>> = Mutex::new(VecDeque::new());
+
+/// Record an action for later error reports. Only the Sentry event uses the
+/// trail, so nothing is kept while reporting is off; turning it on
+/// mid-session starts an empty trail. Messages are scrubbed with the rest of
+/// the event before sending.
+pub fn add_breadcrumb(category: &str, message: &str) {
+    // Without a client the trail would never be sent; don't build it
+    if !sentry_active() {
+        return;
+    }
+    let mut breadcrumbs = BREADCRUMBS.lock().unwrap_or_else(|e| e.into_inner());
+    push_breadcrumb(&mut breadcrumbs, category, message);
+}
+
+fn push_breadcrumb(breadcrumbs: &mut VecDeque<Breadcrumb>, category: &str, message: &str) {
+    if breadcrumbs.len() >= MAX_BREADCRUMBS {
+        breadcrumbs.pop_front();
+    }
+    breadcrumbs.push_back(Breadcrumb {
+        category: Some(category.to_string()),
+        message: Some(message.to_string()),
+        level: sentry::Level::Info,
+        ..Default::default()
+    });
+}
+
+fn recent_breadcrumbs() -> Vec<Breadcrumb> {
+    BREADCRUMBS
+        .lock()
+        .map(|breadcrumbs| breadcrumbs.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// e.g. "Windows 11 Pro 24H2 (26100)". Read once; reading it on Windows
+/// goes to the registry.
+static OS_VERSION: Lazy<Option<String>> = Lazy::new(sysinfo::System::long_os_version);
+
+pub fn os_version() -> Option<String> {
+    OS_VERSION.clone()
+}
+
+fn log_error_locally(error: &str, source: &str, suppressed: u32) {
+    let entry = ErrorLogEntry {
+        timestamp: crate::now_millis(),
+        source: source.to_string(),
+        message: error.to_string(),
+        suppressed: (suppressed > 0).then_some(suppressed),
+    };
+    let _lock = ERROR_LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = append_error_log(&error_log_path(), &entry, ERROR_LOG_MAX_BYTES) {
+        crate::log_warn!("ErrorLog", "Failed to write error log: {}", e);
+    }
+}
+
+/// Size of the current error log file, 0 if there is none.
+pub fn error_log_bytes() -> u64 {
+    fs::metadata(error_log_path()).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Most recent locally logged errors, oldest first.
+pub fn recent_errors(count: usize) -> Vec<ErrorLogEntry> {
+    let _lock = ERROR_LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    read_error_log(&error_log_path(), count)
+}
+
+/// Initialize Sentry for error reporting. Only called once the user has
+/// opted in via `telemetry_enabled`.
+fn init_sentry() {
+    let dsn = dsn();
 
     if dsn.is_empty() {
         crate::log_info!("Sentry", "DSN not configured, error reporting disabled");
         return;
     }
+    // `sentry::init` panics on an invalid DSN
+    let dsn: sentry::types::Dsn = match dsn.parse() {
+        Ok(dsn) => dsn,
+        Err(e) => {
+            crate::log_warn!("Sentry", "Invalid DSN, error reporting disabled: {}", e);
+            return;
+        }
+    };
+
+    let mut slot = SENTRY_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+    if slot.is_some() {
+        return;
+    }
 
     let guard = sentry::init((
         dsn,
@@ -28,24 +557,53 @@ pub fn init_sentry() {
                 .into(),
             ),
             sample_rate: 1.0,
+            max_breadcrumbs: MAX_BREADCRUMBS,
+            // Defaults to the hostname
+            server_name: None,
+            before_send: Some(Arc::new(scrub_event)),
             ..Default::default()
         },
     ));
 
-    if SENTRY_GUARD.set(guard).is_err() {
-        crate::log_warn!("Sentry", "Already initialized");
-    } else {
-        crate::log_info!("Sentry", "Initialized successfully");
-    }
+    *slot = Some(guard);
+    crate::log_info!("Sentry", "Initialized successfully");
 }
 
 /// Capture an error with context
 pub fn capture_error(error: &str, source: &str, extra: Option<&[(&str, &str)]>) {
+    let suppressed = match CAPTURE_RATE_LIMITER.lock() {
+        Ok(mut limiter) => {
+            let suppressed = limiter.check(source, error, Instant::now());
+            crate::metrics::RATE_LIMITER_ENTRIES.set(limiter.entries.len());
+            suppressed
+        }
+        Err(_) => Some(0),
+    };
+    let Some(suppressed) = suppressed else {
+        return;
+    };
+    log_error_locally(error, source, suppressed);
+    // Without a client the event would be dropped anyway; don't build it
+    if !sentry_active() {
+        return;
+    }
+    let cpu_model = crate::monitor_pause::last_cpu_name();
+
     sentry::with_scope(
         |scope| {
             scope.set_tag("source", source);
             scope.set_tag("platform", std::env::consts::OS);
             scope.set_tag("version", APP_VERSION);
+            if let Some(os_version) = OS_VERSION.as_deref() {
+                scope.set_tag("os_version", os_version);
+            }
+            if let Some(cpu_model) = &cpu_model {
+                scope.set_tag("cpu_model", cpu_model);
+            }
+            if suppressed > 0 {
+                // This event plus the duplicates dropped since the last one
+                scope.set_extra("occurrences", (suppressed + 1).into());
+            }
 
             if let Some(extras) = extra {
                 for (key, value) in extras {
@@ -54,7 +612,12 @@ pub fn capture_error(error: &str, source: &str, extra: Option<&[(&str, &str)]>)
             }
         },
         || {
-            sentry::capture_message(error, sentry::Level::Error);
+            sentry::capture_event(Event {
+                message: Some(error.to_string()),
+                level: sentry::Level::Error,
+                breadcrumbs: recent_breadcrumbs().into(),
+                ..Default::default()
+            });
         },
     );
 }
@@ -86,8 +649,227 @@ pub fn capture_window_error(error: &str, operation: &str) {
     );
 }
 
+/// Capture an update check or install error
+pub fn capture_updater_error(error: &str, operation: &str) {
+    capture_error(
+        &format!("[Updater] {}", error),
+        "updater",
+        Some(&[("operation", operation)]),
+    );
+}
+
 /// Capture an LHM daemon error (Windows only)
 #[cfg(target_os = "windows")]
 pub fn capture_lhm_error(error: &str) {
     capture_error(&format!("[LHM] {}", error), "lhm_daemon", None);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_breadcrumb, append_error_log, flush, local_identifier_regex, push_breadcrumb,
+        read_error_log, recent_breadcrumbs, resolve_dsn, rotated_path, scrub, ErrorLogEntry,
+        RateLimiter, MAX_BREADCRUMBS, SCRUB_PATTERNS,
+    };
+    use regex::Regex;
+    use std::collections::VecDeque;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    fn temp_log(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ondo-test-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("ondo-errors.log")
+    }
+
+    fn entry(n: u64) -> ErrorLogEntry {
+        ErrorLogEntry {
+            timestamp: n,
+            source: "hardware".to_string(),
+            message: format!("error {}", n),
+            suppressed: None,
+        }
+    }
+
+    #[test]
+    fn error_log_returns_most_recent_entries() {
+        let path = temp_log("recent");
+        for n in 0..5 {
+            append_error_log(&path, &entry(n), 1024 * 1024).unwrap();
+        }
+        let recent = read_error_log(&path, 2);
+        assert_eq!(recent, vec![entry(3), entry(4)]);
+        assert_eq!(read_error_log(&path, 10).len(), 5);
+        assert!(read_error_log(&path, 0).is_empty());
+    }
+
+    #[test]
+    fn error_log_rotates_past_size_cap() {
+        let path = temp_log("rotate");
+        // Each line is well over 20 bytes, so every append after the first rotates
+        for n in 0..3 {
+            append_error_log(&path, &entry(n), 20).unwrap();
+        }
+        let current = std::fs::read_to_string(&path).unwrap();
+        let rotated = std::fs::read_to_string(rotated_path(&path)).unwrap();
+        assert_eq!(current.lines().count(), 1);
+        assert_eq!(rotated.lines().count(), 1);
+
+        // Reads span both files; entry 0 was dropped by the second rotation
+        assert_eq!(read_error_log(&path, 10), vec![entry(1), entry(2)]);
+    }
+
+    #[test]
+    fn error_log_skips_corrupt_lines() {
+        let path = temp_log("corrupt");
+        std::fs::write(&path, "not json\n").unwrap();
+        append_error_log(&path, &entry(7), 1024 * 1024).unwrap();
+        assert_eq!(read_error_log(&path, 10), vec![entry(7)]);
+    }
+
+    #[test]
+    fn duplicate_captures_are_rate_limited() {
+        let mut limiter = RateLimiter::new(Duration::from_secs(60));
+        let start = Instant::now();
+
+        let forwarded = (0..100)
+            .filter(|i| {
+                let now = start + Duration::from_millis(*i * 10);
+                limiter
+                    .check("Hardware", "LHM daemon exited", now)
+                    .is_some()
+            })
+            .count();
+        assert_eq!(forwarded, 1);
+
+        // Next capture after the window carries the suppressed count
+        let later = start + Duration::from_secs(61);
+        assert_eq!(
+            limiter.check("Hardware", "LHM daemon exited", later),
+            Some(99)
+        );
+        assert_eq!(limiter.check("Hardware", "LHM daemon exited", later), None);
+    }
+
+    #[test]
+    fn rate_limiter_evicts_least_recently_sent_when_full() {
+        let mut limiter = RateLimiter::new(Duration::from_secs(60));
+        let start = Instant::now();
+        for i in 0..RateLimiter::MAX_ENTRIES as u64 + 10 {
+            let now = start + Duration::from_millis(i);
+            assert_eq!(limiter.check("Hardware", &i.to_string(), now), Some(0));
+        }
+        assert_eq!(limiter.entries.len(), RateLimiter::MAX_ENTRIES);
+
+        // The oldest keys were dropped and count as new again; recent ones
+        // are still suppressed
+        let now = start + Duration::from_secs(1);
+        assert_eq!(limiter.check("Hardware", "0", now), Some(0));
+        assert_eq!(
+            limiter.check("Hardware", &(RateLimiter::MAX_ENTRIES + 9).to_string(), now),
+            None
+        );
+    }
+
+    #[test]
+    fn breadcrumbs_keep_only_the_most_recent_actions() {
+        let mut breadcrumbs = VecDeque::new();
+        for n in 0..MAX_BREADCRUMBS + 5 {
+            push_breadcrumb(&mut breadcrumbs, "test", &format!("action {}", n));
+        }
+        assert_eq!(breadcrumbs.len(), MAX_BREADCRUMBS);
+        let messages: Vec<String> = breadcrumbs.into_iter().filter_map(|b| b.message).collect();
+        assert_eq!(messages.first(), Some(&"action 5".to_string()));
+        assert_eq!(
+            messages.last(),
+            Some(&format!("action {}", MAX_BREADCRUMBS + 4))
+        );
+    }
+
+    #[test]
+    fn breadcrumbs_are_not_kept_while_reporting_is_off() {
+        add_breadcrumb("test", "action");
+        assert!(recent_breadcrumbs()
+            .iter()
+            .all(|b| b.category.as_deref() != Some("test")));
+    }
+
+    #[test]
+    fn rate_limit_is_per_source_and_message() {
+        let mut limiter = RateLimiter::new(Duration::from_secs(60));
+        let now = Instant::now();
+        assert_eq!(limiter.check("Hardware", "a", now), Some(0));
+        assert_eq!(limiter.check("Hardware", "b", now), Some(0));
+        assert_eq!(limiter.check("Settings", "a", now), Some(0));
+        assert_eq!(limiter.check("Hardware", "a", now), None);
+    }
+
+    #[test]
+    fn all_patterns_compile() {
+        for (pattern, _) in SCRUB_PATTERNS {
+            assert!(Regex::new(pattern).is_ok(), "bad pattern: {}", pattern);
+        }
+    }
+
+    #[test]
+    fn windows_profile_path_username_is_replaced() {
+        let scrubbed = scrub(r"Failed to read C:\Users\john\AppData\Roaming\Ondo\settings.json");
+        assert_eq!(
+            scrubbed,
+            r"Failed to read C:\Users\<user>\AppData\Roaming\Ondo\settings.json"
+        );
+        assert!(!scrub("d:/users/john/file.txt").contains("john"));
+    }
+
+    #[test]
+    fn unix_home_directories_are_replaced() {
+        assert_eq!(scrub("/Users/jane/Library/x"), "/Users/<user>/Library/x");
+        assert_eq!(scrub("/home/jane/.config"), "/home/<user>/.config");
+    }
+
+    #[test]
+    fn serials_are_replaced() {
+        assert_eq!(
+            scrub("Drive Serial: WD-WX12AB34 failed"),
+            "Drive Serial: <serial> failed"
+        );
+        assert_eq!(scrub("serial_number=S4EWNX0R"), "serial_number=<serial>");
+        assert_eq!(scrub("Volume 1A2B-3C4D"), "Volume <serial>");
+    }
+
+    #[test]
+    fn local_names_match_whole_words_only() {
+        let re = local_identifier_regex("GAMING-PC").unwrap();
+        assert_eq!(
+            re.replace_all("host gaming-pc down", "<computer>"),
+            "host <computer> down"
+        );
+        assert_eq!(re.replace_all("gaming-pcs", "<computer>"), "gaming-pcs");
+        assert!(local_identifier_regex("ab").is_none());
+    }
+
+    #[test]
+    fn unrelated_text_is_untouched() {
+        let message = "[Hardware] Both CPU and GPU data unavailable: timeout";
+        assert_eq!(scrub(message), message);
+    }
+
+    #[test]
+    fn flush_without_a_client_returns_at_once() {
+        let start = Instant::now();
+        assert!(flush(Duration::from_secs(5)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn dsn_setting_beats_environment_beats_build() {
+        let build = "https://key@build.example/1";
+        let env = "https://key@env.example/2";
+        let setting = "https://key@mine.example/3";
+        assert_eq!(resolve_dsn(Some(setting), Some(env), build), setting);
+        assert_eq!(resolve_dsn(None, Some(env), build), env);
+        assert_eq!(resolve_dsn(Some("  "), Some(""), build), build);
+        assert_eq!(resolve_dsn(None, None, ""), "");
+    }
+}
@@ -3,25 +3,33 @@ use crate::{
     NetworkInterfaceData, StorageData,
 };
 
+use crate::sensor_owner::{self, SensorRole};
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[cfg(target_os = "windows")]
-use crate::FanData;
+#[cfg(any(target_os = "windows", test))]
+use crate::{
+    CpuPackageData, FanData, FanUnit, NamedTemperature, PartitionData, SensorError, VoltageData,
+};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[cfg(target_os = "windows")]
 use crate::error_reporting;
 
-#[cfg(target_os = "windows")]
+use crate::settings::{ComponentThresholds, SensorOverride, Thresholds};
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Mutex;
 
 #[cfg(target_os = "windows")]
 use serde::Deserialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LhmDaemonStatus {
     pub supported: bool,
+    /// `viewer` when another session's Ondo owns the sensors and this
+    /// instance deliberately runs without a daemon.
+    pub sensor_role: SensorRole,
     pub running: bool,
     pub pid: Option<u32>,
     pub has_cached_data: bool,
@@ -29,17 +37,123 @@ pub struct LhmDaemonStatus {
     pub error: Option<String>,
 }
 
+/// First line printed by `ondo-hwmon.exe --daemon`, before any data.
+#[derive(Debug, Clone, Serialize, serde::Deserialize, JsonSchema, PartialEq)]
+pub struct LhmHandshake {
+    pub protocol: u32,
+    pub version: Option<String>,
+}
+
+/// Version of the daemon's JSON line format this build understands. Bump
+/// together with `ProtocolVersion` in src-lhm/Program.cs whenever a field is
+/// renamed or removed.
+#[cfg(any(target_os = "windows", test))]
+const LHM_PROTOCOL: u32 = 1;
+
+/// Parse the daemon's first stdout line. Helpers that predate the handshake
+/// start straight with a data line, which is reported as protocol 0.
+#[cfg(any(target_os = "windows", test))]
+fn parse_lhm_handshake(line: &str) -> Result<LhmHandshake, String> {
+    let value: serde_json::Value = serde_json::from_str(line)
+        .map_err(|e| format!("Hardware monitor helper sent invalid output: {}", e))?;
+    if value.get("protocol").is_none() {
+        return Ok(LhmHandshake {
+            protocol: 0,
+            version: None,
+        });
+    }
+    serde_json::from_value(value)
+        .map_err(|e| format!("Hardware monitor helper sent an invalid handshake: {}", e))
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn check_lhm_handshake(handshake: &LhmHandshake) -> Result<(), String> {
+    if handshake.protocol == LHM_PROTOCOL {
+        return Ok(());
+    }
+    Err(format!(
+        "Hardware monitor helper is version {} (protocol {}) but Ondo {} expects protocol {}",
+        handshake.version.as_deref().unwrap_or("unknown"),
+        handshake.protocol,
+        env!("CARGO_PKG_VERSION"),
+        LHM_PROTOCOL
+    ))
+}
+
 // LHM JSON response structures
 #[cfg(target_os = "windows")]
 #[derive(Deserialize, Debug, Clone)]
 struct LhmResponse {
     cpu: Option<LhmCpuData>,
+    /// Every socket, when there is more than one; `cpu` is the first.
+    /// Older helpers don't send it
+    #[serde(default)]
+    cpu_packages: Option<Vec<LhmCpuData>>,
     gpu: Option<LhmGpuData>,
     storage: Option<Vec<LhmStorageData>>,
     motherboard: Option<LhmMotherboardData>,
+    /// Why a section is missing or incomplete; older helpers don't send them
+    #[serde(default)]
+    cpu_error: Option<LhmSensorError>,
+    #[serde(default)]
+    gpu_error: Option<LhmSensorError>,
+}
+
+#[cfg(any(target_os = "windows", test))]
+#[derive(Deserialize, Debug, Clone)]
+struct LhmSensorError {
+    code: String,
+    message: String,
+}
+
+#[cfg(any(target_os = "windows", test))]
+impl LhmSensorError {
+    fn into_sensor_error(self) -> SensorError {
+        SensorError {
+            code: self.code,
+            message: self.message,
+            source: "lhm".to_string(),
+        }
+    }
 }
 
+/// The `cpuError`/`gpuError` string for a `SensorError`.
 #[cfg(target_os = "windows")]
+fn error_message(error: &Option<SensorError>) -> Option<String> {
+    error.as_ref().map(|e| e.message.clone())
+}
+
+/// CPU and GPU errors for a sample from the sysinfo fallback. Without the
+/// helper there are no CPU temperatures at all, and a GPU only when
+/// nvidia-smi or rocm-smi finds one; an incompatible helper is the reason
+/// for both.
+#[cfg(any(target_os = "windows", test))]
+fn fallback_errors(
+    incompatible: Option<SensorError>,
+    has_cpu: bool,
+    gpu_missing: bool,
+) -> (Option<SensorError>, Option<SensorError>) {
+    let cpu = has_cpu.then(|| {
+        SensorError::new(
+            "sysinfo_no_temperature",
+            "CPU temperatures need the LibreHardwareMonitor helper; showing load and clocks only",
+            "sysinfo",
+        )
+    });
+    let gpu = gpu_missing.then(|| {
+        SensorError::new(
+            "sysinfo_no_gpu",
+            "No GPU found by nvidia-smi or rocm-smi",
+            "sysinfo",
+        )
+    });
+    match incompatible {
+        Some(error) => (Some(error.clone()), Some(error)),
+        None => (cpu, gpu),
+    }
+}
+
+#[cfg(any(target_os = "windows", test))]
 #[derive(Deserialize, Debug, Clone)]
 struct LhmCpuData {
     name: String,
@@ -48,14 +162,97 @@ struct LhmCpuData {
     load: f32,
     frequency: f32,
     cores: Option<Vec<LhmCpuCoreData>>,
+    #[serde(default)]
+    power: Option<f32>,
+    #[serde(default)]
+    voltage: Option<f32>,
+    /// Set by `merge_cpu_packages`, not the helper.
+    #[serde(skip)]
+    packages: Option<Vec<CpuPackageData>>,
 }
 
-#[cfg(target_os = "windows")]
+#[cfg(any(target_os = "windows", test))]
 #[derive(Deserialize, Debug, Clone)]
 struct LhmCpuCoreData {
     index: u32,
     temperature: f32,
     load: f32,
+    /// GHz; older helpers don't send it
+    #[serde(default)]
+    frequency: Option<f32>,
+    /// Set by `merge_cpu_packages`, not the helper.
+    #[serde(skip)]
+    package: Option<u32>,
+}
+
+/// One CPU made of the sockets of a multi-socket machine: the hottest
+/// package temperature against the lowest limit, the average load and
+/// clock, the total power, and every core numbered on from the previous
+/// socket's. The sockets themselves go into `packages`.
+#[cfg(any(target_os = "windows", test))]
+fn merge_cpu_packages(packages: Vec<LhmCpuData>) -> Option<LhmCpuData> {
+    let first = packages.first()?.clone();
+    if packages.len() == 1 {
+        return Some(first);
+    }
+    let count = packages.len() as f32;
+    let average = |value: fn(&LhmCpuData) -> f32| {
+        let values: Vec<f32> = packages.iter().map(value).filter(|v| *v > 0.0).collect();
+        if values.is_empty() {
+            0.0
+        } else {
+            values.iter().sum::<f32>() / values.len() as f32
+        }
+    };
+    let name = if packages.iter().all(|p| p.name == first.name) {
+        format!("{} × {}", packages.len(), first.name)
+    } else {
+        packages
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" + ")
+    };
+
+    let mut cores = Vec::new();
+    for (package, cpu) in packages.iter().enumerate() {
+        let offset = cores.len() as u32;
+        cores.extend(cpu.cores.iter().flatten().map(|core| LhmCpuCoreData {
+            index: offset + core.index,
+            package: Some(package as u32),
+            ..core.clone()
+        }));
+    }
+    let powers: Vec<f32> = packages.iter().filter_map(|p| p.power).collect();
+
+    Some(LhmCpuData {
+        name,
+        temperature: packages.iter().map(|p| p.temperature).fold(0.0, f32::max),
+        max_temperature: packages
+            .iter()
+            .map(|p| p.max_temperature)
+            .filter(|t| *t > 0.0)
+            .reduce(f32::min)
+            .unwrap_or(first.max_temperature),
+        load: packages.iter().map(|p| p.load).sum::<f32>() / count,
+        frequency: average(|p| p.frequency),
+        cores: Some(cores),
+        power: (!powers.is_empty()).then(|| powers.iter().sum()),
+        voltage: packages.iter().find_map(|p| p.voltage),
+        packages: Some(
+            packages
+                .iter()
+                .enumerate()
+                .map(|(index, p)| CpuPackageData {
+                    index: index as u32,
+                    name: p.name.clone(),
+                    temperature: p.temperature,
+                    load: p.load,
+                    power: p.power,
+                })
+                .collect(),
+        ),
+    })
 }
 
 #[cfg(target_os = "windows")]
@@ -68,6 +265,16 @@ struct LhmGpuData {
     frequency: f32,
     memory_used: f32,
     memory_total: f32,
+    #[serde(default)]
+    power: Option<f32>,
+    #[serde(default)]
+    power_limit: Option<f32>,
+    #[serde(default)]
+    hotspot_temperature: Option<f32>,
+    #[serde(default)]
+    memory_temperature: Option<f32>,
+    #[serde(default)]
+    fans: Option<Vec<LhmFanData>>,
 }
 
 #[cfg(target_os = "windows")]
@@ -77,6 +284,25 @@ struct LhmStorageData {
     temperature: f32,
     used_percent: f32,
     total_space: f32,
+    #[serde(default)]
+    health_percent: Option<f32>,
+    #[serde(default)]
+    data_written_tb: Option<f32>,
+    #[serde(default)]
+    read_rate: Option<f64>,
+    #[serde(default)]
+    write_rate: Option<f64>,
+    /// Older helpers don't send them
+    #[serde(default)]
+    partitions: Option<Vec<LhmPartitionData>>,
+}
+
+#[cfg(any(target_os = "windows", test))]
+#[derive(Deserialize, Debug, Clone)]
+struct LhmPartitionData {
+    mount_point: String,
+    used_space: f32,
+    total_space: f32,
 }
 
 #[cfg(target_os = "windows")]
@@ -85,44 +311,425 @@ struct LhmMotherboardData {
     name: String,
     temperature: f32,
     fans: Option<Vec<LhmFanData>>,
+    /// Older helpers don't send them
+    #[serde(default)]
+    voltages: Option<Vec<LhmVoltageData>>,
+    #[serde(default)]
+    temperatures: Option<Vec<LhmTemperatureData>>,
 }
 
-#[cfg(target_os = "windows")]
+#[cfg(any(target_os = "windows", test))]
 #[derive(Deserialize, Debug, Clone)]
 struct LhmFanData {
     name: String,
     speed: u32,
+    /// Older helpers only send RPM
+    #[serde(default)]
+    unit: FanUnit,
+}
+
+#[cfg(any(target_os = "windows", test))]
+#[derive(Deserialize, Debug, Clone)]
+struct LhmVoltageData {
+    name: String,
+    volts: f32,
+}
+
+#[cfg(any(target_os = "windows", test))]
+#[derive(Deserialize, Debug, Clone)]
+struct LhmTemperatureData {
+    name: String,
+    temperature: f32,
+}
+
+/// Fans as the daemon reports them. Stopped fans are kept: a card in
+/// fan-stop mode reads 0 RPM, which is worth showing.
+#[cfg(any(target_os = "windows", test))]
+fn lhm_fans(fans: Option<Vec<LhmFanData>>) -> Vec<FanData> {
+    fans.unwrap_or_default()
+        .into_iter()
+        .map(|f| FanData {
+            name: f.name,
+            speed: f.speed,
+            unit: f.unit,
+            stopped: f.speed == 0,
+        })
+        .collect()
+}
+
+/// Whether a voltage sensor is one of the PSU's +12V, +5V or +3.3V rails,
+/// as Super I/O chips name them ("+12V", "12V", "+3.3V", "3VCC" and such).
+fn is_psu_rail(name: &str) -> bool {
+    let name = name.trim().trim_start_matches('+').to_ascii_lowercase();
+    ["12v", "5v", "3.3v", "3vcc", "5vcc", "12vcc"]
+        .iter()
+        .any(|rail| name == *rail || name.starts_with(&format!("{} ", rail)))
+}
+
+/// Voltages as the daemon reports them; `normalize_hardware_data` puts
+/// them in order. Readings of 0 or below are unconnected inputs and are
+/// dropped.
+#[cfg(any(target_os = "windows", test))]
+fn lhm_voltages(voltages: Option<Vec<LhmVoltageData>>) -> Vec<VoltageData> {
+    voltages
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|v| v.volts.is_finite() && v.volts > 0.0)
+        .map(|v| VoltageData {
+            name: v.name,
+            volts: v.volts,
+        })
+        .collect()
+}
+
+/// Board temperatures as the daemon reports them, minus readings outside
+/// 0..150°C, which are unconnected thermistor inputs.
+/// `normalize_hardware_data` puts them in order.
+#[cfg(any(target_os = "windows", test))]
+fn lhm_temperatures(temperatures: Option<Vec<LhmTemperatureData>>) -> Vec<NamedTemperature> {
+    temperatures
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|t| t.temperature > 0.0 && t.temperature < 150.0)
+        .map(|t| NamedTemperature {
+            name: t.name,
+            temperature: t.temperature,
+        })
+        .collect()
+}
+
+/// A drive's volumes as the daemon reports them. Network shares (UNC
+/// paths) and volumes without a size are left out; the daemon already
+/// skips mapped network drives.
+#[cfg(any(target_os = "windows", test))]
+fn lhm_partitions(partitions: Vec<LhmPartitionData>) -> Vec<PartitionData> {
+    partitions
+        .into_iter()
+        .filter(|p| p.total_space > 0.0 && !p.mount_point.starts_with("\\\\"))
+        .map(|p| PartitionData {
+            mount_point: p.mount_point,
+            used_space: p.used_space,
+            total_space: p.total_space,
+        })
+        .collect()
 }
 
 // LHM daemon process state
+#[cfg(any(target_os = "windows", test))]
+use std::collections::VecDeque;
 #[cfg(target_os = "windows")]
 use std::io::{BufRead, BufReader};
 #[cfg(target_os = "windows")]
-use std::process::{Child, ChildStdout};
+use std::process::{Child, ChildStderr, ChildStdout};
+#[cfg(target_os = "windows")]
+use std::sync::Arc;
+
+/// A parsed daemon line and when it arrived.
+#[cfg(target_os = "windows")]
+struct LhmSample {
+    data: LhmResponse,
+    received: std::time::Instant,
+}
+
+#[cfg(target_os = "windows")]
+type LatestLhmSlot = Arc<Mutex<Option<LhmSample>>>;
 
 #[cfg(target_os = "windows")]
 struct LhmDaemon {
     process: Child,
-    reader: BufReader<ChildStdout>,
-    latest_data: Option<LhmResponse>,
+    /// `--daemon` interval this process was spawned with.
+    interval_ms: u32,
+    /// How often it reads storage and the motherboard. Changed through its
+    /// stdin, see `set_slow_interval`.
+    slow_interval_ms: u32,
+    /// Sections it reads; it starts with all of them. Changed through its
+    /// stdin, see `set_monitored_sections`.
+    sections: MonitoredSections,
+    /// When the sampler took over the process; the data age before the
+    /// first sample.
+    started: std::time::Instant,
+    /// Newest successfully parsed sample, written by the stdout reader thread.
+    latest: LatestLhmSlot,
+    reader_thread: Option<std::thread::JoinHandle<()>>,
+    stderr_thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(target_os = "windows")]
+impl LhmDaemon {
+    fn latest_data(&self) -> Option<LhmResponse> {
+        self.latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|sample| sample.data.clone())
+    }
+
+    /// Time since the last parsed sample.
+    fn data_age(&self) -> std::time::Duration {
+        self.latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map_or(self.started, |sample| sample.received)
+            .elapsed()
+    }
+
+    /// Tell the running helper to read storage and the motherboard every
+    /// `interval_ms` from now on. Not retried when it fails.
+    fn reconfigure_slow_interval(&mut self, interval_ms: u32) -> std::io::Result<()> {
+        self.slow_interval_ms = interval_ms;
+        self.send_command(&format!("slow {}", interval_ms))
+    }
+
+    /// Tell the running helper to only update and read `sections` from now
+    /// on. Not retried when it fails.
+    fn reconfigure_sections(&mut self, sections: MonitoredSections) -> std::io::Result<()> {
+        self.sections = sections;
+        self.send_command(&sections.lhm_command())
+    }
+
+    fn send_command(&mut self, line: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let stdin = self
+            .process
+            .stdin
+            .as_mut()
+            .ok_or_else(|| std::io::Error::other("stdin is not piped"))?;
+        writeln!(stdin, "{}", line)?;
+        stdin.flush()
+    }
+
+    /// Kill the helper and wait for the reader threads, which exit on their
+    /// own once the stdout/stderr pipes close.
+    fn stop(mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        if let Some(handle) = self.reader_thread.take() {
+            let _ = handle.join();
+        }
+        if let Some(handle) = self.stderr_thread.take() {
+            let _ = handle.join();
+        }
+    }
 }
 
 #[cfg(target_os = "windows")]
 static LHM_DAEMON: Mutex<Option<LhmDaemon>> = Mutex::new(None);
 
-/// Check whether the LHM daemon's stdout pipe has unread data.
-/// Uses PeekNamedPipe because PIPE_NOWAIT may not work reliably on
-/// anonymous pipes.
+/// Number of stderr lines kept from the LHM daemon.
+#[cfg(any(target_os = "windows", test))]
+const LHM_STDERR_MAX_LINES: usize = 50;
+/// Longer lines (e.g. a .NET stack trace on one line) are truncated.
+#[cfg(any(target_os = "windows", test))]
+const LHM_STDERR_MAX_LINE_LEN: usize = 512;
+
+/// Bounded tail of the daemon's stderr. Oldest lines are dropped first.
+#[cfg(any(target_os = "windows", test))]
+struct StderrTail {
+    lines: VecDeque<String>,
+}
+
+#[cfg(any(target_os = "windows", test))]
+impl StderrTail {
+    const fn new() -> Self {
+        Self {
+            lines: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, line: &str) {
+        let mut line = line.to_string();
+        if line.len() > LHM_STDERR_MAX_LINE_LEN {
+            let mut end = LHM_STDERR_MAX_LINE_LEN;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            line.truncate(end);
+        }
+        if self.lines.len() == LHM_STDERR_MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    fn to_vec(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+}
+
+/// stderr of the current (or most recently exited) daemon. Kept outside
+/// `LhmDaemon` so it survives the process being reaped.
+#[cfg(target_os = "windows")]
+static LHM_STDERR: Mutex<StderrTail> = Mutex::new(StderrTail::new());
+
+/// The current daemon logged an access-denied error, so some sensors are
+/// missing until Ondo runs as administrator.
+#[cfg(target_os = "windows")]
+static LHM_ACCESS_DENIED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Set while monitoring is paused from the tray: the daemon is stopped and
+/// not respawned by `get_lhm_data`.
+#[cfg(target_os = "windows")]
+static LHM_SUSPENDED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Error message recorded the last time the daemon exited on its own.
+#[cfg(target_os = "windows")]
+static LHM_LAST_EXIT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Handshake of the most recently started daemon.
+#[cfg(target_os = "windows")]
+static LHM_HELPER: Mutex<Option<LhmHandshake>> = Mutex::new(None);
+
+/// Set when the installed helper speaks an unsupported protocol. The helper
+/// won't change while the app runs, so it is not respawned after this, and
+/// the message is surfaced through `cpu_error`/`gpu_error`.
+#[cfg(target_os = "windows")]
+static LHM_INCOMPATIBLE: Mutex<Option<String>> = Mutex::new(None);
+
+#[cfg(target_os = "windows")]
+fn lhm_incompatible_error() -> Option<String> {
+    LHM_INCOMPATIBLE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Requested sampling interval for `ondo-hwmon.exe --daemon`. Mirrors the
+/// user's `update_interval_fast` setting; the running daemon keeps the value it
+/// was spawned with in `LhmDaemon::interval_ms`.
+#[cfg(target_os = "windows")]
+static LHM_INTERVAL_MS: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(1000);
+
+/// Shortest silence after which a running daemon counts as stalled.
+#[cfg(any(target_os = "windows", test))]
+const LHM_STALL_AFTER: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Whether a daemon that is still running but last sent data `age` ago has
+/// stopped producing it. Slow intervals get three missed samples of slack.
+#[cfg(any(target_os = "windows", test))]
+fn lhm_is_stalled(age: std::time::Duration, interval_ms: u32) -> bool {
+    age > LHM_STALL_AFTER.max(std::time::Duration::from_millis(u64::from(interval_ms) * 3))
+}
+
+/// Whether a daemon spawned with `running_ms` has to be restarted to honour
+/// `requested_ms`. No running daemon means nothing to restart: the next
+/// poll spawns one with the requested interval anyway.
+#[cfg(any(target_os = "windows", test))]
+fn lhm_interval_needs_restart(running_ms: Option<u32>, requested_ms: u32) -> bool {
+    running_ms.is_some_and(|running| running != requested_ms)
+}
+
+/// Blocking reader for the daemon's stdout. Runs on its own thread so the
+/// sampling path never touches the pipe: each complete line is parsed and
+/// stored as the latest value. A partial final line at EOF (daemon killed
+/// mid-write) is discarded rather than parsed.
+///
+/// The first line is the handshake and is sent to `handshake_tx` instead.
+/// A legacy helper's first line is data, so it is also parsed as a sample.
+#[cfg(target_os = "windows")]
+fn read_lhm_stdout(
+    stdout: ChildStdout,
+    latest: LatestLhmSlot,
+    handshake_tx: std::sync::mpsc::Sender<Result<LhmHandshake, String>>,
+) {
+    let mut reader = BufReader::new(stdout);
+    let mut line = String::new();
+    let mut handshake_tx = Some(handshake_tx);
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => break, // EOF - daemon exited or was killed
+            Ok(_) => {
+                if !line.ends_with('\n') {
+                    break;
+                }
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                if let Some(tx) = handshake_tx.take() {
+                    let handshake = parse_lhm_handshake(trimmed);
+                    let is_data = matches!(&handshake, Ok(h) if h.protocol == 0);
+                    let _ = tx.send(handshake);
+                    if !is_data {
+                        continue;
+                    }
+                }
+                match serde_json::from_str::<LhmResponse>(trimmed) {
+                    Ok(data) => {
+                        *latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(LhmSample {
+                            data,
+                            received: std::time::Instant::now(),
+                        });
+                    }
+                    Err(e) => {
+                        crate::log_warn!("Hardware", "Failed to parse LHM JSON: {}", e);
+                    }
+                }
+            }
+            Err(e) => {
+                crate::log_error!("Hardware", "Failed to read from LHM daemon: {}", e);
+                break;
+            }
+        }
+    }
+    crate::log_debug!("Hardware", "LHM stdout reader exited");
+}
+
+/// Blocking reader for the daemon's stderr. Lines go into `LHM_STDERR` so
+/// they can be attached to crash reports.
+#[cfg(target_os = "windows")]
+fn read_lhm_stderr(stderr: ChildStderr) {
+    let reader = BufReader::new(stderr);
+    for line in reader.lines() {
+        match line {
+            Ok(line) => {
+                let trimmed = line.trim_end();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                crate::log_debug!("Hardware", "LHM stderr: {}", trimmed);
+                if crate::elevation::is_permission_error(trimmed) {
+                    LHM_ACCESS_DENIED.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+                LHM_STDERR
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(trimmed);
+            }
+            Err(_) => break,
+        }
+    }
+}
+
+/// Record an unexpected daemon exit: build an error message carrying the
+/// stderr tail, log it and report it. Call after `LhmDaemon::stop` so the
+/// stderr reader has drained the pipe.
 #[cfg(target_os = "windows")]
-fn pipe_has_data(reader: &BufReader<ChildStdout>) -> bool {
-    use std::os::windows::io::AsRawHandle;
-    use windows::Win32::Foundation::HANDLE;
-    use windows::Win32::System::Pipes::PeekNamedPipe;
+fn report_lhm_exit(reason: &str) {
+    error_reporting::add_breadcrumb("lhm", reason);
+    let message = with_stderr_tail(reason);
+    crate::log_warn!("Hardware", "{}", message);
+    error_reporting::capture_lhm_error(&message);
+    *LHM_LAST_EXIT.lock().unwrap_or_else(|e| e.into_inner()) = Some(message);
+}
 
-    let handle = HANDLE(reader.get_ref().as_raw_handle());
-    let mut bytes_available: u32 = 0;
-    let ok = unsafe { PeekNamedPipe(handle, None, 0, None, Some(&mut bytes_available), None) };
-    ok.is_ok() && bytes_available > 0
+#[cfg(target_os = "windows")]
+fn with_stderr_tail(reason: &str) -> String {
+    let tail = LHM_STDERR
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .to_vec();
+    if tail.is_empty() {
+        format!("{} (no stderr output)", reason)
+    } else {
+        format!("{}; stderr:\n{}", reason, tail.join("\n"))
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -134,6 +741,59 @@ struct NetworkMonitor {
 #[cfg(target_os = "windows")]
 static NETWORK_MONITOR: Mutex<Option<NetworkMonitor>> = Mutex::new(None);
 
+/// sysinfo disk list kept between samples, like `NetworkMonitor`.
+/// `Disks::refresh` still picks up volumes that appear or disappear, but
+/// keeps the existing entries instead of enumerating from scratch.
+#[cfg(target_os = "windows")]
+struct DiskMonitor {
+    disks: Disks,
+    last_refresh: std::time::Instant,
+}
+
+#[cfg(target_os = "windows")]
+static DISK_MONITOR: Mutex<Option<DiskMonitor>> = Mutex::new(None);
+
+/// Bytes transferred since the previous refresh as a rate. `None` until
+/// there is a previous refresh to measure from.
+#[cfg(any(target_os = "windows", test))]
+fn bytes_per_sec(bytes: u64, elapsed_secs: Option<f64>) -> Option<f64> {
+    elapsed_secs
+        .filter(|secs| *secs > 0.0)
+        .map(|secs| bytes as f64 / secs)
+}
+
+/// sysinfo CPU state kept between samples. Usage is measured between two
+/// refreshes, so reusing the `System` turns every sample after the first
+/// into one refresh instead of refresh, sleep, refresh on a fresh `System`.
+#[cfg(target_os = "windows")]
+struct CpuMonitor {
+    system: System,
+    last_refresh: Option<std::time::Instant>,
+}
+
+#[cfg(target_os = "windows")]
+static CPU_MONITOR: Mutex<Option<CpuMonitor>> = Mutex::new(None);
+
+#[cfg(any(target_os = "windows", test))]
+#[derive(Debug, PartialEq)]
+enum CpuRefresh {
+    /// Nothing to measure usage against yet: refresh, wait, refresh.
+    Baseline,
+    Refresh,
+    /// Too soon after the last refresh for usage to mean anything; report
+    /// the last readings again.
+    Reuse,
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn cpu_refresh(since_last_refresh: Option<std::time::Duration>) -> CpuRefresh {
+    match since_last_refresh {
+        None => CpuRefresh::Baseline,
+        Some(elapsed) if elapsed < sysinfo::MINIMUM_CPU_UPDATE_INTERVAL => CpuRefresh::Reuse,
+        Some(_) => CpuRefresh::Refresh,
+    }
+}
+
 /// Read the monitor model name from EDID data in the registry via SetupAPI.
 /// Identifies the primary monitor by matching the DeviceID from EnumDisplayDevices.
 #[cfg(target_os = "windows")]
@@ -406,9 +1066,30 @@ fn get_display_info() -> Option<DisplayData> {
     })
 }
 
+/// Whether the last sample came from LHM, so switching to and from the
+/// sysinfo fallback leaves one breadcrumb instead of one per sample.
 #[cfg(target_os = "windows")]
-pub async fn get_hardware_info() -> Result<HardwareData, String> {
-    tokio::task::spawn_blocking(|| {
+static USING_LHM: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
+
+/// `lhm` is false only for the unplanned fallback; viewer mode never runs
+/// the daemon.
+#[cfg(target_os = "windows")]
+fn note_sample_source(lhm: bool) {
+    if USING_LHM.swap(lhm, std::sync::atomic::Ordering::Relaxed) != lhm {
+        error_reporting::add_breadcrumb(
+            "hardware",
+            if lhm {
+                "Back on LHM data"
+            } else {
+                "Fell back to sysinfo"
+            },
+        );
+    }
+}
+
+#[cfg(target_os = "windows")]
+async fn collect_hardware_info(sections: MonitoredSections) -> Result<HardwareData, String> {
+    tokio::task::spawn_blocking(move || {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_millis() as u64)
@@ -443,9 +1124,14 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
             Some(network)
         };
 
-        if let Some(lhm) = lhm_data {
+        note_sample_source(lhm_data.is_some() || sensor_owner::role() == SensorRole::Viewer);
+        if let Some((lhm, data_age_ms)) = lhm_data {
             // Use LHM data, supplement with sysinfo where needed
-            let cpu = lhm.cpu.map(|c| {
+            let cpu_data = match lhm.cpu_packages {
+                Some(packages) if packages.len() > 1 => merge_cpu_packages(packages),
+                _ => lhm.cpu,
+            };
+            let cpu = cpu_data.filter(|_| sections.cpu).map(|c| {
                 // sysinfo cannot provide CPU temperature on Windows, so use LHM value (0 if unavailable)
                 let temperature = c.temperature;
 
@@ -464,31 +1150,55 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
                     max_temperature: c.max_temperature,
                     load: c.load,
                     frequency,
+                    power: c.power,
+                    voltage: c.voltage,
+                    is_throttling: false,
+                    base_frequency: cpu_base_frequency(),
+                    max_frequency: 0.0,
                     cores: c
                         .cores
                         .map(|cores| {
                             cores
                                 .into_iter()
                                 .map(|core| {
-                                    // If per-core temperature is 0, use the package temperature
-                                    let core_temp = if core.temperature > 0.0 {
-                                        core.temperature
+                                    let frequency = core.frequency.filter(|f| *f > 0.0);
+                                    if core.temperature > 0.0 {
+                                        CpuCoreData {
+                                            index: core.index,
+                                            temperature: core.temperature,
+                                            load: core.load,
+                                            frequency,
+                                            estimated: false,
+                                            package: core.package,
+                                        }
                                     } else {
-                                        temperature
-                                    };
-                                    CpuCoreData {
-                                        index: core.index,
-                                        temperature: core_temp,
-                                        load: core.load,
+                                        // On a multi-socket machine, the core's own package
+                                        let package_temperature = core
+                                            .package
+                                            .and_then(|index| {
+                                                c.packages.as_ref()?.get(index as usize)
+                                            })
+                                            .map_or(temperature, |p| p.temperature);
+                                        CpuCoreData {
+                                            package: core.package,
+                                            ..package_temperature_core(
+                                                core.index,
+                                                core.load,
+                                                frequency,
+                                                package_temperature,
+                                            )
+                                        }
                                     }
                                 })
                                 .collect()
                         })
                         .unwrap_or_default(),
+                    packages: c.packages,
+                    status: SectionStatus::Ok,
                 }
             });
 
-            let gpu = lhm.gpu.map(|g| GpuData {
+            let gpu = lhm.gpu.filter(|_| sections.gpu).map(|g| GpuData {
                 name: g.name,
                 temperature: g.temperature,
                 max_temperature: g.max_temperature,
@@ -496,11 +1206,17 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
                 frequency: g.frequency,
                 memory_used: g.memory_used,
                 memory_total: g.memory_total,
+                power: g.power,
+                power_limit: g.power_limit,
+                hotspot_temperature: g.hotspot_temperature,
+                memory_temperature: g.memory_temperature,
+                fans: lhm_fans(g.fans),
+                status: SectionStatus::Ok,
             });
 
             // For storage: use LHM data, supplement with sysinfo if LHM data is incomplete.
             // Only enumerate disks when some LHM entry is actually missing data.
-            let storage = lhm.storage.map(|storages| {
+            let storage = lhm.storage.filter(|_| sections.storage).map(|storages| {
                 let sysinfo_storage = storages
                     .iter()
                     .any(|s| s.total_space <= 0.0 || s.used_percent <= 0.0)
@@ -530,41 +1246,50 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
                             sysinfo_match.map(|si| si.total_space).unwrap_or(0.0)
                         };
 
-                        let used_space = if s.used_percent > 0.0 {
+                        let used_percent = if s.used_percent > 0.0 {
                             s.used_percent
                         } else {
-                            sysinfo_match.map(|si| si.used_space).unwrap_or(0.0)
+                            sysinfo_match.map(|si| si.used_percent).unwrap_or(0.0)
                         };
 
                         StorageData {
                             name: s.name,
                             temperature: s.temperature,
-                            used_space,
+                            used_space: total_space * used_percent / 100.0,
+                            used_percent,
                             total_space,
+                            health_percent: s.health_percent,
+                            data_written_tb: s.data_written_tb,
+                            read_rate: s.read_rate,
+                            write_rate: s.write_rate,
+                            partitions: s.partitions.map(lhm_partitions),
+                            status: SectionStatus::Ok,
                         }
                     })
                     .collect()
             });
 
+            let cpu_error = lhm
+                .cpu_error
+                .filter(|_| sections.cpu)
+                .map(LhmSensorError::into_sensor_error);
+            let gpu_error = lhm
+                .gpu_error
+                .filter(|_| sections.gpu)
+                .map(LhmSensorError::into_sensor_error);
+
             // For motherboard: use LHM data only (sysinfo cannot provide this)
-            let motherboard = lhm.motherboard.map(|m| {
-                let fans = m
-                    .fans
-                    .map(|fans| {
-                        fans.into_iter()
-                            .map(|f| FanData {
-                                name: f.name,
-                                speed: f.speed,
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                MotherboardData {
-                    name: m.name,
-                    temperature: m.temperature,
-                    fans,
-                }
-            });
+            let motherboard =
+                lhm.motherboard
+                    .filter(|_| sections.motherboard)
+                    .map(|m| MotherboardData {
+                        name: m.name,
+                        temperature: m.temperature,
+                        temperatures: lhm_temperatures(m.temperatures),
+                        fans: lhm_fans(m.fans),
+                        voltages: lhm_voltages(m.voltages),
+                        status: SectionStatus::Ok,
+                    });
 
             Ok(HardwareData {
                 cpu,
@@ -574,22 +1299,79 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
                 network,
                 display: get_display_info(),
                 timestamp,
-                cpu_error: None,
+                cpu_error: error_message(&cpu_error),
+                gpu_error: error_message(&gpu_error),
+                cpu_error_detail: cpu_error,
+                gpu_error_detail: gpu_error,
+                system_power: None,
+                data_age_ms: Some(data_age_ms),
+                limited_access: LHM_ACCESS_DENIED.load(std::sync::atomic::Ordering::Relaxed)
+                    || !crate::elevation::is_elevated(),
+                data_source: "lhm".to_string(),
+                degraded: false,
+                section_timestamps: Default::default(),
+                raw_readings: None,
+            })
+        } else if sensor_owner::role() == SensorRole::Viewer {
+            // Prefer the owning instance's readings when its API is reachable
+            if let Some(mut shared) = fetch_owner_hardware() {
+                shared.network = network;
+                shared.display = get_display_info();
+                shared.timestamp = timestamp;
+                return Ok(shared);
+            }
+            let (cpu, gpu, storage) = sysinfo_fallback_devices(sections);
+            Ok(HardwareData {
+                cpu,
+                gpu,
+                storage,
+                motherboard: None,
+                network,
+                display: get_display_info(),
+                timestamp,
+                cpu_error: Some(VIEWER_MODE_ERROR.to_string()),
                 gpu_error: None,
+                cpu_error_detail: Some(SensorError::new(
+                    "viewer_mode",
+                    VIEWER_MODE_ERROR,
+                    "sysinfo",
+                )),
+                gpu_error_detail: None,
+                system_power: None,
+                data_age_ms: None,
+                limited_access: false,
+                data_source: "sysinfo".to_string(),
+                degraded: true,
+                section_timestamps: Default::default(),
+                raw_readings: None,
             })
         } else {
             // Full fallback to sysinfo (LHM not available)
             crate::log_warn!("Hardware", "LHM unavailable, using sysinfo fallback");
+            let incompatible = lhm_incompatible_error()
+                .map(|message| SensorError::new("lhm_incompatible", message, "lhm"));
+            let (cpu, gpu, storage) = sysinfo_fallback_devices(sections);
+            let (cpu_error, gpu_error) =
+                fallback_errors(incompatible, cpu.is_some(), sections.gpu && gpu.is_none());
             Ok(HardwareData {
-                cpu: sysinfo_cpu_data(true),
-                gpu: get_gpu_info_without_wmi(),
-                storage: sysinfo_storage_data(),
+                cpu,
+                gpu,
+                storage,
                 motherboard: None,
                 network,
                 display: get_display_info(),
                 timestamp,
-                cpu_error: None,
-                gpu_error: None,
+                cpu_error: error_message(&cpu_error),
+                gpu_error: error_message(&gpu_error),
+                cpu_error_detail: cpu_error,
+                gpu_error_detail: gpu_error,
+                system_power: None,
+                data_age_ms: None,
+                limited_access: !crate::elevation::is_elevated(),
+                data_source: "sysinfo".to_string(),
+                degraded: true,
+                section_timestamps: Default::default(),
+                raw_readings: None,
             })
         }
     })
@@ -597,13 +1379,38 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
     .map_err(|e| format!("Task failed: {:?}", e))?
 }
 
+/// The newest daemon sample and its age in milliseconds.
 #[cfg(target_os = "windows")]
-fn get_lhm_data() -> Option<LhmResponse> {
-    let mut daemon_guard = LHM_DAEMON.lock().ok()?;
-
+fn get_lhm_data() -> Option<(LhmResponse, u64)> {
+    // Another session's daemon is already polling SMBus; a second one would
+    // corrupt both sets of readings.
+    if sensor_owner::role() == SensorRole::Viewer {
+        return None;
+    }
+    if LHM_SUSPENDED.load(std::sync::atomic::Ordering::SeqCst) {
+        return None;
+    }
+
+    let mut daemon_guard = LHM_DAEMON.lock().ok()?;
+    let requested_ms = LHM_INTERVAL_MS.load(std::sync::atomic::Ordering::SeqCst);
+
+    // Catch an interval change that raced with `set_lhm_interval`
+    if lhm_interval_needs_restart(daemon_guard.as_ref().map(|d| d.interval_ms), requested_ms) {
+        if let Some(daemon) = daemon_guard.take() {
+            daemon.stop();
+            error_reporting::add_breadcrumb(
+                "lhm",
+                &format!("Restarting daemon for {}ms interval", requested_ms),
+            );
+        }
+    }
+
     // Start daemon if not running
     if daemon_guard.is_none() {
-        match start_lhm_daemon() {
+        if lhm_incompatible_error().is_some() {
+            return None;
+        }
+        match start_lhm_daemon(requested_ms) {
             Ok(daemon) => {
                 *daemon_guard = Some(daemon);
             }
@@ -620,159 +1427,759 @@ fn get_lhm_data() -> Option<LhmResponse> {
     // Check if process is still running
     match daemon.process.try_wait() {
         Ok(Some(status)) => {
-            // Process exited, restart it
-            crate::log_warn!(
-                "Hardware",
-                "LHM daemon exited with status: {}, restarting...",
-                status
-            );
-            *daemon_guard = None;
+            // Process exited, restart it on the next poll
+            if let Some(daemon) = daemon_guard.take() {
+                daemon.stop();
+            }
+            report_lhm_exit(&format!("LHM daemon exited with status: {}", status));
             return None;
         }
         Ok(None) => {
-            // Process still running, read latest line
+            // Still running, but a hung sensor read can stop its output while
+            // the process stays alive
+            let age = daemon.data_age();
+            if lhm_is_stalled(age, daemon.interval_ms) {
+                if let Some(daemon) = daemon_guard.take() {
+                    daemon.stop();
+                }
+                report_lhm_exit(&format!(
+                    "LHM daemon stalled: no data for {:.1}s, restarting",
+                    age.as_secs_f32()
+                ));
+                return None;
+            }
         }
         Err(e) => {
             crate::log_error!("Hardware", "Failed to check LHM daemon status: {}", e);
-            *daemon_guard = None;
+            if let Some(daemon) = daemon_guard.take() {
+                daemon.stop();
+            }
             return None;
         }
     }
 
-    // Read all available lines and keep the latest one
-    let mut latest_line = None;
-    loop {
-        // Check if there's data available before attempting to read
-        if !pipe_has_data(&daemon.reader) {
-            break;
-        }
-
-        let mut line = String::new();
-        match daemon.reader.read_line(&mut line) {
-            Ok(0) => break, // EOF
-            Ok(_) => {
-                let trimmed = line.trim();
-                if !trimmed.is_empty() {
-                    latest_line = Some(trimmed.to_string());
-                }
-            }
-            Err(e) => {
-                crate::log_error!("Hardware", "Failed to read from LHM daemon: {}", e);
-                break;
-            }
+    let slow_interval_ms = SLOW_INTERVAL_MS.load(std::sync::atomic::Ordering::Relaxed);
+    if daemon.slow_interval_ms != slow_interval_ms {
+        match daemon.reconfigure_slow_interval(slow_interval_ms) {
+            Ok(()) => error_reporting::add_breadcrumb(
+                "lhm",
+                &format!("Slow interval set to {}ms", slow_interval_ms),
+            ),
+            // Honoured on the next restart instead
+            Err(e) => crate::log_warn!("Hardware", "Failed to set LHM slow interval: {}", e),
         }
     }
 
-    // Parse the latest line if available
-    if let Some(line) = latest_line {
-        match serde_json::from_str::<LhmResponse>(&line) {
-            Ok(data) => {
-                daemon.latest_data = Some(data.clone());
-                return Some(data);
-            }
-            Err(e) => {
-                crate::log_warn!("Hardware", "Failed to parse LHM JSON: {}", e);
-            }
+    // Disabled sections aren't polled at all, not just left out of the sample
+    let sections = monitored_sections();
+    if daemon.sections != sections {
+        match daemon.reconfigure_sections(sections) {
+            Ok(()) => error_reporting::add_breadcrumb("lhm", &sections.lhm_command()),
+            // Honoured on the next restart instead
+            Err(e) => crate::log_warn!("Hardware", "Failed to set LHM sections: {}", e),
         }
     }
 
-    // Return cached data if no new data available
-    daemon.latest_data.clone()
+    let age_ms = daemon.data_age().as_millis() as u64;
+    daemon.latest_data().map(|data| (data, age_ms))
+}
+
+/// `ondo-hwmon.exe`, installed next to the Ondo executable.
+#[cfg(target_os = "windows")]
+fn lhm_helper_path() -> Result<std::path::PathBuf, String> {
+    let exe_path = std::env::current_exe().map_err(|e| format!("Cannot get exe path: {}", e))?;
+    let exe_dir = exe_path.parent().ok_or("Cannot get exe directory")?;
+    Ok(exe_dir.join("ondo-hwmon.exe"))
+}
+
+/// Whether the LHM helper is installed and speaks a protocol this build
+/// understands, whether or not a daemon is running right now.
+#[cfg(target_os = "windows")]
+pub fn lhm_helper_available() -> bool {
+    lhm_helper_path().is_ok_and(|path| path.exists()) && lhm_incompatible_error().is_none()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn lhm_helper_available() -> bool {
+    false
 }
 
 #[cfg(target_os = "windows")]
-fn start_lhm_daemon() -> Result<LhmDaemon, String> {
-    use std::env;
+fn start_lhm_daemon(interval_ms: u32) -> Result<LhmDaemon, String> {
     use std::os::windows::process::CommandExt;
     use std::process::{Command, Stdio};
 
     // CREATE_NO_WINDOW flag to prevent console window from appearing
     const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-    let exe_path = env::current_exe().map_err(|e| format!("Cannot get exe path: {}", e))?;
-    let exe_dir = exe_path.parent().ok_or("Cannot get exe directory")?;
-    let lhm_path = exe_dir.join("ondo-hwmon.exe");
-
+    let lhm_path = lhm_helper_path()?;
     if !lhm_path.exists() {
         return Err(format!("LHM CLI not found at {:?}", lhm_path));
     }
 
+    // Storage and the motherboard are only read every slow interval, so the
+    // daemon doesn't keep drives from spinning down
+    let slow_interval_ms = SLOW_INTERVAL_MS.load(std::sync::atomic::Ordering::Relaxed);
     let mut child = Command::new(&lhm_path)
-        .args(["--daemon", "1000"]) // 1 second interval
+        .args([
+            "--daemon",
+            &interval_ms.to_string(),
+            &slow_interval_ms.to_string(),
+        ])
         .creation_flags(CREATE_NO_WINDOW)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
-        .stderr(Stdio::null())
+        .stderr(Stdio::piped())
         .spawn()
         .map_err(|e| format!("Failed to start LHM daemon: {}", e))?;
+    crate::metrics::LHM_DAEMON_SPAWNS.inc();
 
     let stdout = child
         .stdout
         .take()
         .ok_or("Failed to capture LHM daemon stdout")?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or("Failed to capture LHM daemon stderr")?;
 
-    let mut reader = BufReader::new(stdout);
+    LHM_STDERR.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    LHM_ACCESS_DENIED.store(false, std::sync::atomic::Ordering::Relaxed);
+
+    let latest: LatestLhmSlot = Arc::new(Mutex::new(None));
+    let reader_latest = Arc::clone(&latest);
+    let (handshake_tx, handshake_rx) = std::sync::mpsc::channel();
+    let reader_thread = match std::thread::Builder::new()
+        .name("lhm-stdout".to_string())
+        .spawn(move || read_lhm_stdout(stdout, reader_latest, handshake_tx))
+    {
+        Ok(handle) => handle,
+        Err(e) => {
+            let _ = child.kill();
+            return Err(format!("Failed to spawn LHM reader thread: {}", e));
+        }
+    };
 
-    crate::log_info!("Hardware", "LHM daemon started, waiting for first data...");
+    // Losing stderr is not fatal; the daemon still works without it.
+    let stderr_thread = match std::thread::Builder::new()
+        .name("lhm-stderr".to_string())
+        .spawn(move || read_lhm_stderr(stderr))
+    {
+        Ok(handle) => Some(handle),
+        Err(e) => {
+            crate::log_warn!("Hardware", "Failed to spawn LHM stderr thread: {}", e);
+            None
+        }
+    };
+
+    let mut daemon = LhmDaemon {
+        process: child,
+        interval_ms,
+        slow_interval_ms,
+        sections: MonitoredSections::ALL,
+        started: std::time::Instant::now(),
+        latest,
+        reader_thread: Some(reader_thread),
+        stderr_thread,
+    };
 
-    // Wait for the first line of data from the daemon (up to 10 seconds)
-    let mut initial_data = None;
+    // Both the handshake and the first sample must arrive within 10 seconds
     let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
-    while std::time::Instant::now() < deadline {
-        let mut line = String::new();
-        // Use a short sleep + peek approach to avoid blocking forever
-        if !pipe_has_data(&reader) {
-            std::thread::sleep(std::time::Duration::from_millis(100));
-            continue;
+
+    let handshake = match handshake_rx.recv_timeout(std::time::Duration::from_secs(10)) {
+        Ok(Ok(handshake)) => handshake,
+        Ok(Err(e)) => {
+            daemon.stop();
+            return Err(e);
+        }
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+            daemon.stop();
+            return Err("LHM daemon sent no handshake within timeout".to_string());
         }
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+            daemon.stop();
+            return Err(with_stderr_tail("LHM daemon exited before its handshake"));
+        }
+    };
+    *LHM_HELPER.lock().unwrap_or_else(|e| e.into_inner()) = Some(handshake.clone());
+    if let Err(e) = check_lhm_handshake(&handshake) {
+        daemon.stop();
+        *LHM_INCOMPATIBLE.lock().unwrap_or_else(|e| e.into_inner()) = Some(e.clone());
+        return Err(e);
+    }
 
-        match reader.read_line(&mut line) {
-            Ok(0) => break, // EOF - daemon exited
-            Ok(_) => {
-                let trimmed = line.trim();
-                if !trimmed.is_empty() {
-                    match serde_json::from_str::<LhmResponse>(trimmed) {
-                        Ok(data) => {
-                            crate::log_info!("Hardware", "LHM daemon ready - first data received");
-                            initial_data = Some(data);
-                            break;
-                        }
-                        Err(e) => {
-                            crate::log_warn!("Hardware", "LHM initial data parse error: {}", e);
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                crate::log_error!("Hardware", "Failed to read initial LHM data: {}", e);
-                break;
-            }
+    crate::log_info!(
+        "Hardware",
+        "LHM daemon {} (protocol {}) started, waiting for first data...",
+        handshake.version.as_deref().unwrap_or("unknown"),
+        handshake.protocol
+    );
+    error_reporting::add_breadcrumb("lhm", "Daemon started");
+
+    while std::time::Instant::now() < deadline {
+        if daemon.latest_data().is_some() {
+            crate::log_info!("Hardware", "LHM daemon ready - first data received");
+            return Ok(daemon);
+        }
+        if daemon
+            .reader_thread
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            // Reader hit EOF - daemon exited before producing data
+            break;
         }
+        std::thread::sleep(std::time::Duration::from_millis(100));
     }
 
-    if initial_data.is_none() {
-        crate::log_warn!(
-            "Hardware",
-            "LHM daemon started but no initial data received within timeout"
-        );
+    crate::log_warn!(
+        "Hardware",
+        "LHM daemon started but no initial data received within timeout"
+    );
+
+    // The startup wait doesn't count towards a stall
+    daemon.started = std::time::Instant::now();
+    Ok(daemon)
+}
+
+#[cfg(target_os = "windows")]
+const VIEWER_MODE_ERROR: &str =
+    "Sensors are in use by Ondo in another user session; showing limited data";
+
+/// Read `/api/hardware` from the owning instance's debug server, if it is
+/// running. Skipped when this process holds the port itself, which would
+/// otherwise make the request loop back into this function.
+#[cfg(target_os = "windows")]
+fn fetch_owner_hardware() -> Option<HardwareData> {
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::time::Duration;
+
+    if crate::debug_server::is_listening() {
+        return None;
     }
 
-    Ok(LhmDaemon {
-        process: child,
-        reader,
-        latest_data: initial_data,
-    })
+    let addr = SocketAddr::from(([127, 0, 0, 1], crate::debug_server::PORT));
+    let timeout = Duration::from_millis(500);
+    let mut stream = TcpStream::connect_timeout(&addr, timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    stream
+        .write_all(b"GET /api/hardware HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n")
+        .ok()?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    if !head.starts_with("HTTP/1.1 200") {
+        return None;
+    }
+    serde_json::from_str(body).ok()
 }
 
 // Shutdown LHM daemon when app exits
 #[cfg(target_os = "windows")]
 pub fn shutdown_lhm_daemon() {
     if let Ok(mut daemon_guard) = LHM_DAEMON.lock() {
-        if let Some(mut daemon) = daemon_guard.take() {
-            let _ = daemon.process.kill();
+        if let Some(daemon) = daemon_guard.take() {
+            daemon.stop();
             crate::log_info!("Hardware", "LHM daemon stopped");
+            error_reporting::add_breadcrumb("lhm", "Daemon stopped");
+        }
+    }
+}
+
+/// Time since the running LHM daemon was started, `None` without one.
+#[cfg(target_os = "windows")]
+pub fn lhm_daemon_uptime() -> Option<std::time::Duration> {
+    let daemon_guard = LHM_DAEMON.lock().ok()?;
+    daemon_guard.as_ref().map(|daemon| daemon.started.elapsed())
+}
+
+/// Replace the running LHM daemon with a fresh one, e.g. so it loads a
+/// driver installed since it started. Blocks until the new daemon delivers
+/// its first sample; call it off the async runtime.
+#[cfg(target_os = "windows")]
+pub fn restart_lhm_daemon() -> Result<(), String> {
+    if sensor_owner::role() == SensorRole::Viewer {
+        return Err("Another Ondo instance owns the sensors".to_string());
+    }
+    // Resuming monitoring starts a fresh one anyway
+    if LHM_SUSPENDED.load(std::sync::atomic::Ordering::SeqCst) {
+        return Ok(());
+    }
+    let mut daemon_guard = LHM_DAEMON.lock().map_err(|e| e.to_string())?;
+    if let Some(daemon) = daemon_guard.take() {
+        daemon.stop();
+    }
+    error_reporting::add_breadcrumb("lhm", "Restarting daemon on request");
+    let daemon = start_lhm_daemon(LHM_INTERVAL_MS.load(std::sync::atomic::Ordering::SeqCst))?;
+    *daemon_guard = Some(daemon);
+    Ok(())
+}
+
+/// Stop the daemon and keep it stopped, or start it again. Starting happens
+/// under `LHM_DAEMON`, so a daemon a poll already brought up is kept rather
+/// than doubled. Blocks until the new daemon delivers its first sample; call
+/// it off the async runtime.
+#[cfg(target_os = "windows")]
+pub fn set_lhm_suspended(suspended: bool) {
+    use std::sync::atomic::Ordering;
+
+    if suspended {
+        LHM_SUSPENDED.store(true, Ordering::SeqCst);
+        shutdown_lhm_daemon();
+        return;
+    }
+    if !LHM_SUSPENDED.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    if sensor_owner::role() == SensorRole::Viewer || lhm_incompatible_error().is_some() {
+        return;
+    }
+    let Ok(mut daemon_guard) = LHM_DAEMON.lock() else {
+        return;
+    };
+    if daemon_guard.is_some() {
+        return;
+    }
+    error_reporting::add_breadcrumb("lhm", "Resuming daemon");
+    match start_lhm_daemon(LHM_INTERVAL_MS.load(Ordering::SeqCst)) {
+        Ok(daemon) => *daemon_guard = Some(daemon),
+        Err(e) => {
+            // Leave the slot empty; the next poll retries the start
+            crate::log_error!("Hardware", "Failed to resume LHM daemon: {}", e);
+            error_reporting::capture_lhm_error(&format!("Failed to resume daemon: {}", e));
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_lhm_suspended(_suspended: bool) {
+    // No daemon on non-Windows
+}
+
+/// Sample hardware data from the platform backend and normalize it. Every
+/// consumer (commands, debug server) goes through here so they all see the
+/// same deterministic shape.
+pub async fn get_hardware_info() -> Result<HardwareData, String> {
+    let generation = crate::metrics::LHM_DAEMON_SPAWNS.get();
+    let slow_due = slow_sections_due(
+        SLOW_SECTIONS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref(),
+        crate::now_millis(),
+        generation,
+        SLOW_INTERVAL_MS.load(std::sync::atomic::Ordering::Relaxed),
+    );
+    let sections = monitored_sections().with_slow(slow_due);
+    let mut data = if crate::mock::is_enabled() {
+        crate::mock::live_sample()
+    } else {
+        collect_hardware_info(sections).await?
+    };
+    // Platforms skip most of the work for disabled sections; this covers
+    // what they can't, like a sample shared by the owning instance
+    sections.mask(&mut data);
+    interleave_slow_sections(
+        &mut data,
+        &mut SLOW_SECTIONS.lock().unwrap_or_else(|e| e.into_inner()),
+        slow_due,
+        generation,
+    );
+    {
+        let overrides = SENSOR_OVERRIDES.lock().unwrap_or_else(|e| e.into_inner());
+        let mut detected = DETECTED_SENSORS.lock().unwrap_or_else(|e| e.into_inner());
+        apply_sensor_overrides(&mut data, &overrides, &mut detected);
+    }
+    normalize_hardware_data(&mut data);
+    crate::simulation::apply(&mut data, crate::now_millis());
+    Ok(data)
+}
+
+/// Device sections to sample, from the `monitor_*` settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitoredSections {
+    pub cpu: bool,
+    pub gpu: bool,
+    pub storage: bool,
+    pub motherboard: bool,
+}
+
+impl MonitoredSections {
+    pub const ALL: Self = Self {
+        cpu: true,
+        gpu: true,
+        storage: true,
+        motherboard: true,
+    };
+
+    /// These sections, leaving out the slow ones unless they are due.
+    fn with_slow(self, due: bool) -> Self {
+        Self {
+            storage: self.storage && due,
+            motherboard: self.motherboard && due,
+            ..self
+        }
+    }
+
+    /// The stdin line that tells the LHM helper which sections to read.
+    #[cfg(any(windows, test))]
+    fn lhm_command(&self) -> String {
+        let names = [
+            (self.cpu, "cpu"),
+            (self.gpu, "gpu"),
+            (self.storage, "storage"),
+            (self.motherboard, "motherboard"),
+        ];
+        std::iter::once("sections")
+            .chain(names.iter().filter(|(on, _)| *on).map(|(_, name)| *name))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn mask(&self, data: &mut HardwareData) {
+        if !self.cpu {
+            data.cpu = None;
+        }
+        if !self.gpu {
+            data.gpu = None;
+        }
+        if !self.storage {
+            data.storage = None;
+        }
+        if !self.motherboard {
+            data.motherboard = None;
+        }
+    }
+}
+
+static MONITORED_SECTIONS: Mutex<MonitoredSections> = Mutex::new(MonitoredSections::ALL);
+
+pub fn set_monitored_sections(sections: MonitoredSections) {
+    if let Ok(mut current) = MONITORED_SECTIONS.lock() {
+        *current = sections;
+    }
+    // A section turned back on shouldn't wait for the next slow tick
+    *SLOW_SECTIONS.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn monitored_sections() -> MonitoredSections {
+    MONITORED_SECTIONS
+        .lock()
+        .map(|sections| *sections)
+        .unwrap_or(MonitoredSections::ALL)
+}
+
+/// `update_interval_slow`: how often storage and the motherboard are read.
+/// CPU and GPU are read on every poll, which follows `update_interval_fast`.
+static SLOW_INTERVAL_MS: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(5000);
+
+/// Polls don't land exactly on the interval; without some slack a 5s slow
+/// interval polled every second would read every 6s.
+const SLOW_TICK_SLACK_MS: u64 = 250;
+
+/// The LHM daemon is told about the change on the next poll, without a
+/// restart.
+pub fn set_slow_interval(interval_ms: u32) {
+    SLOW_INTERVAL_MS.store(interval_ms, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// When each section of a sample was read.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, serde::Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionTimestamps {
+    pub cpu: Option<u64>,
+    pub gpu: Option<u64>,
+    pub storage: Option<u64>,
+    pub motherboard: Option<u64>,
+}
+
+/// Storage and motherboard readings from the last slow tick, served on the
+/// polls in between.
+struct SlowSections {
+    taken_at: u64,
+    /// LHM daemon spawns when they were read; a new daemon reads them again.
+    generation: u64,
+    storage: Option<Vec<StorageData>>,
+    motherboard: Option<MotherboardData>,
+}
+
+static SLOW_SECTIONS: Mutex<Option<SlowSections>> = Mutex::new(None);
+
+fn slow_sections_due(
+    cached: Option<&SlowSections>,
+    now: u64,
+    generation: u64,
+    interval_ms: u32,
+) -> bool {
+    cached.is_none_or(|cached| {
+        cached.generation != generation
+            || now + SLOW_TICK_SLACK_MS >= cached.taken_at + u64::from(interval_ms)
+    })
+}
+
+/// Remember the slow sections of a sample that read them, or fill them in
+/// from the last one that did, and stamp each section with its read time.
+fn interleave_slow_sections(
+    data: &mut HardwareData,
+    cached: &mut Option<SlowSections>,
+    slow_due: bool,
+    generation: u64,
+) {
+    if slow_due {
+        *cached = Some(SlowSections {
+            taken_at: data.timestamp,
+            generation,
+            storage: data.storage.clone(),
+            motherboard: data.motherboard.clone(),
+        });
+    } else if let Some(slow) = cached {
+        data.storage.clone_from(&slow.storage);
+        data.motherboard.clone_from(&slow.motherboard);
+    }
+    let slow_taken_at = cached.as_ref().map(|slow| slow.taken_at);
+    data.section_timestamps = SectionTimestamps {
+        cpu: data.cpu.as_ref().map(|_| data.timestamp),
+        gpu: data.gpu.as_ref().map(|_| data.timestamp),
+        storage: data.storage.as_ref().and(slow_taken_at),
+        motherboard: data.motherboard.as_ref().and(slow_taken_at),
+    };
+}
+
+/// Cap on `DETECTED_SENSORS`, so adapters that come and go with new names
+/// (VPNs, USB tethering) can't grow it for the whole session.
+const MAX_DETECTED_SENSORS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SensorKind {
+    Cpu,
+    Gpu,
+    Storage,
+    Motherboard,
+    Fan,
+    Network,
+}
+
+/// A device as the sensors name it, before any override is applied.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, JsonSchema)]
+pub struct DetectedSensor {
+    pub kind: SensorKind,
+    pub name: String,
+}
+
+/// Copy of `AppSettings::sensor_overrides`, kept in sync by
+/// `set_sensor_overrides`.
+static SENSOR_OVERRIDES: Mutex<BTreeMap<String, SensorOverride>> = Mutex::new(BTreeMap::new());
+
+/// Every device seen this session, hidden ones included.
+static DETECTED_SENSORS: Mutex<BTreeSet<DetectedSensor>> = Mutex::new(BTreeSet::new());
+
+pub fn set_sensor_overrides(overrides: &BTreeMap<String, SensorOverride>) {
+    if let Ok(mut current) = SENSOR_OVERRIDES.lock() {
+        *current = overrides.clone();
+    }
+    // Aliases rename devices and hiding one shifts the drives
+    crate::hardware_static::invalidate();
+}
+
+pub fn detected_sensors() -> Vec<DetectedSensor> {
+    DETECTED_SENSORS
+        .lock()
+        .map(|detected| detected.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Record `name` as detected and apply its override. Returns false when the
+/// device is hidden.
+fn override_device(
+    kind: SensorKind,
+    name: &mut String,
+    overrides: &BTreeMap<String, SensorOverride>,
+    detected: &mut BTreeSet<DetectedSensor>,
+) -> bool {
+    if detected.len() < MAX_DETECTED_SENSORS {
+        detected.insert(DetectedSensor {
+            kind,
+            name: name.clone(),
+        });
+    }
+    let Some(sensor) = overrides.get(name.as_str()) else {
+        return true;
+    };
+    if let Some(alias) = &sensor.alias {
+        name.clone_from(alias);
+    }
+    !sensor.hidden
+}
+
+/// Drop hidden devices and rename aliased ones, recording the original
+/// names in `detected`. Runs before anything else sees the sample, so
+/// hidden devices never reach the statistics or any client.
+fn apply_sensor_overrides(
+    data: &mut HardwareData,
+    overrides: &BTreeMap<String, SensorOverride>,
+    detected: &mut BTreeSet<DetectedSensor>,
+) {
+    let mut keep = |kind, name: &mut String| override_device(kind, name, overrides, detected);
+
+    if let Some(cpu) = data.cpu.as_mut() {
+        if !keep(SensorKind::Cpu, &mut cpu.name) {
+            data.cpu = None;
+        }
+    }
+    if let Some(gpu) = data.gpu.as_mut() {
+        gpu.fans
+            .retain_mut(|fan| keep(SensorKind::Fan, &mut fan.name));
+        if !keep(SensorKind::Gpu, &mut gpu.name) {
+            data.gpu = None;
+        }
+    }
+    if let Some(storage) = data.storage.as_mut() {
+        storage.retain_mut(|drive| keep(SensorKind::Storage, &mut drive.name));
+    }
+    if let Some(motherboard) = data.motherboard.as_mut() {
+        motherboard
+            .fans
+            .retain_mut(|fan| keep(SensorKind::Fan, &mut fan.name));
+        if !keep(SensorKind::Motherboard, &mut motherboard.name) {
+            data.motherboard = None;
         }
     }
+    if let Some(network) = data.network.as_mut() {
+        network.retain_mut(|interface| keep(SensorKind::Network, &mut interface.name));
+    }
+}
+
+/// Put every collection into a stable order. LHM enumeration order is not
+/// stable across daemon restarts, and sysinfo's network list is hash-ordered,
+/// so without this the frontend lists jump around between samples.
+///
+/// The sorts are stable and only look at the primary key, so entries that
+/// share it (e.g. two identical drive models) keep the order the source
+/// lists them in, which doesn't change between samples. Breaking ties on
+/// readings would swap them whenever the readings cross.
+pub fn normalize_hardware_data(data: &mut HardwareData) {
+    if let Some(cpu) = data.cpu.as_mut() {
+        cpu.cores.sort_by_key(|core| core.index);
+    }
+
+    if let Some(storage) = data.storage.as_mut() {
+        storage.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    if let Some(gpu) = data.gpu.as_mut() {
+        gpu.fans.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    if let Some(motherboard) = data.motherboard.as_mut() {
+        motherboard.fans.sort_by(|a, b| a.name.cmp(&b.name));
+        motherboard.temperatures.sort_by(|a, b| a.name.cmp(&b.name));
+        // PSU rails first, the rest of the board after them
+        motherboard.voltages.sort_by(|a, b| {
+            (!is_psu_rail(&a.name))
+                .cmp(&!is_psu_rail(&b.name))
+                .then(a.name.cmp(&b.name))
+        });
+    }
+
+    if let Some(network) = data.network.as_mut() {
+        network.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+/// How far (°C) below `maxTemperature` a device turns warning or critical
+/// when its thresholds don't give a temperature.
+pub const WARNING_MARGIN: f32 = 15.0;
+pub const CRITICAL_MARGIN: f32 = 5.0;
+
+/// A section's reading against its thresholds, as `status` in samples.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    serde::Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionStatus {
+    #[default]
+    Ok,
+    Warning,
+    Critical,
+}
+
+/// Warning and critical temperatures (°C) for a device reaching
+/// `max_temperature`, 0 when it reports none. `None` for a level neither
+/// `limits` nor the device give.
+pub fn temperature_limits(
+    limits: &ComponentThresholds,
+    max_temperature: f32,
+) -> (Option<f32>, Option<f32>) {
+    let below_max = |margin: f32| (max_temperature > 0.0).then_some(max_temperature - margin);
+    (
+        limits
+            .warning_temperature
+            .or_else(|| below_max(WARNING_MARGIN)),
+        limits
+            .critical_temperature
+            .or_else(|| below_max(CRITICAL_MARGIN)),
+    )
+}
+
+/// Where a reading stands against `limits`. A temperature of 0 is a
+/// missing sensor and never flags.
+pub fn section_status(
+    limits: &ComponentThresholds,
+    temperature: f32,
+    max_temperature: f32,
+    load: Option<f32>,
+) -> SectionStatus {
+    let (warning, critical) = temperature_limits(limits, max_temperature);
+    let temperature = Some(temperature).filter(|t| *t > 0.0);
+    let reached = |value: Option<f32>, limit: Option<f32>| matches!((value, limit), (Some(value), Some(limit)) if value >= limit);
+    if reached(temperature, critical) || reached(load, limits.critical_load) {
+        SectionStatus::Critical
+    } else if reached(temperature, warning) || reached(load, limits.warning_load) {
+        SectionStatus::Warning
+    } else {
+        SectionStatus::Ok
+    }
+}
+
+/// Set `status` on every section of `data`.
+pub fn annotate_status(data: &mut HardwareData, thresholds: &Thresholds) {
+    if let Some(cpu) = data.cpu.as_mut() {
+        cpu.status = section_status(
+            &thresholds.cpu,
+            cpu.temperature,
+            cpu.max_temperature,
+            Some(cpu.load),
+        );
+    }
+    if let Some(gpu) = data.gpu.as_mut() {
+        gpu.status = section_status(
+            &thresholds.gpu,
+            gpu.temperature,
+            gpu.max_temperature,
+            Some(gpu.load),
+        );
+    }
+    for drive in data.storage.iter_mut().flatten() {
+        drive.status = section_status(&thresholds.storage, drive.temperature, 0.0, None);
+    }
+    if let Some(motherboard) = data.motherboard.as_mut() {
+        motherboard.status =
+            section_status(&thresholds.motherboard, motherboard.temperature, 0.0, None);
+    }
 }
 
 // sysinfo for hardware data (replaces WMI on Windows to avoid application control policy blocks)
@@ -820,21 +2227,110 @@ fn collect_network_data(networks: &Networks, interval_secs: f64) -> Vec<NetworkI
     result
 }
 
+/// Run three independent readings on their own threads and wait for all of
+/// them, so the slowest one sets the latency instead of their sum.
+#[cfg(any(target_os = "windows", test))]
+fn run_concurrently<A: Send, B: Send, C: Send>(
+    a: impl FnOnce() -> A + Send,
+    b: impl FnOnce() -> B + Send,
+    c: impl FnOnce() -> C + Send,
+) -> (A, B, C) {
+    std::thread::scope(|scope| {
+        let b = scope.spawn(b);
+        let c = scope.spawn(c);
+        let a = a();
+        (a, join_reading(b), join_reading(c))
+    })
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn join_reading<T>(handle: std::thread::ScopedJoinHandle<'_, T>) -> T {
+    handle
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+/// CPU, GPU and storage without LHM, leaving out disabled sections. The
+/// GPU CLIs take a few hundred milliseconds to start and the first CPU
+/// reading waits for a usage baseline, so they are read concurrently.
+#[cfg(target_os = "windows")]
+fn sysinfo_fallback_devices(
+    sections: MonitoredSections,
+) -> (Option<CpuData>, Option<GpuData>, Option<Vec<StorageData>>) {
+    run_concurrently(
+        || sections.cpu.then(|| sysinfo_cpu_data(true)).flatten(),
+        || sections.gpu.then(get_gpu_info_without_wmi).flatten(),
+        || sections.storage.then(sysinfo_storage_data).flatten(),
+    )
+}
+
 /// Fallback CPU data from sysinfo (no WMI dependency).
-/// When `sample_cpu_usage` is false, only name/frequency are meaningful and
-/// the 200ms usage-sampling pause is skipped — used when LHM already has the
-/// live load data and sysinfo is only a supplement.
+/// Rated base clock in GHz from the `~MHz` value Windows writes for the
+/// first processor at boot, the same figure `Win32_Processor.MaxClockSpeed`
+/// reports; 0.0 when it can't be read. Read once.
 #[cfg(target_os = "windows")]
-fn sysinfo_cpu_data(sample_cpu_usage: bool) -> Option<CpuData> {
-    let mut sys = System::new();
-    sys.refresh_cpu_all();
-    if sample_cpu_usage {
-        // Brief pause then refresh again for accurate CPU usage readings.
-        std::thread::sleep(std::time::Duration::from_millis(200));
-        sys.refresh_cpu_all();
-    }
+fn cpu_base_frequency() -> f32 {
+    static BASE_FREQUENCY: std::sync::OnceLock<f32> = std::sync::OnceLock::new();
+    *BASE_FREQUENCY.get_or_init(|| {
+        use windows::core::PCWSTR;
+        use windows::Win32::System::Registry::{
+            RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_DWORD,
+        };
 
-    let cpus = sys.cpus();
+        let key: Vec<u16> = "HARDWARE\\DESCRIPTION\\System\\CentralProcessor\\0\0"
+            .encode_utf16()
+            .collect();
+        let value: Vec<u16> = "~MHz\0".encode_utf16().collect();
+        let mut mhz: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                PCWSTR(key.as_ptr()),
+                PCWSTR(value.as_ptr()),
+                RRF_RT_REG_DWORD,
+                None,
+                Some((&mut mhz as *mut u32).cast()),
+                Some(&mut size),
+            )
+        };
+        if status.is_err() {
+            crate::log_warn!("Hardware", "CPU base clock unavailable: {:?}", status);
+            return 0.0;
+        }
+        mhz as f32 / 1000.0
+    })
+}
+
+/// When `sample_cpu_usage` is false, only name/frequency are meaningful and
+/// the 200ms usage-sampling pause is skipped — used when LHM already has the
+/// live load data and sysinfo is only a supplement. Only the first call with
+/// `sample_cpu_usage` pauses; later ones measure since the previous call.
+#[cfg(target_os = "windows")]
+fn sysinfo_cpu_data(sample_cpu_usage: bool) -> Option<CpuData> {
+    let mut guard = CPU_MONITOR.lock().unwrap_or_else(|e| e.into_inner());
+    let monitor = guard.get_or_insert_with(|| CpuMonitor {
+        system: System::new(),
+        last_refresh: None,
+    });
+    match cpu_refresh(monitor.last_refresh.map(|at| at.elapsed())) {
+        CpuRefresh::Baseline => {
+            monitor.system.refresh_cpu_all();
+            if sample_cpu_usage {
+                // Brief pause then refresh again for accurate CPU usage readings.
+                std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+                monitor.system.refresh_cpu_all();
+            }
+            monitor.last_refresh = Some(std::time::Instant::now());
+        }
+        CpuRefresh::Refresh => {
+            monitor.system.refresh_cpu_all();
+            monitor.last_refresh = Some(std::time::Instant::now());
+        }
+        CpuRefresh::Reuse => {}
+    }
+
+    let cpus = monitor.system.cpus();
     if cpus.is_empty() {
         return None;
     }
@@ -856,6 +2352,9 @@ fn sysinfo_cpu_data(sample_cpu_usage: bool) -> Option<CpuData> {
                 index: i as u32,
                 temperature: 0.0, // sysinfo does not provide CPU temperature on Windows
                 load: if sample_cpu_usage { c.cpu_usage() } else { 0.0 },
+                frequency: None,
+                estimated: false,
+                package: None,
             }
         })
         .collect();
@@ -867,13 +2366,36 @@ fn sysinfo_cpu_data(sample_cpu_usage: bool) -> Option<CpuData> {
         load: total_load,
         frequency: avg_freq,
         cores,
+        power: None,
+        voltage: None,
+        is_throttling: false,
+        base_frequency: cpu_base_frequency(),
+        max_frequency: 0.0,
+        packages: None,
+        status: SectionStatus::Ok,
     })
 }
 
 /// Fallback storage data from sysinfo Disks (no WMI dependency).
 #[cfg(target_os = "windows")]
 fn sysinfo_storage_data() -> Option<Vec<StorageData>> {
-    let disks = Disks::new_with_refreshed_list();
+    let mut guard = DISK_MONITOR.lock().unwrap_or_else(|e| e.into_inner());
+    let elapsed_secs = match guard.as_mut() {
+        Some(monitor) => {
+            let elapsed = monitor.last_refresh.elapsed().as_secs_f64();
+            monitor.disks.refresh(true);
+            monitor.last_refresh = std::time::Instant::now();
+            Some(elapsed)
+        }
+        None => {
+            *guard = Some(DiskMonitor {
+                disks: Disks::new_with_refreshed_list(),
+                last_refresh: std::time::Instant::now(),
+            });
+            None
+        }
+    };
+    let disks = &guard.as_ref()?.disks;
     let storage_data: Vec<StorageData> = disks
         .iter()
         .filter(|d| d.total_space() > 1_073_741_824) // > 1GB
@@ -894,8 +2416,15 @@ fn sysinfo_storage_data() -> Option<Vec<StorageData>> {
             StorageData {
                 name,
                 temperature: 0.0,
-                used_space: used_percent,
+                used_space: used_gb,
+                used_percent,
                 total_space: total_gb,
+                health_percent: None,
+                data_written_tb: None,
+                read_rate: bytes_per_sec(d.usage().read_bytes, elapsed_secs),
+                write_rate: bytes_per_sec(d.usage().written_bytes, elapsed_secs),
+                partitions: None,
+                status: SectionStatus::Ok,
             }
         })
         .collect();
@@ -919,6 +2448,23 @@ fn get_gpu_info_without_wmi() -> Option<GpuData> {
             frequency: stats.frequency,
             memory_used: stats.memory_used,
             memory_total: stats.memory_total,
+            power: None,
+            power_limit: None,
+            hotspot_temperature: None,
+            memory_temperature: None,
+            // nvidia-smi reports the fan duty, not RPM
+            fans: stats
+                .fan_percent
+                .map(|percent| {
+                    vec![FanData {
+                        name: "GPU Fan".to_string(),
+                        speed: percent,
+                        unit: FanUnit::Percent,
+                        stopped: percent == 0,
+                    }]
+                })
+                .unwrap_or_default(),
+            status: SectionStatus::Ok,
         });
     }
 
@@ -932,9 +2478,35 @@ fn get_gpu_info_without_wmi() -> Option<GpuData> {
         frequency,
         memory_used,
         memory_total: 0.0,
+        power: None,
+        power_limit: None,
+        hotspot_temperature: None,
+        memory_temperature: None,
+        fans: Vec::new(),
+        status: SectionStatus::Ok,
     })
 }
 
+/// A core without its own temperature sensor. It shows the package
+/// temperature, flagged as estimated so the UI can tell; a package reading
+/// of 0 means there is nothing to show, which isn't an estimate.
+#[cfg(any(target_os = "windows", target_os = "macos", test))]
+fn package_temperature_core(
+    index: u32,
+    load: f32,
+    frequency: Option<f32>,
+    package_temperature: f32,
+) -> CpuCoreData {
+    CpuCoreData {
+        index,
+        temperature: package_temperature,
+        load,
+        frequency,
+        estimated: package_temperature > 0.0,
+        package: None,
+    }
+}
+
 #[cfg(any(target_os = "windows", test))]
 #[derive(Debug, Clone, PartialEq)]
 struct NvidiaGpuStats {
@@ -944,6 +2516,91 @@ struct NvidiaGpuStats {
     frequency: f32,
     name: String,
     memory_total: f32,
+    /// `None` for passively cooled cards, which report `[N/A]`.
+    fan_percent: Option<u32>,
+}
+
+/// How long a missing nvidia-smi is trusted to stay missing before the next
+/// look, so a driver installed while Ondo runs is picked up.
+#[cfg(any(target_os = "windows", test))]
+const NVIDIA_SMI_REPROBE: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Where nvidia-smi lives when it isn't on the PATH: System32 for DCH
+/// drivers, NVSMI for older ones.
+#[cfg(target_os = "windows")]
+const NVIDIA_SMI_DIRS: [&str; 2] = [
+    r"C:\Windows\System32",
+    r"C:\Program Files\NVIDIA Corporation\NVSMI",
+];
+
+/// Result of the last nvidia-smi lookup. Without it every sample on a
+/// machine without an NVIDIA GPU pays for a process spawn that fails.
+#[cfg(target_os = "windows")]
+struct ToolProbe {
+    path: Option<std::path::PathBuf>,
+    probed_at: Option<std::time::Instant>,
+}
+
+#[cfg(target_os = "windows")]
+static NVIDIA_SMI: Mutex<ToolProbe> = Mutex::new(ToolProbe {
+    path: None,
+    probed_at: None,
+});
+
+/// Whether to look for the tool again, given how long ago it was last
+/// looked for. A found tool is kept until running it fails.
+#[cfg(any(target_os = "windows", test))]
+fn tool_probe_due(probed_ago: Option<std::time::Duration>, found: bool) -> bool {
+    match probed_ago {
+        None => true,
+        Some(_) if found => false,
+        Some(ago) => ago >= NVIDIA_SMI_REPROBE,
+    }
+}
+
+/// First `name` in the `PATH`-style `search_path`, then in `extra_dirs`.
+#[cfg(any(target_os = "windows", test))]
+fn find_executable(
+    name: &str,
+    search_path: Option<&std::ffi::OsStr>,
+    extra_dirs: &[std::path::PathBuf],
+) -> Option<std::path::PathBuf> {
+    search_path
+        .map(|paths| std::env::split_paths(paths).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .iter()
+        .chain(extra_dirs)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// The cached nvidia-smi path, looking it up again when `tool_probe_due`.
+#[cfg(target_os = "windows")]
+fn nvidia_smi_path() -> Option<std::path::PathBuf> {
+    let mut probe = NVIDIA_SMI.lock().unwrap_or_else(|e| e.into_inner());
+    let probed_ago = probe.probed_at.map(|at| at.elapsed());
+    if tool_probe_due(probed_ago, probe.path.is_some()) {
+        let extra_dirs: Vec<_> = NVIDIA_SMI_DIRS
+            .iter()
+            .map(std::path::PathBuf::from)
+            .collect();
+        let path = std::env::var_os("PATH");
+        probe.path = find_executable("nvidia-smi.exe", path.as_deref(), &extra_dirs);
+        probe.probed_at = Some(std::time::Instant::now());
+        if probed_ago.is_none() {
+            crate::log_debug!("Hardware", "nvidia-smi: {:?}", probe.path);
+        }
+    }
+    probe.path.clone()
+}
+
+/// nvidia-smi is there but failed (e.g. no driver loaded): treat it as
+/// missing until the next probe.
+#[cfg(target_os = "windows")]
+fn nvidia_smi_failed() {
+    let mut probe = NVIDIA_SMI.lock().unwrap_or_else(|e| e.into_inner());
+    probe.path = None;
+    probe.probed_at = Some(std::time::Instant::now());
 }
 
 #[cfg(target_os = "windows")]
@@ -953,18 +2610,23 @@ fn get_nvidia_smi_stats() -> Option<NvidiaGpuStats> {
 
     const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-    let output = Command::new("nvidia-smi")
+    let program = nvidia_smi_path()?;
+    let Ok(output) = Command::new(program)
         .args([
-            "--query-gpu=temperature.gpu,utilization.gpu,memory.used,clocks.gr,name,memory.total",
+            "--query-gpu=temperature.gpu,utilization.gpu,memory.used,clocks.gr,name,memory.total,fan.speed",
             "--format=csv,noheader,nounits",
         ])
         .creation_flags(CREATE_NO_WINDOW)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .output()
-        .ok()?;
+    else {
+        nvidia_smi_failed();
+        return None;
+    };
 
     if !output.status.success() {
+        nvidia_smi_failed();
         return None;
     }
 
@@ -987,9 +2649,55 @@ fn parse_nvidia_smi_stats(line: &str) -> Option<NvidiaGpuStats> {
         frequency: parts[3].parse::<f32>().ok()? / 1000.0,
         name: parts[4].to_string(),
         memory_total: parts[5].parse::<f32>().ok()? / 1024.0,
+        fan_percent: parts
+            .get(6)
+            .and_then(|fan| fan.parse::<f32>().ok())
+            .map(|fan| fan.round() as u32),
     })
 }
 
+/// Whether `program` runs and exits successfully with `args`.
+#[cfg(target_os = "windows")]
+fn cli_runs(program: impl AsRef<std::ffi::OsStr>, args: &[&str]) -> bool {
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    Command::new(program)
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Whether the GPU fallback can use nvidia-smi: installed, and a driver is
+/// loaded for it to talk to. Spawns a process; call it off the async runtime.
+#[cfg(target_os = "windows")]
+pub fn nvidia_smi_available() -> bool {
+    nvidia_smi_path().is_some_and(|path| cli_runs(&path, &["-L"]))
+}
+
+/// Whether the GPU fallback can use rocm-smi. Spawns a process; call it off
+/// the async runtime.
+#[cfg(target_os = "windows")]
+pub fn rocm_smi_available() -> bool {
+    cli_runs("rocm-smi", &["--showid"])
+}
+
+/// The vendor GPU tools are only used by the Windows fallback.
+#[cfg(not(target_os = "windows"))]
+pub fn nvidia_smi_available() -> bool {
+    false
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn rocm_smi_available() -> bool {
+    false
+}
+
 // AMD GPU stats using rocm-smi or fallback methods
 #[cfg(target_os = "windows")]
 fn get_amd_gpu_stats() -> Option<(f32, f32, f32, f32)> {
@@ -1040,9 +2748,6 @@ fn get_amd_gpu_stats() -> Option<(f32, f32, f32, f32)> {
 #[cfg(target_os = "macos")]
 use sysinfo::Components;
 
-#[cfg(target_os = "macos")]
-use std::sync::Mutex;
-
 #[cfg(target_os = "macos")]
 struct MacOsMonitor {
     system: System,
@@ -1152,8 +2857,9 @@ fn classify_temperature(label: &str) -> TempKind {
 }
 
 #[cfg(target_os = "macos")]
-pub async fn get_hardware_info() -> Result<HardwareData, String> {
-    tokio::task::spawn_blocking(|| {
+async fn collect_hardware_info(sections: MonitoredSections) -> Result<HardwareData, String> {
+    let storage_enabled = sections.storage;
+    tokio::task::spawn_blocking(move || {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_millis() as u64)
@@ -1174,7 +2880,9 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
         // Refresh sensor data
         monitor.system.refresh_cpu_usage();
         monitor.components.refresh(true);
-        monitor.disks.refresh(true);
+        if storage_enabled {
+            monitor.disks.refresh(true);
+        }
         let net_elapsed = monitor.last_refresh.elapsed().as_secs_f64();
         monitor.networks.refresh(true);
         monitor.last_refresh = std::time::Instant::now();
@@ -1235,23 +2943,12 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
             0.0
         };
 
-        let num_cpus = cpus.len();
+        // No per-core sensors here; per-core clocks are unreliable on Apple
+        // Silicon too
         let cores: Vec<CpuCoreData> = cpus
             .iter()
             .enumerate()
-            .map(|(i, cpu)| {
-                // Estimate per-core temps (slight variation around the package temp)
-                let core_temp = if cpu_temp > 0.0 {
-                    cpu_temp + (i as f32 * 0.3) - (num_cpus as f32 * 0.15)
-                } else {
-                    0.0
-                };
-                CpuCoreData {
-                    index: i as u32,
-                    temperature: core_temp,
-                    load: cpu.cpu_usage(),
-                }
-            })
+            .map(|(i, cpu)| package_temperature_core(i as u32, cpu.cpu_usage(), None, cpu_temp))
             .collect();
 
         let cpu = if !cpu_name.is_empty() {
@@ -1262,6 +2959,13 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
                 load: cpu_load,
                 frequency: cpu_freq,
                 cores,
+                power: None,
+                voltage: None,
+                is_throttling: false,
+                base_frequency: 0.0,
+                max_frequency: 0.0,
+                packages: None,
+                status: SectionStatus::Ok,
             })
         } else {
             None
@@ -1283,6 +2987,12 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
                 frequency: 0.0,
                 memory_used: 0.0,
                 memory_total: monitor.gpu_memory_total,
+                power: None,
+                power_limit: None,
+                hotspot_temperature: None,
+                memory_temperature: None,
+                fans: Vec::new(),
+                status: SectionStatus::Ok,
             })
         } else {
             None
@@ -1329,8 +3039,15 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
                 StorageData {
                     name,
                     temperature: temp,
-                    used_space: used_percent,
+                    used_space: total_gb - available_gb,
+                    used_percent,
                     total_space: total_gb,
+                    health_percent: None,
+                    data_written_tb: None,
+                    read_rate: None,
+                    write_rate: None,
+                    partitions: None,
+                    status: SectionStatus::Ok,
                 }
             })
             .collect();
@@ -1339,7 +3056,10 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
         let motherboard = Some(MotherboardData {
             name: monitor.model_name.clone(),
             temperature: board_temp,
+            temperatures: Vec::new(),
             fans: Vec::new(), // Fan speeds not available through sysinfo on macOS
+            voltages: Vec::new(),
+            status: SectionStatus::Ok,
         });
 
         // Network
@@ -1367,6 +3087,15 @@ pub async fn get_hardware_info() -> Result<HardwareData, String> {
             timestamp,
             cpu_error: None,
             gpu_error: None,
+            cpu_error_detail: None,
+            gpu_error_detail: None,
+            system_power: None,
+            data_age_ms: None,
+            limited_access: false,
+            data_source: "sysinfo".to_string(),
+            degraded: false,
+            section_timestamps: Default::default(),
+            raw_readings: None,
         })
     })
     .await
@@ -1476,6 +3205,52 @@ pub fn shutdown_lhm_daemon() {
     // No-op on non-Windows
 }
 
+#[cfg(not(target_os = "windows"))]
+pub fn lhm_daemon_uptime() -> Option<std::time::Duration> {
+    None
+}
+
+/// Update the daemon interval and, if a daemon is running with a different
+/// one, kill and respawn it. The restart happens while holding `LHM_DAEMON`,
+/// so concurrent `get_hardware_info` calls block until the new daemon is up
+/// instead of observing a half-torn-down one. Blocks for up to the daemon's
+/// startup timeout; call it off the async runtime.
+#[cfg(target_os = "windows")]
+pub fn set_lhm_interval(interval_ms: u32) {
+    LHM_INTERVAL_MS.store(interval_ms, std::sync::atomic::Ordering::SeqCst);
+
+    let Ok(mut daemon_guard) = LHM_DAEMON.lock() else {
+        return;
+    };
+    let running_ms = daemon_guard.as_ref().map(|d| d.interval_ms);
+    if !lhm_interval_needs_restart(running_ms, interval_ms) {
+        return;
+    }
+
+    crate::log_info!(
+        "Hardware",
+        "LHM interval changed {}ms -> {}ms, restarting daemon",
+        running_ms.unwrap_or_default(),
+        interval_ms
+    );
+    if let Some(daemon) = daemon_guard.take() {
+        daemon.stop();
+    }
+    match start_lhm_daemon(interval_ms) {
+        Ok(daemon) => *daemon_guard = Some(daemon),
+        Err(e) => {
+            // Leave the slot empty; the next poll retries the start
+            crate::log_error!("Hardware", "Failed to restart LHM daemon: {}", e);
+            error_reporting::capture_lhm_error(&format!("Failed to restart daemon: {}", e));
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_lhm_interval(_interval_ms: u32) {
+    // No daemon on non-Windows
+}
+
 #[cfg(target_os = "windows")]
 pub fn lhm_daemon_status() -> LhmDaemonStatus {
     let mut daemon_guard = match LHM_DAEMON.lock() {
@@ -1483,6 +3258,7 @@ pub fn lhm_daemon_status() -> LhmDaemonStatus {
         Err(e) => {
             return LhmDaemonStatus {
                 supported: true,
+                sensor_role: sensor_owner::role(),
                 running: false,
                 pid: None,
                 has_cached_data: false,
@@ -1495,6 +3271,7 @@ pub fn lhm_daemon_status() -> LhmDaemonStatus {
     let Some(daemon) = daemon_guard.as_mut() else {
         return LhmDaemonStatus {
             supported: true,
+            sensor_role: sensor_owner::role(),
             running: false,
             pid: None,
             has_cached_data: false,
@@ -1504,13 +3281,17 @@ pub fn lhm_daemon_status() -> LhmDaemonStatus {
     };
 
     let pid = daemon.process.id();
-    let has_cached_data = daemon.latest_data.is_some();
+    let has_cached_data = daemon.latest_data().is_some();
 
     match daemon.process.try_wait() {
         Ok(Some(status)) => {
-            *daemon_guard = None;
+            if let Some(daemon) = daemon_guard.take() {
+                daemon.stop();
+            }
+            report_lhm_exit(&format!("LHM daemon exited with status: {}", status));
             LhmDaemonStatus {
                 supported: true,
+                sensor_role: sensor_owner::role(),
                 running: false,
                 pid: Some(pid),
                 has_cached_data,
@@ -1520,6 +3301,7 @@ pub fn lhm_daemon_status() -> LhmDaemonStatus {
         }
         Ok(None) => LhmDaemonStatus {
             supported: true,
+            sensor_role: sensor_owner::role(),
             running: true,
             pid: Some(pid),
             has_cached_data,
@@ -1528,6 +3310,7 @@ pub fn lhm_daemon_status() -> LhmDaemonStatus {
         },
         Err(e) => LhmDaemonStatus {
             supported: true,
+            sensor_role: sensor_owner::role(),
             running: false,
             pid: Some(pid),
             has_cached_data,
@@ -1541,6 +3324,7 @@ pub fn lhm_daemon_status() -> LhmDaemonStatus {
 pub fn lhm_daemon_status() -> LhmDaemonStatus {
     LhmDaemonStatus {
         supported: false,
+        sensor_role: sensor_owner::role(),
         running: false,
         pid: None,
         has_cached_data: false,
@@ -1549,9 +3333,101 @@ pub fn lhm_daemon_status() -> LhmDaemonStatus {
     }
 }
 
+/// Daemon status plus the captured stderr tail, for troubleshooting.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LhmDiagnostics {
+    pub status: LhmDaemonStatus,
+    pub helper: Option<LhmHandshake>,
+    pub stderr: Vec<String>,
+    pub last_exit_error: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+pub fn lhm_diagnostics() -> LhmDiagnostics {
+    let status = lhm_daemon_status();
+    LhmDiagnostics {
+        status,
+        helper: LHM_HELPER.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        stderr: LHM_STDERR
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .to_vec(),
+        last_exit_error: LHM_LAST_EXIT
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone(),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn lhm_diagnostics() -> LhmDiagnostics {
+    LhmDiagnostics {
+        status: lhm_daemon_status(),
+        helper: None,
+        stderr: Vec::new(),
+        last_exit_error: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{parse_nvidia_smi_stats, NvidiaGpuStats};
+    use super::{annotate_status, section_status, SectionStatus};
+    use super::{
+        apply_sensor_overrides, bytes_per_sec, check_lhm_handshake, cpu_refresh, fallback_errors,
+        lhm_fans, lhm_interval_needs_restart, lhm_is_stalled, lhm_partitions, lhm_temperatures,
+        lhm_voltages, merge_cpu_packages, normalize_hardware_data, override_device,
+        package_temperature_core, parse_lhm_handshake, parse_nvidia_smi_stats, run_concurrently,
+        CpuRefresh, DetectedSensor, LhmCpuCoreData, LhmCpuData, LhmFanData, LhmHandshake,
+        LhmPartitionData, LhmSensorError, LhmTemperatureData, LhmVoltageData, MonitoredSections,
+        NvidiaGpuStats, SensorKind, StderrTail, LHM_PROTOCOL, LHM_STALL_AFTER,
+        LHM_STDERR_MAX_LINES, LHM_STDERR_MAX_LINE_LEN, MAX_DETECTED_SENSORS,
+    };
+    use super::{find_executable, tool_probe_due, NVIDIA_SMI_REPROBE};
+    use super::{interleave_slow_sections, slow_sections_due, SectionTimestamps, SlowSections};
+    use crate::settings::{ComponentThresholds, SensorOverride, Thresholds};
+    use crate::{
+        CpuCoreData, CpuData, FanData, FanUnit, HardwareData, MotherboardData, NamedTemperature,
+        NetworkInterfaceData, SensorError, StorageData, VoltageData,
+    };
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[test]
+    fn missing_nvidia_smi_is_looked_for_again_only_after_the_reprobe_interval() {
+        use std::time::Duration;
+
+        assert!(tool_probe_due(None, false));
+        assert!(!tool_probe_due(Some(Duration::from_secs(1)), false));
+        assert!(tool_probe_due(Some(NVIDIA_SMI_REPROBE), false));
+        // Found: kept until running it fails
+        assert!(!tool_probe_due(Some(Duration::from_secs(3600)), true));
+    }
+
+    #[test]
+    fn executable_is_found_on_the_path_before_the_extra_dirs() {
+        let root = std::env::temp_dir().join(format!("ondo-test-{}-which", std::process::id()));
+        let (on_path, extra, empty) = (root.join("bin"), root.join("extra"), root.join("empty"));
+        for dir in [&on_path, &extra, &empty] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(on_path.join("tool.exe"), b"").unwrap();
+        std::fs::write(extra.join("tool.exe"), b"").unwrap();
+
+        let search = std::env::join_paths([&empty, &on_path]).unwrap();
+        assert_eq!(
+            find_executable("tool.exe", Some(&search), std::slice::from_ref(&extra)),
+            Some(on_path.join("tool.exe"))
+        );
+        assert_eq!(
+            find_executable("tool.exe", None, &[empty.clone(), extra.clone()]),
+            Some(extra.join("tool.exe"))
+        );
+        assert_eq!(
+            find_executable("missing.exe", Some(&search), &[extra]),
+            None
+        );
+        let _ = std::fs::remove_dir_all(&root);
+    }
 
     #[test]
     fn parses_combined_nvidia_smi_output() {
@@ -1567,12 +3443,850 @@ mod tests {
                 frequency: 1.98,
                 name: "NVIDIA GeForce RTX 4080".to_string(),
                 memory_total: 15.9921875,
+                fan_percent: None,
             }
         );
     }
 
+    #[test]
+    fn nvidia_smi_fan_duty_keeps_fan_stop() {
+        let row = |fan| format!("40, 0, 512, 210, NVIDIA GeForce RTX 4070, 12282, {}", fan);
+        let fan_percent = |fan| parse_nvidia_smi_stats(&row(fan)).unwrap().fan_percent;
+        assert_eq!(fan_percent("35"), Some(35));
+        // Fan-stop at idle is a reading, not a missing fan
+        assert_eq!(fan_percent("0"), Some(0));
+        assert_eq!(fan_percent("[N/A]"), None);
+    }
+
+    #[test]
+    fn stopped_fans_are_kept() {
+        let fans = lhm_fans(Some(vec![
+            LhmFanData {
+                name: "GPU Fan 1".to_string(),
+                unit: FanUnit::Rpm,
+                speed: 0,
+            },
+            LhmFanData {
+                name: "GPU Fan 2".to_string(),
+                unit: FanUnit::Rpm,
+                speed: 1450,
+            },
+        ]));
+        let speeds: Vec<(u32, bool)> = fans.iter().map(|f| (f.speed, f.stopped)).collect();
+        assert_eq!(speeds, vec![(0, true), (1450, false)]);
+        assert!(lhm_fans(None).is_empty());
+    }
+
+    #[test]
+    fn fallback_errors_explain_the_missing_sensors() {
+        let (cpu, gpu) = fallback_errors(None, true, true);
+        assert_eq!(cpu.unwrap().code, "sysinfo_no_temperature");
+        let gpu = gpu.unwrap();
+        assert_eq!(
+            (gpu.code.as_str(), gpu.source.as_str()),
+            ("sysinfo_no_gpu", "sysinfo")
+        );
+        assert_eq!(fallback_errors(None, false, false), (None, None));
+
+        let incompatible = SensorError::new("lhm_incompatible", "Helper too old", "lhm");
+        let (cpu, gpu) = fallback_errors(Some(incompatible.clone()), true, false);
+        assert_eq!(cpu.as_ref(), Some(&incompatible));
+        assert_eq!(gpu.as_ref(), Some(&incompatible));
+    }
+
+    #[test]
+    fn helper_errors_are_tagged_with_their_source() {
+        let error: LhmSensorError = serde_json::from_value(serde_json::json!({
+            "code": "lhm_no_gpu",
+            "message": "GPU sensors unavailable: driver not loaded",
+        }))
+        .unwrap();
+        let error = error.into_sensor_error();
+        assert_eq!(error.source, "lhm");
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "lhm_no_gpu");
+        assert_eq!(
+            json["message"],
+            "GPU sensors unavailable: driver not loaded"
+        );
+    }
+
+    #[test]
+    fn fan_units_come_from_the_helper() {
+        let fans: Vec<LhmFanData> = serde_json::from_value(serde_json::json!([
+            { "name": "Fan #1", "speed": 1200, "unit": "rpm" },
+            { "name": "Fan Control #3", "speed": 40, "unit": "percent" },
+            { "name": "Fan #2", "speed": 900 },
+        ]))
+        .unwrap();
+        let fans = lhm_fans(Some(fans));
+        let units: Vec<FanUnit> = fans.iter().map(|f| f.unit).collect();
+        assert_eq!(units, vec![FanUnit::Rpm, FanUnit::Percent, FanUnit::Rpm]);
+        let json = serde_json::to_value(&fans[1]).unwrap();
+        assert_eq!(json["unit"], "percent");
+    }
+
+    #[test]
+    fn dead_voltage_inputs_are_dropped() {
+        let reading = |name: &str, volts: f32| LhmVoltageData {
+            name: name.to_string(),
+            volts,
+        };
+        let voltages = lhm_voltages(Some(vec![
+            reading("Vcore", 1.21),
+            reading("+3.3V", 3.31),
+            reading("Voltage #7", 0.0),
+            reading("DRAM", 1.35),
+            reading("+12V", 12.1),
+            reading("5VCC", 5.02),
+            reading("VBat", f32::NAN),
+        ]));
+        let names: Vec<&str> = voltages.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["Vcore", "+3.3V", "DRAM", "+12V", "5VCC"]);
+        assert!(lhm_voltages(None).is_empty());
+    }
+
+    #[test]
+    fn board_temperatures_keep_their_names() {
+        let reading = |name: &str, temperature: f32| LhmTemperatureData {
+            name: name.to_string(),
+            temperature,
+        };
+        let temperatures = lhm_temperatures(Some(vec![
+            reading("System", 34.0),
+            reading("VRM MOS", 52.5),
+            reading("Temperature #3", 0.0),
+            reading("PCH", 48.0),
+            reading("AUXTIN1", 255.0),
+            reading("Chipset", f32::NAN),
+        ]));
+        let names: Vec<&str> = temperatures.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["System", "VRM MOS", "PCH"]);
+        assert_eq!(temperatures[1].temperature, 52.5);
+        assert!(lhm_temperatures(None).is_empty());
+    }
+
+    #[test]
+    fn partitions_skip_network_shares_and_empty_volumes() {
+        let partitions: Vec<LhmPartitionData> = serde_json::from_value(serde_json::json!([
+            { "mount_point": "C:", "used_space": 412.5, "total_space": 1000.0 },
+            { "mount_point": "D:", "used_space": 0.0, "total_space": 0.0 },
+            { "mount_point": "\\\\nas\\media", "used_space": 1.0, "total_space": 4000.0 },
+        ]))
+        .unwrap();
+        let partitions = lhm_partitions(partitions);
+        assert_eq!(partitions.len(), 1);
+        let json = serde_json::to_value(&partitions[0]).unwrap();
+        assert_eq!(json["mountPoint"], "C:");
+        assert_eq!(json["usedSpace"], 412.5);
+        assert!(lhm_partitions(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn cpu_packages_are_merged_into_one_cpu() {
+        let package = |temperature: f32, load: f32, power: Option<f32>| -> LhmCpuData {
+            serde_json::from_value(serde_json::json!({
+                "name": "Xeon Gold 6248",
+                "temperature": temperature,
+                "max_temperature": 100.0,
+                "load": load,
+                "frequency": 2.5,
+                "cores": [
+                    { "index": 0, "temperature": temperature, "load": load },
+                    { "index": 1, "temperature": 0.0, "load": load },
+                ],
+                "power": power,
+            }))
+            .unwrap()
+        };
+
+        let single = merge_cpu_packages(vec![package(60.0, 30.0, Some(80.0))]).unwrap();
+        assert_eq!(single.name, "Xeon Gold 6248");
+        assert!(single.packages.is_none());
+        assert!(single.cores.unwrap().iter().all(|c| c.package.is_none()));
+
+        let cpu = merge_cpu_packages(vec![
+            package(60.0, 20.0, Some(80.0)),
+            package(72.0, 40.0, Some(95.0)),
+        ])
+        .unwrap();
+        assert_eq!(cpu.name, "2 × Xeon Gold 6248");
+        assert_eq!(cpu.temperature, 72.0);
+        assert_eq!(cpu.load, 30.0);
+        assert_eq!(cpu.frequency, 2.5);
+        assert_eq!(cpu.power, Some(175.0));
+        let cores: Vec<(u32, Option<u32>)> = cpu
+            .cores
+            .unwrap()
+            .iter()
+            .map(|c: &LhmCpuCoreData| (c.index, c.package))
+            .collect();
+        assert_eq!(
+            cores,
+            vec![(0, Some(0)), (1, Some(0)), (2, Some(1)), (3, Some(1))]
+        );
+        let packages = cpu.packages.unwrap();
+        assert_eq!(packages.len(), 2);
+        assert_eq!((packages[1].index, packages[1].temperature), (1, 72.0));
+        assert!(merge_cpu_packages(Vec::new()).is_none());
+    }
+
     #[test]
     fn rejects_incomplete_nvidia_smi_output() {
         assert!(parse_nvidia_smi_stats("62, 48, 6144").is_none());
     }
+
+    #[test]
+    fn cores_without_sensors_report_the_package_temperature_as_estimated() {
+        let core = package_temperature_core(3, 20.0, Some(4.5), 61.0);
+        assert_eq!(core.temperature, 61.0);
+        assert!(core.estimated);
+        assert_eq!(core.frequency, Some(4.5));
+
+        // No package reading either: nothing was estimated
+        let core = package_temperature_core(3, 20.0, None, 0.0);
+        assert_eq!(core.temperature, 0.0);
+        assert!(!core.estimated);
+    }
+
+    #[test]
+    fn core_payloads_without_the_new_fields_still_load() {
+        let core: CpuCoreData =
+            serde_json::from_str(r#"{"index":1,"temperature":50.0,"load":5.0}"#).unwrap();
+        assert_eq!(core.frequency, None);
+        assert!(!core.estimated);
+        let json = serde_json::to_value(&core).unwrap();
+        assert_eq!(json["frequency"], serde_json::Value::Null);
+        assert_eq!(json["estimated"], false);
+    }
+
+    #[test]
+    fn missing_power_sensors_are_omitted_rather_than_zero() {
+        let mut data = sample(0);
+        let json = serde_json::to_value(&data).unwrap();
+        assert!(json["cpu"].get("power").is_none());
+        assert!(json["cpu"].get("voltage").is_none());
+
+        let cpu = data.cpu.as_mut().unwrap();
+        cpu.power = Some(88.0);
+        cpu.voltage = Some(1.25);
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["cpu"]["power"], 88.0);
+        assert_eq!(json["cpu"]["voltage"], 1.25);
+
+        let mock = crate::mock::MockHardware::new(0, 1000).sample_at(0);
+        assert_eq!(
+            serde_json::to_value(&mock).unwrap()["gpu"]["powerLimit"],
+            250.0
+        );
+    }
+
+    #[test]
+    fn storage_reports_used_space_in_gb_and_omits_missing_smart_values() {
+        let data = sample(0);
+        let json = serde_json::to_value(&data).unwrap();
+        let drive = &json["storage"][0];
+        assert_eq!(drive["usedSpace"], 20.0);
+        assert_eq!(drive["usedPercent"], 4.0);
+        for key in [
+            "healthPercent",
+            "dataWrittenTb",
+            "readRate",
+            "writeRate",
+            "partitions",
+        ] {
+            assert!(drive.get(key).is_none(), "{} should be omitted", key);
+        }
+
+        // Payloads from before usedPercent still deserialize
+        let old: StorageData = serde_json::from_value(serde_json::json!({
+            "name": "SSD",
+            "temperature": 38.0,
+            "usedSpace": 200.0,
+            "totalSpace": 1000.0,
+        }))
+        .unwrap();
+        assert_eq!(old.used_percent, 0.0);
+        assert_eq!(old.health_percent, None);
+    }
+
+    #[test]
+    fn sensor_overrides_hide_and_rename_devices_by_reported_name() {
+        let overrides: BTreeMap<String, SensorOverride> = [
+            ("WD Blue 500GB", true, None),
+            ("Samsung SSD 980 PRO 1TB", false, Some("NVMe")),
+            ("Fan #1", true, None),
+            ("Test CPU", true, None),
+        ]
+        .into_iter()
+        .map(|(name, hidden, alias)| {
+            let sensor = SensorOverride {
+                hidden,
+                alias: alias.map(str::to_string),
+            };
+            (name.to_string(), sensor)
+        })
+        .collect();
+        let mut detected = BTreeSet::new();
+        let mut data = sample(0);
+        apply_sensor_overrides(&mut data, &overrides, &mut detected);
+
+        assert!(data.cpu.is_none());
+        let drives: Vec<String> = data.storage.unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(drives, vec!["NVMe", "NVMe"]);
+        let fans: Vec<String> = data
+            .motherboard
+            .unwrap()
+            .fans
+            .into_iter()
+            .map(|f| f.name)
+            .collect();
+        assert!(!fans.contains(&"Fan #1".to_string()));
+
+        // Detected under the reported names, hidden devices included
+        assert!(detected.contains(&DetectedSensor {
+            kind: SensorKind::Storage,
+            name: "WD Blue 500GB".to_string(),
+        }));
+        assert!(detected.contains(&DetectedSensor {
+            kind: SensorKind::Cpu,
+            name: "Test CPU".to_string(),
+        }));
+        assert!(!detected.iter().any(|sensor| sensor.name == "NVMe"));
+    }
+
+    #[test]
+    fn disabled_sections_are_dropped_from_samples() {
+        let mut data = crate::mock::MockHardware::new(0, 1000).sample_at(0);
+        MonitoredSections {
+            storage: false,
+            motherboard: false,
+            ..MonitoredSections::ALL
+        }
+        .mask(&mut data);
+        assert!(data.cpu.is_some());
+        assert!(data.gpu.is_some());
+        assert!(data.storage.is_none());
+        assert!(data.motherboard.is_none());
+        // Not a device section
+        assert!(data.network.is_some());
+    }
+
+    #[test]
+    fn lhm_helper_is_told_the_enabled_sections() {
+        assert_eq!(
+            MonitoredSections::ALL.lhm_command(),
+            "sections cpu gpu storage motherboard"
+        );
+        let sections = MonitoredSections {
+            gpu: false,
+            motherboard: false,
+            ..MonitoredSections::ALL
+        };
+        assert_eq!(sections.lhm_command(), "sections cpu storage");
+        let none = MonitoredSections {
+            cpu: false,
+            gpu: false,
+            storage: false,
+            motherboard: false,
+        };
+        assert_eq!(none.lhm_command(), "sections");
+    }
+
+    #[test]
+    fn slow_sections_are_due_after_their_interval_or_a_daemon_restart() {
+        let cached = SlowSections {
+            taken_at: 10_000,
+            generation: 1,
+            storage: None,
+            motherboard: None,
+        };
+        assert!(slow_sections_due(None, 10_000, 1, 5000));
+        assert!(!slow_sections_due(Some(&cached), 11_000, 1, 5000));
+        // A poll slightly early still counts
+        assert!(slow_sections_due(Some(&cached), 14_900, 1, 5000));
+        assert!(slow_sections_due(Some(&cached), 11_000, 2, 5000));
+    }
+
+    #[test]
+    fn status_follows_max_temperature_until_customized() {
+        let limits = Thresholds::default().cpu;
+        let status = |temperature, load| section_status(&limits, temperature, 100.0, Some(load));
+        assert_eq!(status(84.0, 50.0), SectionStatus::Ok);
+        assert_eq!(status(85.0, 50.0), SectionStatus::Warning);
+        assert_eq!(status(95.0, 50.0), SectionStatus::Critical);
+        assert_eq!(status(60.0, 90.0), SectionStatus::Warning);
+        // A missing sensor is not cold
+        assert_eq!(status(0.0, 10.0), SectionStatus::Ok);
+
+        let custom = ComponentThresholds {
+            warning_temperature: Some(70.0),
+            ..limits
+        };
+        assert_eq!(
+            section_status(&custom, 72.0, 100.0, None),
+            SectionStatus::Warning
+        );
+        // Nothing to go by
+        assert_eq!(
+            section_status(&ComponentThresholds::default(), 120.0, 0.0, Some(100.0)),
+            SectionStatus::Ok
+        );
+    }
+
+    #[test]
+    fn every_section_is_annotated() {
+        let mut data = crate::mock::MockHardware::new(0, 1000).sample_at(0);
+        data.storage.as_mut().unwrap()[0].temperature = 75.0;
+        data.motherboard.as_mut().unwrap().temperature = 90.0;
+        annotate_status(&mut data, &Thresholds::default());
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["storage"][0]["status"], "warning");
+        assert_eq!(json["storage"][1]["status"], "ok");
+        assert_eq!(json["motherboard"]["status"], "critical");
+        assert!(json["cpu"]["status"].is_string());
+        assert!(json["gpu"]["status"].is_string());
+    }
+
+    #[test]
+    fn slow_sections_are_reused_between_slow_ticks() {
+        let mock = crate::mock::MockHardware::new(0, 1000);
+        let mut cached = None;
+
+        let mut first = mock.sample_at(0);
+        interleave_slow_sections(&mut first, &mut cached, true, 1);
+        assert_eq!(
+            first.section_timestamps,
+            SectionTimestamps {
+                cpu: Some(0),
+                gpu: Some(0),
+                storage: Some(0),
+                motherboard: Some(0),
+            }
+        );
+
+        // Slow sections skipped by the platform are filled in from the cache
+        let mut second = mock.sample_at(1);
+        MonitoredSections::ALL.with_slow(false).mask(&mut second);
+        interleave_slow_sections(&mut second, &mut cached, false, 1);
+        assert_eq!(
+            second.section_timestamps,
+            SectionTimestamps {
+                cpu: Some(1000),
+                gpu: Some(1000),
+                storage: Some(0),
+                motherboard: Some(0),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&second.storage).unwrap(),
+            serde_json::to_value(&first.storage).unwrap()
+        );
+        assert_eq!(
+            second.motherboard.map(|m| m.temperature),
+            first.motherboard.map(|m| m.temperature)
+        );
+    }
+
+    #[test]
+    fn cpu_usage_baseline_is_taken_once_then_reused() {
+        use std::time::Duration;
+        assert_eq!(cpu_refresh(None), CpuRefresh::Baseline);
+        assert_eq!(
+            cpu_refresh(Some(Duration::from_secs(1))),
+            CpuRefresh::Refresh
+        );
+        assert_eq!(
+            cpu_refresh(Some(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL)),
+            CpuRefresh::Refresh
+        );
+        // Faster polling (e.g. the debug server alongside the widget) gets
+        // the previous readings instead of a meaningless delta
+        assert_eq!(
+            cpu_refresh(Some(Duration::from_millis(50))),
+            CpuRefresh::Reuse
+        );
+    }
+
+    #[test]
+    fn disk_rates_need_a_previous_refresh() {
+        assert_eq!(bytes_per_sec(4096, None), None);
+        assert_eq!(bytes_per_sec(4096, Some(0.0)), None);
+        assert_eq!(bytes_per_sec(4096, Some(2.0)), Some(2048.0));
+    }
+
+    #[test]
+    fn fallback_readings_overlap_instead_of_adding_up() {
+        use std::time::{Duration, Instant};
+        let reading = |value: u32| {
+            move || {
+                std::thread::sleep(Duration::from_millis(150));
+                value
+            }
+        };
+        let started = Instant::now();
+        let values = run_concurrently(reading(1), reading(2), reading(3));
+        assert_eq!(values, (1, 2, 3));
+        // Sequentially this takes 450ms
+        assert!(started.elapsed() < Duration::from_millis(400));
+    }
+
+    #[test]
+    fn detected_sensors_are_capped() {
+        let mut detected = BTreeSet::new();
+        for i in 0..MAX_DETECTED_SENSORS + 10 {
+            let mut name = format!("vEthernet {}", i);
+            override_device(
+                SensorKind::Network,
+                &mut name,
+                &BTreeMap::new(),
+                &mut detected,
+            );
+        }
+        assert_eq!(detected.len(), MAX_DETECTED_SENSORS);
+    }
+
+    fn sample(rotation: usize) -> HardwareData {
+        let mut cores: Vec<CpuCoreData> = (0..8)
+            .map(|i| CpuCoreData {
+                index: i,
+                temperature: 50.0 + i as f32,
+                load: 10.0 * i as f32,
+                frequency: Some(4.0),
+                estimated: false,
+                package: None,
+            })
+            .collect();
+        let mut storage = vec![
+            StorageData {
+                name: "WD Blue 500GB".to_string(),
+                temperature: 35.0,
+                used_space: 20.0,
+                used_percent: 4.0,
+                total_space: 500.0,
+                health_percent: None,
+                data_written_tb: None,
+                read_rate: None,
+                write_rate: None,
+                partitions: None,
+                status: SectionStatus::Ok,
+            },
+            StorageData {
+                name: "Samsung SSD 980 PRO 1TB".to_string(),
+                temperature: 41.0,
+                used_space: 43.0,
+                used_percent: 4.2,
+                total_space: 1024.0,
+                health_percent: None,
+                data_written_tb: None,
+                read_rate: None,
+                write_rate: None,
+                partitions: None,
+                status: SectionStatus::Ok,
+            },
+            StorageData {
+                name: "Samsung SSD 980 PRO 1TB".to_string(),
+                temperature: 39.0,
+                used_space: 12.0,
+                used_percent: 1.2,
+                total_space: 1024.0,
+                health_percent: None,
+                data_written_tb: None,
+                read_rate: None,
+                write_rate: None,
+                partitions: None,
+                status: SectionStatus::Ok,
+            },
+        ];
+        let mut fans = vec![
+            FanData {
+                name: "Fan #2".to_string(),
+                unit: FanUnit::Rpm,
+                speed: 900,
+                stopped: false,
+            },
+            FanData {
+                name: "Fan #1".to_string(),
+                unit: FanUnit::Rpm,
+                speed: 1200,
+                stopped: false,
+            },
+            FanData {
+                name: "CPU Fan".to_string(),
+                unit: FanUnit::Rpm,
+                speed: 1500,
+                stopped: false,
+            },
+        ];
+        let named = |name: &str, temperature: f32| NamedTemperature {
+            name: name.to_string(),
+            temperature,
+        };
+        let mut temperatures = vec![
+            named("VRM MOS", 52.5),
+            named("System", 34.0),
+            named("PCH", 48.0),
+            named("Temperature #2", 39.5),
+            named("Temperature #2", 41.0),
+        ];
+        let volts = |name: &str, volts: f32| VoltageData {
+            name: name.to_string(),
+            volts,
+        };
+        let mut voltages = vec![
+            volts("Vcore", 1.21),
+            volts("+3.3V", 3.31),
+            volts("VIN4", 1.02),
+            volts("+12V", 12.1),
+            volts("VIN4", 0.98),
+            volts("5VCC", 5.02),
+        ];
+        let mut network = vec![
+            NetworkInterfaceData {
+                name: "Wi-Fi".to_string(),
+                received_per_sec: 100.0,
+                sent_per_sec: 50.0,
+            },
+            NetworkInterfaceData {
+                name: "Ethernet".to_string(),
+                received_per_sec: 2000.0,
+                sent_per_sec: 300.0,
+            },
+        ];
+
+        let (core_count, storage_count, fan_count, temperature_count, voltage_count, network_count) = (
+            cores.len(),
+            storage.len(),
+            fans.len(),
+            temperatures.len(),
+            voltages.len(),
+            network.len(),
+        );
+        cores.rotate_left(rotation % core_count);
+        storage.rotate_left(rotation % storage_count);
+        fans.rotate_left(rotation % fan_count);
+        temperatures.rotate_left(rotation % temperature_count);
+        voltages.rotate_left(rotation % voltage_count);
+        network.rotate_left(rotation % network_count);
+        if rotation % 2 == 1 {
+            cores.reverse();
+            storage.reverse();
+            fans.reverse();
+            temperatures.reverse();
+            voltages.reverse();
+        }
+
+        HardwareData {
+            cpu: Some(CpuData {
+                name: "Test CPU".to_string(),
+                temperature: 60.0,
+                max_temperature: 100.0,
+                load: 30.0,
+                frequency: 4.2,
+                cores,
+                power: None,
+                voltage: None,
+                is_throttling: false,
+                base_frequency: 0.0,
+                max_frequency: 0.0,
+                packages: None,
+                status: SectionStatus::Ok,
+            }),
+            storage: Some(storage),
+            motherboard: Some(MotherboardData {
+                name: "Test Board".to_string(),
+                temperature: 40.0,
+                temperatures,
+                fans,
+                voltages,
+                status: SectionStatus::Ok,
+            }),
+            network: Some(network),
+            timestamp: 1,
+            ..Default::default()
+        }
+    }
+
+    /// The primary keys of every collection, in order.
+    fn keys(data: &HardwareData) -> Vec<String> {
+        let mut keys: Vec<String> = data
+            .cpu
+            .iter()
+            .flat_map(|cpu| cpu.cores.iter().map(|core| core.index.to_string()))
+            .collect();
+        keys.extend(data.storage.iter().flatten().map(|s| s.name.clone()));
+        if let Some(motherboard) = &data.motherboard {
+            keys.extend(motherboard.fans.iter().map(|f| f.name.clone()));
+            keys.extend(motherboard.temperatures.iter().map(|t| t.name.clone()));
+            keys.extend(motherboard.voltages.iter().map(|v| v.name.clone()));
+        }
+        keys.extend(data.network.iter().flatten().map(|n| n.name.clone()));
+        keys
+    }
+
+    #[test]
+    fn normalization_is_independent_of_input_order() {
+        let mut expected = sample(0);
+        normalize_hardware_data(&mut expected);
+
+        for rotation in 1..8 {
+            let mut shuffled = sample(rotation);
+            normalize_hardware_data(&mut shuffled);
+            assert_eq!(keys(&shuffled), keys(&expected));
+        }
+    }
+
+    #[test]
+    fn identical_drives_keep_their_order_when_readings_cross() {
+        let mut data = sample(0);
+        normalize_hardware_data(&mut data);
+        let order = |data: &HardwareData| -> Vec<f32> {
+            data.storage
+                .iter()
+                .flatten()
+                .map(|s| s.used_space)
+                .collect()
+        };
+        // The two "Samsung SSD 980 PRO 1TB" in enumeration order
+        assert_eq!(order(&data), vec![43.0, 12.0, 20.0]);
+
+        let storage = data.storage.as_mut().unwrap();
+        storage[0].temperature = 38.0;
+        storage[1].temperature = 44.0;
+        normalize_hardware_data(&mut data);
+        assert_eq!(order(&data), vec![43.0, 12.0, 20.0]);
+    }
+
+    #[test]
+    fn normalization_sorts_by_primary_key() {
+        let mut data = sample(3);
+        normalize_hardware_data(&mut data);
+
+        let cores: Vec<u32> = data.cpu.unwrap().cores.iter().map(|c| c.index).collect();
+        assert_eq!(cores, (0..8).collect::<Vec<_>>());
+
+        let motherboard = data.motherboard.unwrap();
+        let fans: Vec<&str> = motherboard.fans.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fans, vec!["CPU Fan", "Fan #1", "Fan #2"]);
+
+        let temperatures: Vec<(&str, f32)> = motherboard
+            .temperatures
+            .iter()
+            .map(|t| (t.name.as_str(), t.temperature))
+            .collect();
+        assert_eq!(
+            temperatures,
+            vec![
+                ("PCH", 48.0),
+                ("System", 34.0),
+                // Unnamed inputs sharing a label: as the chip lists them
+                ("Temperature #2", 41.0),
+                ("Temperature #2", 39.5),
+                ("VRM MOS", 52.5),
+            ]
+        );
+
+        let voltages: Vec<(&str, f32)> = motherboard
+            .voltages
+            .iter()
+            .map(|v| (v.name.as_str(), v.volts))
+            .collect();
+        assert_eq!(
+            voltages,
+            vec![
+                ("+12V", 12.1),
+                ("+3.3V", 3.31),
+                ("5VCC", 5.02),
+                // Same name on two inputs: as the chip lists them
+                ("VIN4", 1.02),
+                ("VIN4", 0.98),
+                ("Vcore", 1.21),
+            ]
+        );
+
+        let storage: Vec<f32> = data.storage.unwrap().iter().map(|s| s.used_space).collect();
+        assert_eq!(storage, vec![12.0, 43.0, 20.0]);
+    }
+
+    #[test]
+    fn stderr_tail_keeps_only_the_newest_lines() {
+        let mut tail = StderrTail::new();
+        for i in 0..LHM_STDERR_MAX_LINES + 20 {
+            tail.push(&format!("line {}", i));
+        }
+
+        let lines = tail.to_vec();
+        assert_eq!(lines.len(), LHM_STDERR_MAX_LINES);
+        assert_eq!(lines.first().unwrap(), "line 20");
+        assert_eq!(
+            lines.last().unwrap(),
+            &format!("line {}", LHM_STDERR_MAX_LINES + 19)
+        );
+    }
+
+    #[test]
+    fn stderr_tail_truncates_long_lines_on_char_boundary() {
+        let mut tail = StderrTail::new();
+        tail.push(&"é".repeat(LHM_STDERR_MAX_LINE_LEN));
+
+        let line = &tail.to_vec()[0];
+        assert!(line.len() <= LHM_STDERR_MAX_LINE_LEN);
+        assert!(line.chars().all(|c| c == 'é'));
+    }
+
+    #[test]
+    fn lhm_interval_restart_only_when_running_with_other_interval() {
+        assert!(!lhm_interval_needs_restart(None, 1000));
+        assert!(!lhm_interval_needs_restart(Some(1000), 1000));
+        assert!(lhm_interval_needs_restart(Some(1000), 2000));
+        assert!(lhm_interval_needs_restart(Some(5000), 250));
+    }
+
+    #[test]
+    fn silent_daemon_is_stalled_after_three_missed_samples() {
+        use std::time::Duration;
+        assert!(!lhm_is_stalled(Duration::from_secs(2), 1000));
+        // Never sooner than LHM_STALL_AFTER, however fast the interval
+        assert!(!lhm_is_stalled(LHM_STALL_AFTER, 250));
+        assert!(lhm_is_stalled(Duration::from_millis(5001), 1000));
+        // Slow intervals get three intervals of slack
+        assert!(!lhm_is_stalled(Duration::from_secs(25), 10_000));
+        assert!(lhm_is_stalled(Duration::from_secs(31), 10_000));
+    }
+
+    #[test]
+    fn handshake_with_current_protocol_is_accepted() {
+        let handshake = parse_lhm_handshake(r#"{"protocol":1,"version":"0.1.25"}"#).unwrap();
+        assert_eq!(
+            handshake,
+            LhmHandshake {
+                protocol: LHM_PROTOCOL,
+                version: Some("0.1.25".to_string()),
+            }
+        );
+        assert!(check_lhm_handshake(&handshake).is_ok());
+    }
+
+    #[test]
+    fn mismatched_protocol_is_rejected_with_versions() {
+        let handshake = parse_lhm_handshake(r#"{"protocol":99,"version":"9.9.9"}"#).unwrap();
+        let err = check_lhm_handshake(&handshake).unwrap_err();
+        assert!(err.contains("version 9.9.9"));
+        assert!(err.contains(&format!("expects protocol {}", LHM_PROTOCOL)));
+    }
+
+    #[test]
+    fn missing_handshake_is_treated_as_legacy_protocol() {
+        let handshake = parse_lhm_handshake(r#"{"cpu":{"name":"CPU"},"gpu":null}"#).unwrap();
+        assert_eq!(handshake.protocol, 0);
+        assert_eq!(handshake.version, None);
+        let err = check_lhm_handshake(&handshake).unwrap_err();
+        assert!(err.contains("version unknown (protocol 0)"));
+    }
+
+    #[test]
+    fn garbage_first_line_is_an_error() {
+        assert!(parse_lhm_handshake("Unhandled exception").is_err());
+        assert!(parse_lhm_handshake(r#"{"protocol":"one"}"#).is_err());
+    }
 }
@@ -0,0 +1,186 @@
+//! Hardware identity that doesn't change while the app runs: model names,
+//! core count, VRAM and drive capacities, so clients can fetch it once with
+//! `get_hardware_static`. Taken from a sample once and cached until the LHM
+//! daemon restarts (a driver reload can change what it enumerates), sensor
+//! overrides change, or the sampled devices stop lining up with it.
+//! `get_hardware_data` joins it back into samples, so names stay the same
+//! strings between them.
+
+use crate::HardwareData;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuStatic {
+    pub name: String,
+    pub core_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuStatic {
+    pub name: String,
+    /// In MB, like `memoryTotal` in samples.
+    pub memory_total: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveStatic {
+    pub name: String,
+    /// In GB, like `totalSpace` in samples.
+    pub total_space: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HardwareStatic {
+    pub cpu: Option<CpuStatic>,
+    pub gpu: Option<GpuStatic>,
+    /// In the same order as `storage` in samples.
+    pub storage: Vec<DriveStatic>,
+    pub motherboard: Option<String>,
+}
+
+struct Cached {
+    /// `LHM_DAEMON_SPAWNS` when it was taken.
+    generation: u64,
+    snapshot: HardwareStatic,
+}
+
+static CACHE: Mutex<Option<Cached>> = Mutex::new(None);
+
+/// The cached snapshot, or a new one taken from `data` if there is none for
+/// the running LHM daemon or it doesn't match `data`'s devices.
+pub fn cached(data: &HardwareData) -> HardwareStatic {
+    let generation = crate::metrics::LHM_DAEMON_SPAWNS.get();
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    match cache.as_ref() {
+        Some(cached) if cached.generation == generation && cached.snapshot.matches(data) => {
+            cached.snapshot.clone()
+        }
+        _ => {
+            let snapshot = HardwareStatic::from_sample(data);
+            *cache = Some(Cached {
+                generation,
+                snapshot: snapshot.clone(),
+            });
+            snapshot
+        }
+    }
+}
+
+/// Take the snapshot again from the next sample, e.g. after an alias
+/// renamed a device.
+pub fn invalidate() {
+    *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+impl HardwareStatic {
+    pub fn from_sample(data: &HardwareData) -> Self {
+        Self {
+            cpu: data.cpu.as_ref().map(|cpu| CpuStatic {
+                name: cpu.name.clone(),
+                core_count: cpu.cores.len(),
+            }),
+            gpu: data.gpu.as_ref().map(|gpu| GpuStatic {
+                name: gpu.name.clone(),
+                memory_total: gpu.memory_total,
+            }),
+            storage: data
+                .storage
+                .iter()
+                .flatten()
+                .map(|drive| DriveStatic {
+                    name: drive.name.clone(),
+                    total_space: drive.total_space,
+                })
+                .collect(),
+            motherboard: data.motherboard.as_ref().map(|m| m.name.clone()),
+        }
+    }
+
+    /// Whether every device in `data` has its entry here, with drives in
+    /// the same positions. Sections turned off in `data` don't matter.
+    fn matches(&self, data: &HardwareData) -> bool {
+        (data.cpu.is_none() || self.cpu.is_some())
+            && (data.gpu.is_none() || self.gpu.is_some())
+            && (data.motherboard.is_none() || self.motherboard.is_some())
+            && data
+                .storage
+                .as_ref()
+                .is_none_or(|storage| storage.len() == self.storage.len())
+    }
+
+    /// Put this identity back into a sample it `matches`.
+    pub fn join(&self, data: &mut HardwareData) {
+        if let (Some(cpu), Some(identity)) = (data.cpu.as_mut(), &self.cpu) {
+            cpu.name.clone_from(&identity.name);
+        }
+        if let (Some(gpu), Some(identity)) = (data.gpu.as_mut(), &self.gpu) {
+            gpu.name.clone_from(&identity.name);
+            gpu.memory_total = identity.memory_total;
+        }
+        for (drive, identity) in data.storage.iter_mut().flatten().zip(&self.storage) {
+            drive.name.clone_from(&identity.name);
+            drive.total_space = identity.total_space;
+        }
+        if let (Some(motherboard), Some(name)) = (data.motherboard.as_mut(), &self.motherboard) {
+            motherboard.name.clone_from(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_carries_identity_from_the_sample() {
+        let data = crate::mock::MockHardware::new(0, 1000).sample_at(0);
+        let snapshot = HardwareStatic::from_sample(&data);
+
+        let cpu = snapshot.cpu.unwrap();
+        assert_eq!(cpu.name, "Mock CPU 8-Core");
+        assert_eq!(cpu.core_count, 8);
+        assert_eq!(snapshot.gpu.unwrap().memory_total, 8192.0);
+        assert_eq!(snapshot.motherboard.as_deref(), Some("Mock Board"));
+        let drives: Vec<&str> = snapshot.storage.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(drives, vec!["Mock NVMe SSD", "Mock SATA HDD"]);
+    }
+
+    #[test]
+    fn joining_restores_the_identity_of_a_sample() {
+        let mock = crate::mock::MockHardware::new(0, 1000);
+        let snapshot = HardwareStatic::from_sample(&mock.sample_at(0));
+
+        let mut data = mock.sample_at(5000);
+        data.cpu.as_mut().unwrap().name.clear();
+        data.storage.as_mut().unwrap()[1].name.clear();
+        data.gpu.as_mut().unwrap().memory_total = 0.0;
+        assert!(snapshot.matches(&data));
+        snapshot.join(&mut data);
+        assert_eq!(HardwareStatic::from_sample(&data), snapshot);
+    }
+
+    #[test]
+    fn snapshot_must_cover_the_sampled_devices() {
+        let mut data = crate::mock::MockHardware::new(0, 1000).sample_at(0);
+        let snapshot = HardwareStatic::from_sample(&data);
+
+        // A turned-off section is fine, a new device or a missing drive isn't
+        data.gpu = None;
+        assert!(snapshot.matches(&data));
+        data.storage.as_mut().unwrap().pop();
+        assert!(!snapshot.matches(&data));
+
+        let without_gpu = HardwareStatic {
+            gpu: None,
+            ..snapshot
+        };
+        let data = crate::mock::MockHardware::new(0, 1000).sample_at(0);
+        assert!(!without_gpu.matches(&data));
+    }
+}
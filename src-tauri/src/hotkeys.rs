@@ -0,0 +1,149 @@
+//! Global keyboard shortcuts: show/hide the widget and open settings
+//! without going through the tray.
+
+use crate::settings::AppSettings;
+use crate::tray;
+use std::sync::Mutex;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_global_shortcut::{Builder, GlobalShortcutExt, Shortcut, ShortcutState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    ToggleWindow,
+    OpenSettings,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 2] = [HotkeyAction::ToggleWindow, HotkeyAction::OpenSettings];
+
+    /// The setting holding this action's accelerator.
+    pub fn setting(self, settings: &mut AppSettings) -> &mut Option<String> {
+        match self {
+            HotkeyAction::ToggleWindow => &mut settings.toggle_hotkey,
+            HotkeyAction::OpenSettings => &mut settings.settings_hotkey,
+        }
+    }
+}
+
+struct Binding {
+    action: HotkeyAction,
+    shortcut: Shortcut,
+    accelerator: String,
+}
+
+/// Currently registered shortcuts. Read by the shortcut handler on the main
+/// thread, so it is never held while registering.
+static BINDINGS: Mutex<Vec<Binding>> = Mutex::new(Vec::new());
+
+/// Serializes `bind` calls.
+static BIND_LOCK: Mutex<()> = Mutex::new(());
+
+pub fn plugin() -> TauriPlugin<Wry> {
+    Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let action = BINDINGS.lock().ok().and_then(|bindings| {
+                bindings
+                    .iter()
+                    .find(|b| b.shortcut == *shortcut)
+                    .map(|b| b.action)
+            });
+            match action {
+                Some(HotkeyAction::ToggleWindow) => tray::toggle_main_window(app),
+                Some(HotkeyAction::OpenSettings) => tray::open_settings(app),
+                None => {}
+            }
+        })
+        .build()
+}
+
+/// Parse an accelerator such as `Ctrl+Shift+O`.
+pub fn parse_accelerator(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut \"{}\": {}", accelerator, e))
+}
+
+/// The accelerator currently registered for `action`.
+pub fn bound_accelerator(action: HotkeyAction) -> Option<String> {
+    BINDINGS.lock().ok().and_then(|bindings| {
+        bindings
+            .iter()
+            .find(|b| b.action == action)
+            .map(|b| b.accelerator.clone())
+    })
+}
+
+/// Bind `accelerator` to `action`, replacing its previous shortcut. `None`
+/// unbinds. On failure the previous shortcut stays registered.
+pub fn bind(
+    app: &AppHandle,
+    action: HotkeyAction,
+    accelerator: Option<&str>,
+) -> Result<(), String> {
+    let _serialized = BIND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let new = accelerator.map(parse_accelerator).transpose()?;
+    let old = BINDINGS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .find(|b| b.action == action)
+        .map(|b| b.shortcut);
+    if old == new {
+        return Ok(());
+    }
+
+    let shortcuts = app.global_shortcut();
+    if let (Some(shortcut), Some(accelerator)) = (new, accelerator) {
+        shortcuts.register(shortcut).map_err(|e| {
+            format!(
+                "Shortcut \"{}\" is already in use or can't be registered: {}",
+                accelerator, e
+            )
+        })?;
+    }
+    if let Some(shortcut) = old {
+        if let Err(e) = shortcuts.unregister(shortcut) {
+            crate::log_warn!("Hotkeys", "Failed to unregister old shortcut: {}", e);
+        }
+    }
+
+    let mut bindings = BINDINGS.lock().map_err(|e| e.to_string())?;
+    bindings.retain(|b| b.action != action);
+    if let (Some(shortcut), Some(accelerator)) = (new, accelerator) {
+        bindings.push(Binding {
+            action,
+            shortcut,
+            accelerator: accelerator.to_string(),
+        });
+    }
+    crate::metrics::HOTKEY_BINDINGS.set(bindings.len());
+    crate::log_info!("Hotkeys", "{:?} bound to {:?}", action, accelerator);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_accelerator;
+
+    #[test]
+    fn parses_common_accelerators() {
+        assert!(parse_accelerator("Ctrl+Shift+O").is_ok());
+        assert!(parse_accelerator("Alt+F10").is_ok());
+        assert_eq!(
+            parse_accelerator("ctrl+shift+o").unwrap(),
+            parse_accelerator("Ctrl+Shift+O").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_accelerators() {
+        for bad in ["", "Ctrl+", "Ctrl+Shift+Nope", "Hyper Mega+O"] {
+            let err = parse_accelerator(bad).unwrap_err();
+            assert!(err.starts_with("Invalid shortcut"), "{}", err);
+        }
+    }
+}
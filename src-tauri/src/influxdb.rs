@@ -0,0 +1,613 @@
+//! Optional push of hardware samples to InfluxDB 2.x (`influxdb` setting),
+//! for monitoring stacks built on InfluxDB and Grafana rather than scraping.
+//! Every sample taken by the sampler is turned into line protocol and
+//! buffered; a task writes the buffer to `/api/v2/write`, gzipped, every
+//! `flush_interval_secs` or as soon as `batch_size` points are waiting.
+//!
+//! `publish` never waits on the network. Points that can't be written stay
+//! buffered, up to `MAX_BUFFERED_POINTS` after which the oldest are dropped.
+//! A rejected token is reported once per configuration rather than on every
+//! batch.
+
+use crate::settings::{AppSettings, InfluxDbSettings};
+use crate::{error_reporting, metrics, FanUnit, HardwareData};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use once_cell::sync::Lazy;
+use reqwest::header::{AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE};
+use reqwest::{StatusCode, Url};
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{watch, Notify};
+
+/// Points kept while the server can't be reached, about 15 minutes of
+/// samples from a typical desktop at the default interval.
+const MAX_BUFFERED_POINTS: usize = 10_000;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Tagged on every point so several machines can share a bucket.
+static HOST: Lazy<String> =
+    Lazy::new(|| sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string()));
+
+/// Set while a writer runs, so `publish` is free otherwise.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// `batch_size` of the running writer.
+static BATCH_SIZE: AtomicUsize = AtomicUsize::new(usize::MAX);
+static BUFFER: Mutex<Buffer> = Mutex::new(Buffer::new());
+/// Wakes the writer before its interval is up once a batch is waiting.
+static FLUSH: Lazy<Notify> = Lazy::new(Notify::new);
+/// A rejected token has been reported for the current configuration.
+static AUTH_REPORTED: AtomicBool = AtomicBool::new(false);
+
+struct RunningWriter {
+    settings: InfluxDbSettings,
+    /// Dropping this stops the writer.
+    _shutdown: watch::Sender<()>,
+}
+
+static WRITER: Mutex<Option<RunningWriter>> = Mutex::new(None);
+
+/// Points waiting to be written, oldest first.
+struct Buffer {
+    points: VecDeque<String>,
+    /// Points have been dropped since the last successful write.
+    dropping: bool,
+}
+
+impl Buffer {
+    const fn new() -> Self {
+        Self {
+            points: VecDeque::new(),
+            dropping: false,
+        }
+    }
+
+    /// Drop the oldest points beyond the limit. True when this starts a run
+    /// of drops, so it is logged once.
+    fn trim(&mut self) -> bool {
+        let excess = self.points.len().saturating_sub(MAX_BUFFERED_POINTS);
+        if excess == 0 {
+            return false;
+        }
+        self.points.drain(..excess);
+        !std::mem::replace(&mut self.dropping, true)
+    }
+
+    fn push(&mut self, points: Vec<String>) -> bool {
+        self.points.extend(points);
+        self.trim()
+    }
+
+    fn take(&mut self, max: usize) -> Vec<String> {
+        let count = max.min(self.points.len());
+        self.points.drain(..count).collect()
+    }
+
+    /// Return a batch that failed to write ahead of anything newer.
+    fn put_back(&mut self, batch: Vec<String>) -> bool {
+        for point in batch.into_iter().rev() {
+            self.points.push_front(point);
+        }
+        self.trim()
+    }
+}
+
+fn lock_buffer() -> std::sync::MutexGuard<'static, Buffer> {
+    BUFFER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn escape(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// One line of line protocol. Fields that aren't finite numbers are left
+/// out, and a point left without fields is skipped.
+struct Point {
+    series: String,
+    fields: Vec<String>,
+}
+
+impl Point {
+    fn new(measurement: &str) -> Self {
+        Self {
+            series: escape(measurement, &[',', ' ']),
+            fields: Vec::new(),
+        }
+        .tag("host", &HOST)
+    }
+
+    fn tag(mut self, key: &str, value: &str) -> Self {
+        // An empty tag value is invalid line protocol
+        if !value.is_empty() {
+            let special = [',', '=', ' '];
+            self.series.push_str(&format!(
+                ",{}={}",
+                escape(key, &special),
+                escape(value, &special)
+            ));
+        }
+        self
+    }
+
+    fn field<T: Into<f64> + Display + Copy>(mut self, key: &str, value: Option<T>) -> Self {
+        if let Some(value) = value.filter(|v| Into::<f64>::into(*v).is_finite()) {
+            self.fields
+                .push(format!("{}={}", escape(key, &[',', '=', ' ']), value));
+        }
+        self
+    }
+
+    fn line(self, timestamp_ms: u64) -> Option<String> {
+        (!self.fields.is_empty())
+            .then(|| format!("{} {} {}", self.series, self.fields.join(","), timestamp_ms))
+    }
+}
+
+fn fan_unit(unit: FanUnit) -> &'static str {
+    match unit {
+        FanUnit::Rpm => "rpm",
+        FanUnit::Percent => "percent",
+    }
+}
+
+/// `data` as line protocol, one point per device, with millisecond
+/// timestamps.
+fn points(data: &HardwareData) -> Vec<String> {
+    let mut points = Vec::new();
+    if let Some(cpu) = &data.cpu {
+        points.push(
+            Point::new("ondo_cpu")
+                .tag("name", &cpu.name)
+                .field("temperature", Some(cpu.temperature))
+                .field("load", Some(cpu.load))
+                .field("frequency", Some(cpu.frequency))
+                .field("power", cpu.power)
+                .field("voltage", cpu.voltage),
+        );
+    }
+    if let Some(gpu) = &data.gpu {
+        points.push(
+            Point::new("ondo_gpu")
+                .tag("name", &gpu.name)
+                .field("temperature", Some(gpu.temperature))
+                .field("load", Some(gpu.load))
+                .field("frequency", Some(gpu.frequency))
+                .field("memory_used", Some(gpu.memory_used))
+                .field("memory_total", Some(gpu.memory_total))
+                .field("power", gpu.power)
+                .field("hotspot_temperature", gpu.hotspot_temperature)
+                .field("memory_temperature", gpu.memory_temperature),
+        );
+        for fan in &gpu.fans {
+            points.push(
+                Point::new("ondo_fan")
+                    .tag("name", &fan.name)
+                    .tag("unit", fan_unit(fan.unit))
+                    .field("speed", Some(fan.speed)),
+            );
+        }
+    }
+    for drive in data.storage.iter().flatten() {
+        points.push(
+            Point::new("ondo_storage")
+                .tag("name", &drive.name)
+                .field("temperature", Some(drive.temperature))
+                .field("used_percent", Some(drive.used_percent))
+                .field("read_rate", drive.read_rate)
+                .field("write_rate", drive.write_rate),
+        );
+    }
+    if let Some(board) = &data.motherboard {
+        points.push(
+            Point::new("ondo_motherboard")
+                .tag("name", &board.name)
+                .field("temperature", Some(board.temperature)),
+        );
+        for sensor in &board.temperatures {
+            points.push(
+                Point::new("ondo_motherboard")
+                    .tag("name", &board.name)
+                    .tag("sensor", &sensor.name)
+                    .field("temperature", Some(sensor.temperature)),
+            );
+        }
+        for fan in &board.fans {
+            points.push(
+                Point::new("ondo_fan")
+                    .tag("name", &fan.name)
+                    .tag("unit", fan_unit(fan.unit))
+                    .field("speed", Some(fan.speed)),
+            );
+        }
+    }
+    for interface in data.network.iter().flatten() {
+        points.push(
+            Point::new("ondo_network")
+                .tag("interface", &interface.name)
+                .field("received_per_sec", Some(interface.received_per_sec))
+                .field("sent_per_sec", Some(interface.sent_per_sec)),
+        );
+    }
+    if let Some(power) = &data.system_power {
+        points.push(
+            Point::new("ondo_system").field("power", Some(power.system_power_estimate_watts)),
+        );
+    }
+    points
+        .into_iter()
+        .filter_map(|point| point.line(data.timestamp))
+        .collect()
+}
+
+/// The write endpoint for `settings`. Fails on a URL that isn't http(s) or
+/// a missing bucket.
+fn write_url(settings: &InfluxDbSettings) -> Result<Url, String> {
+    let mut url = Url::parse(&format!("{}/api/v2/write", settings.url))
+        .map_err(|e| format!("Invalid InfluxDB URL \"{}\": {}", settings.url, e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!(
+            "Invalid InfluxDB URL \"{}\": expected http or https",
+            settings.url
+        ));
+    }
+    if settings.bucket.is_empty() {
+        return Err("An InfluxDB bucket is required".to_string());
+    }
+    {
+        let mut query = url.query_pairs_mut();
+        // InfluxDB 1.8's v2 API takes no org
+        if !settings.org.is_empty() {
+            query.append_pair("org", &settings.org);
+        }
+        query
+            .append_pair("bucket", &settings.bucket)
+            .append_pair("precision", "ms");
+    }
+    Ok(url)
+}
+
+fn gzip(lines: &[String]) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(lines.join("\n").as_bytes())
+        .and_then(|()| encoder.finish())
+        .map_err(|e| format!("Failed to compress points: {}", e))
+}
+
+#[derive(Debug, PartialEq)]
+enum WriteError {
+    /// The token was refused: 401 or 403.
+    Auth(String),
+    /// The points were refused and sending them again won't help.
+    Rejected(String),
+    /// The server couldn't be reached or failed; worth retrying.
+    Unavailable(String),
+}
+
+impl Display for WriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auth(e) | Self::Rejected(e) | Self::Unavailable(e) => f.write_str(e),
+        }
+    }
+}
+
+fn classify(status: StatusCode, message: &str) -> Result<(), WriteError> {
+    let error = format!("InfluxDB returned {}: {}", status, message.trim());
+    match status {
+        s if s.is_success() => Ok(()),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(WriteError::Auth(error)),
+        StatusCode::TOO_MANY_REQUESTS => Err(WriteError::Unavailable(error)),
+        s if s.is_client_error() => Err(WriteError::Rejected(error)),
+        _ => Err(WriteError::Unavailable(error)),
+    }
+}
+
+async fn write(
+    client: &reqwest::Client,
+    url: &Url,
+    token: Option<&str>,
+    lines: &[String],
+) -> Result<(), WriteError> {
+    let body = gzip(lines).map_err(WriteError::Rejected)?;
+    let mut request = client
+        .post(url.clone())
+        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(CONTENT_ENCODING, "gzip")
+        .timeout(REQUEST_TIMEOUT)
+        .body(body);
+    if let Some(token) = token {
+        request = request.header(AUTHORIZATION, format!("Token {}", token));
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| WriteError::Unavailable(format!("InfluxDB not reachable: {}", e)))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let message = response.text().await.unwrap_or_default();
+    classify(status, &message)
+}
+
+/// Write everything buffered, `batch_size` points at a time, stopping at the
+/// first batch the server couldn't take. Returns whether it was failing.
+async fn flush(
+    client: &reqwest::Client,
+    url: &Url,
+    settings: &InfluxDbSettings,
+    failing: bool,
+) -> bool {
+    loop {
+        let batch = lock_buffer().take(settings.batch_size as usize);
+        if batch.is_empty() {
+            if failing {
+                crate::log_info!("InfluxDB", "Writing again");
+            }
+            return false;
+        }
+        match write(client, url, settings.token.as_deref(), &batch).await {
+            Ok(()) => {
+                lock_buffer().dropping = false;
+            }
+            Err(WriteError::Rejected(e)) => {
+                crate::log_warn!("InfluxDB", "Dropped {} points: {}", batch.len(), e);
+            }
+            Err(e) => {
+                if lock_buffer().put_back(batch) {
+                    crate::log_warn!("InfluxDB", "Buffer full, dropping the oldest points");
+                }
+                if let WriteError::Auth(message) = &e {
+                    if !AUTH_REPORTED.swap(true, Ordering::Relaxed) {
+                        error_reporting::capture_error(
+                            &format!("[InfluxDB] {}", message),
+                            "influxdb",
+                            Some(&[("operation", "write")]),
+                        );
+                    }
+                }
+                if !failing {
+                    crate::log_warn!("InfluxDB", "Write failed, keeping points: {}", e);
+                }
+                return true;
+            }
+        }
+    }
+}
+
+async fn run(url: Url, settings: InfluxDbSettings, mut shutdown: watch::Receiver<()>) {
+    let client = reqwest::Client::new();
+    let mut ticker =
+        tokio::time::interval(Duration::from_secs(u64::from(settings.flush_interval_secs)));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut failing = false;
+    loop {
+        tokio::select! {
+            _ = shutdown.changed() => break,
+            _ = ticker.tick() => {}
+            _ = FLUSH.notified() => {}
+        }
+        failing = flush(&client, &url, &settings, failing).await;
+    }
+}
+
+/// Start, restart or stop the writer to match `settings`. No-op when the
+/// running writer already matches. Fails on an unusable URL or a missing
+/// bucket.
+pub fn configure(settings: &AppSettings) -> Result<(), String> {
+    let wanted = settings.influxdb.enabled.then(|| settings.influxdb.clone());
+    let mut writer = WRITER.lock().map_err(|e| e.to_string())?;
+    if writer.as_ref().map(|w| &w.settings) == wanted.as_ref() {
+        return Ok(());
+    }
+    if writer.take().is_some() {
+        ENABLED.store(false, Ordering::Relaxed);
+        crate::log_info!("InfluxDB", "Stopped");
+    }
+    let Some(influxdb) = wanted else {
+        lock_buffer().points.clear();
+        return Ok(());
+    };
+    let url = write_url(&influxdb)?;
+    crate::log_info!(
+        "InfluxDB",
+        "Writing to {} every {}s",
+        influxdb.url,
+        influxdb.flush_interval_secs
+    );
+
+    AUTH_REPORTED.store(false, Ordering::Relaxed);
+    BATCH_SIZE.store(influxdb.batch_size as usize, Ordering::Relaxed);
+    ENABLED.store(true, Ordering::Relaxed);
+    let (shutdown_tx, shutdown_rx) = watch::channel(());
+    let task = metrics::task_started();
+    let running = influxdb.clone();
+    tauri::async_runtime::spawn(async move {
+        let _task = task;
+        run(url, running, shutdown_rx).await
+    });
+    *writer = Some(RunningWriter {
+        settings: influxdb,
+        _shutdown: shutdown_tx,
+    });
+    Ok(())
+}
+
+/// Stop the writer. Points not written yet are lost. Called on app exit.
+pub fn stop() {
+    if let Ok(mut writer) = WRITER.lock() {
+        if writer.take().is_some() {
+            ENABLED.store(false, Ordering::Relaxed);
+            crate::log_info!("InfluxDB", "Stopped");
+        }
+    }
+}
+
+/// Queue a sample for the next write. Cheap when the push is off.
+pub fn publish(data: &HardwareData) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let points = points(data);
+    let (waiting, started_dropping) = {
+        let mut buffer = lock_buffer();
+        let started_dropping = buffer.push(points);
+        (buffer.points.len(), started_dropping)
+    };
+    if started_dropping {
+        crate::log_warn!("InfluxDB", "Buffer full, dropping the oldest points");
+    }
+    if waiting >= BATCH_SIZE.load(Ordering::Relaxed) {
+        FLUSH.notify_one();
+    }
+}
+
+/// Check `settings` from the settings panel by writing one
+/// `ondo_connection_test` point, which exercises the URL, token, org and
+/// bucket exactly as the push does.
+pub async fn test_connection(settings: &InfluxDbSettings) -> Result<(), String> {
+    let url = write_url(settings)?;
+    let point = Point::new("ondo_connection_test")
+        .field("ok", Some(1u32))
+        .line(crate::now_millis())
+        .unwrap_or_default();
+    write(
+        &reqwest::Client::new(),
+        &url,
+        settings.token.as_deref(),
+        &[point],
+    )
+    .await
+    .map_err(|e| match e {
+        WriteError::Auth(message) => format!("InfluxDB rejected the token ({})", message),
+        other => other.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockHardware;
+
+    fn sample() -> HardwareData {
+        let mut data = MockHardware::new(1_699_000_000_000, 1000).sample_at(0);
+        let cpu = data.cpu.as_mut().unwrap();
+        cpu.name = "Ryzen 7 7800X3D".to_string();
+        (cpu.temperature, cpu.load, cpu.frequency) = (62.1, 31.0, 4.5);
+        (cpu.power, cpu.voltage) = (None, Some(f32::NAN));
+        let storage = data.storage.as_mut().unwrap();
+        storage.truncate(1);
+        storage[0].name = "Samsung SSD 990 PRO, 2TB".to_string();
+        (storage[0].temperature, storage[0].used_percent) = (41.0, 50.5);
+        let network = &mut data.network.as_mut().unwrap()[0];
+        network.name = "Wi-Fi=2".to_string();
+        (network.received_per_sec, network.sent_per_sec) = (1024.0, 0.0);
+        data.gpu = None;
+        data.motherboard = None;
+        data
+    }
+
+    #[test]
+    fn samples_become_line_protocol() {
+        let host = escape(&HOST, &[',', '=', ' ']);
+        assert_eq!(
+            points(&sample()),
+            vec![
+                format!(
+                    "ondo_cpu,host={},name=Ryzen\\ 7\\ 7800X3D temperature=62.1,load=31,frequency=4.5 1699000000000",
+                    host
+                ),
+                format!(
+                    "ondo_storage,host={},name=Samsung\\ SSD\\ 990\\ PRO\\,\\ 2TB temperature=41,used_percent=50.5 1699000000000",
+                    host
+                ),
+                format!(
+                    "ondo_network,host={},interface=Wi-Fi\\=2 received_per_sec=1024,sent_per_sec=0 1699000000000",
+                    host
+                ),
+            ]
+        );
+        // Nothing to write, nothing sent
+        assert_eq!(Point::new("ondo_cpu").line(0), None);
+    }
+
+    #[test]
+    fn full_buffer_drops_the_oldest_points() {
+        let mut buffer = Buffer::new();
+        let numbered = |range: std::ops::Range<usize>| -> Vec<String> {
+            range.map(|n| n.to_string()).collect()
+        };
+        assert!(!buffer.push(numbered(0..MAX_BUFFERED_POINTS)));
+        assert!(buffer.push(numbered(MAX_BUFFERED_POINTS..MAX_BUFFERED_POINTS + 2)));
+        assert_eq!(buffer.points.front().map(String::as_str), Some("2"));
+        // Logged once per run of drops
+        assert!(!buffer.push(numbered(0..1)));
+
+        let batch = buffer.take(3);
+        assert_eq!(batch, ["3", "4", "5"]);
+        buffer.push(numbered(0..3));
+        // A failed batch goes back in front and pushes out the oldest
+        buffer.put_back(batch);
+        assert_eq!(buffer.points.len(), MAX_BUFFERED_POINTS);
+        assert_eq!(buffer.points.front().map(String::as_str), Some("6"));
+    }
+
+    #[test]
+    fn write_url_carries_org_bucket_and_precision() {
+        let settings = InfluxDbSettings {
+            url: "https://influx.lan:8086".to_string(),
+            org: "home lab".to_string(),
+            bucket: "ondo".to_string(),
+            ..InfluxDbSettings::default()
+        };
+        assert_eq!(
+            write_url(&settings).unwrap().as_str(),
+            "https://influx.lan:8086/api/v2/write?org=home+lab&bucket=ondo&precision=ms"
+        );
+        let ftp = InfluxDbSettings {
+            url: "ftp://influx.lan".to_string(),
+            ..settings.clone()
+        };
+        assert!(write_url(&ftp).unwrap_err().contains("http"));
+        let no_bucket = InfluxDbSettings {
+            bucket: String::new(),
+            ..settings
+        };
+        assert!(write_url(&no_bucket).is_err());
+    }
+
+    #[test]
+    fn only_bad_requests_are_dropped() {
+        assert_eq!(classify(StatusCode::NO_CONTENT, ""), Ok(()));
+        assert!(matches!(
+            classify(StatusCode::UNAUTHORIZED, "unauthorized access"),
+            Err(WriteError::Auth(_))
+        ));
+        assert!(matches!(
+            classify(StatusCode::BAD_REQUEST, "field type conflict"),
+            Err(WriteError::Rejected(_))
+        ));
+        for status in [
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::SERVICE_UNAVAILABLE,
+        ] {
+            assert!(matches!(
+                classify(status, ""),
+                Err(WriteError::Unavailable(_))
+            ));
+        }
+    }
+}
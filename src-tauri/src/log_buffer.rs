@@ -3,7 +3,7 @@ use serde::Serialize;
 use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-const MAX_LINES: usize = 5000;
+pub const MAX_LINES: usize = 5000;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct LogEntry {
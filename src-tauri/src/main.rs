@@ -1,32 +1,67 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+// The command list in api_schema.rs outgrows json!'s default limit
+#![recursion_limit = "256"]
 
+mod alerts;
+mod api_schema;
 mod app_metrics;
 mod audio;
+mod changelog;
+mod clipboard;
+mod commands;
 mod debug_server;
+mod elevation;
 mod error_reporting;
 mod fps_monitor;
 mod hardware;
+mod hardware_static;
+mod hotkeys;
+mod influxdb;
 mod log_buffer;
+mod metrics;
+mod mock;
+mod monitor_pause;
+mod monitoring_mode;
+mod power;
+mod release_notes;
+mod sampler;
+mod sensor_owner;
+mod session_stats;
 mod settings;
+mod simulation;
+mod smoothing;
+mod soak;
+mod throttling;
 mod tray;
 mod window_debug;
 mod window_monitor;
+mod ws_server;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
-use tauri::{AppHandle, Manager, State};
+use tauri::Manager;
 use tokio::sync::oneshot;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CpuCoreData {
     index: u32,
     temperature: f32,
     load: f32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+    /// Current clock in GHz, when the source reports per-core clocks.
+    #[serde(default)]
+    frequency: Option<f32>,
+    /// `temperature` is the package reading, not a per-core sensor.
+    #[serde(default)]
+    estimated: bool,
+    /// Socket the core is on, on machines with more than one CPU.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    package: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CpuData {
     name: String,
     temperature: f32,
@@ -35,9 +70,41 @@ pub struct CpuData {
     load: f32,
     frequency: f32, // Current frequency in GHz
     cores: Vec<CpuCoreData>,
+    // Absent rather than 0 when there is no sensor, so the UI can tell
+    // "not supported" from a real reading
+    #[serde(skip_serializing_if = "Option::is_none")]
+    power: Option<f32>, // Package power in W
+    #[serde(skip_serializing_if = "Option::is_none")]
+    voltage: Option<f32>, // Vcore in V
+    // See `throttling` for how this is decided
+    #[serde(rename = "isThrottling", default)]
+    is_throttling: bool,
+    // Rated base clock and the highest clock reached, in GHz, to put
+    // `frequency` in context; 0.0 when unknown
+    #[serde(rename = "baseFrequency", default)]
+    base_frequency: f32,
+    #[serde(rename = "maxFrequency", default)]
+    max_frequency: f32,
+    // Against the `thresholds` setting, see `hardware::annotate_status`
+    #[serde(default)]
+    status: hardware::SectionStatus,
+    // One entry per socket on multi-socket machines, which the fields above
+    // then sum up: hottest temperature, average load, total power
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    packages: Option<Vec<CpuPackageData>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CpuPackageData {
+    index: u32,
+    name: String,
+    temperature: f32,
+    load: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    power: Option<f32>, // W
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GpuData {
     name: String,
     temperature: f32,
@@ -49,32 +116,111 @@ pub struct GpuData {
     memory_used: f32,
     #[serde(rename = "memoryTotal")]
     memory_total: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    power: Option<f32>, // Board power in W
+    #[serde(rename = "powerLimit", skip_serializing_if = "Option::is_none")]
+    power_limit: Option<f32>, // Board power limit in W
+    // Cards throttle on the hotspot (junction) temperature, not the core
+    #[serde(rename = "hotspotTemperature", skip_serializing_if = "Option::is_none")]
+    hotspot_temperature: Option<f32>,
+    #[serde(rename = "memoryTemperature", skip_serializing_if = "Option::is_none")]
+    memory_temperature: Option<f32>,
+    // Includes fans stopped at idle (0 RPM)
+    #[serde(default)]
+    fans: Vec<FanData>,
+    #[serde(default)]
+    status: hardware::SectionStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StorageData {
     name: String,
     temperature: f32,
     #[serde(rename = "usedSpace")]
     used_space: f32, // in GB
+    #[serde(rename = "usedPercent", default)]
+    used_percent: f32,
     #[serde(rename = "totalSpace")]
     total_space: f32, // in GB
+    // SMART values, absent when the drive or the daemon doesn't report them
+    #[serde(rename = "healthPercent", skip_serializing_if = "Option::is_none")]
+    health_percent: Option<f32>, // Remaining life
+    #[serde(rename = "dataWrittenTb", skip_serializing_if = "Option::is_none")]
+    data_written_tb: Option<f32>,
+    #[serde(rename = "readRate", skip_serializing_if = "Option::is_none")]
+    read_rate: Option<f64>, // bytes/sec
+    #[serde(rename = "writeRate", skip_serializing_if = "Option::is_none")]
+    write_rate: Option<f64>, // bytes/sec
+    // Volumes on the drive, where the source can tell which are; empty for
+    // a drive with none mounted (raw, BitLocker-locked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    partitions: Option<Vec<PartitionData>>,
+    #[serde(default)]
+    status: hardware::SectionStatus,
+}
+
+/// A volume on a physical drive, e.g. C: on a disk that also holds D:.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PartitionData {
+    #[serde(rename = "mountPoint")]
+    mount_point: String, // Drive letter ("C:") or mount point
+    #[serde(rename = "usedSpace")]
+    used_space: f32, // in GB
+    #[serde(rename = "totalSpace")]
+    total_space: f32, // in GB
+}
+
+/// What `FanData::speed` counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FanUnit {
+    #[default]
+    Rpm,
+    /// Duty cycle, for fans that only report how hard they are driven
+    Percent,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FanData {
     name: String,
-    speed: u32, // RPM
+    speed: u32, // See `unit`
+    #[serde(default)]
+    unit: FanUnit,
+    /// Reported at 0: a fan that died or is held in fan-stop, as opposed to
+    /// one that isn't reported at all.
+    #[serde(default)]
+    stopped: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VoltageData {
+    name: String,
+    volts: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NamedTemperature {
+    name: String,
+    temperature: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MotherboardData {
     name: String,
+    // The system temperature out of `temperatures`
     temperature: f32,
+    // Every board sensor (chipset, VRM, PCH, ...), by name
+    #[serde(default)]
+    temperatures: Vec<NamedTemperature>,
     fans: Vec<FanData>,
+    // PSU rails (+12V, +5V, +3.3V) first; empty without a Super I/O chip
+    #[serde(default)]
+    voltages: Vec<VoltageData>,
+    #[serde(default)]
+    status: hardware::SectionStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NetworkInterfaceData {
     name: String,
     #[serde(rename = "receivedPerSec")]
@@ -83,7 +229,7 @@ pub struct NetworkInterfaceData {
     sent_per_sec: f64, // bytes/sec
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DisplayData {
     name: Option<String>, // Monitor model name
     #[serde(rename = "refreshRate")]
@@ -93,7 +239,30 @@ pub struct DisplayData {
     fps_process_name: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Why a section is missing or reads worse than usual (no temperatures,
+/// estimated cores, ...).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SensorError {
+    /// Stable identifier, e.g. `lhm_no_gpu` or `sysinfo_no_temperature`.
+    pub code: String,
+    pub message: String,
+    /// What reported it, as in `HardwareData::data_source`.
+    pub source: String,
+}
+
+impl SensorError {
+    // Only the Windows sources report errors
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub fn new(code: &str, message: impl Into<String>, source: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+            source: source.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct HardwareData {
     cpu: Option<CpuData>,
     gpu: Option<GpuData>,
@@ -102,19 +271,141 @@ pub struct HardwareData {
     network: Option<Vec<NetworkInterfaceData>>,
     display: Option<DisplayData>,
     timestamp: u64,
+    /// `cpu_error_detail`'s message, for clients that only read the string.
     #[serde(rename = "cpuError")]
     cpu_error: Option<String>,
     #[serde(rename = "gpuError")]
     gpu_error: Option<String>,
+    #[serde(
+        rename = "cpuErrorDetail",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    cpu_error_detail: Option<SensorError>,
+    #[serde(
+        rename = "gpuErrorDetail",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    gpu_error_detail: Option<SensorError>,
+    #[serde(rename = "systemPower")]
+    system_power: Option<power::SystemPowerEstimate>,
+    /// How old the sensor readings are, when they come from a source that
+    /// samples on its own schedule (the LHM daemon).
+    #[serde(rename = "dataAgeMs")]
+    data_age_ms: Option<u64>,
+    /// Some sensors were refused for lack of administrator rights.
+    #[serde(rename = "limitedAccess", default)]
+    limited_access: bool,
+    /// What produced the readings: "lhm" (the LHM daemon), "sysinfo" (the
+    /// fallback without LHM, and macOS) or "mock".
+    #[serde(rename = "dataSource", default)]
+    data_source: String,
+    /// The readings come from a fallback missing sensors this platform
+    /// normally has: sysinfo on Windows, where LHM is expected.
+    #[serde(default)]
+    degraded: bool,
+    /// When each section was read. Storage and the motherboard are read
+    /// less often than CPU and GPU, see `update_interval_slow`.
+    #[serde(rename = "sectionTimestamps", default)]
+    section_timestamps: hardware::SectionTimestamps,
+    /// The readings before smoothing, present while `smoothing_factor` is
+    /// above 0.
+    #[serde(
+        rename = "rawReadings",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    raw_readings: Option<smoothing::RawReadings>,
 }
 
 pub struct AppState {
-    pub settings: Mutex<settings::AppSettings>,
+    pub settings: settings::SharedSettings,
     pub debug_server_running: AtomicBool,
     pub debug_server_shutdown: Mutex<Option<oneshot::Sender<()>>>,
+    pub energy: Mutex<power::EnergyTracker>,
+    pub session_stats: Mutex<session_stats::SessionStats>,
+    pub tray_readout: tray::TrayReadout,
+    pub smoother: Mutex<smoothing::Smoother>,
+    pub throttle: Mutex<throttling::ThrottleDetector>,
+    pub alerts: Mutex<alerts::AlertLog>,
 }
 
 impl AppState {
+    /// Fill in derived values and update per-sample bookkeeping. Applied to
+    /// every sample handed to the UI.
+    pub fn process_sample(&self, data: &mut HardwareData) {
+        let (
+            power_config,
+            update_interval,
+            fahrenheit,
+            tray_temperature,
+            smoothing_factor,
+            thresholds,
+        ) = {
+            let settings = self.settings.lock();
+            (
+                power::PowerConfig::from(&*settings),
+                settings.update_interval_fast,
+                settings.temperature_unit == "fahrenheit",
+                settings.tray_show_temperature,
+                settings.smoothing_factor,
+                settings.thresholds,
+            )
+        };
+
+        data.system_power = power::estimate_system_power(
+            power::ComponentPower::from_sample(
+                data.cpu.is_some(),
+                data.cpu.as_ref().and_then(|c| c.power),
+            ),
+            power::ComponentPower::from_sample(
+                data.gpu.is_some(),
+                data.gpu.as_ref().and_then(|g| g.power),
+            ),
+            &power_config,
+        );
+
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        self.energy
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(
+                data.timestamp,
+                &date,
+                data.system_power
+                    .as_ref()
+                    .map(|p| p.system_power_estimate_watts),
+                power::max_sample_gap_ms(update_interval),
+            );
+
+        self.session_stats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record_sample(data);
+
+        self.throttle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .apply(data);
+
+        // After the statistics, so their min/max see the measured values
+        self.smoother
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .apply(data, smoothing_factor);
+        // On the smoothed values, so the colors match what is shown
+        hardware::annotate_status(data, &thresholds);
+
+        self.tray_readout.update(
+            data.cpu.as_ref().map(|c| c.temperature),
+            data.gpu.as_ref().map(|g| g.temperature),
+            fahrenheit,
+            tray_temperature,
+            now_millis(),
+        );
+    }
+
     /// Start or stop the debug HTTP listener. No-op when already in the
     /// requested state. Used by both the Tauri command and the tray menu.
     pub fn set_debug_server(&self, enabled: bool) -> Result<(), String> {
@@ -147,540 +438,95 @@ impl AppState {
     }
 }
 
-#[tauri::command]
-async fn get_hardware_data() -> Result<HardwareData, String> {
-    match hardware::get_hardware_info().await {
-        Ok(data) => {
-            // Report if both CPU and GPU are null (indicates a problem)
-            if data.cpu.is_none() && data.gpu.is_none() {
-                let error_detail = data
-                    .cpu_error
-                    .as_deref()
-                    .or(data.gpu_error.as_deref())
-                    .unwrap_or("Unknown error");
-                error_reporting::capture_hardware_error(
-                    &format!("Both CPU and GPU data unavailable: {}", error_detail),
-                    "both",
-                );
-            }
-            Ok(data)
-        }
-        Err(e) => {
-            error_reporting::capture_hardware_error(&e, "get_hardware_info");
-            Err(e)
-        }
-    }
-}
-
-#[tauri::command]
-async fn get_settings(state: State<'_, AppState>) -> Result<settings::AppSettings, String> {
-    state.settings.lock().map(|s| s.clone()).map_err(|e| {
-        let err = e.to_string();
-        error_reporting::capture_settings_error(&err, "get_settings");
-        err
-    })
-}
-
-#[tauri::command]
-async fn save_settings(
-    state: State<'_, AppState>,
-    settings: settings::AppSettings,
-) -> Result<(), String> {
-    // Update state first, then drop the lock before await
-    {
-        let mut current = state.settings.lock().map_err(|e| {
-            let err = e.to_string();
-            error_reporting::capture_settings_error(&err, "save_settings_lock");
-            err
-        })?;
-        *current = settings.clone();
-    }
-    settings::save_settings_to_file(&settings)
-        .await
-        .map_err(|e| {
-            error_reporting::capture_settings_error(&e, "save_settings_file");
-            e
-        })
-}
-
-#[tauri::command]
-async fn set_always_on_top(app: AppHandle, enabled: bool) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window.set_always_on_top(enabled).map_err(|e| {
-            let err = e.to_string();
-            error_reporting::capture_window_error(&err, "set_always_on_top");
-            err
-        })?;
-    }
-    Ok(())
-}
-
-#[tauri::command]
-async fn set_window_position(app: AppHandle, position: String) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        let monitor = window
-            .current_monitor()
-            .map_err(|e| {
-                let err = e.to_string();
-                error_reporting::capture_window_error(&err, "set_window_position_monitor");
-                err
-            })?
-            .ok_or_else(|| {
-                error_reporting::capture_window_error("No monitor found", "set_window_position");
-                "No monitor found".to_string()
-            })?;
-
-        let monitor_size = monitor.size();
-        let window_size = window.outer_size().map_err(|e| {
-            let err = e.to_string();
-            error_reporting::capture_window_error(&err, "set_window_position_size");
-            err
-        })?;
-        let position =
-            anchored_window_position(*monitor.position(), *monitor_size, window_size, &position);
-
-        window.set_position(position).map_err(|e| {
-            let err = e.to_string();
-            error_reporting::capture_window_error(&err, "set_window_position_set");
-            err
-        })?;
-    }
-    Ok(())
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
 }
 
-#[tauri::command]
-async fn set_auto_start(enabled: bool) -> Result<(), String> {
-    settings::set_auto_start(enabled).await.map_err(|e| {
-        error_reporting::capture_settings_error(&e, "set_auto_start");
-        e
-    })
-}
-
-// PawnIO driver check and installation
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PawnIOStatus {
-    installed: bool,
-    checking: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    service_state: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    driver_file_exists: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    error: Option<String>,
-}
-
-#[cfg(target_os = "windows")]
-pub fn get_pawnio_detailed_status() -> PawnIOStatus {
-    use std::os::windows::process::CommandExt;
-    use std::process::Command;
-
-    const CREATE_NO_WINDOW: u32 = 0x08000000;
-
-    // Get driver binary path from service config (sc qc)
-    let driver_file_exists = {
-        let qc_output = Command::new("sc")
-            .args(["qc", "PawnIO"])
-            .creation_flags(CREATE_NO_WINDOW)
-            .output();
-        if let Ok(qc) = qc_output {
-            let qc_stdout = String::from_utf8_lossy(&qc.stdout);
-            // Extract BINARY_PATH_NAME from sc qc output and check if file exists
-            if let Some(line) = qc_stdout.lines().find(|l| l.contains("BINARY_PATH_NAME")) {
-                let path_str = line
-                    .split(':')
-                    .skip(1)
-                    .collect::<Vec<_>>()
-                    .join(":")
-                    .trim()
-                    .to_string();
-                // Remove leading \??\ prefix if present
-                let clean_path = path_str.trim_start_matches(r"\??\");
-                let exists = std::path::Path::new(clean_path).exists();
-                crate::log_debug!(
-                    "PawnIO",
-                    "Driver binary path: {} (exists={})",
-                    clean_path,
-                    exists
-                );
-                exists
-            } else {
-                false
-            }
-        } else {
-            false
-        }
-    };
-
-    // Check if PawnIO service/driver is registered and its state
-    let output = Command::new("sc")
-        .args(["query", "PawnIO"])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
-
-    match output {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let stderr = String::from_utf8_lossy(&output.stderr);
-
-            let service_state = if stdout.contains("RUNNING") {
-                "RUNNING".to_string()
-            } else if stdout.contains("STOPPED") {
-                "STOPPED".to_string()
-            } else if stdout.contains("START_PENDING") {
-                "START_PENDING".to_string()
-            } else if stdout.contains("STOP_PENDING") {
-                "STOP_PENDING".to_string()
-            } else if !stderr.is_empty() || stdout.contains("1060") {
-                "NOT_FOUND".to_string()
-            } else {
-                format!("UNKNOWN: {}", stdout.trim())
-            };
-
-            let installed = service_state == "RUNNING";
-
-            crate::log_info!(
-                "PawnIO",
-                "Driver status: service={}, driver_file={}",
-                service_state,
-                driver_file_exists
-            );
-            if !installed {
-                crate::log_warn!(
-                    "PawnIO",
-                    "Driver is not running. CPU temperature may not be available. sc output: {}",
-                    stdout.trim()
-                );
-            }
+fn main() {
+    // Capture all `log` crate output (including Tauri internals) into the debug server buffer
+    log_buffer::init_logger();
 
-            PawnIOStatus {
-                installed,
-                checking: false,
-                service_state: Some(service_state),
-                driver_file_exists: Some(driver_file_exists),
-                error: None,
-            }
-        }
-        Err(e) => {
-            let error_msg = format!("Failed to query PawnIO service: {}", e);
-            crate::log_error!("PawnIO", "{}", error_msg);
-
-            PawnIOStatus {
-                installed: false,
-                checking: false,
-                service_state: None,
-                driver_file_exists: Some(driver_file_exists),
-                error: Some(error_msg),
+    if let Some(minutes) = soak::requested_minutes(std::env::args().skip(1)) {
+        std::process::exit(match minutes {
+            Ok(minutes) => soak::run(minutes),
+            Err(usage) => {
+                eprintln!("{}", usage);
+                2
             }
-        }
-    }
-}
-
-#[cfg(not(target_os = "windows"))]
-pub fn get_pawnio_detailed_status() -> PawnIOStatus {
-    PawnIOStatus {
-        installed: true,
-        checking: false,
-        service_state: Some("N/A (non-Windows)".to_string()),
-        driver_file_exists: None,
-        error: None,
+        });
     }
-}
 
-#[tauri::command]
-async fn check_pawnio_status() -> Result<PawnIOStatus, String> {
-    Ok(get_pawnio_detailed_status())
-}
+    // Before anything that could panic, so even startup crashes are logged
+    error_reporting::install_panic_hook();
 
-#[cfg(target_os = "windows")]
-#[tauri::command]
-async fn download_and_install_pawnio() -> Result<String, String> {
-    // Get the bundled PawnIO installer path
-    let exe_path = std::env::current_exe().map_err(|e| format!("Failed to get exe path: {}", e))?;
-    let exe_dir = exe_path.parent().ok_or("Failed to get exe directory")?;
-
-    // In development, the resources are in src-tauri/resources
-    // In production, they're next to the exe
-    let installer_path = exe_dir.join("resources").join("PawnIO_setup.exe");
-    let installer_path = if installer_path.exists() {
-        installer_path
-    } else {
-        // Fallback to direct path (production build)
-        exe_dir.join("PawnIO_setup.exe")
-    };
-
-    if !installer_path.exists() {
-        return Err(format!(
-            "PawnIO installer not found at {:?}",
-            installer_path
-        ));
-    }
-
-    // Run the installer with UAC elevation (ShellExecuteW with "runas")
-    // Use -install -silent for PawnIO silent installation
-    tokio::task::spawn_blocking(move || {
-        use std::ffi::OsStr;
-        use std::os::windows::ffi::OsStrExt;
-
-        let path_wide: Vec<u16> = OsStr::new(installer_path.to_str().unwrap_or_default())
-            .encode_wide()
-            .chain(std::iter::once(0))
-            .collect();
-
-        let verb_wide: Vec<u16> = OsStr::new("runas")
-            .encode_wide()
-            .chain(std::iter::once(0))
-            .collect();
-
-        // Silent install parameter (PawnIO uses -install -silent, not NSIS /S)
-        let params_wide: Vec<u16> = OsStr::new("-install -silent")
-            .encode_wide()
-            .chain(std::iter::once(0))
-            .collect();
-
-        unsafe {
-            use windows::core::PCWSTR;
-            use windows::Win32::UI::Shell::ShellExecuteW;
-            use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
-
-            let result = ShellExecuteW(
-                None,
-                PCWSTR(verb_wide.as_ptr()),
-                PCWSTR(path_wide.as_ptr()),
-                PCWSTR(params_wide.as_ptr()),
-                PCWSTR::null(),
-                SW_SHOWNORMAL,
-            );
+    let mut initial_settings =
+        settings::load_settings_from_file().unwrap_or_else(|_| settings::AppSettings::default());
 
-            // ShellExecuteW returns a value > 32 on success
-            if result.0 as isize <= 32 {
-                return Err(format!(
-                    "Failed to launch installer (error code: {})",
-                    result.0 as isize
-                ));
+    // Record this version and queue the "what's new" notice after an update
+    if release_notes::check_for_update(&mut initial_settings) {
+        let to_save = initial_settings.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = settings::save_settings_to_file(&to_save).await {
+                crate::log_warn!("ReleaseNotes", "Failed to save last run version: {}", e);
             }
-        }
-
-        Ok(
-            "PawnIO driver is being installed. Please restart Ondo after installation completes."
-                .to_string(),
-        )
-    })
-    .await
-    .map_err(|e| format!("Install task failed: {}", e))?
-}
-
-#[cfg(not(target_os = "windows"))]
-#[tauri::command]
-async fn download_and_install_pawnio() -> Result<String, String> {
-    Ok("PawnIO is only required on Windows.".to_string())
-}
-
-#[tauri::command]
-async fn set_always_on_back(app: AppHandle, enabled: bool) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        if enabled {
-            // Disable always on top first
-            window.set_always_on_top(false).map_err(|e| {
-                let err = e.to_string();
-                error_reporting::capture_window_error(&err, "set_always_on_back_top");
-                err
-            })?;
-            // Set always on bottom
-            window.set_always_on_bottom(true).map_err(|e| {
-                let err = e.to_string();
-                error_reporting::capture_window_error(&err, "set_always_on_back_bottom");
-                err
-            })?;
-        } else {
-            window.set_always_on_bottom(false).map_err(|e| {
-                let err = e.to_string();
-                error_reporting::capture_window_error(&err, "set_always_on_back_disable");
-                err
-            })?;
-        }
-    }
-    Ok(())
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WindowStateData {
-    x: i32,
-    y: i32,
-    width: u32,
-    height: u32,
-}
-
-#[tauri::command]
-async fn get_window_state(app: AppHandle) -> Result<WindowStateData, String> {
-    if let Some(window) = app.get_webview_window("main") {
-        let position = window.outer_position().map_err(|e| {
-            let err = e.to_string();
-            error_reporting::capture_window_error(&err, "get_window_state_position");
-            err
-        })?;
-        let size = window.inner_size().map_err(|e| {
-            let err = e.to_string();
-            error_reporting::capture_window_error(&err, "get_window_state_size");
-            err
-        })?;
-        Ok(WindowStateData {
-            x: position.x,
-            y: position.y,
-            width: size.width,
-            height: size.height,
-        })
-    } else {
-        let err = "Window not found".to_string();
-        error_reporting::capture_window_error(&err, "get_window_state");
-        Err(err)
-    }
-}
-
-#[tauri::command]
-async fn restore_window_state(app: AppHandle, state: WindowStateData) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window
-            .set_position(tauri::PhysicalPosition::new(state.x, state.y))
-            .map_err(|e| {
-                let err = e.to_string();
-                error_reporting::capture_window_error(&err, "restore_window_state_position");
-                err
-            })?;
-        window
-            .set_size(tauri::PhysicalSize::new(state.width, state.height))
-            .map_err(|e| {
-                let err = e.to_string();
-                error_reporting::capture_window_error(&err, "restore_window_state_size");
-                err
-            })?;
-    }
-    Ok(())
-}
-
-#[tauri::command]
-async fn set_window_min_size(
-    app: AppHandle,
-    width: Option<u32>,
-    height: Option<u32>,
-) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        let min_size = match (width, height) {
-            (Some(w), Some(h)) => Some(tauri::PhysicalSize::new(w, h)),
-            _ => None,
-        };
-        window.set_min_size(min_size).map_err(|e| {
-            let err = e.to_string();
-            error_reporting::capture_window_error(&err, "set_window_min_size");
-            err
-        })?;
+        });
     }
-    Ok(())
-}
 
-#[tauri::command]
-async fn set_window_shadow(app: AppHandle, enable: bool) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window.set_shadow(enable).map_err(|e| {
-            let err = e.to_string();
-            error_reporting::capture_window_error(&err, "set_window_shadow");
-            err
-        })?;
-    }
-    Ok(())
-}
+    // Error reporting is opt-in
+    error_reporting::set_dsn_setting(initial_settings.sentry_dsn.as_deref());
+    error_reporting::set_telemetry_enabled(initial_settings.telemetry_enabled);
 
-#[tauri::command]
-async fn get_audio_devices() -> Result<Vec<audio::AudioDevice>, String> {
-    audio::get_audio_devices()
-}
-
-#[tauri::command]
-async fn set_default_audio_device(device_id: String, device_type: String) -> Result<(), String> {
-    audio::set_default_audio_device(&device_id, &device_type)
-}
-
-#[tauri::command]
-async fn toggle_debug_server(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
-    state.set_debug_server(enabled)
-}
+    let start_minimized = std::env::args().any(|arg| arg == settings::MINIMIZED_ARG);
 
-#[derive(Clone, Copy)]
-enum AxisAnchor {
-    Start,
-    Center,
-    End,
-}
-
-fn anchored_axis(origin: i32, monitor_len: u32, window_len: u32, anchor: AxisAnchor) -> i32 {
-    let origin = i64::from(origin);
-    let available = (i64::from(monitor_len) - i64::from(window_len)).max(0);
-    let coordinate = match anchor {
-        AxisAnchor::Start => origin,
-        AxisAnchor::Center => origin + available / 2,
-        AxisAnchor::End => origin + available,
-    };
-
-    coordinate.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
-}
-
-fn anchored_window_position(
-    monitor_position: tauri::PhysicalPosition<i32>,
-    monitor_size: tauri::PhysicalSize<u32>,
-    window_size: tauri::PhysicalSize<u32>,
-    position: &str,
-) -> tauri::PhysicalPosition<i32> {
-    let (horizontal, vertical) = match position {
-        "left" => (AxisAnchor::Start, AxisAnchor::Center),
-        "right" => (AxisAnchor::End, AxisAnchor::Center),
-        "top-left" => (AxisAnchor::Start, AxisAnchor::Start),
-        "top-right" => (AxisAnchor::End, AxisAnchor::Start),
-        "bottom-left" => (AxisAnchor::Start, AxisAnchor::End),
-        "bottom-right" => (AxisAnchor::End, AxisAnchor::End),
-        _ => (AxisAnchor::End, AxisAnchor::Center),
-    };
-
-    tauri::PhysicalPosition::new(
-        anchored_axis(
-            monitor_position.x,
-            monitor_size.width,
-            window_size.width,
-            horizontal,
-        ),
-        anchored_axis(
-            monitor_position.y,
-            monitor_size.height,
-            window_size.height,
-            vertical,
-        ),
-    )
-}
-
-fn main() {
-    // Capture all `log` crate output (including Tauri internals) into the debug server buffer
-    log_buffer::init_logger();
-
-    // Initialize Sentry for error reporting
-    error_reporting::init_sentry();
-
-    let initial_settings =
-        settings::load_settings_from_file().unwrap_or_else(|_| settings::AppSettings::default());
+    // An update may have moved the executable the login entry points at
+    let auto_start = initial_settings.auto_start;
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = settings::verify_auto_start(auto_start).await {
+            error_reporting::capture_settings_error(&e, "verify_auto_start");
+        }
+    });
+    hardware::set_lhm_interval(initial_settings.update_interval_fast);
+    hardware::set_sensor_overrides(&initial_settings.sensor_overrides);
+    hardware::set_monitored_sections(initial_settings.monitored_sections());
+    hardware::set_slow_interval(initial_settings.update_interval_slow);
+    mock::set_enabled(initial_settings.mock_data);
+    sensor_owner::start();
 
     // Clone values we need for setup before moving into AppState
     let startup_position = initial_settings.position.clone();
+    let startup_position_margin = initial_settings.position_margin;
     let startup_always_on_top = initial_settings.always_on_top;
     let startup_always_on_back = initial_settings.always_on_back;
+    let startup_click_through = initial_settings.click_through;
     let startup_debug_server = initial_settings.debug_server;
+    let startup_pause_when_hidden = initial_settings.pause_when_hidden;
+    // Only carried over a restart when the user asked for it
+    if !initial_settings.remember_monitoring_pause {
+        initial_settings.monitoring_paused = false;
+    }
+    let startup_monitoring_paused = initial_settings.monitoring_paused;
+    // Before the first poll can spawn the daemon
+    hardware::set_lhm_suspended(startup_monitoring_paused);
     let startup_window_state = initial_settings.window_state.clone();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .plugin(hotkeys::plugin())
         .manage(AppState {
-            settings: Mutex::new(initial_settings),
+            settings: settings::SharedSettings::new(initial_settings),
             debug_server_running: AtomicBool::new(false),
             debug_server_shutdown: Mutex::new(None),
+            energy: Mutex::new(power::EnergyTracker::load()),
+            session_stats: Mutex::new(session_stats::SessionStats::new(now_millis())),
+            tray_readout: tray::TrayReadout::default(),
+            smoother: Mutex::new(smoothing::Smoother::default()),
+            throttle: Mutex::new(throttling::ThrottleDetector::default()),
+            alerts: Mutex::new(alerts::AlertLog::load()),
         })
         .setup(move |app| {
             // Start debug HTTP server first so tray::setup_tray can read the
@@ -692,21 +538,53 @@ fn main() {
             // Setup system tray
             tray::setup_tray(app)?;
 
+            // Global shortcuts; a failure only costs the shortcut
+            if let Err(e) =
+                commands::settings::sync_hotkeys(app.handle(), &app.state::<AppState>().settings)
+            {
+                crate::log_warn!("Hotkeys", "Startup shortcuts not registered: {}", e);
+            }
+
+            // Sample feed for external tools; a failure only costs the feed
+            let ws_result = ws_server::configure(&app.state::<AppState>().settings.lock());
+            if let Err(e) = ws_result {
+                crate::log_warn!("WsServer", "Not started: {}", e);
+            }
+            let influxdb_result = influxdb::configure(&app.state::<AppState>().settings.lock());
+            if let Err(e) = influxdb_result {
+                crate::log_warn!("InfluxDB", "Not started: {}", e);
+            }
+
+            // Window starts visible, so this only arms the pause for later
+            monitor_pause::set_pause_when_hidden(app.handle(), startup_pause_when_hidden);
+            if startup_monitoring_paused {
+                monitor_pause::set_monitoring_paused(app.handle(), true);
+            }
+
+            // After the pause state, so a paused start doesn't read once
+            sampler::start(app.handle().clone());
+
             // Start window monitor for mini mode (detects maximized foreground windows)
             window_monitor::start_monitoring(app.handle().clone());
 
             // Start FPS monitoring (ETW-based, requires admin on Windows)
             fps_monitor::start();
 
+            commands::updater::spawn_startup_check(app.handle().clone());
+
             // Position window on startup
             if let Some(window) = app.get_webview_window("main") {
                 // Restore saved window state if available
                 if let Some(ref state) = startup_window_state {
-                    let _ = window.set_position(tauri::PhysicalPosition::new(state.x, state.y));
-                    let _ = window.set_size(tauri::PhysicalSize::new(state.width, state.height));
+                    // Re-centers if the saved monitor was unplugged
+                    let _ = commands::window::apply_window_state(&window, &state.into());
                 } else {
                     // Set initial position based on setting
-                    let _ = set_initial_position(&window, &startup_position);
+                    let _ = commands::window::move_to_anchor(
+                        &window,
+                        &startup_position,
+                        startup_position_margin,
+                    );
                 }
 
                 // Set always on top/back
@@ -715,6 +593,16 @@ fn main() {
                 } else if startup_always_on_top {
                     let _ = window.set_always_on_top(true);
                 }
+                if startup_click_through {
+                    let _ = window.set_ignore_cursor_events(true);
+                }
+                // After click-through, which rewrites the extended style
+                commands::window::reapply_opacity(app.handle());
+
+                // Started by the logon task: straight to the tray
+                if start_minimized {
+                    tray::hide_main_window(app.handle());
+                }
 
                 // Register HWND for the window-debug endpoints (Windows only)
                 #[cfg(target_os = "windows")]
@@ -727,31 +615,46 @@ fn main() {
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            get_hardware_data,
-            get_settings,
-            save_settings,
-            set_always_on_top,
-            set_always_on_back,
-            set_window_position,
-            set_auto_start,
-            get_window_state,
-            restore_window_state,
-            set_window_min_size,
-            set_window_shadow,
-            check_pawnio_status,
-            download_and_install_pawnio,
-            get_audio_devices,
-            set_default_audio_device,
-            toggle_debug_server,
-        ])
-        .on_window_event(|window, event| {
-            if let tauri::WindowEvent::Destroyed = event {
-                if window.label() == "main" {
-                    // Shutdown LHM daemon when main window is destroyed
-                    hardware::shutdown_lhm_daemon();
+        .invoke_handler(commands::invoke_handler())
+        .on_page_load(|webview, payload| {
+            if webview.label() == "main"
+                && payload.event() == tauri::webview::PageLoadEvent::Finished
+            {
+                release_notes::emit_pending_notice(webview.app_handle());
+            }
+        })
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } if window.label() == "main" => {
+                let close_to_tray = window.state::<AppState>().settings.lock().close_to_tray;
+                if close_to_tray {
+                    // Keep monitoring; quitting is left to the tray
+                    api.prevent_close();
+                    tray::hide_main_window(window.app_handle());
+                } else {
+                    commands::window::save_window_state_on_exit(window.app_handle());
                 }
             }
+            tauri::WindowEvent::Moved(position) if window.label() == "main" => {
+                commands::window::snap_to_edges(window.app_handle(), *position);
+                commands::window::window_geometry_changed(window.app_handle());
+            }
+            tauri::WindowEvent::Resized(_) if window.label() == "main" => {
+                commands::window::window_geometry_changed(window.app_handle());
+            }
+            tauri::WindowEvent::Destroyed if window.label() == "main" => {
+                // Shutdown LHM daemon when main window is destroyed
+                hardware::shutdown_lhm_daemon();
+                ws_server::stop();
+                influxdb::stop();
+                window
+                    .state::<AppState>()
+                    .energy
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .persist();
+                error_reporting::flush(error_reporting::SHUTDOWN_FLUSH_TIMEOUT);
+            }
+            _ => {}
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -759,16 +662,7 @@ fn main() {
     // Shutdown LHM daemon and FPS monitor on app exit
     fps_monitor::stop();
     hardware::shutdown_lhm_daemon();
-}
-
-fn set_initial_position(
-    window: &tauri::WebviewWindow,
-    position: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let monitor = window.current_monitor()?.ok_or("No monitor")?;
-    let window_size = window.outer_size()?;
-    let position =
-        anchored_window_position(*monitor.position(), *monitor.size(), window_size, position);
-    window.set_position(position)?;
-    Ok(())
+    ws_server::stop();
+    influxdb::stop();
+    error_reporting::flush(error_reporting::SHUTDOWN_FLUSH_TIMEOUT);
 }
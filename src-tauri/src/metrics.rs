@@ -0,0 +1,197 @@
+//! Internal resource counters for catching slow leaks in sessions that run
+//! for weeks. Updated with relaxed atomics where things are allocated or
+//! spawned; read by `get_internal_metrics`, the debug server's /status and
+//! the `--soak-test` harness.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A monotonic count or a gauge, depending on how the site updates it.
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+
+    pub fn set(&self, value: usize) {
+        self.0.store(value as u64, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+pub static SAMPLES_COLLECTED: Counter = Counter::new();
+pub static SAMPLE_ERRORS: Counter = Counter::new();
+pub static LHM_DAEMON_SPAWNS: Counter = Counter::new();
+/// Background tasks spawned and not yet finished, see `task_started`.
+pub static TASKS_RUNNING: Counter = Counter::new();
+/// Sensors tracked by the session statistics.
+pub static SESSION_SENSORS: Counter = Counter::new();
+/// Days held by the energy tracker.
+pub static ENERGY_DAYS: Counter = Counter::new();
+/// Keys held by the error-capture rate limiter.
+pub static RATE_LIMITER_ENTRIES: Counter = Counter::new();
+pub static HOTKEY_BINDINGS: Counter = Counter::new();
+/// Connected WebSocket clients.
+pub static WS_CLIENTS: Counter = Counter::new();
+
+/// Counts a background task as running until dropped. Create it before
+/// spawning and move it into the task.
+pub struct TaskGuard(());
+
+pub fn task_started() -> TaskGuard {
+    TASKS_RUNNING.inc();
+    TaskGuard(())
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        TASKS_RUNNING.dec();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalMetrics {
+    pub samples_collected: u64,
+    pub sample_errors: u64,
+    pub lhm_daemon_spawns: u64,
+    pub tasks_running: u64,
+    pub session_sensors: u64,
+    pub energy_days: u64,
+    pub rate_limiter_entries: u64,
+    pub hotkey_bindings: u64,
+    pub websocket_clients: u64,
+    pub log_buffer_lines: u64,
+    /// Size of the current local error log file.
+    pub error_log_bytes: u64,
+}
+
+pub fn snapshot() -> InternalMetrics {
+    InternalMetrics {
+        samples_collected: SAMPLES_COLLECTED.get(),
+        sample_errors: SAMPLE_ERRORS.get(),
+        lhm_daemon_spawns: LHM_DAEMON_SPAWNS.get(),
+        tasks_running: TASKS_RUNNING.get(),
+        session_sensors: SESSION_SENSORS.get(),
+        energy_days: ENERGY_DAYS.get(),
+        rate_limiter_entries: RATE_LIMITER_ENTRIES.get(),
+        hotkey_bindings: HOTKEY_BINDINGS.get(),
+        websocket_clients: WS_CLIENTS.get(),
+        log_buffer_lines: crate::log_buffer::count() as u64,
+        error_log_bytes: crate::error_reporting::error_log_bytes(),
+    }
+}
+
+/// Upper bounds that must hold however long the app runs, as
+/// `(name, value, limit)`.
+fn bounds(metrics: &InternalMetrics) -> [(&'static str, u64, u64); 8] {
+    [
+        ("tasksRunning", metrics.tasks_running, 64),
+        ("sessionSensors", metrics.session_sensors, 256),
+        (
+            "energyDays",
+            metrics.energy_days,
+            crate::power::MAX_DAYS as u64,
+        ),
+        ("rateLimiterEntries", metrics.rate_limiter_entries, 1024),
+        (
+            "hotkeyBindings",
+            metrics.hotkey_bindings,
+            crate::hotkeys::HotkeyAction::ALL.len() as u64,
+        ),
+        (
+            "websocketClients",
+            metrics.websocket_clients,
+            crate::ws_server::MAX_CLIENTS,
+        ),
+        (
+            "logBufferLines",
+            metrics.log_buffer_lines,
+            crate::log_buffer::MAX_LINES as u64,
+        ),
+        (
+            "errorLogBytes",
+            metrics.error_log_bytes,
+            crate::error_reporting::ERROR_LOG_MAX_BYTES + 64 * 1024,
+        ),
+    ]
+}
+
+/// Counters over their bound, described for a log line.
+pub fn violations(metrics: &InternalMetrics) -> Vec<String> {
+    bounds(metrics)
+        .into_iter()
+        .filter(|(_, value, limit)| value > limit)
+        .map(|(name, value, limit)| format!("{} = {} exceeds {}", name, value, limit))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet() -> InternalMetrics {
+        InternalMetrics {
+            samples_collected: 1_000_000,
+            sample_errors: 0,
+            lhm_daemon_spawns: 3,
+            tasks_running: 2,
+            session_sensors: 8,
+            energy_days: 30,
+            rate_limiter_entries: 4,
+            hotkey_bindings: 2,
+            websocket_clients: 1,
+            log_buffer_lines: 100,
+            error_log_bytes: 2048,
+        }
+    }
+
+    #[test]
+    fn monotonic_counts_are_not_bounded() {
+        assert!(violations(&quiet()).is_empty());
+    }
+
+    #[test]
+    fn gauges_over_their_bound_are_reported() {
+        let metrics = InternalMetrics {
+            tasks_running: 65,
+            session_sensors: 10_000,
+            ..quiet()
+        };
+        assert_eq!(
+            violations(&metrics),
+            vec![
+                "tasksRunning = 65 exceeds 64".to_string(),
+                "sessionSensors = 10000 exceeds 256".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn counter_never_goes_below_zero() {
+        let counter = Counter::new();
+        counter.inc();
+        counter.inc();
+        counter.dec();
+        assert_eq!(counter.get(), 1);
+        counter.dec();
+        counter.dec();
+        // Never wraps below zero
+        assert_eq!(counter.get(), 0);
+    }
+}
@@ -0,0 +1,348 @@
+//! Synthetic hardware samples for running the pipeline without sensors.
+//! Deterministic: sample `n` is the same on every run, and timestamps
+//! advance by a fixed step so time can run faster than the wall clock.
+//! Readings follow slow sine waves, each core on its own, with noise from a
+//! PRNG seeded by `n`.
+//!
+//! Also served as live data on any platform when the `mock_data` setting
+//! or `ONDO_MOCK=1` is set, so the UI can be worked on without sensors.
+
+use crate::commands::hardware::HardwareSource;
+use crate::{
+    CpuCoreData, CpuData, DisplayData, FanData, FanUnit, GpuData, HardwareData, MotherboardData,
+    NamedTemperature, NetworkInterfaceData, StorageData, VoltageData,
+};
+use once_cell::sync::Lazy;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// xorshift64: small, fast and plenty random for fake readings.
+struct XorShift64(u64);
+
+impl XorShift64 {
+    /// Generator for sample `n`. The seed is scrambled so neighbouring
+    /// samples don't start from similar states.
+    fn for_sample(n: u64) -> Self {
+        let seed = n.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        Self(seed | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Uniform in `-amplitude..amplitude`.
+    fn noise(&mut self, amplitude: f32) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        (unit * 2.0 - 1.0) * amplitude
+    }
+}
+
+pub struct MockHardware {
+    start_ms: u64,
+    step_ms: u64,
+    tick: AtomicU64,
+}
+
+impl MockHardware {
+    /// Samples start at `start_ms` and are `step_ms` apart.
+    pub fn new(start_ms: u64, step_ms: u64) -> Self {
+        Self {
+            start_ms,
+            step_ms,
+            tick: AtomicU64::new(0),
+        }
+    }
+
+    /// The `n`th sample.
+    pub fn sample_at(&self, n: u64) -> HardwareData {
+        // Slow load cycle with a faster ripple and some noise, so values
+        // move like a real machine
+        let mut rng = XorShift64::for_sample(n);
+        let phase = n as f32 / 120.0;
+        let load = (50.0 + 40.0 * phase.sin() + 5.0 * (phase * 7.0).sin() + rng.noise(3.0))
+            .clamp(0.0, 100.0);
+        let cpu_temperature = 40.0 + load * 0.45 + rng.noise(0.5);
+        let gpu_load = (load * 0.8 + 10.0 + rng.noise(4.0)).clamp(0.0, 100.0);
+
+        HardwareData {
+            cpu: Some(CpuData {
+                name: "Mock CPU 8-Core".to_string(),
+                temperature: cpu_temperature,
+                max_temperature: 100.0,
+                load,
+                frequency: 3.0 + load / 100.0 * 2.0,
+                cores: (0..8)
+                    .map(|index| {
+                        // Each core drifts on its own wave around the package load
+                        let drift = 12.0 * (phase * 3.0 + index as f32 * 0.8).sin();
+                        let core_load = (load + drift + rng.noise(6.0)).clamp(0.0, 100.0);
+                        CpuCoreData {
+                            index,
+                            temperature: cpu_temperature
+                                + (core_load - load) * 0.15
+                                + rng.noise(1.0),
+                            load: core_load,
+                            frequency: Some(3.0 + core_load / 100.0 * 2.0 + rng.noise(0.05)),
+                            estimated: false,
+                            package: None,
+                        }
+                    })
+                    .collect(),
+                power: Some(15.0 + load * 1.2),
+                voltage: Some(0.9 + load * 0.004),
+                is_throttling: false,
+                base_frequency: 3.0,
+                max_frequency: 5.0,
+                packages: None,
+                status: Default::default(),
+            }),
+            gpu: Some(GpuData {
+                name: "Mock GPU".to_string(),
+                temperature: 35.0 + gpu_load * 0.4,
+                max_temperature: 90.0,
+                load: gpu_load,
+                frequency: 1.2 + gpu_load / 100.0,
+                memory_used: 2048.0 + gpu_load * 20.0,
+                memory_total: 8192.0,
+                power: Some(20.0 + gpu_load * 2.0),
+                power_limit: Some(250.0),
+                hotspot_temperature: Some(47.0 + gpu_load * 0.45),
+                memory_temperature: Some(40.0 + gpu_load * 0.3),
+                // Fan-stop below 30% load
+                fans: vec![FanData {
+                    name: "GPU Fan 1".to_string(),
+                    unit: FanUnit::Rpm,
+                    speed: if gpu_load < 30.0 {
+                        0
+                    } else {
+                        900 + (gpu_load * 12.0) as u32
+                    },
+                    stopped: gpu_load < 30.0,
+                }],
+                status: Default::default(),
+            }),
+            storage: Some(vec![
+                StorageData {
+                    name: "Mock NVMe SSD".to_string(),
+                    temperature: 38.0 + load * 0.1,
+                    used_space: 512.0,
+                    used_percent: 50.0,
+                    total_space: 1024.0,
+                    health_percent: None,
+                    data_written_tb: None,
+                    read_rate: None,
+                    write_rate: None,
+                    partitions: None,
+                    status: Default::default(),
+                },
+                StorageData {
+                    name: "Mock SATA HDD".to_string(),
+                    temperature: 32.0,
+                    used_space: 1500.0,
+                    used_percent: 37.5,
+                    total_space: 4000.0,
+                    health_percent: None,
+                    data_written_tb: None,
+                    read_rate: None,
+                    write_rate: None,
+                    partitions: None,
+                    status: Default::default(),
+                },
+            ]),
+            motherboard: Some(MotherboardData {
+                name: "Mock Board".to_string(),
+                temperature: 30.0 + load * 0.1,
+                temperatures: vec![
+                    NamedTemperature {
+                        name: "System".to_string(),
+                        temperature: 30.0 + load * 0.1,
+                    },
+                    NamedTemperature {
+                        name: "VRM MOS".to_string(),
+                        temperature: 38.0 + load * 0.3,
+                    },
+                    NamedTemperature {
+                        name: "PCH".to_string(),
+                        temperature: 45.0,
+                    },
+                ],
+                fans: vec![
+                    FanData {
+                        name: "CPU Fan".to_string(),
+                        unit: FanUnit::Rpm,
+                        speed: 800 + (load * 10.0) as u32,
+                        stopped: false,
+                    },
+                    FanData {
+                        name: "System Fan".to_string(),
+                        unit: FanUnit::Rpm,
+                        speed: 600,
+                        stopped: false,
+                    },
+                ],
+                voltages: vec![
+                    VoltageData {
+                        name: "+12V".to_string(),
+                        volts: 12.1 - load * 0.001,
+                    },
+                    VoltageData {
+                        name: "+5V".to_string(),
+                        volts: 5.02,
+                    },
+                    VoltageData {
+                        name: "+3.3V".to_string(),
+                        volts: 3.31,
+                    },
+                    VoltageData {
+                        name: "Vcore".to_string(),
+                        volts: 0.9 + load * 0.004,
+                    },
+                ],
+                status: Default::default(),
+            }),
+            network: Some(vec![NetworkInterfaceData {
+                name: "Ethernet".to_string(),
+                received_per_sec: (load as f64) * 10_000.0 * (1.0 + rng.noise(0.5) as f64),
+                sent_per_sec: (load as f64) * 1_000.0 * (1.0 + rng.noise(0.5) as f64),
+            }]),
+            display: Some(DisplayData {
+                name: Some("Mock Display".to_string()),
+                refresh_rate: 144,
+                fps: None,
+                fps_process_name: None,
+            }),
+            timestamp: self.start_ms + n * self.step_ms,
+            data_source: "mock".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn next_sample(&self) -> HardwareData {
+        self.sample_at(self.tick.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl HardwareSource for MockHardware {
+    fn sample(&self) -> impl Future<Output = Result<HardwareData, String>> + Send {
+        std::future::ready(Ok(self.next_sample()))
+    }
+}
+
+/// Environment variable that turns mock data on regardless of the setting.
+const MOCK_ENV_VAR: &str = "ONDO_MOCK";
+
+/// Whether `get_hardware_info` serves mock samples, see `set_enabled`.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static LIVE: Lazy<MockHardware> = Lazy::new(|| MockHardware::new(crate::now_millis(), 1000));
+
+fn env_requested(value: Option<&str>) -> bool {
+    matches!(
+        value.map(|v| v.trim().to_ascii_lowercase()).as_deref(),
+        Some("1" | "true" | "yes")
+    )
+}
+
+/// Serve mock samples when `setting` (the `mock_data` setting) or
+/// `ONDO_MOCK=1` asks for them. Returns whether they are served.
+pub fn set_enabled(setting: bool) -> bool {
+    let enabled = setting || env_requested(std::env::var(MOCK_ENV_VAR).ok().as_deref());
+    if ENABLED.swap(enabled, Ordering::Relaxed) != enabled {
+        crate::log_info!(
+            "Hardware",
+            "Mock data {}",
+            if enabled { "on" } else { "off" }
+        );
+    }
+    enabled
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// The next mock sample, stamped with the current time.
+pub fn live_sample() -> HardwareData {
+    let mut data = LIVE.next_sample();
+    data.timestamp = crate::now_millis();
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn samples_are_deterministic_and_evenly_spaced() {
+        let a = MockHardware::new(1_000, 250);
+        let b = MockHardware::new(1_000, 250);
+        for n in 0..3 {
+            let sample = a.sample().await.unwrap();
+            assert_eq!(sample.timestamp, 1_000 + n * 250);
+            assert_eq!(sample.data_source, "mock");
+            assert_eq!(
+                serde_json::to_value(&sample).unwrap(),
+                serde_json::to_value(b.sample_at(n)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn env_var_accepts_the_usual_spellings() {
+        for value in ["1", "true", " TRUE ", "yes"] {
+            assert!(env_requested(Some(value)), "{}", value);
+        }
+        for value in ["0", "", "false", "no"] {
+            assert!(!env_requested(Some(value)), "{}", value);
+        }
+        assert!(!env_requested(None));
+    }
+
+    #[test]
+    fn readings_stay_in_range() {
+        let mock = MockHardware::new(0, 1000);
+        for n in 0..10_000 {
+            let sample = mock.sample_at(n);
+            let cpu = sample.cpu.unwrap();
+            assert!((0.0..=100.0).contains(&cpu.load), "load {}", cpu.load);
+            assert!(cpu.temperature > 0.0 && cpu.temperature < cpu.max_temperature);
+            for core in &cpu.cores {
+                assert!(
+                    (0.0..=100.0).contains(&core.load),
+                    "core load {}",
+                    core.load
+                );
+                assert!(core.temperature > 0.0 && core.temperature < cpu.max_temperature);
+            }
+            assert!((0.0..=100.0).contains(&sample.gpu.unwrap().load));
+        }
+    }
+
+    #[test]
+    fn cores_vary_independently() {
+        let mock = MockHardware::new(0, 1000);
+        let loads = |n| -> Vec<f32> {
+            mock.sample_at(n)
+                .cpu
+                .unwrap()
+                .cores
+                .iter()
+                .map(|core| core.load)
+                .collect()
+        };
+        let first = loads(0);
+        let mut distinct = first.clone();
+        distinct.sort_by(f32::total_cmp);
+        distinct.dedup();
+        assert_eq!(distinct.len(), first.len());
+        // The gap between two cores changes from sample to sample
+        let gaps: Vec<f32> = (0..5).map(|n| loads(n)[0] - loads(n)[1]).collect();
+        assert!(gaps.windows(2).any(|pair| (pair[0] - pair[1]).abs() > 0.5));
+    }
+}
@@ -0,0 +1,271 @@
+//! Slow sampling while the widget is hidden (`pause_when_hidden`). The
+//! background sampler keeps ticking and `get_hardware_data` keeps answering,
+//! but while paused both get the last sample and the hardware is only read
+//! every `KEEP_ALIVE_MS`. Those reads go through the whole pipeline, so the
+//! tray tooltip stays roughly fresh and temperature alerts still fire. The
+//! LHM helper is restarted with the same slow interval so it idles too;
+//! showing the widget goes back to full rate on the next tick.
+//!
+//! "Pause monitoring" in the tray goes further: no sampling at all and the
+//! LHM helper is stopped, so benchmarks don't see Ondo's own reads.
+
+use crate::{AppState, HardwareData};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Sampling interval while paused.
+pub const KEEP_ALIVE_MS: u32 = 30_000;
+
+/// How the hardware is read in a given `PauseState`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sampling {
+    Live,
+    /// Every `KEEP_ALIVE_MS`, while the widget is hidden.
+    KeepAlive,
+    /// Not at all, while paused from the tray.
+    Stopped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PauseState {
+    window_visible: bool,
+    pause_when_hidden: bool,
+    /// "Pause monitoring" in the tray.
+    monitoring_paused: bool,
+}
+
+impl PauseState {
+    fn sampling(self) -> Sampling {
+        if self.monitoring_paused {
+            Sampling::Stopped
+        } else if self.pause_when_hidden && !self.window_visible {
+            Sampling::KeepAlive
+        } else {
+            Sampling::Live
+        }
+    }
+}
+
+static STATE: Mutex<PauseState> = Mutex::new(PauseState {
+    window_visible: true,
+    pause_when_hidden: false,
+    monitoring_paused: false,
+});
+
+/// Most recent sample and when it was taken, served while paused.
+static LAST_SAMPLE: Mutex<Option<(u64, HardwareData)>> = Mutex::new(None);
+
+/// Serializes daemon interval changes, see `reconcile_lhm_interval`.
+static RECONCILE_LOCK: Mutex<()> = Mutex::new(());
+
+fn sampling() -> Sampling {
+    STATE
+        .lock()
+        .map(|state| state.sampling())
+        .unwrap_or(Sampling::Live)
+}
+
+/// Whether monitoring is paused from the tray.
+pub fn is_monitoring_paused() -> bool {
+    sampling() == Sampling::Stopped
+}
+
+/// Apply `change` and, if that changed how the hardware is sampled, bring
+/// the LHM daemon in line.
+fn transition(app: &AppHandle, change: impl FnOnce(&mut PauseState)) {
+    let (before, after) = {
+        let Ok(mut state) = STATE.lock() else {
+            return;
+        };
+        let before = state.sampling();
+        change(&mut state);
+        (before, state.sampling())
+    };
+    if before == after {
+        return;
+    }
+
+    crate::log_info!(
+        "Monitor",
+        "Sampling {}",
+        match after {
+            Sampling::Live => "resumed",
+            Sampling::KeepAlive => "slowed down",
+            Sampling::Stopped => "stopped",
+        }
+    );
+    let app = app.clone();
+    let task = crate::metrics::task_started();
+    // Restarting the daemon blocks until it delivers its first sample
+    tauri::async_runtime::spawn_blocking(move || {
+        let _task = task;
+        reconcile_lhm_interval(&app)
+    });
+}
+
+/// Record that the main window was shown or hidden.
+pub fn set_window_visible(app: &AppHandle, visible: bool) {
+    transition(app, |state| state.window_visible = visible);
+}
+
+pub fn set_pause_when_hidden(app: &AppHandle, enabled: bool) {
+    transition(app, |state| state.pause_when_hidden = enabled);
+}
+
+/// Stop or resume all sampling, update the tray and tell the widget with
+/// `monitoring-paused`. Storing the flag is up to the caller.
+pub fn set_monitoring_paused(app: &AppHandle, paused: bool) {
+    transition(app, |state| state.monitoring_paused = paused);
+    app.state::<AppState>().tray_readout.set_paused(paused);
+    crate::tray::set_pause_monitoring_checked(app, paused);
+    if let Err(e) = app.emit("monitoring-paused", paused) {
+        crate::log_warn!("Monitor", "Failed to emit monitoring-paused: {}", e);
+    }
+}
+
+/// LHM daemon interval for the user's `update_interval_fast`.
+fn lhm_interval(update_interval: u32, paused: bool) -> u32 {
+    if paused {
+        update_interval.max(KEEP_ALIVE_MS)
+    } else {
+        update_interval
+    }
+}
+
+/// Set the daemon interval from the current settings, monitoring mode and
+/// pause state, or stop the daemon while monitoring is paused. Reads them
+/// under one lock instead of taking them as arguments, so when the state
+/// flips rapidly (visibility, or pause clicked twice) the last call to
+/// finish always applies the latest state, whatever order the spawned calls
+/// run in.
+pub fn reconcile_lhm_interval(app: &AppHandle) {
+    let _serialized = RECONCILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let update_interval = app.state::<AppState>().settings.lock().update_interval_fast;
+    let sampling = sampling();
+    if sampling == Sampling::Stopped {
+        crate::hardware::set_lhm_suspended(true);
+        return;
+    }
+    crate::hardware::set_lhm_interval(lhm_interval(
+        crate::monitoring_mode::fast_interval(update_interval),
+        sampling == Sampling::KeepAlive,
+    ));
+    crate::hardware::set_lhm_suspended(false);
+}
+
+/// The cached sample, if it can be served in place of a new one: always
+/// while stopped, and while fresh enough when keeping alive.
+fn fresh_sample(
+    last: Option<&(u64, HardwareData)>,
+    sampling: Sampling,
+    now: u64,
+) -> Option<HardwareData> {
+    let (taken, data) = last?;
+    match sampling {
+        Sampling::Live => None,
+        Sampling::KeepAlive => {
+            (now.saturating_sub(*taken) < u64::from(KEEP_ALIVE_MS)).then(|| data.clone())
+        }
+        Sampling::Stopped => Some(data.clone()),
+    }
+}
+
+/// While paused, the last sample unless it is older than `KEEP_ALIVE_MS`;
+/// while monitoring is paused, the last sample whatever its age. `None`
+/// means a new sample should be taken.
+pub fn cached_sample(now: u64) -> Option<HardwareData> {
+    let sampling = sampling();
+    let last = LAST_SAMPLE.lock().ok()?;
+    fresh_sample(last.as_ref(), sampling, now)
+}
+
+/// The last sample handed out, whatever its age.
+pub fn last_sample() -> Option<HardwareData> {
+    let last = LAST_SAMPLE.lock().ok()?;
+    last.as_ref().map(|(_, data)| data.clone())
+}
+
+/// `dataSource` of the last sample handed out.
+pub fn last_data_source() -> Option<String> {
+    let last = LAST_SAMPLE.lock().ok()?;
+    last.as_ref().map(|(_, data)| data.data_source.clone())
+}
+
+/// CPU model in the last sample handed out.
+pub fn last_cpu_name() -> Option<String> {
+    let last = LAST_SAMPLE.lock().ok()?;
+    last.as_ref()?.1.cpu.as_ref().map(|cpu| cpu.name.clone())
+}
+
+pub fn remember_sample(now: u64, data: &HardwareData) {
+    if let Ok(mut last) = LAST_SAMPLE.lock() {
+        *last = Some((now, data.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> (u64, HardwareData) {
+        let data = HardwareData {
+            timestamp: 1,
+            ..Default::default()
+        };
+        (1_000, data)
+    }
+
+    #[test]
+    fn paused_only_when_hidden_and_enabled() {
+        let state = |window_visible, pause_when_hidden| PauseState {
+            window_visible,
+            pause_when_hidden,
+            monitoring_paused: false,
+        };
+        assert_eq!(state(false, true).sampling(), Sampling::KeepAlive);
+        assert_eq!(state(true, true).sampling(), Sampling::Live);
+        assert_eq!(state(false, false).sampling(), Sampling::Live);
+        assert_eq!(state(true, false).sampling(), Sampling::Live);
+    }
+
+    #[test]
+    fn pausing_monitoring_stops_sampling_whatever_the_window() {
+        for (window_visible, pause_when_hidden) in
+            [(true, true), (true, false), (false, true), (false, false)]
+        {
+            let state = PauseState {
+                window_visible,
+                pause_when_hidden,
+                monitoring_paused: true,
+            };
+            assert_eq!(state.sampling(), Sampling::Stopped);
+        }
+    }
+
+    #[test]
+    fn daemon_slows_down_while_paused() {
+        assert_eq!(lhm_interval(1000, false), 1000);
+        assert_eq!(lhm_interval(1000, true), KEEP_ALIVE_MS);
+        // Never speed up a slower user interval
+        assert_eq!(lhm_interval(60_000, true), 60_000);
+    }
+
+    #[test]
+    fn cached_sample_served_only_while_paused_and_fresh() {
+        let last = sample();
+        let keep_alive = Sampling::KeepAlive;
+        assert!(fresh_sample(Some(&last), keep_alive, 1_000 + 29_999).is_some());
+        assert!(fresh_sample(Some(&last), keep_alive, 1_000 + 30_000).is_none());
+        assert!(fresh_sample(Some(&last), Sampling::Live, 1_001).is_none());
+        assert!(fresh_sample(None, keep_alive, 1_001).is_none());
+    }
+
+    #[test]
+    fn cached_sample_served_at_any_age_while_stopped() {
+        let last = sample();
+        let stopped = Sampling::Stopped;
+        assert!(fresh_sample(Some(&last), stopped, 1_000 + 3_600_000).is_some());
+        // Nothing cached yet: one sample is taken
+        assert!(fresh_sample(None, stopped, 1_001).is_none());
+    }
+}
@@ -0,0 +1,238 @@
+//! Performance mode for benchmark runs: CPU and GPU are read every
+//! `PERFORMANCE_INTERVAL_MS` and the UI polls as often, while storage and
+//! the motherboard are read only once a minute so the extra samples cost as
+//! little as possible. Reverts to normal after `performance_mode_minutes`
+//! or when set back explicitly.
+//!
+//! The LHM daemon is restarted for the new interval through
+//! `monitor_pause::reconcile_lhm_interval`, which serializes interval
+//! changes; `get_lhm_data` holds the daemon lock while it reads, so a
+//! restart never happens under an in-flight read.
+
+use crate::{monitor_pause, now_millis, settings, AppState};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// CPU and GPU sampling interval in performance mode.
+pub const PERFORMANCE_INTERVAL_MS: u32 = settings::MIN_UPDATE_INTERVAL_MS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitoringMode {
+    Normal,
+    Performance,
+}
+
+impl MonitoringMode {
+    fn parse(mode: &str) -> Result<Self, String> {
+        match mode {
+            "normal" => Ok(Self::Normal),
+            "performance" => Ok(Self::Performance),
+            other => Err(format!(
+                "Unknown monitoring mode \"{}\"; expected normal or performance",
+                other
+            )),
+        }
+    }
+}
+
+/// Payload of `monitoring-mode-changed`.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringModeStatus {
+    pub mode: MonitoringMode,
+    /// How often CPU and GPU are read, and the UI should poll.
+    pub interval_ms: u32,
+    /// When performance mode reverts to normal, ms since epoch.
+    pub until: Option<u64>,
+}
+
+/// A running performance mode. `generation` tells the revert timer of an
+/// earlier `set` that it has been superseded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Performance {
+    until_ms: u64,
+    generation: u64,
+}
+
+static PERFORMANCE: Mutex<Option<Performance>> = Mutex::new(None);
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn current() -> Option<Performance> {
+    *PERFORMANCE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// `performance` unless it has run out by `now`; the revert timer can be
+/// late after the machine slept.
+fn active(performance: Option<Performance>, now: u64) -> Option<Performance> {
+    performance.filter(|p| now < p.until_ms)
+}
+
+fn status_at(
+    performance: Option<Performance>,
+    update_interval: u32,
+    now: u64,
+) -> MonitoringModeStatus {
+    match active(performance, now) {
+        Some(p) => MonitoringModeStatus {
+            mode: MonitoringMode::Performance,
+            interval_ms: PERFORMANCE_INTERVAL_MS.min(update_interval),
+            until: Some(p.until_ms),
+        },
+        None => MonitoringModeStatus {
+            mode: MonitoringMode::Normal,
+            interval_ms: update_interval,
+            until: None,
+        },
+    }
+}
+
+/// CPU and GPU sampling interval for the user's `update_interval_fast`.
+pub fn fast_interval(update_interval: u32) -> u32 {
+    status_at(current(), update_interval, now_millis()).interval_ms
+}
+
+/// Storage and motherboard interval for the user's `update_interval_slow`:
+/// as slow as allowed in performance mode.
+pub fn slow_interval(update_interval_slow: u32) -> u32 {
+    if active(current(), now_millis()).is_some() {
+        settings::MAX_UPDATE_INTERVAL_MS.max(update_interval_slow)
+    } else {
+        update_interval_slow
+    }
+}
+
+pub fn status(app: &AppHandle) -> Result<MonitoringModeStatus, String> {
+    let update_interval = app.state::<AppState>().settings.lock().update_interval_fast;
+    Ok(status_at(current(), update_interval, now_millis()))
+}
+
+/// Switch to `mode` ("normal" or "performance"). Entering performance mode
+/// again restarts its timeout.
+pub fn set(app: &AppHandle, mode: &str) -> Result<MonitoringModeStatus, String> {
+    let mode = MonitoringMode::parse(mode)?;
+    let minutes = app
+        .state::<AppState>()
+        .settings
+        .lock()
+        .performance_mode_minutes;
+
+    let performance = {
+        let mut slot = PERFORMANCE.lock().unwrap_or_else(|e| e.into_inner());
+        let generation = GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+        *slot = (mode == MonitoringMode::Performance).then(|| Performance {
+            until_ms: now_millis() + u64::from(minutes) * 60_000,
+            generation,
+        });
+        *slot
+    };
+    if let Some(performance) = performance {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(u64::from(minutes) * 60)).await;
+            revert(&app, performance.generation);
+        });
+    }
+
+    match performance {
+        Some(_) => crate::log_info!("Monitor", "Performance mode for {} min", minutes),
+        None => crate::log_info!("Monitor", "Normal mode"),
+    }
+    apply(app)
+}
+
+/// Back to normal when the performance mode started as `generation` is
+/// still the one running.
+fn revert(app: &AppHandle, generation: u64) {
+    {
+        let mut slot = PERFORMANCE.lock().unwrap_or_else(|e| e.into_inner());
+        if slot.map(|p| p.generation) != Some(generation) {
+            return;
+        }
+        *slot = None;
+    }
+    crate::log_info!("Monitor", "Performance mode timed out");
+    if let Err(e) = apply(app) {
+        crate::log_warn!("Monitor", "Failed to leave performance mode: {}", e);
+    }
+}
+
+/// Bring the sampling intervals and the LHM daemon in line with the mode,
+/// and tell the widget with `monitoring-mode-changed`.
+fn apply(app: &AppHandle) -> Result<MonitoringModeStatus, String> {
+    let update_interval_slow = app.state::<AppState>().settings.lock().update_interval_slow;
+    crate::hardware::set_slow_interval(slow_interval(update_interval_slow));
+
+    let handle = app.clone();
+    let task = crate::metrics::task_started();
+    // Restarting the daemon blocks until it delivers its first sample
+    tauri::async_runtime::spawn_blocking(move || {
+        let _task = task;
+        monitor_pause::reconcile_lhm_interval(&handle)
+    });
+
+    let status = status(app)?;
+    if let Err(e) = app.emit("monitoring-mode-changed", &status) {
+        crate::log_warn!("Monitor", "Failed to emit monitoring-mode-changed: {}", e);
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modes_are_parsed() {
+        assert_eq!(MonitoringMode::parse("normal"), Ok(MonitoringMode::Normal));
+        assert_eq!(
+            MonitoringMode::parse("performance"),
+            Ok(MonitoringMode::Performance)
+        );
+        assert!(MonitoringMode::parse("turbo")
+            .unwrap_err()
+            .contains("turbo"));
+    }
+
+    #[test]
+    fn performance_mode_samples_faster_until_it_runs_out() {
+        let performance = Some(Performance {
+            until_ms: 10_000,
+            generation: 1,
+        });
+        let status = status_at(performance, 1000, 9_999);
+        assert_eq!(status.mode, MonitoringMode::Performance);
+        assert_eq!(status.interval_ms, PERFORMANCE_INTERVAL_MS);
+        assert_eq!(status.until, Some(10_000));
+
+        let status = status_at(performance, 1000, 10_000);
+        assert_eq!(
+            status,
+            MonitoringModeStatus {
+                mode: MonitoringMode::Normal,
+                interval_ms: 1000,
+                until: None,
+            }
+        );
+        assert_eq!(status_at(None, 2000, 0).interval_ms, 2000);
+    }
+
+    #[test]
+    fn status_serializes_in_camel_case() {
+        let status = status_at(
+            Some(Performance {
+                until_ms: 5_000,
+                generation: 1,
+            }),
+            1000,
+            0,
+        );
+        let json = serde_json::to_value(status).unwrap();
+        assert_eq!(json["mode"], "performance");
+        assert_eq!(json["intervalMs"], 250);
+        assert_eq!(json["until"], 5_000);
+    }
+}
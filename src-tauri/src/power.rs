@@ -0,0 +1,418 @@
+//! Whole-system power estimate and daily energy accounting.
+//!
+//! Only CPU package and GPU power are measured. The rest of the platform
+//! (board, RAM, drives, fans, PSU losses) is covered by a configurable base
+//! offset, so the headline number is an estimate unless the user sets the
+//! offset to zero and both components report real readings.
+
+use crate::settings::{self, AppSettings};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Days of history kept in energy.json.
+pub const MAX_DAYS: usize = 366;
+/// How often accumulated energy is written to disk.
+const PERSIST_EVERY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct SystemPowerEstimate {
+    #[serde(rename = "systemPowerEstimateWatts")]
+    pub system_power_estimate_watts: f32,
+    /// False only when every present component had a real reading and the
+    /// base offset is zero.
+    #[serde(rename = "isEstimated")]
+    pub is_estimated: bool,
+}
+
+/// Power reading for one component in a single sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComponentPower {
+    /// Component not present in the sample at all.
+    Absent,
+    /// Component present but without a power sensor.
+    Unmeasured,
+    Measured(f32),
+}
+
+impl ComponentPower {
+    pub fn from_sample(present: bool, watts: Option<f32>) -> Self {
+        match (present, watts) {
+            (false, _) => ComponentPower::Absent,
+            (true, Some(w)) if w.is_finite() && w > 0.0 => ComponentPower::Measured(w),
+            (true, _) => ComponentPower::Unmeasured,
+        }
+    }
+}
+
+/// User-tunable inputs, taken from `AppSettings`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerConfig {
+    pub base_watts: f32,
+    pub cpu_fallback_watts: Option<f32>,
+    pub gpu_fallback_watts: Option<f32>,
+}
+
+impl From<&AppSettings> for PowerConfig {
+    fn from(settings: &AppSettings) -> Self {
+        Self {
+            base_watts: settings.power_base_watts,
+            cpu_fallback_watts: settings.power_cpu_fallback_watts,
+            gpu_fallback_watts: settings.power_gpu_fallback_watts,
+        }
+    }
+}
+
+/// Estimate total system power. Returns `None` unless at least one real
+/// power sensor reported, so the number is never made up from nothing.
+/// Unmeasured components use their configured fallback, or count as zero.
+pub fn estimate_system_power(
+    cpu: ComponentPower,
+    gpu: ComponentPower,
+    config: &PowerConfig,
+) -> Option<SystemPowerEstimate> {
+    let measured = [cpu, gpu]
+        .iter()
+        .any(|c| matches!(c, ComponentPower::Measured(_)));
+    if !measured {
+        return None;
+    }
+
+    let mut is_estimated = config.base_watts > 0.0;
+    let mut total = config.base_watts.max(0.0);
+    for (component, fallback) in [
+        (cpu, config.cpu_fallback_watts),
+        (gpu, config.gpu_fallback_watts),
+    ] {
+        match component {
+            ComponentPower::Absent => {}
+            ComponentPower::Measured(w) => total += w,
+            ComponentPower::Unmeasured => {
+                is_estimated = true;
+                total += fallback.unwrap_or(0.0).max(0.0);
+            }
+        }
+    }
+
+    Some(SystemPowerEstimate {
+        system_power_estimate_watts: total,
+        is_estimated,
+    })
+}
+
+/// Energy between two samples, by the trapezoid rule. Gaps longer than
+/// `max_gap_ms` (sleep, hibernate, app paused) are not integrated across.
+pub fn energy_kwh(prev_watts: f32, watts: f32, elapsed_ms: u64, max_gap_ms: u64) -> f64 {
+    if elapsed_ms == 0 || elapsed_ms > max_gap_ms {
+        return 0.0;
+    }
+    let avg_watts = (f64::from(prev_watts) + f64::from(watts)) / 2.0;
+    avg_watts * elapsed_ms as f64 / 3_600_000.0 / 1000.0
+}
+
+/// Largest sample spacing still treated as continuous, for a given update
+/// interval. Allows for a few slow or skipped polls.
+pub fn max_sample_gap_ms(update_interval_ms: u32) -> u64 {
+    (u64::from(update_interval_ms) * 3).max(5_000)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct EnergyFile {
+    /// Local date (YYYY-MM-DD) -> kWh.
+    days: BTreeMap<String, f64>,
+}
+
+/// Accumulates estimated energy per local day and persists it to
+/// `energy.json` next to settings.json.
+pub struct EnergyTracker {
+    file: EnergyFile,
+    last_sample: Option<(u64, f32)>,
+    last_persist: Instant,
+    dirty: bool,
+    /// Never written to disk; used by the soak test.
+    in_memory: bool,
+}
+
+impl EnergyTracker {
+    pub fn load() -> Self {
+        let file = fs::read_to_string(energy_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self::with_file(file)
+    }
+
+    /// A tracker that starts empty and is never persisted.
+    pub fn in_memory() -> Self {
+        Self {
+            in_memory: true,
+            ..Self::with_file(EnergyFile::default())
+        }
+    }
+
+    fn with_file(file: EnergyFile) -> Self {
+        crate::metrics::ENERGY_DAYS.set(file.days.len());
+        Self {
+            file,
+            last_sample: None,
+            last_persist: Instant::now(),
+            dirty: false,
+            in_memory: false,
+        }
+    }
+
+    /// Add one sample. `watts` is `None` when no estimate was possible,
+    /// which also breaks the integration so the next estimate starts fresh.
+    pub fn record(&mut self, timestamp_ms: u64, date: &str, watts: Option<f32>, max_gap_ms: u64) {
+        let Some(watts) = watts else {
+            self.last_sample = None;
+            return;
+        };
+        if let Some((prev_ts, prev_watts)) = self.last_sample {
+            let kwh = energy_kwh(
+                prev_watts,
+                watts,
+                timestamp_ms.saturating_sub(prev_ts),
+                max_gap_ms,
+            );
+            if kwh > 0.0 {
+                *self.file.days.entry(date.to_string()).or_insert(0.0) += kwh;
+                self.dirty = true;
+            }
+        }
+        self.last_sample = Some((timestamp_ms, watts));
+
+        while self.file.days.len() > MAX_DAYS {
+            self.file.days.pop_first();
+        }
+        crate::metrics::ENERGY_DAYS.set(self.file.days.len());
+        if self.dirty && self.last_persist.elapsed() >= PERSIST_EVERY {
+            self.persist();
+        }
+    }
+
+    pub fn persist(&mut self) {
+        if !self.dirty || self.in_memory {
+            return;
+        }
+        match serde_json::to_string_pretty(&self.file) {
+            Ok(content) => {
+                if let Err(e) = fs::write(energy_path(), content) {
+                    crate::log_warn!("Power", "Failed to save energy stats: {}", e);
+                }
+            }
+            Err(e) => crate::log_warn!("Power", "Failed to serialize energy stats: {}", e),
+        }
+        self.dirty = false;
+        self.last_persist = Instant::now();
+    }
+
+    pub fn report(&self, price_per_kwh: f64) -> EnergyReport {
+        EnergyReport {
+            price_per_kwh,
+            days: self
+                .file
+                .days
+                .iter()
+                .map(|(date, kwh)| EnergyDay {
+                    date: date.clone(),
+                    kwh: *kwh,
+                    cost: kwh * price_per_kwh,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EnergyDay {
+    pub date: String,
+    pub kwh: f64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EnergyReport {
+    pub price_per_kwh: f64,
+    /// Oldest first.
+    pub days: Vec<EnergyDay>,
+}
+
+fn energy_path() -> PathBuf {
+    settings::data_dir().join("energy.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: PowerConfig = PowerConfig {
+        base_watts: 40.0,
+        cpu_fallback_watts: Some(65.0),
+        gpu_fallback_watts: None,
+    };
+
+    #[test]
+    fn no_estimate_without_a_real_sensor() {
+        assert_eq!(
+            estimate_system_power(
+                ComponentPower::Unmeasured,
+                ComponentPower::Unmeasured,
+                &CONFIG
+            ),
+            None
+        );
+        assert_eq!(
+            estimate_system_power(ComponentPower::Absent, ComponentPower::Absent, &CONFIG),
+            None
+        );
+    }
+
+    #[test]
+    fn all_measured_with_zero_base_is_not_estimated() {
+        let config = PowerConfig {
+            base_watts: 0.0,
+            ..CONFIG
+        };
+        let estimate = estimate_system_power(
+            ComponentPower::Measured(80.0),
+            ComponentPower::Measured(200.0),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(estimate.system_power_estimate_watts, 280.0);
+        assert!(!estimate.is_estimated);
+    }
+
+    #[test]
+    fn base_offset_is_added_and_marks_estimate() {
+        let estimate = estimate_system_power(
+            ComponentPower::Measured(80.0),
+            ComponentPower::Measured(200.0),
+            &CONFIG,
+        )
+        .unwrap();
+        assert_eq!(estimate.system_power_estimate_watts, 320.0);
+        assert!(estimate.is_estimated);
+    }
+
+    #[test]
+    fn partial_sensors_use_fallbacks() {
+        // CPU unmeasured -> 65 W fallback
+        let estimate = estimate_system_power(
+            ComponentPower::Unmeasured,
+            ComponentPower::Measured(150.0),
+            &CONFIG,
+        )
+        .unwrap();
+        assert_eq!(estimate.system_power_estimate_watts, 255.0);
+        assert!(estimate.is_estimated);
+
+        // GPU unmeasured without fallback counts as zero, still estimated
+        let config = PowerConfig {
+            base_watts: 0.0,
+            ..CONFIG
+        };
+        let estimate = estimate_system_power(
+            ComponentPower::Measured(90.0),
+            ComponentPower::Unmeasured,
+            &config,
+        )
+        .unwrap();
+        assert_eq!(estimate.system_power_estimate_watts, 90.0);
+        assert!(estimate.is_estimated);
+    }
+
+    #[test]
+    fn absent_gpu_is_not_a_missing_sensor() {
+        let config = PowerConfig {
+            base_watts: 0.0,
+            ..CONFIG
+        };
+        let estimate = estimate_system_power(
+            ComponentPower::Measured(45.0),
+            ComponentPower::Absent,
+            &config,
+        )
+        .unwrap();
+        assert_eq!(estimate.system_power_estimate_watts, 45.0);
+        assert!(!estimate.is_estimated);
+    }
+
+    #[test]
+    fn component_power_ignores_zero_and_invalid_readings() {
+        assert_eq!(
+            ComponentPower::from_sample(false, Some(50.0)),
+            ComponentPower::Absent
+        );
+        assert_eq!(
+            ComponentPower::from_sample(true, None),
+            ComponentPower::Unmeasured
+        );
+        assert_eq!(
+            ComponentPower::from_sample(true, Some(0.0)),
+            ComponentPower::Unmeasured
+        );
+        assert_eq!(
+            ComponentPower::from_sample(true, Some(f32::NAN)),
+            ComponentPower::Unmeasured
+        );
+        assert_eq!(
+            ComponentPower::from_sample(true, Some(12.5)),
+            ComponentPower::Measured(12.5)
+        );
+    }
+
+    #[test]
+    fn energy_uses_trapezoid_and_skips_gaps() {
+        // 1 kW for one hour
+        let kwh = energy_kwh(1000.0, 1000.0, 3_600_000, u64::MAX);
+        assert!((kwh - 1.0).abs() < 1e-9);
+        // Ramp 0 -> 200 W over 30 min averages 100 W -> 0.05 kWh
+        let kwh = energy_kwh(0.0, 200.0, 1_800_000, u64::MAX);
+        assert!((kwh - 0.05).abs() < 1e-9);
+        // Gap longer than allowed contributes nothing
+        assert_eq!(energy_kwh(300.0, 300.0, 60_000, 5_000), 0.0);
+        assert_eq!(energy_kwh(300.0, 300.0, 0, 5_000), 0.0);
+    }
+
+    #[test]
+    fn tracker_accumulates_per_day_and_resets_after_missing_estimate() {
+        let mut tracker = EnergyTracker::with_file(EnergyFile::default());
+        let gap = max_sample_gap_ms(1000);
+
+        tracker.record(0, "2026-01-01", Some(360.0), gap);
+        tracker.record(1_000, "2026-01-01", Some(360.0), gap);
+        tracker.record(2_000, "2026-01-01", Some(360.0), gap);
+        // 360 W for 2 s = 0.0002 kWh
+        let day = tracker.file.days["2026-01-01"];
+        assert!((day - 0.0002).abs() < 1e-12);
+
+        // No estimate breaks the chain: the next sample only starts a new one
+        tracker.record(3_000, "2026-01-01", None, gap);
+        tracker.record(4_000, "2026-01-01", Some(360.0), gap);
+        assert!((tracker.file.days["2026-01-01"] - day).abs() < 1e-12);
+
+        // A gap longer than allowed (machine asleep) adds nothing
+        tracker.record(4_000 + gap + 1, "2026-01-02", Some(360.0), gap);
+        assert!(!tracker.file.days.contains_key("2026-01-02"));
+
+        // Energy after midnight goes to the new day
+        tracker.record(5_000 + gap + 1, "2026-01-02", Some(360.0), gap);
+        assert!(tracker.file.days["2026-01-02"] > 0.0);
+    }
+
+    #[test]
+    fn report_prices_each_day() {
+        let mut file = EnergyFile::default();
+        file.days.insert("2026-01-01".to_string(), 2.0);
+        let report = EnergyTracker::with_file(file).report(0.25);
+        assert_eq!(report.days.len(), 1);
+        assert_eq!(report.days[0].cost, 0.5);
+    }
+}
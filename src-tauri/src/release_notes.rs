@@ -0,0 +1,180 @@
+//! Release notes bundled at build time from CHANGELOG.md, and the
+//! "what's new" notice shown once after an update.
+
+use crate::changelog::{self, ReleaseNote, Version};
+use crate::settings::AppSettings;
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// Generated by build.rs, which already rejected a malformed changelog.
+static RELEASE_NOTES: Lazy<Vec<ReleaseNote>> = Lazy::new(|| {
+    serde_json::from_str(include_str!(concat!(
+        env!("OUT_DIR"),
+        "/release-notes.json"
+    )))
+    .unwrap_or_default()
+});
+
+static CURRENT_VERSION: Lazy<Version> = Lazy::new(|| {
+    Version::parse(env!("CARGO_PKG_VERSION")).expect("package version is valid semver")
+});
+
+/// Emitted with the `ondo-updated` event on the first launch after an
+/// update.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotice {
+    pub previous_version: String,
+    pub version: String,
+    /// Releases after `previous_version`, newest first.
+    pub notes: Vec<ReleaseNote>,
+}
+
+/// Notice waiting for the page to load.
+static PENDING_NOTICE: Mutex<Option<UpdateNotice>> = Mutex::new(None);
+
+/// Bundled notes newer than `since_version` (all of them when `None`),
+/// newest first.
+pub fn notes_since(since_version: Option<&str>) -> Result<Vec<ReleaseNote>, String> {
+    let since = since_version.map(Version::parse).transpose()?;
+    Ok(
+        changelog::notes_between(&RELEASE_NOTES, since.as_ref(), &CURRENT_VERSION)
+            .into_iter()
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Notes for the running version only, for the tray "What's new" entry.
+pub fn current_notes() -> Vec<ReleaseNote> {
+    let current = CURRENT_VERSION.to_string();
+    RELEASE_NOTES
+        .iter()
+        .filter(|note| note.version == current)
+        .cloned()
+        .collect()
+}
+
+/// Compare the last version that ran with this one and record the current
+/// version in `settings`. Returns the notice to show when this is the first
+/// launch after an upgrade; a fresh install or a downgrade shows nothing.
+/// The bool is whether `settings` changed and needs saving.
+fn detect_update(
+    settings: &mut AppSettings,
+    notes: &[ReleaseNote],
+    current: &Version,
+) -> (Option<UpdateNotice>, bool) {
+    let current_text = current.to_string();
+    let previous = settings.last_run_version.replace(current_text.clone());
+    let changed = previous.as_deref() != Some(current_text.as_str());
+
+    let notice = previous.and_then(|previous| {
+        let previous_version = Version::parse(&previous).ok()?;
+        if previous_version >= *current {
+            return None;
+        }
+        Some(UpdateNotice {
+            previous_version: previous,
+            version: current_text,
+            notes: changelog::notes_between(notes, Some(&previous_version), current)
+                .into_iter()
+                .cloned()
+                .collect(),
+        })
+    });
+    (notice, changed)
+}
+
+/// Run once at startup, before the settings are handed to `AppState`.
+/// Returns whether `settings` needs saving.
+pub fn check_for_update(settings: &mut AppSettings) -> bool {
+    let (notice, changed) = detect_update(settings, &RELEASE_NOTES, &CURRENT_VERSION);
+    if let Some(notice) = notice {
+        crate::log_info!(
+            "ReleaseNotes",
+            "Updated from {} to {}",
+            notice.previous_version,
+            notice.version
+        );
+        if let Ok(mut pending) = PENDING_NOTICE.lock() {
+            *pending = Some(notice);
+        }
+    }
+    changed
+}
+
+/// Emit the pending `ondo-updated` event, once, after the UI has loaded and
+/// can listen for it.
+pub fn emit_pending_notice(app: &AppHandle) {
+    let notice = PENDING_NOTICE
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take());
+    if let Some(notice) = notice {
+        if let Err(e) = app.emit("ondo-updated", &notice) {
+            crate::log_warn!("ReleaseNotes", "Failed to emit ondo-updated: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes() -> Vec<ReleaseNote> {
+        changelog::parse_changelog(
+            "## [0.3.0]\n### Added\n- c\n## [0.2.0]\n### Added\n- b\n## [0.1.0]\n### Added\n- a\n",
+        )
+        .unwrap()
+    }
+
+    fn run(last: Option<&str>, current: &str) -> (AppSettings, Option<UpdateNotice>, bool) {
+        let mut settings = AppSettings {
+            last_run_version: last.map(str::to_string),
+            ..AppSettings::default()
+        };
+        let (notice, changed) =
+            detect_update(&mut settings, &notes(), &Version::parse(current).unwrap());
+        (settings, notice, changed)
+    }
+
+    #[test]
+    fn upgrade_carries_the_skipped_releases() {
+        let (settings, notice, changed) = run(Some("0.1.0"), "0.3.0");
+        let notice = notice.unwrap();
+        assert_eq!(notice.previous_version, "0.1.0");
+        assert_eq!(notice.version, "0.3.0");
+        let versions: Vec<_> = notice.notes.iter().map(|n| n.version.as_str()).collect();
+        assert_eq!(versions, vec!["0.3.0", "0.2.0"]);
+        assert!(changed);
+        assert_eq!(settings.last_run_version.as_deref(), Some("0.3.0"));
+    }
+
+    #[test]
+    fn fresh_install_only_records_the_version() {
+        let (settings, notice, changed) = run(None, "0.3.0");
+        assert!(notice.is_none());
+        assert!(changed);
+        assert_eq!(settings.last_run_version.as_deref(), Some("0.3.0"));
+    }
+
+    #[test]
+    fn same_version_or_downgrade_shows_nothing() {
+        assert_eq!(run(Some("0.3.0"), "0.3.0").1, None);
+        assert!(!run(Some("0.3.0"), "0.3.0").2);
+        assert_eq!(run(Some("0.3.0"), "0.2.0").1, None);
+        assert_eq!(run(Some("garbage"), "0.2.0").1, None);
+    }
+
+    #[test]
+    fn bundled_notes_parse() {
+        // Empty in dev builds while the next release is under [Unreleased].
+        let current = CURRENT_VERSION.to_string();
+        assert!(current_notes().iter().all(|note| note.version == current));
+        assert!(notes_since(Some("not a version")).is_err());
+        assert_eq!(notes_since(None).unwrap(), *RELEASE_NOTES);
+    }
+}
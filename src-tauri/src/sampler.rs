@@ -0,0 +1,75 @@
+//! Background sampling: a task started in `setup` reads the hardware every
+//! `update_interval_fast` (or the performance mode interval, see
+//! `monitoring_mode`), whether or not the widget is open, so statistics,
+//! alerts, the tray tooltip and the WebSocket feed keep advancing. Each
+//! sample is emitted as `hardware-update`, and a failed read as
+//! `hardware-error` with the message; `get_hardware_data` remains for
+//! one-off reads.
+//!
+//! While sampling is paused (see `monitor_pause`) a tick reads the hardware
+//! only once the last sample has gone stale, and emits nothing otherwise.
+
+use crate::commands::hardware::take_sample;
+use crate::{monitor_pause, monitoring_mode, now_millis, AppState};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How long to wait before the next tick, given that the last one took
+/// `elapsed`. A slow read delays the next one rather than stacking up.
+fn next_delay(interval_ms: u32, elapsed: Duration) -> Duration {
+    Duration::from_millis(u64::from(interval_ms)).saturating_sub(elapsed)
+}
+
+/// The interval for the current settings and monitoring mode; read on
+/// every tick, so changes apply from the next one.
+fn interval_ms(app: &AppHandle) -> u32 {
+    let update_interval = app.state::<AppState>().settings.lock().update_interval_fast;
+    monitoring_mode::fast_interval(update_interval)
+}
+
+async fn tick(app: &AppHandle) {
+    if monitor_pause::cached_sample(now_millis()).is_some() {
+        return;
+    }
+    match take_sample(app).await {
+        Ok(data) => {
+            if let Err(e) = app.emit("hardware-update", &data) {
+                crate::log_warn!("Sampler", "Failed to emit hardware-update: {}", e);
+            }
+        }
+        // Already counted and reported by `collect_sample`
+        Err(e) => {
+            let _ = app.emit("hardware-error", &e);
+        }
+    }
+}
+
+/// Start sampling in the background for the rest of the session.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        crate::log_info!("Sampler", "Started");
+        loop {
+            let started = Instant::now();
+            tick(&app).await;
+            tokio::time::sleep(next_delay(interval_ms(&app), started.elapsed())).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_keep_to_the_interval_without_stacking_up() {
+        assert_eq!(
+            next_delay(1000, Duration::from_millis(200)),
+            Duration::from_millis(800)
+        );
+        assert_eq!(
+            next_delay(1000, Duration::from_millis(1500)),
+            Duration::ZERO
+        );
+        assert_eq!(next_delay(250, Duration::ZERO), Duration::from_millis(250));
+    }
+}
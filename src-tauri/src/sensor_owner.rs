@@ -0,0 +1,255 @@
+//! Decides which Ondo instance may talk to the sensors.
+//!
+//! With fast user switching every signed-in session runs its own Ondo and
+//! its own `ondo-hwmon.exe`, and two daemons polling SMBus at the same time
+//! corrupt each other's readings. A global named mutex elects one "owner";
+//! every other instance runs as a "viewer" that never spawns the daemon.
+//!
+//! The mutex is held by a dedicated thread for the lifetime of the process.
+//! When the owning session logs off, Windows marks the mutex abandoned and
+//! the next viewer poll takes over.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SensorRole {
+    /// This instance runs the LHM daemon.
+    Owner,
+    /// Another session owns the sensors; this instance shows limited data.
+    Viewer,
+}
+
+/// Result of a non-blocking attempt to take the owner lock.
+#[cfg(any(target_os = "windows", test))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcquireOutcome {
+    Acquired,
+    /// The previous owner exited without releasing (session logged off).
+    Abandoned,
+    HeldElsewhere,
+}
+
+/// The cross-session lock primitive. Implemented by a Win32 named mutex in
+/// production and by a scripted fake in tests.
+#[cfg(any(target_os = "windows", test))]
+pub trait OwnerLock {
+    fn try_acquire(&mut self) -> AcquireOutcome;
+}
+
+#[cfg(any(target_os = "windows", test))]
+pub struct Ownership<L: OwnerLock> {
+    lock: L,
+    role: SensorRole,
+}
+
+#[cfg(any(target_os = "windows", test))]
+impl<L: OwnerLock> Ownership<L> {
+    pub fn new(lock: L) -> Self {
+        let mut ownership = Self {
+            lock,
+            role: SensorRole::Viewer,
+        };
+        ownership.poll();
+        ownership
+    }
+
+    pub fn role(&self) -> SensorRole {
+        self.role
+    }
+
+    /// Try to become owner. Ownership is never given up voluntarily, so once
+    /// owner this no longer touches the lock.
+    pub fn poll(&mut self) -> SensorRole {
+        if self.role == SensorRole::Owner {
+            return self.role;
+        }
+        match self.lock.try_acquire() {
+            AcquireOutcome::Acquired => {
+                self.role = SensorRole::Owner;
+            }
+            AcquireOutcome::Abandoned => {
+                crate::log_info!(
+                    "SensorOwner",
+                    "Previous sensor owner went away, taking over"
+                );
+                self.role = SensorRole::Owner;
+            }
+            AcquireOutcome::HeldElsewhere => {}
+        }
+        self.role
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{AcquireOutcome, OwnerLock, Ownership, SensorRole};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+    use windows::core::w;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE, WAIT_ABANDONED, WAIT_OBJECT_0};
+    use windows::Win32::System::Threading::{CreateMutexW, WaitForSingleObject};
+
+    /// `Global\` makes the mutex visible across sessions.
+    const MUTEX_NAME: windows::core::PCWSTR = w!("Global\\OndoSensorOwner");
+    const VIEWER_POLL: Duration = Duration::from_secs(2);
+
+    static VIEWER: AtomicBool = AtomicBool::new(false);
+
+    struct NamedMutex(HANDLE);
+
+    // The handle is only used from the owner thread.
+    unsafe impl Send for NamedMutex {}
+
+    impl OwnerLock for NamedMutex {
+        fn try_acquire(&mut self) -> AcquireOutcome {
+            let result = unsafe { WaitForSingleObject(self.0, 0) };
+            if result == WAIT_OBJECT_0 {
+                AcquireOutcome::Acquired
+            } else if result == WAIT_ABANDONED {
+                AcquireOutcome::Abandoned
+            } else {
+                AcquireOutcome::HeldElsewhere
+            }
+        }
+    }
+
+    impl Drop for NamedMutex {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = CloseHandle(self.0);
+            }
+        }
+    }
+
+    pub fn role() -> SensorRole {
+        if VIEWER.load(Ordering::SeqCst) {
+            SensorRole::Viewer
+        } else {
+            SensorRole::Owner
+        }
+    }
+
+    /// Elect the sensor owner. Blocks until the initial role is known so the
+    /// first hardware poll already respects it.
+    pub fn start() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("sensor-owner".to_string())
+            .spawn(move || {
+                let mutex = match unsafe { CreateMutexW(None, false, MUTEX_NAME) } {
+                    Ok(handle) => NamedMutex(handle),
+                    Err(e) => {
+                        // Can't coordinate; behave as a standalone instance
+                        crate::log_warn!("SensorOwner", "Failed to create owner mutex: {}", e);
+                        let _ = tx.send(());
+                        return;
+                    }
+                };
+
+                let mut ownership = Ownership::new(mutex);
+                VIEWER.store(ownership.role() == SensorRole::Viewer, Ordering::SeqCst);
+                let _ = tx.send(());
+
+                if ownership.role() == SensorRole::Viewer {
+                    crate::log_warn!(
+                        "SensorOwner",
+                        "Sensors are owned by Ondo in another session, running as viewer"
+                    );
+                    while ownership.poll() == SensorRole::Viewer {
+                        std::thread::sleep(VIEWER_POLL);
+                    }
+                    VIEWER.store(false, Ordering::SeqCst);
+                }
+
+                crate::log_info!("SensorOwner", "This instance owns the sensors");
+                // A mutex is owned by a thread, not a process. Keep this one
+                // alive so ownership lasts until the process exits.
+                loop {
+                    std::thread::park();
+                }
+            });
+
+        match spawned {
+            Ok(_) => {
+                let _ = rx.recv_timeout(Duration::from_secs(5));
+            }
+            Err(e) => {
+                crate::log_warn!("SensorOwner", "Failed to spawn owner thread: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::SensorRole;
+
+    // No daemon to share on other platforms
+    pub fn role() -> SensorRole {
+        SensorRole::Owner
+    }
+
+    pub fn start() {}
+}
+
+pub use platform::{role, start};
+
+#[cfg(test)]
+mod tests {
+    use super::{AcquireOutcome, OwnerLock, Ownership, SensorRole};
+    use std::collections::VecDeque;
+
+    /// Returns scripted outcomes; panics if polled more than expected.
+    struct ScriptedLock(VecDeque<AcquireOutcome>);
+
+    impl OwnerLock for ScriptedLock {
+        fn try_acquire(&mut self) -> AcquireOutcome {
+            self.0.pop_front().expect("lock polled after script ended")
+        }
+    }
+
+    fn ownership(script: &[AcquireOutcome]) -> Ownership<ScriptedLock> {
+        Ownership::new(ScriptedLock(script.iter().copied().collect()))
+    }
+
+    #[test]
+    fn first_instance_becomes_owner() {
+        let owner = ownership(&[AcquireOutcome::Acquired]);
+        assert_eq!(owner.role(), SensorRole::Owner);
+    }
+
+    #[test]
+    fn second_instance_stays_viewer_while_owner_is_alive() {
+        let mut viewer = ownership(&[
+            AcquireOutcome::HeldElsewhere,
+            AcquireOutcome::HeldElsewhere,
+            AcquireOutcome::HeldElsewhere,
+        ]);
+        assert_eq!(viewer.role(), SensorRole::Viewer);
+        assert_eq!(viewer.poll(), SensorRole::Viewer);
+        assert_eq!(viewer.poll(), SensorRole::Viewer);
+    }
+
+    #[test]
+    fn viewer_takes_over_abandoned_mutex() {
+        let mut viewer = ownership(&[AcquireOutcome::HeldElsewhere, AcquireOutcome::Abandoned]);
+        assert_eq!(viewer.role(), SensorRole::Viewer);
+        assert_eq!(viewer.poll(), SensorRole::Owner);
+    }
+
+    #[test]
+    fn viewer_takes_over_released_mutex() {
+        let mut viewer = ownership(&[AcquireOutcome::HeldElsewhere, AcquireOutcome::Acquired]);
+        assert_eq!(viewer.poll(), SensorRole::Owner);
+    }
+
+    #[test]
+    fn owner_does_not_poll_the_lock_again() {
+        // Script has a single entry; a second try_acquire would panic
+        let mut owner = ownership(&[AcquireOutcome::Acquired]);
+        assert_eq!(owner.poll(), SensorRole::Owner);
+        assert_eq!(owner.poll(), SensorRole::Owner);
+    }
+}
@@ -0,0 +1,239 @@
+//! Per-sensor min/max/average since the app started (or since the last
+//! reset), so peaks during a gaming session can be read back afterwards.
+
+use crate::HardwareData;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorStats {
+    pub min: f32,
+    pub max: f32,
+    /// Running mean over all counted samples.
+    pub avg: f64,
+    pub sample_count: u64,
+    /// Sample timestamp (ms since epoch) when `max` was reached.
+    pub max_at: u64,
+}
+
+impl SensorStats {
+    fn new(value: f32, timestamp: u64) -> Self {
+        Self {
+            min: value,
+            max: value,
+            avg: f64::from(value),
+            sample_count: 1,
+            max_at: timestamp,
+        }
+    }
+
+    fn add(&mut self, value: f32, timestamp: u64) {
+        self.sample_count += 1;
+        self.avg += (f64::from(value) - self.avg) / self.sample_count as f64;
+        self.min = self.min.min(value);
+        if value > self.max {
+            self.max = value;
+            self.max_at = timestamp;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStatsSnapshot {
+    /// When the session began (app start or last reset), ms since epoch.
+    pub started_at: u64,
+    /// Keyed by sensor, e.g. `cpu.temperature` or `storage.<name>.temperature`.
+    /// A second drive of the same model is `storage.<name> #2.temperature`.
+    pub sensors: BTreeMap<String, SensorStats>,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionStats {
+    started_at: u64,
+    sensors: BTreeMap<String, SensorStats>,
+}
+
+impl SessionStats {
+    pub fn new(started_at: u64) -> Self {
+        Self {
+            started_at,
+            sensors: BTreeMap::new(),
+        }
+    }
+
+    /// Add one reading. Exactly 0.0 means "no reading" throughout the
+    /// hardware layer, so it is skipped rather than dragging min/avg down.
+    pub fn record(&mut self, key: &str, value: f32, timestamp: u64) {
+        if value == 0.0 || !value.is_finite() {
+            return;
+        }
+        match self.sensors.get_mut(key) {
+            Some(stats) => stats.add(value, timestamp),
+            None => {
+                self.sensors
+                    .insert(key.to_string(), SensorStats::new(value, timestamp));
+                crate::metrics::SESSION_SENSORS.set(self.sensors.len());
+            }
+        }
+    }
+
+    pub fn record_sample(&mut self, data: &HardwareData) {
+        let ts = data.timestamp;
+        if let Some(cpu) = &data.cpu {
+            self.record("cpu.temperature", cpu.temperature, ts);
+            self.record("cpu.load", cpu.load, ts);
+        }
+        if let Some(gpu) = &data.gpu {
+            self.record("gpu.temperature", gpu.temperature, ts);
+            self.record("gpu.load", gpu.load, ts);
+            self.record("gpu.memoryUsed", gpu.memory_used, ts);
+        }
+        let storage = data.storage.as_deref().unwrap_or_default();
+        for (i, drive) in storage.iter().enumerate() {
+            // Samples are normalized, so drives of the same model keep their
+            // position among each other from one sample to the next
+            let nth = storage[..i].iter().filter(|d| d.name == drive.name).count();
+            let key = if nth == 0 {
+                format!("storage.{}.temperature", drive.name)
+            } else {
+                format!("storage.{} #{}.temperature", drive.name, nth + 1)
+            };
+            self.record(&key, drive.temperature, ts);
+        }
+    }
+
+    pub fn snapshot(&self) -> SessionStatsSnapshot {
+        SessionStatsSnapshot {
+            started_at: self.started_at,
+            sensors: self.sensors.clone(),
+        }
+    }
+
+    pub fn reset(&mut self, now: u64) {
+        self.started_at = now;
+        self.sensors.clear();
+        crate::metrics::SESSION_SENSORS.set(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SessionStats;
+    use crate::{CpuData, HardwareData, StorageData};
+
+    #[test]
+    fn tracks_min_max_and_running_mean() {
+        let mut stats = SessionStats::new(0);
+        for (ts, value) in [(1, 50.0), (2, 70.0), (3, 60.0), (4, 40.0)] {
+            stats.record("cpu.temperature", value, ts);
+        }
+
+        let cpu = &stats.snapshot().sensors["cpu.temperature"];
+        assert_eq!(cpu.min, 40.0);
+        assert_eq!(cpu.max, 70.0);
+        assert_eq!(cpu.max_at, 2);
+        assert_eq!(cpu.sample_count, 4);
+        assert!((cpu.avg - 55.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zero_readings_are_excluded() {
+        let mut stats = SessionStats::new(0);
+        stats.record("gpu.temperature", 0.0, 1);
+        assert!(stats.snapshot().sensors.is_empty());
+
+        stats.record("gpu.temperature", 45.0, 2);
+        stats.record("gpu.temperature", 0.0, 3);
+        stats.record("gpu.temperature", f32::NAN, 4);
+        let gpu = &stats.snapshot().sensors["gpu.temperature"];
+        assert_eq!(gpu.min, 45.0);
+        assert_eq!(gpu.sample_count, 1);
+    }
+
+    #[test]
+    fn records_cpu_and_each_storage_device() {
+        let data = HardwareData {
+            cpu: Some(CpuData {
+                name: "CPU".to_string(),
+                temperature: 65.0,
+                max_temperature: 100.0,
+                load: 20.0,
+                frequency: 4.0,
+                cores: Vec::new(),
+                power: None,
+                voltage: None,
+                is_throttling: false,
+                base_frequency: 0.0,
+                max_frequency: 0.0,
+                packages: None,
+                status: Default::default(),
+            }),
+            storage: Some(vec![
+                StorageData {
+                    name: "NVMe A".to_string(),
+                    temperature: 41.0,
+                    used_space: 1.0,
+                    used_percent: 50.0,
+                    total_space: 2.0,
+                    health_percent: None,
+                    data_written_tb: None,
+                    read_rate: None,
+                    write_rate: None,
+                    partitions: None,
+                    status: Default::default(),
+                },
+                StorageData {
+                    name: "NVMe B".to_string(),
+                    temperature: 0.0,
+                    used_space: 1.0,
+                    used_percent: 50.0,
+                    total_space: 2.0,
+                    health_percent: None,
+                    data_written_tb: None,
+                    read_rate: None,
+                    write_rate: None,
+                    partitions: None,
+                    status: Default::default(),
+                },
+            ]),
+            timestamp: 10,
+            ..Default::default()
+        };
+
+        let mut stats = SessionStats::new(0);
+        stats.record_sample(&data);
+        let keys: Vec<_> = stats.snapshot().sensors.into_keys().collect();
+        assert_eq!(
+            keys,
+            vec!["cpu.load", "cpu.temperature", "storage.NVMe A.temperature"]
+        );
+    }
+
+    #[test]
+    fn identical_drives_are_tracked_separately() {
+        let mut data = crate::mock::MockHardware::new(0, 1000).sample_at(0);
+        let storage = data.storage.as_mut().unwrap();
+        storage[1].name = storage[0].name.clone();
+        storage[0].temperature = 40.0;
+        storage[1].temperature = 30.0;
+
+        let mut stats = SessionStats::new(0);
+        stats.record_sample(&data);
+        let sensors = stats.snapshot().sensors;
+        assert_eq!(sensors["storage.Mock NVMe SSD.temperature"].max, 40.0);
+        assert_eq!(sensors["storage.Mock NVMe SSD #2.temperature"].max, 30.0);
+    }
+
+    #[test]
+    fn reset_clears_and_restarts_session() {
+        let mut stats = SessionStats::new(0);
+        stats.record("cpu.load", 10.0, 1);
+        stats.reset(99);
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.started_at, 99);
+        assert!(snapshot.sensors.is_empty());
+    }
+}
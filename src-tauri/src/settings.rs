@@ -1,14 +1,191 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowState {
     pub x: i32,
     pub y: i32,
     pub width: u32,
     pub height: u32,
+    #[serde(default)]
+    pub monitor_name: Option<String>,
+}
+
+/// How one device is shown, see `AppSettings::sensor_overrides`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SensorOverride {
+    /// Drop the device from samples, statistics and the WebSocket feed.
+    pub hidden: bool,
+    /// Name to show instead of the one the sensors report.
+    pub alias: Option<String>,
+}
+
+impl SensorOverride {
+    fn is_noop(&self) -> bool {
+        !self.hidden && self.alias.is_none()
+    }
+}
+
+/// When temperature alerts stay silent, see `alerts`. Times are local
+/// "HH:MM"; a window ending before it starts runs past midnight.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuietHours {
+    pub enabled: bool,
+    pub start: String,
+    pub end: String,
+    /// Critical alerts still notify during quiet hours.
+    pub allow_critical: bool,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+            allow_critical: true,
+        }
+    }
+}
+
+/// Warning and critical levels for one kind of device, see
+/// `hardware::section_status`. A temperature left unset follows the
+/// device's `maxTemperature`; a load left unset is never flagged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ComponentThresholds {
+    /// °C.
+    pub warning_temperature: Option<f32>,
+    pub critical_temperature: Option<f32>,
+    /// %.
+    pub warning_load: Option<f32>,
+    pub critical_load: Option<f32>,
+}
+
+impl ComponentThresholds {
+    fn sanitize(&mut self) {
+        let clamp = |value: Option<f32>, max: f32| {
+            value.filter(|v| v.is_finite()).map(|v| v.clamp(0.0, max))
+        };
+        self.warning_temperature = clamp(self.warning_temperature, 150.0);
+        self.critical_temperature = clamp(self.critical_temperature, 150.0);
+        self.warning_load = clamp(self.warning_load, 100.0);
+        self.critical_load = clamp(self.critical_load, 100.0);
+        // Critical is never below warning
+        if let (Some(warning), Some(critical)) =
+            (self.warning_temperature, self.critical_temperature)
+        {
+            self.critical_temperature = Some(critical.max(warning));
+        }
+        if let (Some(warning), Some(critical)) = (self.warning_load, self.critical_load) {
+            self.critical_load = Some(critical.max(warning));
+        }
+    }
+}
+
+/// When each section turns warning or critical. CPU and GPU temperatures
+/// follow their `maxTemperature` until customized; drives and the board
+/// report none, so theirs are fixed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Thresholds {
+    pub cpu: ComponentThresholds,
+    pub gpu: ComponentThresholds,
+    pub storage: ComponentThresholds,
+    pub motherboard: ComponentThresholds,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        let busy = ComponentThresholds {
+            warning_load: Some(85.0),
+            critical_load: Some(95.0),
+            ..ComponentThresholds::default()
+        };
+        let fixed = |warning: f32, critical: f32| ComponentThresholds {
+            warning_temperature: Some(warning),
+            critical_temperature: Some(critical),
+            ..ComponentThresholds::default()
+        };
+        Self {
+            cpu: busy,
+            gpu: busy,
+            // NVMe drives run warm and throttle around 80°C
+            storage: fixed(70.0, 80.0),
+            motherboard: fixed(70.0, 85.0),
+        }
+    }
+}
+
+/// Pushing samples to InfluxDB 2.x, see `influxdb`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InfluxDbSettings {
+    pub enabled: bool,
+    /// Server root, e.g. `http://localhost:8086`.
+    pub url: String,
+    pub org: String,
+    pub bucket: String,
+    /// API token with write access to the bucket.
+    pub token: Option<String>,
+    /// Seconds between writes.
+    pub flush_interval_secs: u32,
+    /// Points that trigger a write before the interval is up.
+    pub batch_size: u32,
+}
+
+impl Default for InfluxDbSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "http://localhost:8086".to_string(),
+            org: String::new(),
+            bucket: "ondo".to_string(),
+            token: None,
+            flush_interval_secs: 10,
+            batch_size: 500,
+        }
+    }
+}
+
+impl InfluxDbSettings {
+    pub fn sanitize(&mut self) {
+        for value in [&mut self.url, &mut self.org, &mut self.bucket] {
+            *value = value.trim().to_string();
+        }
+        self.url = self.url.trim_end_matches('/').to_string();
+        self.token = self
+            .token
+            .take()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+        self.flush_interval_secs = self.flush_interval_secs.clamp(1, 3600);
+        self.batch_size = self.batch_size.clamp(1, 5000);
+    }
+}
+
+/// Minutes since midnight of a "HH:MM" time.
+pub fn parse_clock_time(time: &str) -> Option<u16> {
+    let (hours, minutes) = time.split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let (hours, minutes): (u16, u16) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+impl QuietHours {
+    /// Start and end in minutes since midnight.
+    pub fn window(&self) -> Option<(u16, u16)> {
+        Some((parse_clock_time(&self.start)?, parse_clock_time(&self.end)?))
+    }
 }
 
 // `#[serde(default)]` at the struct level makes every missing field fall
@@ -17,41 +194,295 @@ pub struct WindowState {
 // `default = "..."` overrides are kept where the helper value diverges from
 // what `Default::default()` alone would produce (it doesn't here, but the
 // explicit form documents intent).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase", default)]
 pub struct AppSettings {
     pub position: String,
+    /// Gap in pixels between the widget and the screen edges it is docked
+    /// to.
+    pub position_margin: u32,
+    /// How close (in pixels) a dragged widget has to come to a screen edge
+    /// to snap to it. 0 turns snapping off.
+    pub snap_threshold: u32,
     pub opacity: u32,
     pub always_on_top: bool,
     pub always_on_back: bool,
+    /// Let mouse input pass through the widget to the window below. Only
+    /// changed via `set_click_through` or the tray, never by `save_settings`.
+    pub click_through: bool,
     pub auto_start: bool,
-    pub update_interval: u32,
+    /// The window close button hides the widget to the tray instead of
+    /// quitting.
+    pub close_to_tray: bool,
+    /// Sampling interval for CPU and GPU, and how often the UI polls.
+    /// Stored as `updateInterval` before the slow interval existed.
+    #[serde(alias = "updateInterval")]
+    pub update_interval_fast: u32,
+    /// Sampling interval for storage and the motherboard, whose readings
+    /// change slowly. Drives are left alone in between, so they can spin
+    /// down. Never faster than `update_interval_fast`.
+    pub update_interval_slow: u32,
+    /// Smoothing of the CPU and GPU temperature, load and clock between
+    /// samples: 0 shows raw readings, 0.9 smooths heavily. See `smoothing`.
+    pub smoothing_factor: f32,
+    /// How long performance mode lasts before sampling goes back to
+    /// normal, see `monitoring_mode`.
+    pub performance_mode_minutes: u32,
+    /// Device sections to sample. Disabled ones are not read at all and are
+    /// missing from samples.
+    pub monitor_cpu: bool,
+    pub monitor_gpu: bool,
+    pub monitor_storage: bool,
+    pub monitor_motherboard: bool,
+    /// Serve synthetic samples instead of reading the sensors, for UI work.
+    /// `ONDO_MOCK=1` does the same without changing the setting.
+    pub mock_data: bool,
+    /// Sample only every 30s while the widget is hidden to the tray.
+    pub pause_when_hidden: bool,
+    /// "Pause monitoring" in the tray is on. Only changed via
+    /// `set_monitoring_paused` or the tray, never by `save_settings`.
+    pub monitoring_paused: bool,
+    /// Start paused if monitoring was paused when Ondo last exited.
+    pub remember_monitoring_pause: bool,
     pub theme: String,
     pub temperature_unit: String,
     pub compact_mode: bool,
+    /// Replace the tray logo with the current CPU temperature.
+    pub tray_show_temperature: bool,
     pub debug_server: bool,
+    /// Broadcast samples over a local WebSocket, see `ws_server`.
+    pub websocket_server: bool,
+    pub websocket_port: u16,
+    /// Listen on all interfaces instead of loopback only. Requires a token.
+    pub websocket_allow_lan: bool,
+    /// Required from WebSocket clients when set.
+    pub websocket_token: Option<String>,
+    pub influxdb: InfluxDbSettings,
+    /// Global shortcut that shows/hides the widget, e.g. `Ctrl+Shift+O`.
+    pub toggle_hotkey: Option<String>,
+    /// Global shortcut that opens the settings panel.
+    pub settings_hotkey: Option<String>,
+    /// Notify when the CPU or GPU gets close to its maximum temperature.
+    pub alerts_enabled: bool,
+    pub quiet_hours: QuietHours,
+    /// Warning and critical levels behind each section's `status` and the
+    /// temperature alerts.
+    pub thresholds: Thresholds,
+    /// Opt-in for Sentry error reporting. Off unless the user enables it.
+    pub telemetry_enabled: bool,
+    /// Report to this DSN instead of the `SENTRY_DSN` environment variable
+    /// or the build's default.
+    pub sentry_dsn: Option<String>,
+    /// Watts added to CPU + GPU power for the rest of the platform in the
+    /// system power estimate.
+    pub power_base_watts: f32,
+    /// Assumed CPU power when the CPU has no power sensor.
+    pub power_cpu_fallback_watts: Option<f32>,
+    /// Assumed GPU power when the GPU has no power sensor.
+    pub power_gpu_fallback_watts: Option<f32>,
+    /// Used to show the cost of the estimated daily energy use.
+    pub electricity_price_per_kwh: f64,
     pub section_order: Vec<String>,
     pub hidden_sections: Vec<String>,
+    /// Hidden or renamed devices, keyed by the name the sensors report (as
+    /// listed by `list_detected_sensors`).
+    pub sensor_overrides: BTreeMap<String, SensorOverride>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub window_state: Option<WindowState>,
+    /// Version that last ran, to show release notes after an update. Set by
+    /// the backend at startup; never taken from `save_settings`.
+    pub last_run_version: Option<String>,
+    /// Release channel to update from: "stable" or "beta".
+    pub update_channel: String,
+    /// Version the user chose not to be reminded about.
+    pub skip_version: Option<String>,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             position: "right".to_string(),
+            position_margin: 0,
+            snap_threshold: 20,
             opacity: 95,
             always_on_top: false,
             always_on_back: false,
+            click_through: false,
             auto_start: false,
-            update_interval: 1000,
+            close_to_tray: true,
+            update_interval_fast: 1000,
+            update_interval_slow: 5000,
+            smoothing_factor: 0.0,
+            performance_mode_minutes: 30,
+            monitor_cpu: true,
+            monitor_gpu: true,
+            monitor_storage: true,
+            monitor_motherboard: true,
+            mock_data: false,
+            pause_when_hidden: true,
+            monitoring_paused: false,
+            remember_monitoring_pause: false,
             theme: "auto".to_string(),
             temperature_unit: "celsius".to_string(),
             compact_mode: false,
+            tray_show_temperature: false,
             debug_server: false,
+            websocket_server: false,
+            websocket_port: crate::ws_server::DEFAULT_PORT,
+            websocket_allow_lan: false,
+            websocket_token: None,
+            influxdb: InfluxDbSettings::default(),
+            toggle_hotkey: None,
+            settings_hotkey: None,
+            alerts_enabled: true,
+            quiet_hours: QuietHours::default(),
+            thresholds: Thresholds::default(),
+            telemetry_enabled: false,
+            sentry_dsn: None,
+            power_base_watts: 40.0,
+            power_cpu_fallback_watts: None,
+            power_gpu_fallback_watts: None,
+            electricity_price_per_kwh: 0.0,
             section_order: default_section_order(),
             hidden_sections: Vec::new(),
+            sensor_overrides: BTreeMap::new(),
             window_state: None,
+            last_run_version: None,
+            update_channel: "stable".to_string(),
+            skip_version: None,
+        }
+    }
+}
+
+/// The live settings in `AppState`. A panic while they are locked would
+/// poison a plain `Mutex` and fail every later read and save until a
+/// restart; here the lock is taken back with the settings as they were,
+/// and the recovery is reported once.
+#[derive(Debug, Default)]
+pub struct SharedSettings(Mutex<AppSettings>);
+
+impl SharedSettings {
+    pub fn new(settings: AppSettings) -> Self {
+        Self(Mutex::new(settings))
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, AppSettings> {
+        self.0.lock().unwrap_or_else(|poisoned| {
+            self.0.clear_poison();
+            let message = format!(
+                "Settings lock recovered after a panic: {}",
+                crate::error_reporting::last_panic().unwrap_or_else(|| "unknown".to_string())
+            );
+            crate::log_warn!("Settings", "{}", message);
+            crate::error_reporting::capture_settings_error(&message, "lock_poisoned");
+            poisoned.into_inner()
+        })
+    }
+}
+
+/// Bounds for the update intervals. Anything faster than 250ms pegs a core in the
+/// LHM helper; anything slower than a minute makes the widget look frozen.
+pub const MIN_UPDATE_INTERVAL_MS: u32 = 250;
+pub const MAX_UPDATE_INTERVAL_MS: u32 = 60_000;
+
+/// Longest performance mode, so a forgotten one doesn't keep sampling at
+/// full speed all day.
+pub const MAX_PERFORMANCE_MODE_MINUTES: u32 = 240;
+
+/// Larger snap distances would make the widget jump across the screen.
+const MAX_SNAP_THRESHOLD: u32 = 100;
+
+/// Upper bound for any configured wattage, to keep a typo from producing
+/// absurd energy totals.
+const MAX_CONFIGURED_WATTS: f32 = 2_000.0;
+
+fn sanitize_watts(watts: f32) -> f32 {
+    if watts.is_finite() {
+        watts.clamp(0.0, MAX_CONFIGURED_WATTS)
+    } else {
+        0.0
+    }
+}
+
+impl AppSettings {
+    pub fn monitored_sections(&self) -> crate::hardware::MonitoredSections {
+        crate::hardware::MonitoredSections {
+            cpu: self.monitor_cpu,
+            gpu: self.monitor_gpu,
+            storage: self.monitor_storage,
+            motherboard: self.monitor_motherboard,
+        }
+    }
+
+    /// Clamp values that would otherwise hurt the system or the UI. Applied to
+    /// everything coming from the frontend and from settings.json.
+    pub fn sanitize(&mut self) {
+        self.update_interval_fast = self
+            .update_interval_fast
+            .clamp(MIN_UPDATE_INTERVAL_MS, MAX_UPDATE_INTERVAL_MS);
+        self.update_interval_slow = self
+            .update_interval_slow
+            .clamp(self.update_interval_fast, MAX_UPDATE_INTERVAL_MS);
+        self.smoothing_factor = if self.smoothing_factor.is_finite() {
+            self.smoothing_factor
+                .clamp(0.0, crate::smoothing::MAX_SMOOTHING_FACTOR)
+        } else {
+            0.0
+        };
+        self.performance_mode_minutes = self
+            .performance_mode_minutes
+            .clamp(1, MAX_PERFORMANCE_MODE_MINUTES);
+        self.snap_threshold = self.snap_threshold.min(MAX_SNAP_THRESHOLD);
+        self.power_base_watts = sanitize_watts(self.power_base_watts);
+        self.power_cpu_fallback_watts = self.power_cpu_fallback_watts.map(sanitize_watts);
+        self.power_gpu_fallback_watts = self.power_gpu_fallback_watts.map(sanitize_watts);
+        if !self.electricity_price_per_kwh.is_finite() || self.electricity_price_per_kwh < 0.0 {
+            self.electricity_price_per_kwh = 0.0;
+        }
+        for time in [&mut self.quiet_hours.start, &mut self.quiet_hours.end] {
+            *time = time.trim().to_string();
+        }
+        if self.quiet_hours.window().is_none() {
+            self.quiet_hours = QuietHours {
+                enabled: self.quiet_hours.enabled,
+                allow_critical: self.quiet_hours.allow_critical,
+                ..QuietHours::default()
+            };
+        }
+        for thresholds in [
+            &mut self.thresholds.cpu,
+            &mut self.thresholds.gpu,
+            &mut self.thresholds.storage,
+            &mut self.thresholds.motherboard,
+        ] {
+            thresholds.sanitize();
+        }
+        self.influxdb.sanitize();
+        if self.websocket_port == 0 {
+            self.websocket_port = crate::ws_server::DEFAULT_PORT;
+        }
+        for hotkey in [
+            &mut self.toggle_hotkey,
+            &mut self.settings_hotkey,
+            &mut self.websocket_token,
+            &mut self.sentry_dsn,
+            &mut self.skip_version,
+        ] {
+            *hotkey = hotkey
+                .take()
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty());
+        }
+        for sensor in self.sensor_overrides.values_mut() {
+            sensor.alias = sensor
+                .alias
+                .take()
+                .map(|alias| alias.trim().to_string())
+                .filter(|alias| !alias.is_empty());
+        }
+        self.sensor_overrides.retain(|_, sensor| !sensor.is_noop());
+        if !matches!(self.update_channel.as_str(), "stable" | "beta") {
+            self.update_channel = "stable".to_string();
         }
     }
 }
@@ -68,11 +499,16 @@ fn default_section_order() -> Vec<String> {
     ]
 }
 
-fn get_settings_path() -> PathBuf {
+/// Per-user Ondo data directory (settings.json and other state files).
+pub fn data_dir() -> PathBuf {
     let app_data = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
     let ondo_dir = app_data.join("Ondo");
     fs::create_dir_all(&ondo_dir).ok();
-    ondo_dir.join("settings.json")
+    ondo_dir
+}
+
+pub fn get_settings_path() -> PathBuf {
+    data_dir().join("settings.json")
 }
 
 pub fn load_settings_from_file() -> Result<AppSettings, String> {
@@ -82,8 +518,16 @@ pub fn load_settings_from_file() -> Result<AppSettings, String> {
     }
     let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
     match serde_json::from_str::<AppSettings>(&content) {
-        Ok(settings) => Ok(settings),
+        Ok(mut settings) => {
+            settings.sanitize();
+            crate::error_reporting::add_breadcrumb("settings", "Loaded settings");
+            Ok(settings)
+        }
         Err(parse_err) => {
+            crate::error_reporting::add_breadcrumb(
+                "settings",
+                "settings.json unreadable, using defaults",
+            );
             // Rename the broken file aside so the next save() doesn't
             // overwrite the user's last known config with defaults.
             let ts = std::time::SystemTime::now()
@@ -107,7 +551,122 @@ pub fn load_settings_from_file() -> Result<AppSettings, String> {
 pub async fn save_settings_to_file(settings: &AppSettings) -> Result<(), String> {
     let path = get_settings_path();
     let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(&path, content).map_err(|e| e.to_string())
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    crate::error_reporting::add_breadcrumb("settings", "Saved settings");
+    Ok(())
+}
+
+/// Named settings snapshots, kept in `profiles.json` next to settings.json
+/// and switched between with `apply_profile`.
+pub type Profiles = BTreeMap<String, AppSettings>;
+
+/// Longest profile name, so it fits a tray menu entry.
+pub const MAX_PROFILE_NAME_LEN: usize = 32;
+/// Most profiles kept; the tray submenu lists them all.
+pub const MAX_PROFILES: usize = 20;
+
+/// Trimmed `name` if it can name a profile: not empty, at most
+/// `MAX_PROFILE_NAME_LEN` characters and no control characters.
+pub fn validate_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name is empty".to_string());
+    }
+    if name.chars().count() > MAX_PROFILE_NAME_LEN {
+        return Err(format!(
+            "Profile name is longer than {} characters",
+            MAX_PROFILE_NAME_LEN
+        ));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("Profile name contains control characters".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// The stored name of the profile called `name`, ignoring case, so "Desk"
+/// and "desk" can't both exist.
+pub fn find_profile<'a>(profiles: &'a Profiles, name: &str) -> Option<&'a String> {
+    let name = name.trim();
+    profiles.keys().find(|key| key.eq_ignore_ascii_case(name))
+}
+
+fn get_profiles_path() -> PathBuf {
+    data_dir().join("profiles.json")
+}
+
+pub fn load_profiles_from_file() -> Result<Profiles, String> {
+    let path = get_profiles_path();
+    if !path.exists() {
+        return Ok(Profiles::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let mut profiles: Profiles = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse profiles.json: {}", e))?;
+    for settings in profiles.values_mut() {
+        settings.sanitize();
+    }
+    Ok(profiles)
+}
+
+pub fn save_profiles_to_file(profiles: &Profiles) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(profiles).map_err(|e| e.to_string())?;
+    fs::write(get_profiles_path(), content).map_err(|e| e.to_string())?;
+    crate::error_reporting::add_breadcrumb("settings", "Saved profiles");
+    Ok(())
+}
+
+/// Format version of exported settings files. Bump it when a change to
+/// the document can't be read by the previous version.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// A settings file written by `export_settings`: the settings and profiles
+/// with the format version and the Ondo version that wrote them.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsExport {
+    pub format_version: u32,
+    pub app_version: String,
+    pub settings: AppSettings,
+    #[serde(default)]
+    pub profiles: Profiles,
+}
+
+impl SettingsExport {
+    pub fn new(settings: AppSettings, profiles: Profiles) -> Self {
+        Self {
+            format_version: EXPORT_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            settings,
+            profiles,
+        }
+    }
+
+    /// Read an exported file. The settings and profiles go through the same
+    /// defaults and `sanitize` as settings.json. Fails as a whole on
+    /// anything but a readable document of a known format version.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let document: serde_json::Value =
+            serde_json::from_str(content).map_err(|e| format!("Not a settings file: {}", e))?;
+        let version = document
+            .get("formatVersion")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or("Not an Ondo settings file: formatVersion is missing")?;
+        if version > u64::from(EXPORT_FORMAT_VERSION) {
+            return Err(format!(
+                "Settings file format {} is newer than this version of Ondo reads ({}); update Ondo first",
+                version, EXPORT_FORMAT_VERSION
+            ));
+        }
+        let mut export: Self = serde_json::from_value(document)
+            .map_err(|e| format!("Invalid settings file: {}", e))?;
+        export.settings.sanitize();
+        for (name, settings) in export.profiles.iter_mut() {
+            validate_profile_name(name)?;
+            settings.sanitize();
+        }
+        Ok(export)
+    }
 }
 
 // Auto-start uses a Task Scheduler logon task, NOT the HKCU Run registry key.
@@ -116,6 +675,26 @@ pub async fn save_settings_to_file(settings: &AppSettings) -> Result<(), String>
 // `/RL HIGHEST` is the supported way to auto-start an elevated app.
 const AUTOSTART_TASK_NAME: &str = "Ondo";
 
+/// Passed by the logon task so Ondo starts hidden in the tray.
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+/// Error from `set_auto_start` when the task can't be created without
+/// administrator rights, so the UI can explain it.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub const AUTO_START_NEEDS_ADMIN: &str = "Creating the auto-start task needs administrator rights";
+
+/// How auto-start is registered on this platform, for
+/// `get_auto_start_status`.
+pub const AUTO_START_MECHANISM: &str = if cfg!(target_os = "windows") {
+    "task-scheduler"
+} else if cfg!(target_os = "linux") {
+    "xdg-autostart"
+} else if cfg!(target_os = "macos") {
+    "launch-agent"
+} else {
+    "none"
+};
+
 // Argument builders are plain functions (not cfg-gated) so they stay unit-testable
 // on non-Windows dev hosts.
 #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
@@ -130,7 +709,7 @@ fn schtasks_create_args(exe_path: &str) -> Vec<String> {
         "/RL",
         "HIGHEST",
         "/TR",
-        &format!("\"{}\"", exe_path),
+        &format!("\"{}\" {}", exe_path, MINIMIZED_ARG),
     ]
     .iter()
     .map(|s| s.to_string())
@@ -145,6 +724,73 @@ fn schtasks_delete_args() -> Vec<String> {
         .collect()
 }
 
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn schtasks_query_args() -> Vec<String> {
+    ["/Query", "/TN", AUTOSTART_TASK_NAME, "/XML"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Command-line tools write UTF-16 when their output is redirected on some
+/// Windows versions and the ANSI code page on others.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn decode_tool_output(bytes: &[u8]) -> String {
+    let utf16 = bytes.starts_with(&[0xFF, 0xFE]) || bytes.get(1) == Some(&0);
+    if !utf16 {
+        return String::from_utf8_lossy(bytes).into_owned();
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+        .trim_start_matches('\u{feff}')
+        .to_string()
+}
+
+/// The text of `<tag>` in a scheduled task's XML definition, unquoted:
+/// `Command` for the program it runs, `Arguments` for its arguments.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn task_xml_element(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    let text = xml[start..end]
+        .trim()
+        .trim_matches('"')
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+    Some(text)
+}
+
+#[cfg(target_os = "windows")]
+pub fn auto_start_is_current() -> Result<bool, String> {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let exe_path = std::env::current_exe().map_err(|e| e.to_string())?;
+    let output = Command::new("schtasks")
+        .args(schtasks_query_args())
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| e.to_string())?;
+    // A missing task fails the query; it needs registering as well
+    if !output.status.success() {
+        return Ok(false);
+    }
+    // Tasks from before `MINIMIZED_ARG` are registered again to add it
+    let xml = decode_tool_output(&output.stdout);
+    Ok(task_xml_element(&xml, "Command")
+        .is_some_and(|command| command.eq_ignore_ascii_case(&exe_path.to_string_lossy()))
+        && task_xml_element(&xml, "Arguments").as_deref() == Some(MINIMIZED_ARG))
+}
+
 #[cfg(target_os = "windows")]
 pub async fn set_auto_start(enabled: bool) -> Result<(), String> {
     use std::os::windows::process::CommandExt;
@@ -174,7 +820,10 @@ pub async fn set_auto_start(enabled: bool) -> Result<(), String> {
             .map_err(|e| e.to_string())?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_tool_output(&output.stderr);
+            if crate::elevation::is_permission_error(&stderr) {
+                return Err(AUTO_START_NEEDS_ADMIN.to_string());
+            }
             return Err(format!(
                 "Failed to create auto-start scheduled task: {}",
                 stderr.trim()
@@ -193,16 +842,512 @@ pub async fn set_auto_start(enabled: bool) -> Result<(), String> {
     Ok(())
 }
 
-#[cfg(not(target_os = "windows"))]
+// On Linux, auto-start is an XDG autostart entry, which every mainstream
+// desktop environment runs at login.
+#[cfg(target_os = "linux")]
+const AUTOSTART_DESKTOP_FILE: &str = "Ondo.desktop";
+
+/// Quote a path for the `Exec` key: double quotes with `"`, `` ` ``, `$` and
+/// `\` backslash-escaped, then the key-value escaping of backslashes on top,
+/// and `%` doubled so it isn't read as a field code.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn desktop_exec_quote(path: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in path.chars() {
+        match c {
+            '"' | '`' | '$' => {
+                quoted.push_str("\\\\");
+                quoted.push(c);
+            }
+            '\\' => quoted.push_str("\\\\\\\\"),
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn autostart_desktop_entry(exe_path: &str) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Ondo\n\
+         Comment=Hardware monitor widget\n\
+         Exec={}\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        desktop_exec_quote(exe_path)
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn autostart_dir() -> Result<PathBuf, String> {
+    Ok(dirs::config_dir()
+        .ok_or("No config directory to put the autostart entry in")?
+        .join("autostart"))
+}
+
+/// The executable login should start.
+#[cfg(target_os = "linux")]
+fn autostart_exe() -> Result<PathBuf, String> {
+    // Inside an AppImage, current_exe is in a mount that only exists while
+    // this instance runs; the image itself is in $APPIMAGE.
+    match std::env::var_os("APPIMAGE") {
+        Some(appimage) => Ok(PathBuf::from(appimage)),
+        None => std::env::current_exe().map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn auto_start_is_current() -> Result<bool, String> {
+    let entry_path = autostart_dir()?.join(AUTOSTART_DESKTOP_FILE);
+    let expected = autostart_desktop_entry(&autostart_exe()?.to_string_lossy());
+    Ok(fs::read_to_string(entry_path).is_ok_and(|entry| entry == expected))
+}
+
+#[cfg(target_os = "linux")]
+pub async fn set_auto_start(enabled: bool) -> Result<(), String> {
+    let autostart_dir = autostart_dir()?;
+    let entry_path = autostart_dir.join(AUTOSTART_DESKTOP_FILE);
+
+    if enabled {
+        let exe_path = autostart_exe()?;
+        fs::create_dir_all(&autostart_dir).map_err(|e| {
+            format!(
+                "Failed to create {}: {}",
+                autostart_dir.to_string_lossy(),
+                e
+            )
+        })?;
+        fs::write(
+            &entry_path,
+            autostart_desktop_entry(&exe_path.to_string_lossy()),
+        )
+        .map_err(|e| format!("Failed to write {}: {}", entry_path.to_string_lossy(), e))?;
+        crate::log_info!("Settings", "Autostart entry written");
+    } else {
+        match fs::remove_file(&entry_path) {
+            Ok(()) => crate::log_info!("Settings", "Autostart entry removed"),
+            // Never enabled, or already removed
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(format!(
+                    "Failed to remove {}: {}",
+                    entry_path.to_string_lossy(),
+                    e
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// On macOS, auto-start is a per-user LaunchAgent with `RunAtLoad`.
+const LAUNCH_AGENT_LABEL: &str = "com.ondo.app";
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn launch_agent_plist(exe_path: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        LAUNCH_AGENT_LABEL,
+        xml_escape(exe_path)
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn launchctl(action: &str, plist_path: &std::path::Path) -> Result<(), String> {
+    let output = std::process::Command::new("launchctl")
+        .arg(action)
+        .arg("-w")
+        .arg(plist_path)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "launchctl {} failed: {}",
+            action,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn launch_agents_dir() -> Result<PathBuf, String> {
+    Ok(dirs::home_dir()
+        .ok_or("No home directory to put the LaunchAgent in")?
+        .join("Library")
+        .join("LaunchAgents"))
+}
+
+#[cfg(target_os = "macos")]
+pub fn auto_start_is_current() -> Result<bool, String> {
+    let plist_path = launch_agents_dir()?.join(format!("{}.plist", LAUNCH_AGENT_LABEL));
+    let exe_path = std::env::current_exe().map_err(|e| e.to_string())?;
+    let expected = launch_agent_plist(&exe_path.to_string_lossy());
+    Ok(fs::read_to_string(plist_path).is_ok_and(|plist| plist == expected))
+}
+
+#[cfg(target_os = "macos")]
+pub async fn set_auto_start(enabled: bool) -> Result<(), String> {
+    let agents_dir = launch_agents_dir()?;
+    let plist_path = agents_dir.join(format!("{}.plist", LAUNCH_AGENT_LABEL));
+
+    // Unload an existing agent either way: enabling again rewrites it with
+    // the current path, and launchctl refuses to load a loaded job. Failure
+    // only means it wasn't loaded.
+    if plist_path.exists() {
+        let _ = launchctl("unload", &plist_path);
+    }
+
+    if enabled {
+        let exe_path = std::env::current_exe().map_err(|e| e.to_string())?;
+        fs::create_dir_all(&agents_dir)
+            .map_err(|e| format!("Failed to create {}: {}", agents_dir.to_string_lossy(), e))?;
+        fs::write(&plist_path, launch_agent_plist(&exe_path.to_string_lossy()))
+            .map_err(|e| format!("Failed to write {}: {}", plist_path.to_string_lossy(), e))?;
+        launchctl("load", &plist_path)?;
+        crate::log_info!("Settings", "LaunchAgent loaded");
+    } else {
+        match fs::remove_file(&plist_path) {
+            Ok(()) => crate::log_info!("Settings", "LaunchAgent removed"),
+            // Never enabled, or already removed
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(format!(
+                    "Failed to remove {}: {}",
+                    plist_path.to_string_lossy(),
+                    e
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
 pub async fn set_auto_start(_enabled: bool) -> Result<(), String> {
-    // Auto-start is Windows-specific
+    // Not supported on this platform yet
     Ok(())
 }
 
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub fn auto_start_is_current() -> Result<bool, String> {
+    Ok(true)
+}
+
+/// With auto-start enabled, re-register it if it no longer starts this
+/// executable, e.g. after an update installed Ondo somewhere else or the
+/// entry was deleted. Called once at startup.
+pub async fn verify_auto_start(enabled: bool) -> Result<(), String> {
+    if !enabled || auto_start_is_current()? {
+        return Ok(());
+    }
+    crate::log_info!(
+        "Settings",
+        "Auto-start doesn't launch this executable, registering it again"
+    );
+    set_auto_start(true).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn sanitize_clamps_update_interval() {
+        let mut settings = AppSettings {
+            update_interval_fast: 10,
+            ..AppSettings::default()
+        };
+        settings.sanitize();
+        assert_eq!(settings.update_interval_fast, MIN_UPDATE_INTERVAL_MS);
+
+        settings.update_interval_fast = 1_000_000;
+        settings.sanitize();
+        assert_eq!(settings.update_interval_fast, MAX_UPDATE_INTERVAL_MS);
+
+        settings.update_interval_fast = 2000;
+        settings.sanitize();
+        assert_eq!(settings.update_interval_fast, 2000);
+    }
+
+    #[test]
+    fn slow_interval_is_never_faster_than_the_fast_one() {
+        let mut settings = AppSettings {
+            update_interval_fast: 3000,
+            update_interval_slow: 1000,
+            ..AppSettings::default()
+        };
+        settings.sanitize();
+        assert_eq!(settings.update_interval_slow, 3000);
+
+        settings.update_interval_slow = 10_000;
+        settings.sanitize();
+        assert_eq!(settings.update_interval_slow, 10_000);
+    }
+
+    #[test]
+    fn sanitize_keeps_smoothing_factor_below_one() {
+        let mut settings = AppSettings {
+            smoothing_factor: 1.0,
+            ..AppSettings::default()
+        };
+        settings.sanitize();
+        assert_eq!(
+            settings.smoothing_factor,
+            crate::smoothing::MAX_SMOOTHING_FACTOR
+        );
+
+        settings.smoothing_factor = -0.5;
+        settings.sanitize();
+        assert_eq!(settings.smoothing_factor, 0.0);
+
+        settings.smoothing_factor = f32::NAN;
+        settings.sanitize();
+        assert_eq!(settings.smoothing_factor, 0.0);
+    }
+
+    #[test]
+    fn exported_settings_read_back() {
+        let settings = AppSettings {
+            position: "top-left".to_string(),
+            update_interval_fast: 10,
+            ..AppSettings::default()
+        };
+        let mut profiles = Profiles::new();
+        profiles.insert("Desk".to_string(), AppSettings::default());
+        let content = serde_json::to_string(&SettingsExport::new(settings, profiles)).unwrap();
+
+        let export = SettingsExport::parse(&content).unwrap();
+        assert_eq!(export.format_version, EXPORT_FORMAT_VERSION);
+        assert_eq!(export.settings.position, "top-left");
+        // Sanitized like settings.json
+        assert_eq!(export.settings.update_interval_fast, MIN_UPDATE_INTERVAL_MS);
+        assert!(export.profiles.contains_key("Desk"));
+    }
+
+    #[test]
+    fn unreadable_or_newer_exports_are_refused() {
+        assert!(SettingsExport::parse("not json").is_err());
+        assert!(SettingsExport::parse(r#"{"position": "left"}"#)
+            .unwrap_err()
+            .contains("formatVersion"));
+        let newer = format!(
+            r#"{{"formatVersion": {}, "appVersion": "9.0.0", "settings": {{}}}}"#,
+            EXPORT_FORMAT_VERSION + 1
+        );
+        assert!(SettingsExport::parse(&newer)
+            .unwrap_err()
+            .contains("update Ondo"));
+        let wrong_type =
+            r#"{"formatVersion": 1, "appVersion": "0.1.25", "settings": {"opacity": "high"}}"#;
+        assert!(SettingsExport::parse(wrong_type)
+            .unwrap_err()
+            .starts_with("Invalid settings file"));
+        let bad_profile = r#"{"formatVersion": 1, "appVersion": "0.1.25", "settings": {}, "profiles": {" ": {}}}"#;
+        assert!(SettingsExport::parse(bad_profile).is_err());
+    }
+
+    #[test]
+    fn profile_names_are_validated() {
+        assert_eq!(validate_profile_name("  Streaming ").unwrap(), "Streaming");
+        assert!(validate_profile_name("   ").is_err());
+        assert!(validate_profile_name(&"x".repeat(MAX_PROFILE_NAME_LEN + 1)).is_err());
+        assert!(validate_profile_name(&"é".repeat(MAX_PROFILE_NAME_LEN)).is_ok());
+        assert!(validate_profile_name("Desk\nsetup").is_err());
+    }
+
+    #[test]
+    fn profiles_are_found_ignoring_case() {
+        let mut profiles = Profiles::new();
+        profiles.insert("Desk".to_string(), AppSettings::default());
+        assert_eq!(
+            find_profile(&profiles, "desk").map(String::as_str),
+            Some("Desk")
+        );
+        assert_eq!(
+            find_profile(&profiles, " DESK ").map(String::as_str),
+            Some("Desk")
+        );
+        assert!(find_profile(&profiles, "Stream").is_none());
+    }
+
+    #[test]
+    fn sanitize_caps_snap_threshold() {
+        let mut settings = AppSettings {
+            snap_threshold: 5_000,
+            ..AppSettings::default()
+        };
+        settings.sanitize();
+        assert_eq!(settings.snap_threshold, MAX_SNAP_THRESHOLD);
+    }
+
+    #[test]
+    fn old_update_interval_is_read_as_the_fast_one() {
+        let settings: AppSettings = serde_json::from_str(r#"{"updateInterval":2000}"#).unwrap();
+        assert_eq!(settings.update_interval_fast, 2000);
+        assert_eq!(settings.update_interval_slow, 5000);
+    }
+
+    #[test]
+    fn sanitize_trims_hotkeys_and_drops_blank_ones() {
+        let mut settings = AppSettings {
+            toggle_hotkey: Some("  Ctrl+Shift+O ".to_string()),
+            settings_hotkey: Some("   ".to_string()),
+            ..AppSettings::default()
+        };
+        settings.sanitize();
+        assert_eq!(settings.toggle_hotkey.as_deref(), Some("Ctrl+Shift+O"));
+        assert_eq!(settings.settings_hotkey, None);
+    }
+
+    #[test]
+    fn sanitize_resets_unreadable_quiet_hours() {
+        let mut settings = AppSettings {
+            quiet_hours: QuietHours {
+                enabled: true,
+                start: " 23:30 ".to_string(),
+                end: "6:00".to_string(),
+                allow_critical: false,
+            },
+            ..AppSettings::default()
+        };
+        settings.sanitize();
+        assert_eq!(
+            settings.quiet_hours,
+            QuietHours {
+                enabled: true,
+                allow_critical: false,
+                ..QuietHours::default()
+            }
+        );
+
+        settings.quiet_hours.start = "23:30".to_string();
+        settings.sanitize();
+        assert_eq!(settings.quiet_hours.window(), Some((23 * 60 + 30, 7 * 60)));
+        assert_eq!(parse_clock_time("24:00"), None);
+    }
+
+    #[test]
+    fn sanitize_keeps_thresholds_in_order() {
+        let mut settings = AppSettings::default();
+        settings.thresholds.cpu = ComponentThresholds {
+            warning_temperature: Some(90.0),
+            critical_temperature: Some(80.0),
+            warning_load: Some(f32::NAN),
+            critical_load: Some(250.0),
+        };
+        settings.sanitize();
+        assert_eq!(
+            settings.thresholds.cpu,
+            ComponentThresholds {
+                warning_temperature: Some(90.0),
+                critical_temperature: Some(90.0),
+                warning_load: None,
+                critical_load: Some(100.0),
+            }
+        );
+        // Older settings.json files get the defaults
+        let settings: AppSettings = serde_json::from_str(r#"{"opacity": 80}"#).unwrap();
+        assert_eq!(settings.thresholds, Thresholds::default());
+        assert_eq!(settings.thresholds.cpu.warning_temperature, None);
+    }
+
+    #[test]
+    fn sanitize_tidies_influxdb_settings() {
+        let mut settings = AppSettings {
+            influxdb: InfluxDbSettings {
+                url: " http://nas:8086/ ".to_string(),
+                token: Some("  ".to_string()),
+                flush_interval_secs: 0,
+                batch_size: 1_000_000,
+                ..InfluxDbSettings::default()
+            },
+            ..AppSettings::default()
+        };
+        settings.sanitize();
+        assert_eq!(settings.influxdb.url, "http://nas:8086");
+        assert_eq!(settings.influxdb.token, None);
+        assert_eq!(settings.influxdb.flush_interval_secs, 1);
+        assert_eq!(settings.influxdb.batch_size, 5000);
+    }
+
+    #[test]
+    fn sanitize_resets_unknown_update_channel() {
+        let mut settings = AppSettings {
+            update_channel: "nightly".to_string(),
+            skip_version: Some(" ".to_string()),
+            ..AppSettings::default()
+        };
+        settings.sanitize();
+        assert_eq!(settings.update_channel, "stable");
+        assert_eq!(settings.skip_version, None);
+
+        settings.update_channel = "beta".to_string();
+        settings.sanitize();
+        assert_eq!(settings.update_channel, "beta");
+    }
+
+    #[test]
+    fn sanitize_drops_sensor_overrides_that_change_nothing() {
+        let mut settings = AppSettings::default();
+        for (name, hidden, alias) in [
+            ("USB Enclosure", true, None),
+            ("Samsung SSD 980 PRO 1TB", false, Some(" NVMe ")),
+            ("Fan #3", false, Some("  ")),
+            ("Fan #4", false, None),
+        ] {
+            settings.sensor_overrides.insert(
+                name.to_string(),
+                SensorOverride {
+                    hidden,
+                    alias: alias.map(str::to_string),
+                },
+            );
+        }
+        settings.sanitize();
+        let names: Vec<&str> = settings
+            .sensor_overrides
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(names, vec!["Samsung SSD 980 PRO 1TB", "USB Enclosure"]);
+        assert_eq!(
+            settings.sensor_overrides["Samsung SSD 980 PRO 1TB"]
+                .alias
+                .as_deref(),
+            Some("NVMe")
+        );
+    }
+
     #[test]
     fn create_args_register_elevated_logon_task() {
         let args = schtasks_create_args(r"C:\Program Files\Ondo\ondo.exe");
@@ -223,11 +1368,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn desktop_entry_execs_the_quoted_exe_path() {
+        let entry = autostart_desktop_entry("/opt/Ondo Beta/ondo");
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("\nType=Application\n"));
+        assert!(entry.contains("\nExec=\"/opt/Ondo Beta/ondo\"\n"));
+    }
+
+    #[test]
+    fn desktop_exec_quote_escapes_reserved_characters() {
+        assert_eq!(desktop_exec_quote("/usr/bin/ondo"), r#""/usr/bin/ondo""#);
+        assert_eq!(
+            desktop_exec_quote(r#"/home/a"b/$HOME/100%/back\slash"#),
+            r#""/home/a\\"b/\\$HOME/100%%/back\\\\slash""#
+        );
+    }
+
+    #[test]
+    fn launch_agent_runs_the_escaped_exe_at_load() {
+        let plist = launch_agent_plist("/Applications/R&D <Beta>.app/Contents/MacOS/ondo");
+        assert!(plist.contains("<string>com.ondo.app</string>"));
+        assert!(plist.contains(
+            "<string>/Applications/R&amp;D &lt;Beta&gt;.app/Contents/MacOS/ondo</string>"
+        ));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+    }
+
+    #[test]
+    fn task_command_is_read_from_task_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <Actions Context="Author">
+    <Exec>
+      <Command>"C:\Program Files\R&amp;D\Ondo\ondo.exe"</Command>
+      <Arguments>--minimized</Arguments>
+    </Exec>
+  </Actions>
+</Task>"#;
+        assert_eq!(
+            task_xml_element(xml, "Command").as_deref(),
+            Some(r"C:\Program Files\R&D\Ondo\ondo.exe")
+        );
+        assert_eq!(
+            task_xml_element(xml, "Arguments").as_deref(),
+            Some(MINIMIZED_ARG)
+        );
+        assert_eq!(task_xml_element("<Task></Task>", "Command"), None);
+    }
+
+    #[test]
+    fn tool_output_is_decoded_as_utf16_or_ansi() {
+        let text = "<Command>C:\\ondo.exe</Command>";
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(decode_tool_output(&utf16), text);
+        assert_eq!(decode_tool_output(text.as_bytes()), text);
+        assert_eq!(decode_tool_output(b""), "");
+    }
+
     #[test]
     fn create_args_quote_exe_path_for_spaces() {
         let args = schtasks_create_args(r"C:\Program Files\Ondo\ondo.exe");
-        let tr = args.last().unwrap();
-        assert!(tr.starts_with('"') && tr.ends_with('"'));
+        assert_eq!(
+            args.last().unwrap(),
+            r#""C:\Program Files\Ondo\ondo.exe" --minimized"#
+        );
     }
 
     #[test]
@@ -0,0 +1,146 @@
+//! Simulated readings for testing alerts and color thresholds without
+//! heating the hardware. `simulate_reading` sets CPU and GPU temperatures
+//! that replace the measured ones in every sample for a while, before
+//! statistics, smoothing, the tray and the WebSocket feed see them.
+//!
+//! Debug builds only: release builds refuse to start a simulation, so a
+//! running Ondo can't be made to show false readings.
+
+use crate::HardwareData;
+use std::sync::Mutex;
+
+/// How long a simulated reading lasts when no duration is given.
+pub const DEFAULT_DURATION_SECS: u32 = 10;
+/// Longest simulation, so a forgotten one can't stick around.
+pub const MAX_DURATION_SECS: u32 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Simulation {
+    cpu_temperature: f32,
+    gpu_temperature: f32,
+    /// ms since epoch.
+    until_ms: u64,
+}
+
+static ACTIVE: Mutex<Option<Simulation>> = Mutex::new(None);
+
+/// Whether this build accepts simulated readings.
+pub const fn available() -> bool {
+    cfg!(debug_assertions)
+}
+
+fn validate(temperature: f32, what: &str) -> Result<f32, String> {
+    if temperature.is_finite() && (0.0..=150.0).contains(&temperature) {
+        Ok(temperature)
+    } else {
+        Err(format!("{} temperature must be between 0 and 150°C", what))
+    }
+}
+
+/// Report `cpu_temperature` and `gpu_temperature` (°C) for `duration_secs`
+/// from `now`, replacing any running simulation.
+pub fn start(
+    cpu_temperature: f32,
+    gpu_temperature: f32,
+    duration_secs: u32,
+    now: u64,
+) -> Result<(), String> {
+    if !available() {
+        return Err("Simulated readings are only available in debug builds".to_string());
+    }
+    let simulation = Simulation {
+        cpu_temperature: validate(cpu_temperature, "CPU")?,
+        gpu_temperature: validate(gpu_temperature, "GPU")?,
+        until_ms: now + u64::from(duration_secs.clamp(1, MAX_DURATION_SECS)) * 1000,
+    };
+    *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()) = Some(simulation);
+    crate::log_info!(
+        "Simulation",
+        "CPU {}°C, GPU {}°C for {}s",
+        cpu_temperature,
+        gpu_temperature,
+        duration_secs.clamp(1, MAX_DURATION_SECS)
+    );
+    Ok(())
+}
+
+fn apply_simulation(slot: &mut Option<Simulation>, data: &mut HardwareData, now: u64) {
+    let Some(simulation) = *slot else {
+        return;
+    };
+    if now >= simulation.until_ms {
+        *slot = None;
+        crate::log_info!("Simulation", "Ended");
+        return;
+    }
+    // Only devices that are there; a missing GPU stays missing
+    if let Some(cpu) = data.cpu.as_mut() {
+        cpu.temperature = simulation.cpu_temperature;
+    }
+    if let Some(gpu) = data.gpu.as_mut() {
+        gpu.temperature = simulation.gpu_temperature;
+    }
+}
+
+/// Put the simulated temperatures into `data` while a simulation runs.
+pub fn apply(data: &mut HardwareData, now: u64) {
+    apply_simulation(
+        &mut ACTIVE.lock().unwrap_or_else(|e| e.into_inner()),
+        data,
+        now,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockHardware;
+
+    fn temperatures(data: &HardwareData) -> (f32, f32) {
+        (
+            data.cpu.as_ref().unwrap().temperature,
+            data.gpu.as_ref().unwrap().temperature,
+        )
+    }
+
+    #[test]
+    fn readings_are_replaced_until_the_simulation_ends() {
+        let mut slot = Some(Simulation {
+            cpu_temperature: 98.0,
+            gpu_temperature: 91.0,
+            until_ms: 10_000,
+        });
+        let mut data = MockHardware::new(0, 1000).sample_at(0);
+        let measured = temperatures(&data);
+
+        apply_simulation(&mut slot, &mut data, 9_999);
+        assert_eq!(temperatures(&data), (98.0, 91.0));
+
+        let mut data = MockHardware::new(0, 1000).sample_at(0);
+        apply_simulation(&mut slot, &mut data, 10_000);
+        assert_eq!(temperatures(&data), measured);
+        assert!(slot.is_none());
+    }
+
+    #[test]
+    fn missing_devices_stay_missing() {
+        let mut slot = Some(Simulation {
+            cpu_temperature: 98.0,
+            gpu_temperature: 91.0,
+            until_ms: 10_000,
+        });
+        let mut data = MockHardware::new(0, 1000).sample_at(0);
+        data.gpu = None;
+        apply_simulation(&mut slot, &mut data, 0);
+        assert!(data.gpu.is_none());
+        assert_eq!(data.cpu.unwrap().temperature, 98.0);
+    }
+
+    #[test]
+    fn implausible_temperatures_are_refused() {
+        assert!(validate(95.0, "CPU").is_ok());
+        assert!(validate(-5.0, "CPU").is_err());
+        assert!(validate(f32::NAN, "GPU").is_err());
+        assert!(validate(400.0, "GPU").unwrap_err().starts_with("GPU"));
+    }
+}
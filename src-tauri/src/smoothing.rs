@@ -0,0 +1,162 @@
+//! Exponential moving average over the headline CPU and GPU readings, so
+//! the widget doesn't jitter from tick to tick. Controlled by the
+//! `smoothing_factor` setting; 0 leaves samples untouched. The unsmoothed
+//! values travel along in `rawReadings`, and session statistics are
+//! recorded before smoothing.
+
+use crate::HardwareData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Highest accepted `smoothing_factor`. At 1.0 the readings would never move.
+pub const MAX_SMOOTHING_FACTOR: f32 = 0.95;
+
+/// The smoothed readings as measured, in the same units.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RawReadings {
+    pub cpu_temperature: Option<f32>,
+    pub cpu_load: Option<f32>,
+    pub cpu_frequency: Option<f32>,
+    pub gpu_temperature: Option<f32>,
+    pub gpu_load: Option<f32>,
+    pub gpu_frequency: Option<f32>,
+}
+
+/// Running average of one reading.
+#[derive(Debug, Default)]
+struct Ema(Option<f32>);
+
+impl Ema {
+    /// Fold in `raw` and return the new average. A temperature of 0 means
+    /// no sensor: it is passed through and the average starts over.
+    fn update(&mut self, raw: f32, factor: f32, zero_is_missing: bool) -> f32 {
+        if !raw.is_finite() || (zero_is_missing && raw <= 0.0) {
+            self.0 = None;
+            return raw;
+        }
+        let smoothed = match self.0 {
+            Some(previous) => factor * previous + (1.0 - factor) * raw,
+            None => raw,
+        };
+        self.0 = Some(smoothed);
+        smoothed
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Smoother {
+    /// `dataSource` the averages were built from.
+    source: String,
+    cpu_temperature: Ema,
+    cpu_load: Ema,
+    cpu_frequency: Ema,
+    gpu_temperature: Ema,
+    gpu_load: Ema,
+    gpu_frequency: Ema,
+}
+
+impl Smoother {
+    /// Replace the headline readings in `data` with their averages and keep
+    /// the measured ones in `raw_readings`. Does nothing (and forgets the
+    /// averages) when `factor` is 0. Starts over when the data source
+    /// changes, since readings from different sources don't line up.
+    pub fn apply(&mut self, data: &mut HardwareData, factor: f32) {
+        if factor <= 0.0 || data.data_source != self.source {
+            *self = Self {
+                source: data.data_source.clone(),
+                ..Self::default()
+            };
+        }
+        if factor <= 0.0 {
+            return;
+        }
+        let factor = factor.min(MAX_SMOOTHING_FACTOR);
+
+        let mut raw = RawReadings::default();
+        if let Some(cpu) = data.cpu.as_mut() {
+            raw.cpu_temperature = Some(cpu.temperature);
+            raw.cpu_load = Some(cpu.load);
+            raw.cpu_frequency = Some(cpu.frequency);
+            cpu.temperature = self.cpu_temperature.update(cpu.temperature, factor, true);
+            cpu.load = self.cpu_load.update(cpu.load, factor, false);
+            cpu.frequency = self.cpu_frequency.update(cpu.frequency, factor, false);
+        }
+        if let Some(gpu) = data.gpu.as_mut() {
+            raw.gpu_temperature = Some(gpu.temperature);
+            raw.gpu_load = Some(gpu.load);
+            raw.gpu_frequency = Some(gpu.frequency);
+            gpu.temperature = self.gpu_temperature.update(gpu.temperature, factor, true);
+            gpu.load = self.gpu_load.update(gpu.load, factor, false);
+            gpu.frequency = self.gpu_frequency.update(gpu.frequency, factor, false);
+        }
+        data.raw_readings = Some(raw);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockHardware;
+
+    fn sample_with(cpu_temperature: f32, source: &str) -> HardwareData {
+        let mut data = MockHardware::new(0, 1000).sample_at(0);
+        data.cpu.as_mut().unwrap().temperature = cpu_temperature;
+        data.data_source = source.to_string();
+        data
+    }
+
+    fn cpu_temperature(data: &HardwareData) -> f32 {
+        data.cpu.as_ref().unwrap().temperature
+    }
+
+    #[test]
+    fn zero_factor_leaves_samples_untouched() {
+        let mut smoother = Smoother::default();
+        let mut data = sample_with(50.0, "lhm");
+        smoother.apply(&mut data, 0.0);
+        let mut data = sample_with(80.0, "lhm");
+        smoother.apply(&mut data, 0.0);
+        assert_eq!(cpu_temperature(&data), 80.0);
+        assert!(data.raw_readings.is_none());
+    }
+
+    #[test]
+    fn readings_move_part_way_and_raw_values_are_kept() {
+        let mut smoother = Smoother::default();
+        let mut first = sample_with(50.0, "lhm");
+        smoother.apply(&mut first, 0.5);
+        assert_eq!(cpu_temperature(&first), 50.0);
+
+        let mut second = sample_with(70.0, "lhm");
+        smoother.apply(&mut second, 0.5);
+        assert_eq!(cpu_temperature(&second), 60.0);
+        let raw = second.raw_readings.unwrap();
+        assert_eq!(raw.cpu_temperature, Some(70.0));
+        assert!(raw.gpu_load.is_some());
+    }
+
+    #[test]
+    fn averages_start_over_when_the_source_changes() {
+        let mut smoother = Smoother::default();
+        let mut data = sample_with(50.0, "sysinfo");
+        smoother.apply(&mut data, 0.9);
+        let mut data = sample_with(70.0, "lhm");
+        smoother.apply(&mut data, 0.9);
+        assert_eq!(cpu_temperature(&data), 70.0);
+    }
+
+    #[test]
+    fn missing_temperature_is_not_averaged_in() {
+        let mut smoother = Smoother::default();
+        let mut data = sample_with(60.0, "lhm");
+        smoother.apply(&mut data, 0.5);
+        let mut data = sample_with(0.0, "lhm");
+        smoother.apply(&mut data, 0.5);
+        assert_eq!(cpu_temperature(&data), 0.0);
+        // The next reading starts a new average instead of pulling toward 0
+        let mut data = sample_with(62.0, "lhm");
+        smoother.apply(&mut data, 0.5);
+        assert_eq!(cpu_temperature(&data), 62.0);
+    }
+}
@@ -0,0 +1,133 @@
+//! `ondo --soak-test <minutes>`: run the sample pipeline headless against
+//! `MockHardware` as fast as it will go, checking the internal counters
+//! stay within their bounds. Exits non-zero on a violation, so it can gate
+//! changes to anything that runs per sample.
+
+use crate::commands::hardware::collect_sample;
+use crate::mock::MockHardware;
+use crate::{
+    alerts, metrics, now_millis, power, session_stats, settings, smoothing, throttling, tray,
+    AppState,
+};
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Samples between counter checks.
+const CHECK_EVERY: u64 = 10_000;
+
+/// The minutes passed with `--soak-test`, if the flag is present.
+pub fn requested_minutes(args: impl IntoIterator<Item = String>) -> Option<Result<u64, String>> {
+    let mut args = args.into_iter();
+    args.by_ref().find(|arg| arg == "--soak-test")?;
+    Some(
+        args.next()
+            .and_then(|minutes| minutes.parse().ok())
+            .ok_or_else(|| "usage: --soak-test <minutes>".to_string()),
+    )
+}
+
+/// State as the app builds it, but with nothing persisted and no tray.
+fn headless_state() -> AppState {
+    AppState {
+        settings: settings::SharedSettings::default(),
+        debug_server_running: AtomicBool::new(false),
+        debug_server_shutdown: Mutex::new(None),
+        energy: Mutex::new(power::EnergyTracker::in_memory()),
+        session_stats: Mutex::new(session_stats::SessionStats::new(now_millis())),
+        tray_readout: tray::TrayReadout::default(),
+        smoother: Mutex::new(smoothing::Smoother::default()),
+        throttle: Mutex::new(throttling::ThrottleDetector::default()),
+        alerts: Mutex::new(alerts::AlertLog::in_memory()),
+    }
+}
+
+/// Sample until `duration` has passed. Returns the number of samples, or
+/// the counter violations that stopped the run.
+async fn soak(duration: Duration) -> Result<u64, Vec<String>> {
+    let state = headless_state();
+    let step_ms = u64::from(state.settings.lock().update_interval_fast);
+    let source = MockHardware::new(now_millis(), step_ms);
+    let deadline = Instant::now() + duration;
+
+    let mut samples = 0u64;
+    loop {
+        collect_sample(&source, |data| state.process_sample(data))
+            .await
+            .map_err(|e| vec![e])?;
+        samples += 1;
+
+        let done = Instant::now() >= deadline;
+        if done || samples.is_multiple_of(CHECK_EVERY) {
+            let violations = metrics::violations(&metrics::snapshot());
+            if !violations.is_empty() {
+                return Err(violations);
+            }
+        }
+        if done {
+            return Ok(samples);
+        }
+    }
+}
+
+/// Run the soak test and return the process exit code.
+pub fn run(minutes: u64) -> i32 {
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            crate::log_error!("Soak", "Failed to start runtime: {}", e);
+            return 2;
+        }
+    };
+
+    crate::log_info!("Soak", "Running for {} minute(s)", minutes);
+    match runtime.block_on(soak(Duration::from_secs(minutes * 60))) {
+        Ok(samples) => {
+            crate::log_info!(
+                "Soak",
+                "Passed after {} samples: {:?}",
+                samples,
+                metrics::snapshot()
+            );
+            0
+        }
+        Err(violations) => {
+            for violation in &violations {
+                crate::log_error!("Soak", "{}", violation);
+            }
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_soak_flag() {
+        assert_eq!(requested_minutes(args(&[])), None);
+        assert_eq!(requested_minutes(args(&["--minimized"])), None);
+        assert_eq!(
+            requested_minutes(args(&["--soak-test", "30"])),
+            Some(Ok(30))
+        );
+        assert!(requested_minutes(args(&["--soak-test"])).unwrap().is_err());
+        assert!(requested_minutes(args(&["--soak-test", "soon"]))
+            .unwrap()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn short_soak_stays_within_bounds() {
+        let samples = soak(Duration::from_millis(200)).await.unwrap();
+        assert!(samples > 0);
+    }
+}
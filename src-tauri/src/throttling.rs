@@ -0,0 +1,181 @@
+//! Thermal throttling heuristic for the CPU, setting `isThrottling` on
+//! samples. Neither LHM nor sysinfo expose PROCHOT or the throttle status
+//! bits, so it is inferred from the readings: the CPU is flagged when, for
+//! `CONFIRM_SAMPLES` samples in a row, all of these hold:
+//!
+//! - load is at least `MIN_LOAD`, so a low clock isn't just idle power saving;
+//! - temperature is within `TEMPERATURE_MARGIN` of `maxTemperature`;
+//! - the clock is below `MAX_CLOCK_RATIO` of the highest clock seen since
+//!   the data source last changed, which stands in for the boost clock.
+//!
+//! Requiring both a low clock and a high temperature keeps false positives
+//! rare: a hot CPU at full clock, or a slow one running cool, isn't flagged.
+
+use crate::HardwareData;
+
+/// Load (%) below which a low clock is taken as power saving.
+const MIN_LOAD: f32 = 80.0;
+/// How close (°C) to `maxTemperature` the CPU has to be.
+const TEMPERATURE_MARGIN: f32 = 5.0;
+/// Clock relative to the highest clock seen, below which it counts as held back.
+const MAX_CLOCK_RATIO: f32 = 0.85;
+/// Consecutive samples that must agree before the flag is raised.
+const CONFIRM_SAMPLES: u32 = 2;
+
+#[derive(Debug, Default)]
+pub struct ThrottleDetector {
+    /// `dataSource` the peak clock was seen on.
+    source: String,
+    /// Highest CPU clock seen, in GHz.
+    peak_frequency: f32,
+    /// Samples in a row that looked throttled.
+    streak: u32,
+}
+
+/// Whether one sample looks throttled against `peak_frequency`.
+fn looks_throttled(
+    temperature: f32,
+    max_temperature: f32,
+    load: f32,
+    frequency: f32,
+    peak_frequency: f32,
+) -> bool {
+    temperature > 0.0
+        && max_temperature > 0.0
+        && frequency > 0.0
+        && load >= MIN_LOAD
+        && temperature >= max_temperature - TEMPERATURE_MARGIN
+        && frequency < peak_frequency * MAX_CLOCK_RATIO
+}
+
+impl ThrottleDetector {
+    /// Set `is_throttling` on the CPU in `data`, and `max_frequency` to the
+    /// highest clock seen when the source has none. Needs the measured
+    /// readings, so runs before smoothing.
+    pub fn apply(&mut self, data: &mut HardwareData) {
+        if data.data_source != self.source {
+            *self = Self {
+                source: data.data_source.clone(),
+                ..Self::default()
+            };
+        }
+        let Some(cpu) = data.cpu.as_mut() else {
+            self.streak = 0;
+            return;
+        };
+
+        if looks_throttled(
+            cpu.temperature,
+            cpu.max_temperature,
+            cpu.load,
+            cpu.frequency,
+            self.peak_frequency,
+        ) {
+            self.streak += 1;
+        } else {
+            self.streak = 0;
+        }
+        if cpu.frequency.is_finite() {
+            self.peak_frequency = self.peak_frequency.max(cpu.frequency);
+        }
+        cpu.is_throttling = self.streak >= CONFIRM_SAMPLES;
+        // Without a rated boost clock, the highest clock seen is the best
+        // guess there is
+        if cpu.max_frequency <= 0.0 {
+            cpu.max_frequency = self.peak_frequency.max(cpu.base_frequency);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockHardware;
+
+    fn sample(temperature: f32, load: f32, frequency: f32) -> HardwareData {
+        let mut data = MockHardware::new(0, 1000).sample_at(0);
+        let cpu = data.cpu.as_mut().unwrap();
+        cpu.temperature = temperature;
+        cpu.max_temperature = 100.0;
+        cpu.load = load;
+        cpu.frequency = frequency;
+        data
+    }
+
+    fn run(detector: &mut ThrottleDetector, temperature: f32, load: f32, frequency: f32) -> bool {
+        let mut data = sample(temperature, load, frequency);
+        detector.apply(&mut data);
+        data.cpu.unwrap().is_throttling
+    }
+
+    #[test]
+    fn hot_and_held_back_under_load_is_throttling() {
+        let mut detector = ThrottleDetector::default();
+        assert!(!run(&mut detector, 70.0, 100.0, 4.8));
+        assert!(!run(&mut detector, 97.0, 100.0, 3.2));
+        assert!(run(&mut detector, 98.0, 100.0, 3.0));
+        // Clock recovers: cleared at once
+        assert!(!run(&mut detector, 96.0, 100.0, 4.7));
+    }
+
+    #[test]
+    fn one_condition_alone_is_not_throttling() {
+        let mut detector = ThrottleDetector::default();
+        run(&mut detector, 60.0, 100.0, 4.8);
+        for _ in 0..3 {
+            // Hot at full clock
+            assert!(!run(&mut detector, 98.0, 100.0, 4.7));
+        }
+        for _ in 0..3 {
+            // Low clock while cool
+            assert!(!run(&mut detector, 60.0, 100.0, 2.0));
+        }
+        for _ in 0..3 {
+            // Hot and slow, but mostly idle
+            assert!(!run(&mut detector, 98.0, 20.0, 2.0));
+        }
+    }
+
+    #[test]
+    fn missing_readings_never_flag() {
+        let mut detector = ThrottleDetector::default();
+        run(&mut detector, 0.0, 100.0, 4.8);
+        for _ in 0..3 {
+            assert!(!run(&mut detector, 0.0, 100.0, 2.0));
+        }
+    }
+
+    #[test]
+    fn max_frequency_falls_back_to_the_peak_clock() {
+        let mut detector = ThrottleDetector::default();
+        let mut data = sample(60.0, 100.0, 4.8);
+        let cpu = data.cpu.as_mut().unwrap();
+        cpu.base_frequency = 3.6;
+        cpu.max_frequency = 0.0;
+        detector.apply(&mut data);
+        assert_eq!(data.cpu.unwrap().max_frequency, 4.8);
+
+        // Never below the base clock, and a rated one is kept
+        let mut data = sample(60.0, 5.0, 1.2);
+        let cpu = data.cpu.as_mut().unwrap();
+        cpu.base_frequency = 3.6;
+        cpu.max_frequency = 0.0;
+        ThrottleDetector::default().apply(&mut data);
+        assert_eq!(data.cpu.unwrap().max_frequency, 3.6);
+        let mut data = sample(60.0, 100.0, 4.8);
+        data.cpu.as_mut().unwrap().max_frequency = 5.7;
+        detector.apply(&mut data);
+        assert_eq!(data.cpu.unwrap().max_frequency, 5.7);
+    }
+
+    #[test]
+    fn peak_clock_starts_over_with_a_new_source() {
+        let mut detector = ThrottleDetector::default();
+        run(&mut detector, 60.0, 100.0, 4.8);
+        let mut data = sample(98.0, 100.0, 3.0);
+        data.data_source = "sysinfo".to_string();
+        detector.apply(&mut data);
+        detector.apply(&mut data);
+        assert!(!data.cpu.unwrap().is_throttling);
+    }
+}
@@ -1,11 +1,485 @@
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
 
 use tauri::{
-    menu::{CheckMenuItem, Menu, MenuItem},
-    tray::TrayIconBuilder,
-    App, Emitter, Manager,
+    image::Image,
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{TrayIcon, TrayIconBuilder},
+    App, AppHandle, Emitter, Manager, WebviewWindow, Wry,
 };
 
+use crate::monitor_pause;
+
+/// Handle to the click-through check item, so changes made through the
+/// `set_click_through` command are reflected in the tray.
+struct ClickThroughItem(CheckMenuItem<Wry>);
+
+/// Quick-toggle check items, kept in sync when the settings change
+/// elsewhere.
+struct AlwaysOnTopItem(CheckMenuItem<Wry>);
+struct AlwaysOnBackItem(CheckMenuItem<Wry>);
+struct AutoStartItem(CheckMenuItem<Wry>);
+struct CompactItem(CheckMenuItem<Wry>);
+struct PauseMonitoringItem(CheckMenuItem<Wry>);
+struct DebugServerItem(CheckMenuItem<Wry>);
+
+/// Entries of the "Position" submenu as `(position, label)`.
+const POSITIONS: [(&str, &str); 6] = [
+    ("left", "Left"),
+    ("right", "Right"),
+    ("top-left", "Top left"),
+    ("top-right", "Top right"),
+    ("bottom-left", "Bottom left"),
+    ("bottom-right", "Bottom right"),
+];
+
+/// Menu IDs of the position items are this prefix plus the position.
+const POSITION_ID_PREFIX: &str = "position:";
+
+/// The "Position" check items, in `POSITIONS` order.
+struct PositionItems(Vec<CheckMenuItem<Wry>>);
+
+/// Menu IDs of the profile items are this prefix plus the profile name.
+const PROFILE_ID_PREFIX: &str = "profile:";
+
+/// The "Profiles" submenu, refilled when profiles are saved or deleted.
+struct ProfilesMenu(Submenu<Wry>);
+
+/// Minimum time between tooltip and icon changes.
+const READOUT_INTERVAL_MS: u64 = 1000;
+
+/// The tray icon, kept in `AppState` so each sample can refresh the
+/// tooltip, and optionally the icon, with the current temperatures.
+#[derive(Default)]
+pub struct TrayReadout {
+    icon: Mutex<Option<TrayIcon>>,
+    /// The app logo, restored when the temperature icon is turned off.
+    logo: Mutex<Option<Image<'static>>>,
+    /// Pixels of the temperature icon on display; `None` while the logo is.
+    shown_icon: Mutex<Option<Vec<u8>>>,
+    last_update_ms: AtomicU64,
+    /// Monitoring is paused; the tooltip says so instead of showing readings.
+    paused: AtomicBool,
+    /// Unacknowledged alerts, shown above the readings.
+    alert: Mutex<Option<String>>,
+}
+
+/// Tooltip while monitoring is paused from the tray.
+const PAUSED_TOOLTIP: &str = "Ondo — paused";
+
+impl TrayReadout {
+    fn set_icon(&self, icon: TrayIcon, logo: Image<'static>) {
+        if let Ok(mut slot) = self.icon.lock() {
+            *slot = Some(icon);
+        }
+        if let Ok(mut slot) = self.logo.lock() {
+            *slot = Some(logo);
+        }
+    }
+
+    /// Show the temperatures (in °C) in the tooltip and, with
+    /// `show_temperature`, the CPU temperature as the icon. At most once per
+    /// `READOUT_INTERVAL_MS`.
+    pub fn update(
+        &self,
+        cpu: Option<f32>,
+        gpu: Option<f32>,
+        fahrenheit: bool,
+        show_temperature: bool,
+        now: u64,
+    ) {
+        if self.paused.load(Ordering::SeqCst) {
+            return;
+        }
+        let last = self.last_update_ms.load(Ordering::SeqCst);
+        if now.saturating_sub(last) < READOUT_INTERVAL_MS {
+            return;
+        }
+        // Concurrent samples: only the one that claims the slot updates
+        if self
+            .last_update_ms
+            .compare_exchange(last, now, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return;
+        }
+        let Ok(icon) = self.icon.lock() else {
+            return;
+        };
+        let Some(icon) = icon.as_ref() else {
+            return;
+        };
+        let readings = tooltip_text(cpu, gpu, fahrenheit);
+        let tooltip = match self.alert.lock().ok().and_then(|alert| alert.clone()) {
+            Some(alert) => format!("{}\n{}", alert, readings),
+            None => readings,
+        };
+        let _ = icon.set_tooltip(Some(tooltip));
+
+        if show_temperature {
+            let rgba = temperature_icon_rgba(cpu, fahrenheit);
+            let Ok(mut shown) = self.shown_icon.lock() else {
+                return;
+            };
+            // Most samples don't change the rounded reading
+            if shown.as_ref() != Some(&rgba) {
+                let image = Image::new_owned(rgba.clone(), ICON_SIZE, ICON_SIZE);
+                if let Err(e) = icon.set_icon(Some(image)) {
+                    crate::log_warn!("Tray", "Failed to set temperature icon: {}", e);
+                }
+                *shown = Some(rgba);
+            }
+        } else {
+            self.restore_logo_on(icon);
+        }
+    }
+
+    /// Show `PAUSED_TOOLTIP` and the logo while paused. On resume the next
+    /// sample brings the readings back.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+        if !paused {
+            self.last_update_ms.store(0, Ordering::SeqCst);
+            return;
+        }
+        if let Ok(icon) = self.icon.lock() {
+            if let Some(icon) = icon.as_ref() {
+                let _ = icon.set_tooltip(Some(PAUSED_TOOLTIP));
+                self.restore_logo_on(icon);
+            }
+        }
+    }
+
+    /// Alert lines for the tooltip, from the next sample on.
+    pub fn set_alert(&self, alert: Option<String>) {
+        if let Ok(mut slot) = self.alert.lock() {
+            *slot = alert;
+        }
+        self.last_update_ms.store(0, Ordering::SeqCst);
+    }
+
+    /// Put the app logo back if the temperature icon is showing.
+    pub fn restore_logo(&self) {
+        if let Ok(icon) = self.icon.lock() {
+            if let Some(icon) = icon.as_ref() {
+                self.restore_logo_on(icon);
+            }
+        }
+    }
+
+    fn restore_logo_on(&self, icon: &TrayIcon) {
+        let Ok(mut shown) = self.shown_icon.lock() else {
+            return;
+        };
+        if shown.take().is_some() {
+            let logo = self.logo.lock().ok().and_then(|logo| logo.clone());
+            let _ = icon.set_icon(logo);
+        }
+    }
+}
+
+/// e.g. "CPU 62°C · GPU 58°C". A missing or zero reading (no sensor) shows
+/// as "—".
+fn tooltip_text(cpu: Option<f32>, gpu: Option<f32>, fahrenheit: bool) -> String {
+    let format = |celsius: Option<f32>| match celsius.filter(|t| *t > 0.0) {
+        Some(t) if fahrenheit => format!("{:.0}°F", t * 9.0 / 5.0 + 32.0),
+        Some(t) => format!("{:.0}°C", t),
+        None => "—".to_string(),
+    };
+    format!("CPU {} · GPU {}", format(cpu), format(gpu))
+}
+
+/// Tray icon edge length in pixels. Windows scales it to the tray size.
+const ICON_SIZE: u32 = 32;
+
+/// CPU temperature (°C) thresholds for the yellow and red backgrounds.
+const ICON_WARM_CELSIUS: f32 = 70.0;
+const ICON_HOT_CELSIUS: f32 = 85.0;
+
+/// 3x5 pixel glyphs for the icon text, one row per byte, high bit left.
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        _ => [0b000, 0b000, 0b111, 0b000, 0b000],
+    }
+}
+
+/// RGBA pixels of the tray icon for a CPU temperature in °C: the rounded
+/// reading on a green, yellow or red background, or "--" on grey when
+/// there is no reading.
+fn temperature_icon_rgba(celsius: Option<f32>, fahrenheit: bool) -> Vec<u8> {
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+
+    let celsius = celsius.filter(|t| *t > 0.0);
+    let (background, foreground) = match celsius {
+        None => ([96, 96, 96, 255], WHITE),
+        Some(t) if t >= ICON_HOT_CELSIUS => ([204, 40, 40, 255], WHITE),
+        Some(t) if t >= ICON_WARM_CELSIUS => ([240, 190, 0, 255], BLACK),
+        Some(_) => ([30, 150, 60, 255], WHITE),
+    };
+    let text = match celsius {
+        Some(t) if fahrenheit => format!("{:.0}", (t * 9.0 / 5.0 + 32.0).min(999.0)),
+        Some(t) => format!("{:.0}", t.min(999.0)),
+        None => "--".to_string(),
+    };
+
+    let mut rgba: Vec<u8> = background
+        .iter()
+        .copied()
+        .cycle()
+        .take((ICON_SIZE * ICON_SIZE * 4) as usize)
+        .collect();
+
+    // Glyphs are 3 units wide with 1 unit between them; scale to fill the
+    // width, then center.
+    let chars = text.chars().count() as u32;
+    let text_units = chars * 4 - 1;
+    let scale = ((ICON_SIZE - 2) / text_units).clamp(1, (ICON_SIZE - 2) / 5);
+    let left = (ICON_SIZE - text_units * scale) / 2;
+    let top = (ICON_SIZE - 5 * scale) / 2;
+
+    for (i, c) in text.chars().enumerate() {
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..3u32 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                let x0 = left + (i as u32 * 4 + col) * scale;
+                let y0 = top + row as u32 * scale;
+                for y in y0..y0 + scale {
+                    for x in x0..x0 + scale {
+                        let offset = ((y * ICON_SIZE + x) * 4) as usize;
+                        rgba[offset..offset + 4].copy_from_slice(&foreground);
+                    }
+                }
+            }
+        }
+    }
+    rgba
+}
+
+/// Show and focus the main window. All show/hide goes through here and
+/// `toggle_main_window` so sampling can pause while the window is hidden.
+fn show_main_window(app: &AppHandle) -> Option<WebviewWindow> {
+    let window = app.get_webview_window("main")?;
+    let _ = window.show();
+    let _ = window.set_focus();
+    monitor_pause::set_window_visible(app, true);
+    Some(window)
+}
+
+/// Hide the main window to the tray. Monitoring keeps running.
+pub fn hide_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+        monitor_pause::set_window_visible(app, false);
+    }
+}
+
+/// Hide the main window if visible, otherwise show and focus it. Shared by
+/// the tray left-click and the global shortcut.
+pub fn toggle_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            hide_main_window(app);
+        } else {
+            show_main_window(app);
+        }
+    }
+}
+
+/// Show the main window and ask the UI to open the settings panel.
+pub fn open_settings(app: &AppHandle) {
+    if let Some(window) = show_main_window(app) {
+        let _ = window.emit("open-settings", ());
+    }
+}
+
+/// Show the main window and ask the UI to display the release notes for the
+/// running version.
+pub fn show_release_notes(app: &AppHandle) {
+    if let Some(window) = show_main_window(app) {
+        let _ = window.emit("show-release-notes", crate::release_notes::current_notes());
+    }
+}
+
+pub fn set_click_through_checked(app: &AppHandle, enabled: bool) {
+    if let Some(item) = app.try_state::<ClickThroughItem>() {
+        let _ = item.0.set_checked(enabled);
+    }
+}
+
+pub fn set_always_on_top_checked(app: &AppHandle, enabled: bool) {
+    if let Some(item) = app.try_state::<AlwaysOnTopItem>() {
+        let _ = item.0.set_checked(enabled);
+    }
+}
+
+pub fn set_always_on_back_checked(app: &AppHandle, enabled: bool) {
+    if let Some(item) = app.try_state::<AlwaysOnBackItem>() {
+        let _ = item.0.set_checked(enabled);
+    }
+}
+
+pub fn set_auto_start_checked(app: &AppHandle, enabled: bool) {
+    if let Some(item) = app.try_state::<AutoStartItem>() {
+        let _ = item.0.set_checked(enabled);
+    }
+}
+
+pub fn set_debug_server_checked(app: &AppHandle, enabled: bool) {
+    if let Some(item) = app.try_state::<DebugServerItem>() {
+        let _ = item.0.set_checked(enabled);
+    }
+}
+
+pub fn set_pause_monitoring_checked(app: &AppHandle, paused: bool) {
+    if let Some(item) = app.try_state::<PauseMonitoringItem>() {
+        let _ = item.0.set_checked(paused);
+    }
+}
+
+pub fn set_compact_checked(app: &AppHandle, enabled: bool) {
+    if let Some(item) = app.try_state::<CompactItem>() {
+        let _ = item.0.set_checked(enabled);
+    }
+}
+
+/// Check the entry for `position` in the "Position" submenu and clear the
+/// others.
+pub fn set_position_checked(app: &AppHandle, position: &str) {
+    if let Some(items) = app.try_state::<PositionItems>() {
+        for ((name, _), item) in POSITIONS.iter().zip(&items.0) {
+            let _ = item.set_checked(*name == position);
+        }
+    }
+}
+
+/// Replace the entries of the "Profiles" submenu with one per name.
+fn fill_profiles_menu<M: Manager<Wry>>(
+    manager: &M,
+    menu: &Submenu<Wry>,
+    names: &[String],
+) -> tauri::Result<()> {
+    while menu.remove_at(0)?.is_some() {}
+    if names.is_empty() {
+        let empty = MenuItem::new(manager, "No saved profiles", false, None::<&str>)?;
+        return menu.append(&empty);
+    }
+    for name in names {
+        let item = MenuItem::with_id(
+            manager,
+            format!("{}{}", PROFILE_ID_PREFIX, name),
+            name,
+            true,
+            None::<&str>,
+        )?;
+        menu.append(&item)?;
+    }
+    Ok(())
+}
+
+/// Show `names` in the "Profiles" submenu.
+pub fn set_profiles(app: &AppHandle, names: &[String]) {
+    if let Some(menu) = app.try_state::<ProfilesMenu>() {
+        if let Err(e) = fill_profiles_menu(app, &menu.0, names) {
+            crate::log_warn!("Tray", "Failed to update the profiles menu: {}", e);
+        }
+    }
+}
+
+/// Flip always-on-top from the tray. Turning it on clears always-on-back,
+/// as the settings panel does.
+fn toggle_always_on_top(app: &AppHandle) -> Result<(), String> {
+    let enabled = !app.state::<crate::AppState>().settings.lock().always_on_top;
+    let patch = if enabled {
+        serde_json::json!({ "alwaysOnTop": true, "alwaysOnBack": false })
+    } else {
+        serde_json::json!({ "alwaysOnTop": false })
+    };
+    crate::commands::settings::apply_settings_patch(app, &patch).map_err(|e| e.to_string())?;
+
+    if let Some(window) = app.get_webview_window("main") {
+        if enabled {
+            crate::commands::window::pin_on_back(&window, false)?;
+        }
+        crate::commands::window::pin_on_top(&window, enabled)?;
+    }
+    Ok(())
+}
+
+/// Flip always-on-back from the tray. Turning it on clears always-on-top.
+fn toggle_always_on_back(app: &AppHandle) -> Result<(), String> {
+    let enabled = !app
+        .state::<crate::AppState>()
+        .settings
+        .lock()
+        .always_on_back;
+    let patch = if enabled {
+        serde_json::json!({ "alwaysOnBack": true, "alwaysOnTop": false })
+    } else {
+        serde_json::json!({ "alwaysOnBack": false })
+    };
+    crate::commands::settings::apply_settings_patch(app, &patch).map_err(|e| e.to_string())?;
+
+    if let Some(window) = app.get_webview_window("main") {
+        if enabled {
+            crate::commands::window::pin_on_top(&window, false)?;
+        }
+        crate::commands::window::pin_on_back(&window, enabled)?;
+    }
+    Ok(())
+}
+
+/// Flip auto start from the tray: change the login entry, then store the
+/// setting once that worked.
+async fn toggle_auto_start(app: &AppHandle) -> Result<(), String> {
+    let enabled = !app.state::<crate::AppState>().settings.lock().auto_start;
+    crate::commands::settings::set_auto_start(enabled).await?;
+    crate::commands::settings::apply_settings_patch(
+        app,
+        &serde_json::json!({ "autoStart": enabled }),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Move the widget to a position preset from the tray and store it, as
+/// choosing it in the settings panel does.
+fn move_to_position(app: &AppHandle, position: &str) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("main") {
+        let margin = crate::commands::window::position_margin(app);
+        crate::commands::window::move_to_anchor(&window, position, margin)?;
+    }
+    crate::commands::settings::apply_settings_patch(
+        app,
+        &serde_json::json!({ "position": position }),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Flip compact mode from the tray. The UI applies it on `settings-changed`.
+fn toggle_compact(app: &AppHandle) -> Result<(), String> {
+    let enabled = !app.state::<crate::AppState>().settings.lock().compact_mode;
+    crate::commands::settings::apply_settings_patch(
+        app,
+        &serde_json::json!({ "compactMode": enabled }),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
     // Read initial debug-server state from AppState so the check mark
     // reflects the running listener.
@@ -13,10 +487,35 @@ pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
         .state::<crate::AppState>()
         .debug_server_running
         .load(Ordering::SeqCst);
+    let initial = app.state::<crate::AppState>().settings.lock().clone();
 
     // Create menu items
     let show = MenuItem::with_id(app, "show", "Show Ondo", true, None::<&str>)?;
     let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
+    let position_items = POSITIONS
+        .iter()
+        .map(|(name, label)| {
+            CheckMenuItem::with_id(
+                app,
+                format!("{}{}", POSITION_ID_PREFIX, name),
+                *label,
+                true,
+                *name == initial.position,
+                None::<&str>,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let position_refs: Vec<&dyn IsMenuItem<Wry>> = position_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let position = Submenu::with_items(app, "Position", true, &position_refs)?;
+    let profiles = Submenu::new(app, "Profiles", true)?;
+    fill_profiles_menu(
+        app,
+        &profiles,
+        &crate::commands::profiles::saved_profile_names(),
+    )?;
     let debug_server = CheckMenuItem::with_id(
         app,
         "debug_server",
@@ -25,38 +524,191 @@ pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
         debug_server_initial,
         None::<&str>,
     )?;
-    let separator = MenuItem::with_id(app, "sep", "─────────", false, None::<&str>)?;
+    // Always reachable from the tray, since a click-through window can't
+    // be clicked to turn the mode off.
+    let click_through = CheckMenuItem::with_id(
+        app,
+        "click_through",
+        "Click-through",
+        true,
+        initial.click_through,
+        None::<&str>,
+    )?;
+    let always_on_top = CheckMenuItem::with_id(
+        app,
+        "toggle_always_on_top",
+        "Always on top",
+        true,
+        initial.always_on_top,
+        None::<&str>,
+    )?;
+    let always_on_back = CheckMenuItem::with_id(
+        app,
+        "toggle_always_on_back",
+        "Always on back",
+        true,
+        initial.always_on_back,
+        None::<&str>,
+    )?;
+    let auto_start = CheckMenuItem::with_id(
+        app,
+        "toggle_auto_start",
+        "Start at login",
+        true,
+        initial.auto_start,
+        None::<&str>,
+    )?;
+    let compact = CheckMenuItem::with_id(
+        app,
+        "toggle_compact",
+        "Compact mode",
+        true,
+        initial.compact_mode,
+        None::<&str>,
+    )?;
+    let pause_monitoring = CheckMenuItem::with_id(
+        app,
+        "toggle_pause_monitoring",
+        "Pause monitoring",
+        true,
+        initial.monitoring_paused,
+        None::<&str>,
+    )?;
+    let whats_new = MenuItem::with_id(
+        app,
+        "whats_new",
+        format!("About Ondo {} / What's new", env!("CARGO_PKG_VERSION")),
+        true,
+        None::<&str>,
+    )?;
     let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
 
-    // Build menu
+    // Build menu: window, widget placement, app behavior, about, quit.
+    // Native separators, which screen readers skip.
     let menu = Menu::with_items(
         app,
-        &[&show, &settings, &debug_server, &separator, &quit],
+        &[
+            &show,
+            &settings,
+            &PredefinedMenuItem::separator(app)?,
+            &position,
+            &profiles,
+            &always_on_top,
+            &always_on_back,
+            &compact,
+            &click_through,
+            &PredefinedMenuItem::separator(app)?,
+            &pause_monitoring,
+            &auto_start,
+            &debug_server,
+            &PredefinedMenuItem::separator(app)?,
+            &whats_new,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
     )?;
 
     // The check item handle is captured by the menu-event closure so we can
     // sync its state if the toggle succeeds.
     let debug_server_check = debug_server.clone();
+    app.manage(ClickThroughItem(click_through));
+    app.manage(AlwaysOnTopItem(always_on_top));
+    app.manage(AlwaysOnBackItem(always_on_back));
+    app.manage(AutoStartItem(auto_start));
+    app.manage(CompactItem(compact));
+    app.manage(PauseMonitoringItem(pause_monitoring));
+    app.manage(DebugServerItem(debug_server));
+    app.manage(PositionItems(position_items));
+    app.manage(ProfilesMenu(profiles));
 
     // Create tray icon
-    let _tray = TrayIconBuilder::new()
-        .icon(app.default_window_icon().unwrap().clone())
+    let logo = app.default_window_icon().unwrap().clone().to_owned();
+    let tray = TrayIconBuilder::new()
+        .icon(logo.clone())
         .menu(&menu)
         .tooltip("Ondo - Hardware Monitor")
         .on_menu_event(move |app, event| {
             match event.id.as_ref() {
                 "show" => {
-                    if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
+                    show_main_window(app);
+                }
+                "settings" => open_settings(app),
+                "whats_new" => show_release_notes(app),
+                "toggle_always_on_top" => {
+                    if let Err(e) = toggle_always_on_top(app) {
+                        crate::log_error!("Tray", "Failed to toggle always on top: {}", e);
+                    }
+                    // Menus flip the check mark themselves; show the stored state
+                    let stored = app.state::<crate::AppState>().settings.lock().always_on_top;
+                    set_always_on_top_checked(app, stored);
+                }
+                "toggle_always_on_back" => {
+                    if let Err(e) = toggle_always_on_back(app) {
+                        crate::log_error!("Tray", "Failed to toggle always on back: {}", e);
+                    }
+                    let stored = app
+                        .state::<crate::AppState>()
+                        .settings
+                        .lock()
+                        .always_on_back;
+                    set_always_on_back_checked(app, stored);
+                }
+                "toggle_auto_start" => {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = toggle_auto_start(&app).await {
+                            crate::log_error!("Tray", "Failed to toggle auto start: {}", e);
+                        }
+                        let stored = app.state::<crate::AppState>().settings.lock().auto_start;
+                        set_auto_start_checked(&app, stored);
+                    });
+                }
+                "toggle_compact" => {
+                    if let Err(e) = toggle_compact(app) {
+                        crate::log_error!("Tray", "Failed to toggle compact mode: {}", e);
                     }
+                    let stored = app.state::<crate::AppState>().settings.lock().compact_mode;
+                    set_compact_checked(app, stored);
+                }
+                id if id.starts_with(POSITION_ID_PREFIX) => {
+                    let position = id.trim_start_matches(POSITION_ID_PREFIX);
+                    if let Err(e) = move_to_position(app, position) {
+                        crate::log_error!("Tray", "Failed to move to {}: {}", position, e);
+                    }
+                    let stored = app
+                        .state::<crate::AppState>()
+                        .settings
+                        .lock()
+                        .position
+                        .clone();
+                    set_position_checked(app, &stored);
+                }
+                id if id.starts_with(PROFILE_ID_PREFIX) => {
+                    let name = id.trim_start_matches(PROFILE_ID_PREFIX).to_string();
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) =
+                            crate::commands::profiles::apply_profile_by_name(&app, &name).await
+                        {
+                            crate::log_error!("Tray", "Failed to apply profile {}: {}", name, e);
+                        }
+                    });
                 }
-                "settings" => {
-                    if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                        // Emit event to open settings
-                        let _ = window.emit("open-settings", ());
+                "toggle_pause_monitoring" => {
+                    let current = monitor_pause::is_monitoring_paused();
+                    if let Err(e) =
+                        crate::commands::hardware::apply_monitoring_paused(app, !current)
+                    {
+                        crate::log_error!("Tray", "Failed to toggle pause monitoring: {}", e);
+                        set_pause_monitoring_checked(app, current);
+                    }
+                }
+                "click_through" => {
+                    let state = app.state::<crate::AppState>();
+                    let current = state.settings.lock().click_through;
+                    if let Err(e) = crate::commands::window::apply_click_through(app, !current) {
+                        crate::log_error!("Tray", "Failed to toggle click-through: {}", e);
+                        set_click_through_checked(app, current);
                     }
                 }
                 "debug_server" => {
@@ -81,19 +733,10 @@ pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
                     let _ = debug_server_check.set_checked(new_state);
 
                     // Persist the new value so it survives restart.
-                    let settings_to_save = match state.settings.lock() {
-                        Ok(mut guard) => {
-                            guard.debug_server = new_state;
-                            guard.clone()
-                        }
-                        Err(e) => {
-                            crate::log_error!(
-                                "Tray",
-                                "settings lock poisoned while persisting debug_server: {}",
-                                e
-                            );
-                            return;
-                        }
+                    let settings_to_save = {
+                        let mut guard = state.settings.lock();
+                        guard.debug_server = new_state;
+                        guard.clone()
                     };
                     tauri::async_runtime::spawn(async move {
                         if let Err(e) =
@@ -114,6 +757,8 @@ pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
                     );
                 }
                 "quit" => {
+                    crate::commands::window::save_window_state_on_exit(app);
+                    crate::error_reporting::flush(crate::error_reporting::SHUTDOWN_FLUSH_TIMEOUT);
                     app.exit(0);
                 }
                 _ => {}
@@ -126,18 +771,71 @@ pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
                 ..
             } = event
             {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    if window.is_visible().unwrap_or(false) {
-                        let _ = window.hide();
-                    } else {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
-                }
+                toggle_main_window(tray.app_handle());
             }
         })
         .build(app)?;
+    app.state::<crate::AppState>()
+        .tray_readout
+        .set_icon(tray, logo);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tooltip_shows_both_temperatures() {
+        assert_eq!(
+            tooltip_text(Some(62.4), Some(57.6), false),
+            "CPU 62°C · GPU 58°C"
+        );
+        assert_eq!(
+            tooltip_text(Some(62.0), Some(58.0), true),
+            "CPU 144°F · GPU 136°F"
+        );
+    }
+
+    #[test]
+    fn tooltip_marks_missing_readings() {
+        assert_eq!(tooltip_text(None, Some(58.0), false), "CPU — · GPU 58°C");
+        // A CPU without PawnIO reports 0
+        assert_eq!(tooltip_text(Some(0.0), None, false), "CPU — · GPU —");
+    }
+
+    fn pixel(rgba: &[u8], x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * ICON_SIZE + x) * 4) as usize;
+        rgba[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn icon_background_follows_thresholds() {
+        let corner = |celsius| pixel(&temperature_icon_rgba(celsius, false), 0, 0);
+        assert_eq!(corner(Some(55.0)), [30, 150, 60, 255]);
+        assert_eq!(corner(Some(ICON_WARM_CELSIUS)), [240, 190, 0, 255]);
+        assert_eq!(corner(Some(ICON_HOT_CELSIUS)), [204, 40, 40, 255]);
+        assert_eq!(corner(None), [96, 96, 96, 255]);
+        // Thresholds apply to °C whatever the display unit
+        assert_eq!(
+            pixel(&temperature_icon_rgba(Some(55.0), true), 0, 0),
+            [30, 150, 60, 255]
+        );
+    }
+
+    #[test]
+    fn icon_draws_centered_digits() {
+        let rgba = temperature_icon_rgba(Some(88.0), false);
+        assert_eq!(rgba.len(), (ICON_SIZE * ICON_SIZE * 4) as usize);
+        // "88" is 7 units wide at scale 4: x 2..30, y 6..26. The top-left
+        // pixel of the first 8 is lit, the gap column between digits isn't.
+        assert_eq!(pixel(&rgba, 2, 6), [255, 255, 255, 255]);
+        assert_eq!(pixel(&rgba, 14, 6), [204, 40, 40, 255]);
+        assert_eq!(pixel(&rgba, 1, 6), [204, 40, 40, 255]);
+
+        // Three digits still fit
+        let rgba = temperature_icon_rgba(Some(50.0), true);
+        assert_ne!(rgba, temperature_icon_rgba(Some(50.0), false));
+    }
+}
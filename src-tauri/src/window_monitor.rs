@@ -15,6 +15,11 @@ struct CursorNearPayload {
     near: bool,
 }
 
+/// Whether the widget is in mini mode (a maximized window on its display).
+pub fn is_mini_mode() -> bool {
+    LAST_STATE.load(Ordering::Relaxed)
+}
+
 /// Start background monitoring for maximized/fullscreen windows on Ondo's
 /// display. Emits `"minimode-changed"` whenever the answer flips.
 pub fn start_monitoring(app: AppHandle) {
@@ -66,6 +71,14 @@ pub fn start_monitoring(app: AppHandle) {
                             "deactivated"
                         }
                     );
+                    crate::error_reporting::add_breadcrumb(
+                        "window",
+                        if is_maximized {
+                            "Entered mini mode"
+                        } else {
+                            "Left mini mode"
+                        },
+                    );
                 }
             }
 
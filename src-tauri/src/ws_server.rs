@@ -0,0 +1,416 @@
+//! Optional WebSocket feed of hardware samples for external tools
+//! (`websocket_server` setting). Every sample handed to the UI is also
+//! broadcast here, so enabling it never samples the hardware twice.
+//!
+//! Clients connect to `ws://127.0.0.1:<port>/?token=<token>` (or send the
+//! token as `Authorization: Bearer <token>`) and receive each sample as the
+//! same JSON `get_hardware_data` returns. Sending `{"subscribe": ["cpu",
+//! "gpu"]}` limits frames to those fields plus `timestamp`.
+//!
+//! Without a token only non-browser clients are accepted: any web page can
+//! open a socket to localhost, but browsers always send `Origin` with it.
+
+use crate::settings::AppSettings;
+use crate::{metrics, HardwareData};
+use futures_util::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, watch};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+pub const DEFAULT_PORT: u16 = 19211;
+
+/// Samples queued per client. A client that falls further behind skips the
+/// oldest frames instead of growing the queue.
+const FRAME_BUFFER: usize = 4;
+
+/// Connections beyond this are closed right after accept.
+pub const MAX_CLIENTS: u64 = 16;
+
+/// A client whose socket doesn't drain within this is disconnected.
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Top-level `HardwareData` keys as they appear in JSON.
+static SAMPLE_FIELDS: Lazy<BTreeSet<String>> = Lazy::new(|| {
+    schemars::schema_for!(HardwareData)
+        .schema
+        .object
+        .map(|object| object.properties.into_keys().collect())
+        .unwrap_or_default()
+});
+
+static SAMPLES: Lazy<broadcast::Sender<Arc<Value>>> =
+    Lazy::new(|| broadcast::channel(FRAME_BUFFER).0);
+
+#[derive(Debug, Clone, PartialEq)]
+struct ServerConfig {
+    port: u16,
+    allow_lan: bool,
+    token: Option<String>,
+}
+
+impl ServerConfig {
+    /// `None` when the server is disabled.
+    fn from_settings(settings: &AppSettings) -> Option<Self> {
+        settings.websocket_server.then(|| Self {
+            port: settings.websocket_port,
+            allow_lan: settings.websocket_allow_lan,
+            token: settings.websocket_token.clone(),
+        })
+    }
+}
+
+struct RunningServer {
+    config: ServerConfig,
+    /// Dropping this stops the accept loop and every client.
+    _shutdown: watch::Sender<()>,
+}
+
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+/// Start, restart or stop the server to match `settings`. No-op when the
+/// running server already matches. Fails when the port can't be bound or
+/// LAN access is requested without a token.
+pub fn configure(settings: &AppSettings) -> Result<(), String> {
+    let wanted = ServerConfig::from_settings(settings);
+    let mut server = SERVER.lock().map_err(|e| e.to_string())?;
+    if server.as_ref().map(|s| &s.config) == wanted.as_ref() {
+        return Ok(());
+    }
+    if server.take().is_some() {
+        crate::log_info!("WsServer", "Stopped");
+    }
+    let Some(config) = wanted else {
+        return Ok(());
+    };
+    if config.allow_lan && config.token.is_none() {
+        return Err("A WebSocket token is required to accept LAN connections".to_string());
+    }
+
+    let ip = if config.allow_lan {
+        Ipv4Addr::UNSPECIFIED
+    } else {
+        Ipv4Addr::LOCALHOST
+    };
+    let addr = SocketAddr::from((ip, config.port));
+    // Bind here rather than in the task so a busy port is reported to the
+    // caller
+    let listener = std::net::TcpListener::bind(addr)
+        .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+        .map_err(|e| format!("Failed to bind WebSocket server to {}: {}", addr, e))?;
+    crate::log_info!("WsServer", "Listening on ws://{}", addr);
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(());
+    let task = metrics::task_started();
+    let token = config.token.clone();
+    tauri::async_runtime::spawn(async move {
+        let _task = task;
+        serve(listener, token, shutdown_rx).await
+    });
+    *server = Some(RunningServer {
+        config,
+        _shutdown: shutdown_tx,
+    });
+    Ok(())
+}
+
+/// Stop the server and disconnect all clients. Called on app exit.
+pub fn stop() {
+    if let Ok(mut server) = SERVER.lock() {
+        if server.take().is_some() {
+            crate::log_info!("WsServer", "Stopped");
+        }
+    }
+}
+
+/// Broadcast a sample to connected clients. Cheap when nobody is connected.
+pub fn publish(data: &HardwareData) {
+    if SAMPLES.receiver_count() == 0 {
+        return;
+    }
+    match serde_json::to_value(data) {
+        // Only fails when there are no receivers left
+        Ok(value) => drop(SAMPLES.send(Arc::new(value))),
+        Err(e) => crate::log_warn!("WsServer", "Failed to serialize sample: {}", e),
+    }
+}
+
+async fn serve(
+    listener: std::net::TcpListener,
+    token: Option<String>,
+    mut shutdown: watch::Receiver<()>,
+) {
+    let listener = match TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            crate::log_error!("WsServer", "Failed to start listener: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        let (stream, peer) = tokio::select! {
+            _ = shutdown.changed() => break,
+            conn = listener.accept() => match conn {
+                Ok(conn) => conn,
+                Err(e) => {
+                    crate::log_error!("WsServer", "Accept error: {}", e);
+                    continue;
+                }
+            },
+        };
+        if metrics::WS_CLIENTS.get() >= MAX_CLIENTS {
+            crate::log_warn!("WsServer", "Rejected {}: too many clients", peer);
+            continue;
+        }
+
+        let client = ClientGuard::new();
+        let token = token.clone();
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let _client = client;
+            match handle_client(stream, token, shutdown).await {
+                Ok(()) => crate::log_debug!("WsServer", "{} disconnected", peer),
+                Err(e) => crate::log_debug!("WsServer", "{} dropped: {}", peer, e),
+            }
+        });
+    }
+}
+
+/// Counts a connected client until dropped.
+struct ClientGuard(());
+
+impl ClientGuard {
+    fn new() -> Self {
+        metrics::WS_CLIENTS.inc();
+        Self(())
+    }
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        metrics::WS_CLIENTS.dec();
+    }
+}
+
+async fn handle_client(
+    stream: TcpStream,
+    token: Option<String>,
+    mut shutdown: watch::Receiver<()>,
+) -> Result<(), String> {
+    // The error response type is fixed by tungstenite
+    #[allow(clippy::result_large_err)]
+    let check_token = |request: &Request, response: Response| {
+        let header = |name: &str| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        if authorized(
+            token.as_deref(),
+            request.uri().query(),
+            header("authorization"),
+            header("origin"),
+        ) {
+            Ok(response)
+        } else {
+            let mut rejection = ErrorResponse::new(Some("Invalid or missing token".to_string()));
+            *rejection.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(rejection)
+        }
+    };
+    let socket = tokio::time::timeout(
+        HANDSHAKE_TIMEOUT,
+        tokio_tungstenite::accept_hdr_async(stream, check_token),
+    )
+    .await
+    .map_err(|_| "handshake timed out".to_string())?
+    .map_err(|e| e.to_string())?;
+
+    let (mut sink, mut incoming) = socket.split();
+    let mut samples = SAMPLES.subscribe();
+    let mut subscription: Option<BTreeSet<String>> = None;
+
+    loop {
+        let reply = tokio::select! {
+            _ = shutdown.changed() => {
+                let _ = sink.send(Message::Close(None)).await;
+                return Ok(());
+            }
+            message = incoming.next() => match message {
+                Some(Ok(Message::Text(text))) => match parse_subscribe(text.as_str()) {
+                    Ok(fields) => {
+                        subscription = Some(fields);
+                        continue;
+                    }
+                    Err(e) => serde_json::json!({ "error": e }),
+                },
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.to_string()),
+            },
+            sample = samples.recv() => match sample {
+                Ok(sample) => filter_sample(&sample, subscription.as_ref()),
+                // Slow client: the skipped frames are simply gone
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+        };
+
+        tokio::time::timeout(SEND_TIMEOUT, sink.send(Message::text(reply.to_string())))
+            .await
+            .map_err(|_| "send timed out".to_string())?
+            .map_err(|e| e.to_string())?;
+    }
+}
+
+/// Whether a handshake presents the configured token, either as a `token`
+/// query parameter or an `Authorization: Bearer` header. When no token is
+/// configured, anything but a browser (a handshake with an `Origin`) goes.
+fn authorized(
+    token: Option<&str>,
+    query: Option<&str>,
+    authorization: Option<&str>,
+    origin: Option<&str>,
+) -> bool {
+    let Some(token) = token else {
+        return origin.is_none();
+    };
+    let from_query = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .filter_map(|pair| pair.strip_prefix("token="));
+    let from_header = authorization.and_then(|value| value.strip_prefix("Bearer "));
+    from_query
+        .chain(from_header)
+        .any(|presented| presented == token)
+}
+
+#[derive(Deserialize)]
+struct SubscribeMessage {
+    subscribe: Vec<String>,
+}
+
+/// Parse a `{"subscribe": [...]}` message into the fields to send.
+fn parse_subscribe(text: &str) -> Result<BTreeSet<String>, String> {
+    let message: SubscribeMessage = serde_json::from_str(text)
+        .map_err(|_| r#"expected {"subscribe": ["cpu", ...]}"#.to_string())?;
+    let unknown: Vec<&str> = message
+        .subscribe
+        .iter()
+        .filter(|field| !SAMPLE_FIELDS.contains(*field))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("unknown fields: {}", unknown.join(", ")));
+    }
+    Ok(message.subscribe.into_iter().collect())
+}
+
+/// The sample restricted to `fields`, always keeping `timestamp`. All of it
+/// when the client hasn't subscribed.
+fn filter_sample(sample: &Value, fields: Option<&BTreeSet<String>>) -> Value {
+    let (Some(fields), Value::Object(object)) = (fields, sample) else {
+        return sample.clone();
+    };
+    let filtered: Map<String, Value> = object
+        .iter()
+        .filter(|(key, _)| *key == "timestamp" || fields.contains(*key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    Value::Object(filtered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn token_accepted_from_query_or_header() {
+        assert!(authorized(None, None, None, None));
+        assert!(authorized(Some("s3cret"), Some("token=s3cret"), None, None));
+        assert!(authorized(
+            Some("s3cret"),
+            Some("a=1&token=s3cret"),
+            None,
+            None
+        ));
+        assert!(authorized(
+            Some("s3cret"),
+            None,
+            Some("Bearer s3cret"),
+            None
+        ));
+        assert!(!authorized(Some("s3cret"), None, None, None));
+        assert!(!authorized(Some("s3cret"), Some("token=wrong"), None, None));
+        assert!(!authorized(
+            Some("s3cret"),
+            Some("token=s3cret2"),
+            None,
+            None
+        ));
+        assert!(!authorized(Some("s3cret"), None, Some("s3cret"), None));
+    }
+
+    #[test]
+    fn browsers_need_a_token() {
+        let page = Some("https://example.com");
+        assert!(!authorized(None, None, None, page));
+        assert!(!authorized(None, None, None, Some("null")));
+        assert!(authorized(Some("s3cret"), Some("token=s3cret"), None, page));
+        assert!(!authorized(Some("s3cret"), None, None, page));
+    }
+
+    #[test]
+    fn subscribe_accepts_only_sample_fields() {
+        let fields = parse_subscribe(r#"{"subscribe": ["cpu", "gpu"]}"#).unwrap();
+        assert_eq!(
+            fields,
+            BTreeSet::from(["cpu".to_string(), "gpu".to_string()])
+        );
+        assert!(parse_subscribe(r#"{"subscribe": ["systemPower"]}"#).is_ok());
+        assert_eq!(
+            parse_subscribe(r#"{"subscribe": ["cpu", "fans"]}"#),
+            Err("unknown fields: fans".to_string())
+        );
+        assert!(parse_subscribe("hello").is_err());
+    }
+
+    #[test]
+    fn filter_keeps_subscribed_fields_and_timestamp() {
+        let sample = json!({ "cpu": { "load": 5 }, "gpu": null, "storage": [], "timestamp": 7 });
+        assert_eq!(filter_sample(&sample, None), sample);
+        let fields = BTreeSet::from(["cpu".to_string()]);
+        assert_eq!(
+            filter_sample(&sample, Some(&fields)),
+            json!({ "cpu": { "load": 5 }, "timestamp": 7 })
+        );
+    }
+
+    #[test]
+    fn disabled_settings_mean_no_server() {
+        let mut settings = AppSettings::default();
+        assert_eq!(ServerConfig::from_settings(&settings), None);
+        settings.websocket_server = true;
+        assert_eq!(
+            ServerConfig::from_settings(&settings),
+            Some(ServerConfig {
+                port: DEFAULT_PORT,
+                allow_lan: false,
+                token: None,
+            })
+        );
+    }
+}
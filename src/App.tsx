@@ -7,11 +7,12 @@ import { BootSequence } from "./components/BootSequence";
 import { SettingsPanel } from "./components/SettingsPanel";
 import { UpdateNotification } from "./components/UpdateNotification";
 import { RestorePanel } from "./components/RestorePanel";
+import { AlertToast } from "./components/AlertToast";
 import { useHardwareData } from "./hooks/useHardwareData";
 import { useSettings } from "./hooks/useSettings";
 import { useUpdater } from "./hooks/useUpdater";
 import { useAudioDevices } from "./hooks/useAudioDevices";
-import type { WindowState, SectionType } from "./types";
+import type { Alert, AppSettings, MonitoringModeStatus, WindowState, SectionType } from "./types";
 import "./styles/App.css";
 
 function App() {
@@ -21,12 +22,23 @@ function App() {
   const [showUpdateNotification, setShowUpdateNotification] = useState(true);
   const [dismissedUpdateVersion, setDismissedUpdateVersion] = useState<string | null>(null);
   const [updateMessage, setUpdateMessage] = useState<string | null>(null);
+  const [alert, setAlert] = useState<Alert | null>(null);
+  const dismissAlert = useCallback(() => setAlert(null), []);
+  const acknowledgeAlert = useCallback((id: number) => {
+    invoke("acknowledge_alert", { id }).catch(console.error);
+    setAlert(null);
+  }, []);
   const [miniMode, setMiniMode] = useState(false);
   const [cursorNear, setCursorNear] = useState(false);
+  const [monitoringPaused, setMonitoringPaused] = useState(false);
+  const [monitoringMode, setMonitoringMode] = useState<MonitoringModeStatus | null>(null);
   const savedWindowStateRef = useRef<WindowState | null>(null);
   const { settings, updateSettings } = useSettings();
-  const { hardwareData, isLoading, error } = useHardwareData(settings.updateInterval);
-  const { updateInfo, checking, downloading, progress, error: updateError, downloadAndInstall, checkForUpdate } = useUpdater();
+  const performanceMode = monitoringMode?.mode === "performance";
+  const pollInterval =
+    monitoringMode?.mode === "performance" ? monitoringMode.intervalMs : settings.updateIntervalFast;
+  const { hardwareData, isLoading, error } = useHardwareData(pollInterval);
+  const { updateInfo, checking, downloading, progress, error: updateError, downloadAndInstall, checkForUpdate, skipVersion } = useUpdater();
   const { devices: audioDevices, switching: audioSwitching, switchDevice: switchAudioDevice } = useAudioDevices();
   const activeUpdateVersion = updateInfo?.available ? (updateInfo.version ?? "__unknown__") : null;
 
@@ -63,6 +75,35 @@ function App() {
     return () => clearTimeout(bootTimer);
   }, []);
 
+  // "Pause monitoring" in the tray; the stored flag covers a paused start
+  useEffect(() => {
+    setMonitoringPaused(!!settings.monitoringPaused);
+  }, [settings.monitoringPaused]);
+
+  useEffect(() => {
+    const unlisten = listen<boolean>("monitoring-paused", (event) => {
+      setMonitoringPaused(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Performance mode polls faster until it times out or is reset
+  useEffect(() => {
+    invoke<MonitoringModeStatus>("get_monitoring_mode")
+      .then(setMonitoringMode)
+      .catch(console.error);
+    const unlisten = listen<MonitoringModeStatus>("monitoring-mode-changed", (event) => {
+      setMonitoringMode(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Listen for tray menu events
   useEffect(() => {
     const unlisten = listen("open-settings", () => {
@@ -74,6 +115,22 @@ function App() {
     };
   }, []);
 
+  // Temperature alerts (quiet hours are applied by the backend)
+  useEffect(() => {
+    const unlisten = listen<Alert>("temperature-alert", (event) => {
+      setAlert(event.payload);
+    });
+    // Cooled down: take the toast away without acknowledging it
+    const unlistenResolved = listen<Alert>("alert-resolved", (event) => {
+      setAlert((current) => (current?.id === event.payload.id ? null : current));
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+      unlistenResolved.then((fn) => fn());
+    };
+  }, []);
+
   // Listen for cursor proximity in mini mode
   useEffect(() => {
     const unlisten = listen<{ near: boolean }>("cursor-near-minimode", (event) => {
@@ -104,6 +161,8 @@ function App() {
           await invoke("set_window_shadow", { enable: false });
           // Make window click-through so users can interact with apps below
           await getCurrentWindow().setIgnoreCursorEvents(true);
+          // Changing click-through drops the window opacity; put it back
+          await invoke("set_window_opacity", { opacity: settings.opacity });
 
           // Wait a frame for React to render mini content, then fit window to it
           requestAnimationFrame(async () => {
@@ -134,8 +193,10 @@ function App() {
           });
           // Re-enable window shadow
           await invoke("set_window_shadow", { enable: true });
-          // Restore click interaction
-          await getCurrentWindow().setIgnoreCursorEvents(false);
+          // Restore click interaction, unless click-through is turned on
+          const stored = await invoke<AppSettings | null>("get_settings");
+          await getCurrentWindow().setIgnoreCursorEvents(stored?.clickThrough ?? false);
+          await invoke("set_window_opacity", { opacity: stored?.opacity ?? settings.opacity });
           // Restore saved window size, then reposition to avoid overflow
           await invoke("restore_window_state", { state: savedWindowStateRef.current });
           savedWindowStateRef.current = null;
@@ -149,7 +210,7 @@ function App() {
     return () => {
       unlisten.then((fn) => fn());
     };
-  }, [settings.position]);
+  }, [settings.position, settings.opacity]);
 
   // Apply theme to document root
   useEffect(() => {
@@ -161,52 +222,6 @@ function App() {
     }
   }, [settings.theme]);
 
-  // Save window state periodically and before close
-  const saveWindowStateRef = useRef<ReturnType<typeof setTimeout> | null>(null);
-
-  const saveWindowState = useCallback(async () => {
-    try {
-      const state = await invoke<WindowState>("get_window_state");
-      if (state) {
-        await updateSettings({ windowState: state });
-      }
-    } catch {
-      // Ignore errors
-    }
-  }, [updateSettings]);
-
-  // Save window state when app is about to close
-  useEffect(() => {
-    const handleBeforeUnload = () => {
-      saveWindowState();
-    };
-
-    window.addEventListener("beforeunload", handleBeforeUnload);
-    return () => {
-      window.removeEventListener("beforeunload", handleBeforeUnload);
-    };
-  }, [saveWindowState]);
-
-  // Periodically save window state (debounced)
-  useEffect(() => {
-    const handleResize = () => {
-      if (saveWindowStateRef.current) {
-        clearTimeout(saveWindowStateRef.current);
-      }
-      saveWindowStateRef.current = setTimeout(() => {
-        saveWindowState();
-      }, 1000);
-    };
-
-    window.addEventListener("resize", handleResize);
-    return () => {
-      window.removeEventListener("resize", handleResize);
-      if (saveWindowStateRef.current) {
-        clearTimeout(saveWindowStateRef.current);
-      }
-    };
-  }, [saveWindowState]);
-
   const handleSettingsToggle = () => {
     setShowSettings(!showSettings);
   };
@@ -234,7 +249,8 @@ function App() {
   return (
     <div
       className={`app-container${miniMode ? " mini" : ""}`}
-      style={{ opacity: miniMode ? (cursorNear ? (settings.opacity / 100) * 0.1 : (settings.opacity / 100) * 0.5) : settings.opacity / 100 }}
+      // The opacity setting is applied to the whole window by the backend
+      style={{ opacity: miniMode ? (cursorNear ? 0.1 : 0.5) : 1 }}
     >
       {!miniMode && <div className="scanlines" />}
       <HudWidget
@@ -253,6 +269,9 @@ function App() {
         miniMode={miniMode}
         compactMode={settings.compactMode}
         temperatureUnit={settings.temperatureUnit}
+        updateInterval={pollInterval}
+        monitoringPaused={monitoringPaused}
+        performanceMode={performanceMode}
       />
       {showSettings && (
         <SettingsPanel
@@ -283,11 +302,22 @@ function App() {
           error={updateError}
           onUpdate={downloadAndInstall}
           onDismiss={() => {
+            if (updateInfo.version) {
+              skipVersion(updateInfo.version);
+            }
             setDismissedUpdateVersion(activeUpdateVersion);
             setShowUpdateNotification(false);
           }}
         />
       )}
+      {alert && !showSettings && (
+        <AlertToast
+          alert={alert}
+          temperatureUnit={settings.temperatureUnit}
+          onDismiss={dismissAlert}
+          onAcknowledge={() => acknowledgeAlert(alert.id)}
+        />
+      )}
     </div>
   );
 }
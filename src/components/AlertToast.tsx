@@ -0,0 +1,39 @@
+import { useEffect } from "react";
+import type { Alert } from "../types";
+import "./AlertToast.css";
+
+interface AlertToastProps {
+  alert: Alert;
+  temperatureUnit: "celsius" | "fahrenheit";
+  onDismiss: () => void; // Timed out: stays unacknowledged in the tray
+  onAcknowledge: () => void;
+}
+
+// How long a toast stays up unless dismissed
+const TOAST_MS = 8000;
+
+export function AlertToast({ alert, temperatureUnit, onDismiss, onAcknowledge }: AlertToastProps) {
+  useEffect(() => {
+    const timer = setTimeout(onDismiss, TOAST_MS);
+    return () => clearTimeout(timer);
+  }, [alert, onDismiss]);
+
+  const toUnit = (c: number) =>
+    temperatureUnit === "fahrenheit"
+      ? `${Math.round(c * 9 / 5 + 32)}℉`
+      : `${Math.round(c)}℃`;
+
+  return (
+    <div className={`alert-toast alert-toast-${alert.severity}`}>
+      <span className="alert-toast-title">
+        {alert.component.toUpperCase()} {alert.severity === "critical" ? "CRITICAL" : "HOT"}
+      </span>
+      <span className="alert-toast-text">
+        {toUnit(alert.value)} (limit {toUnit(alert.threshold)})
+      </span>
+      <button className="alert-toast-dismiss" onClick={onAcknowledge}>
+        ×
+      </button>
+    </div>
+  );
+}
@@ -4,6 +4,7 @@ interface CoreData {
   index: number;
   temperature: number;
   load: number;
+  estimated?: boolean;
 }
 
 interface CpuCoreGridProps {
@@ -31,7 +32,11 @@ export function CpuCoreGrid({ cores, maxTemp, temperatureUnit = "celsius" }: Cpu
       </div>
       <div className="core-grid-content">
         {cores.map((core) => (
-          <div key={core.index} className="core-item">
+          <div
+            key={core.index}
+            className={core.estimated ? "core-item estimated" : "core-item"}
+            title={core.estimated ? "Package temperature (no per-core sensor)" : undefined}
+          >
             <span className="core-index">C{core.index}</span>
             <div className="core-temp-bar">
               <div
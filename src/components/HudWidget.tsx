@@ -1,8 +1,9 @@
 import { useState, useRef, useCallback, useEffect } from "react";
 import { flushSync } from "react-dom";
+import { invoke } from "@tauri-apps/api/core";
 import { getVersion } from "@tauri-apps/api/app";
 import { getCurrentWindow } from "@tauri-apps/api/window";
-import type { HardwareData, SectionType, AudioDevice } from "../types";
+import type { HardwareData, SectionStatus, SectionType, AudioDevice } from "../types";
 
 const COLLAPSED_KEY = "ondo_collapsed_sections";
 const SHOW_CORES_KEY = "ondo_show_cpu_cores";
@@ -47,6 +48,9 @@ interface HudWidgetProps {
   miniMode?: boolean;
   compactMode?: boolean;
   temperatureUnit?: "celsius" | "fahrenheit";
+  updateInterval?: number;
+  monitoringPaused?: boolean;
+  performanceMode?: boolean;
 }
 
 export function HudWidget({
@@ -65,6 +69,9 @@ export function HudWidget({
   miniMode,
   compactMode,
   temperatureUnit = "celsius",
+  updateInterval = 1000,
+  monitoringPaused = false,
+  performanceMode = false,
 }: HudWidgetProps) {
   const [showCpuCores, setShowCpuCores] = useState(() => {
     try {
@@ -90,9 +97,19 @@ export function HudWidget({
   const sectionRefs = useRef<Map<SectionType, HTMLDivElement>>(new Map());
   const trashZoneRef = useRef<HTMLDivElement | null>(null);
   const { cpu, gpu } = hardwareData;
+  // Same threshold the backend uses to restart a stalled sensor daemon
+  const isStale =
+    (hardwareData.dataAgeMs ?? 0) > Math.max(5000, 3 * updateInterval);
+  const isLimited = !isStale && !!hardwareData.limitedAccess;
+  // The backend flags the Windows sysinfo fallback; on macOS sysinfo is normal
+  const isFallback = !!hardwareData.degraded;
+  // Storage and the motherboard are read less often than the rest
+  const readAgo = (readAt?: number | null) =>
+    readAt ? `Read ${Math.round((hardwareData.timestamp - readAt) / 1000)}s ago` : undefined;
   const isFahrenheit = temperatureUnit === "fahrenheit";
   const toUnit = (c: number) => isFahrenheit ? Math.round(c * 9 / 5 + 32) : Math.round(c);
   const tempUnit = isFahrenheit ? "℉" : "℃";
+  const fanUnit = (fan: { unit?: string }) => (fan.unit === "percent" ? "%" : "RPM");
   const toMax = (c: number) => isFahrenheit ? c * 9 / 5 + 32 : c;
 
   const displayFps = hardwareData.display?.fps ?? null;
@@ -155,10 +172,10 @@ export function HudWidget({
     });
   }, []);
 
-  const getTemperatureStatus = (temp: number, max: number) => {
-    const ratio = temp / max;
-    if (ratio >= 0.9) return "danger";
-    if (ratio >= 0.75) return "warning";
+  // Decided by the backend from the thresholds setting
+  const getTemperatureStatus = (status?: SectionStatus) => {
+    if (status === "critical") return "danger";
+    if (status === "warning") return "warning";
     return "normal";
   };
 
@@ -387,7 +404,7 @@ export function HudWidget({
                 value={toUnit(cpu.temperature)}
                 max={toMax(cpu.maxTemperature)}
                 unit={tempUnit}
-                status={getTemperatureStatus(cpu.temperature, cpu.maxTemperature)}
+                status={getTemperatureStatus(cpu.status)}
                 label="TEMP"
               />
               <div className="metric-divider" />
@@ -404,14 +421,56 @@ export function HudWidget({
             </div>
 
             {cpu.frequency > 0 && (
-              <div className="cpu-frequency">
+              <div
+                className="cpu-frequency"
+                title={
+                  cpu.baseFrequency
+                    ? `Base ${cpu.baseFrequency.toFixed(2)} GHz`
+                    : undefined
+                }
+              >
                 <span className="frequency-label">FREQ</span>
                 <span className="frequency-value">
-                  {cpu.frequency.toFixed(2)} GHz
+                  {cpu.frequency.toFixed(2)}
+                  {cpu.maxFrequency ? ` / ${cpu.maxFrequency.toFixed(2)}` : ""} GHz
+                </span>
+                {cpu.isThrottling && (
+                  <span className="throttling-badge" title="Clock held back by temperature">
+                    THROTTLING
+                  </span>
+                )}
+              </div>
+            )}
+
+            {(cpu.power !== undefined || cpu.voltage !== undefined) && (
+              <div className="cpu-frequency">
+                <span className="frequency-label">PWR</span>
+                <span className="frequency-value">
+                  {[
+                    cpu.power !== undefined ? `${cpu.power.toFixed(0)} W` : null,
+                    cpu.voltage !== undefined ? `${cpu.voltage.toFixed(3)} V` : null,
+                  ]
+                    .filter(Boolean)
+                    .join(" · ")}
                 </span>
               </div>
             )}
 
+            {cpu.packages?.map((pkg) => (
+              <div className="cpu-frequency" key={pkg.index} title={pkg.name}>
+                <span className="frequency-label">CPU{pkg.index}</span>
+                <span className="frequency-value">
+                  {[
+                    `${toUnit(pkg.temperature)}${tempUnit}`,
+                    `${pkg.load.toFixed(0)}%`,
+                    pkg.power !== undefined ? `${pkg.power.toFixed(0)} W` : null,
+                  ]
+                    .filter(Boolean)
+                    .join(" · ")}
+                </span>
+              </div>
+            ))}
+
             {showCpuCores && cpu.cores && cpu.cores.length > 0 && (
               <CpuCoreGrid cores={cpu.cores} maxTemp={cpu.maxTemperature} temperatureUnit={temperatureUnit} />
             )}
@@ -465,7 +524,7 @@ export function HudWidget({
                 value={toUnit(gpu.temperature)}
                 max={toMax(gpu.maxTemperature)}
                 unit={tempUnit}
-                status={getTemperatureStatus(gpu.temperature, gpu.maxTemperature)}
+                status={getTemperatureStatus(gpu.status)}
                 label="TEMP"
               />
               <div className="metric-divider" />
@@ -490,6 +549,45 @@ export function HudWidget({
               </div>
             )}
 
+            {gpu.power !== undefined && (
+              <div className="gpu-frequency">
+                <span className="frequency-label">PWR</span>
+                <span className="frequency-value">
+                  {gpu.powerLimit !== undefined
+                    ? `${gpu.power.toFixed(0)}/${gpu.powerLimit.toFixed(0)} W`
+                    : `${gpu.power.toFixed(0)} W`}
+                </span>
+              </div>
+            )}
+
+            {gpu.hotspotTemperature !== undefined && (
+              <div className="gpu-frequency">
+                <span className="frequency-label">HOTSPOT</span>
+                <span className="frequency-value">
+                  {toUnit(gpu.hotspotTemperature)}{tempUnit}
+                  {gpu.memoryTemperature !== undefined &&
+                    ` · MEM ${toUnit(gpu.memoryTemperature)}${tempUnit}`}
+                </span>
+              </div>
+            )}
+
+            {gpu.fans && gpu.fans.length > 0 && (
+              <div className="gpu-frequency">
+                <span className="frequency-label">FAN</span>
+                <span className="frequency-value">
+                  {gpu.fans
+                    .map((fan) =>
+                      fan.stopped
+                        ? "STOPPED"
+                        : fan.unit === "percent"
+                          ? `${fan.speed}%`
+                          : `${fan.speed} RPM`
+                    )
+                    .join(" · ")}
+                </span>
+              </div>
+            )}
+
             {gpu.memoryTotal > 0 && (
               <div className="gpu-memory">
                 <span className="memory-label">{gpu.memoryUsed > 0 ? "VRAM" : "MEM"}</span>
@@ -525,6 +623,7 @@ export function HudWidget({
           className={`hud-section-header${collapsed ? " collapsed" : ""}`}
           onClick={() => toggleCollapse("storage")}
           style={{ cursor: "pointer" }}
+          title={readAgo(hardwareData.sectionTimestamps?.storage)}
         >
           <div className="section-indicator storage" />
           <span className="section-label">SSD</span>
@@ -538,7 +637,7 @@ export function HudWidget({
               {hardwareData.storage.map((drive, i) => (
                 <span key={i} className="collapsed-val">
                   {drive.temperature > 0 && <>{toUnit(drive.temperature)}<span className="collapsed-val-unit">{tempUnit}</span>{" "}</>}
-                  {Math.round(drive.usedSpace)}<span className="collapsed-val-unit">%</span>
+                  {Math.round(drive.usedPercent)}<span className="collapsed-val-unit">%</span>
                 </span>
               ))}
             </div>
@@ -550,7 +649,7 @@ export function HudWidget({
           <div className="collapsed-bar-group">
             {hardwareData.storage.map((drive, i) => (
               <div key={i} className="collapsed-bar">
-                <div className="collapsed-bar-fill storage" style={{ width: `${Math.min(drive.usedSpace, 100)}%` }} />
+                <div className="collapsed-bar-fill storage" style={{ width: `${Math.min(drive.usedPercent, 100)}%` }} />
               </div>
             ))}
           </div>
@@ -569,7 +668,7 @@ export function HudWidget({
                     value={toUnit(drive.temperature)}
                     max={toMax(70)}
                     unit={tempUnit}
-                    status={getTemperatureStatus(drive.temperature, 70)}
+                    status={getTemperatureStatus(drive.status)}
                     label="TEMP"
                   />
                 ) : (
@@ -584,12 +683,12 @@ export function HudWidget({
                 <div className="metric-item">
                   <span className="metric-label">USED</span>
                   <span className="metric-value">
-                    {drive.usedSpace > 0 ? `${Math.round(drive.usedSpace)}%` : "0%"}
+                    {drive.usedPercent > 0 ? `${Math.round(drive.usedPercent)}%` : "0%"}
                   </span>
                   <div className="metric-bar">
                     <div
                       className="metric-bar-fill storage"
-                      style={{ width: `${Math.min(drive.usedSpace, 100)}%` }}
+                      style={{ width: `${Math.min(drive.usedPercent, 100)}%` }}
                     />
                   </div>
                 </div>
@@ -601,6 +700,20 @@ export function HudWidget({
                   {drive.totalSpace > 0 ? `${Math.round(drive.totalSpace)}GB` : "N/A"}
                 </span>
               </div>
+              {drive.healthPercent != null && (
+                <div className="storage-capacity-info">
+                  <span className="capacity-label">HEALTH</span>
+                  <span className="capacity-value">{Math.round(drive.healthPercent)}%</span>
+                </div>
+              )}
+              {drive.partitions?.map((partition) => (
+                <div key={partition.mountPoint} className="storage-capacity-info">
+                  <span className="capacity-label">{partition.mountPoint}</span>
+                  <span className="capacity-value">
+                    {Math.round(partition.usedSpace)}/{Math.round(partition.totalSpace)}GB
+                  </span>
+                </div>
+              ))}
             </div>
           ))
         )}
@@ -617,6 +730,7 @@ export function HudWidget({
           className={`hud-section-header${collapsed ? " collapsed" : ""}`}
           onClick={() => toggleCollapse("motherboard")}
           style={{ cursor: "pointer" }}
+          title={readAgo(hardwareData.sectionTimestamps?.motherboard)}
         >
           <div className="section-indicator motherboard" />
           <span className="section-label">MB</span>
@@ -629,7 +743,7 @@ export function HudWidget({
                 <span className="collapsed-val">{toUnit(hardwareData.motherboard.temperature)}<span className="collapsed-val-unit">{tempUnit}</span></span>
               )}
               {hardwareData.motherboard.fans.length > 0 && (
-                <span className="collapsed-val">{hardwareData.motherboard.fans[0].speed}<span className="collapsed-val-unit">RPM</span></span>
+                <span className="collapsed-val">{hardwareData.motherboard.fans[0].speed}<span className="collapsed-val-unit">{fanUnit(hardwareData.motherboard.fans[0])}</span></span>
               )}
             </div>
           )}
@@ -643,7 +757,7 @@ export function HudWidget({
                 value={toUnit(hardwareData.motherboard.temperature)}
                 max={toMax(80)}
                 unit={tempUnit}
-                status={getTemperatureStatus(hardwareData.motherboard.temperature, 80)}
+                status={getTemperatureStatus(hardwareData.motherboard.status)}
                 label="TEMP"
               />
             ) : (
@@ -660,9 +774,13 @@ export function HudWidget({
               {hardwareData.motherboard.fans.length > 0 ? (
                 <div className="fan-speeds">
                   {hardwareData.motherboard.fans.slice(0, 3).map((fan, idx) => (
-                    <div key={idx} className="fan-speed-item" title={fan.name}>
+                    <div
+                      key={idx}
+                      className={fan.stopped ? "fan-speed-item stopped" : "fan-speed-item"}
+                      title={fan.stopped ? `${fan.name}: stopped` : fan.name}
+                    >
                       <span className="fan-speed-value">{fan.speed}</span>
-                      <span className="fan-speed-unit">RPM</span>
+                      <span className="fan-speed-unit">{fanUnit(fan)}</span>
                     </div>
                   ))}
                 </div>
@@ -672,6 +790,30 @@ export function HudWidget({
             </div>
           </div>
         )}
+
+        {!collapsed && hardwareData.motherboard.temperatures && hardwareData.motherboard.temperatures.length > 1 && (
+          <div className="cpu-frequency">
+            <span className="frequency-label">TEMPS</span>
+            <span className="frequency-value">
+              {hardwareData.motherboard.temperatures
+                .slice(0, 4)
+                .map((t) => `${t.name} ${toUnit(t.temperature)}${tempUnit}`)
+                .join(" · ")}
+            </span>
+          </div>
+        )}
+
+        {!collapsed && hardwareData.motherboard.voltages && hardwareData.motherboard.voltages.length > 0 && (
+          <div className="cpu-frequency">
+            <span className="frequency-label">VOLT</span>
+            <span className="frequency-value">
+              {hardwareData.motherboard.voltages
+                .slice(0, 4)
+                .map((v) => `${v.name} ${v.volts.toFixed(2)}`)
+                .join(" · ")}
+            </span>
+          </div>
+        )}
       </>
     );
   };
@@ -930,7 +1072,7 @@ export function HudWidget({
         case "storage":
           if (hardwareData.storage && hardwareData.storage.length > 0) {
             for (const [index, drive] of hardwareData.storage.entries()) {
-              rows.push(renderMiniRow(`storage-${drive.name}-${index}`, "storage", "SSD", drive.temperature, drive.usedSpace, "storage"));
+              rows.push(renderMiniRow(`storage-${drive.name}-${index}`, "storage", "SSD", drive.temperature, drive.usedPercent, "storage"));
             }
           }
           break;
@@ -1002,7 +1144,7 @@ export function HudWidget({
 
   // ── Normal mode render ─────────────────────────────────────────────────
   return (
-    <div className="hud-widget">
+    <div className={`hud-widget${performanceMode ? " performance" : ""}`}>
       {/* Drag region - separate from header */}
       <div className="drag-region" onMouseDown={() => getCurrentWindow().startDragging()} />
 
@@ -1086,8 +1228,64 @@ export function HudWidget({
 
       {/* Footer status */}
       <div className="hud-footer">
-        <div className="status-indicator online" />
-        <span className="status-text">MONITORING</span>
+        <div
+          className={`status-indicator ${monitoringPaused || isStale || isLimited || isFallback ? "stale" : "online"}`}
+        />
+        <span
+          className="status-text"
+          title={isFallback ? "Install PawnIO from the settings for full sensor data" : undefined}
+        >
+          {monitoringPaused
+            ? "PAUSED"
+            : performanceMode
+              ? "PERFORMANCE"
+              : isStale
+              ? "SENSORS STALLED"
+              : isLimited
+                ? "LIMITED ACCESS"
+                : isFallback
+                  ? "LIMITED DATA"
+                  : "MONITORING"}
+        </span>
+        {monitoringPaused && (
+          <button
+            className="admin-restart-button"
+            title="Resume sampling the sensors"
+            onClick={() => {
+              invoke("set_monitoring_paused", { paused: false }).catch((e) =>
+                console.error("[HudWidget] Resume monitoring failed:", e)
+              );
+            }}
+          >
+            RESUME
+          </button>
+        )}
+        {!monitoringPaused && performanceMode && (
+          <button
+            className="admin-restart-button"
+            title="Back to the normal update interval"
+            onClick={() => {
+              invoke("set_monitoring_mode", { mode: "normal" }).catch((e) =>
+                console.error("[HudWidget] Leaving performance mode failed:", e)
+              );
+            }}
+          >
+            NORMAL
+          </button>
+        )}
+        {!monitoringPaused && !performanceMode && isLimited && (
+          <button
+            className="admin-restart-button"
+            title="Some sensors need administrator rights"
+            onClick={() => {
+              invoke("restart_as_admin").catch((e) =>
+                console.error("[HudWidget] Restart as admin failed:", e)
+              );
+            }}
+          >
+            RESTART AS ADMIN
+          </button>
+        )}
         <span className="timestamp">{TIME_FORMATTER.format(new Date())}</span>
       </div>
     </div>
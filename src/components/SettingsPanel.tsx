@@ -1,7 +1,15 @@
 import { useState, useEffect } from "react";
 import { getVersion } from "@tauri-apps/api/app";
 import { invoke } from "@tauri-apps/api/core";
-import type { AppSettings, PawnIOStatus } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  AppSettings,
+  InfluxDbSettings,
+  PawnIOInstallResult,
+  PawnIOStatus,
+  QuietHours,
+  SystemInfo,
+} from "../types";
 import { testSentryError } from "../sentry";
 import "./SettingsPanel.css";
 
@@ -40,6 +48,42 @@ export function SettingsPanel({
   const [pawnioStatus, setPawnioStatus] = useState<PawnIOStatus | null>(null);
   const [installingPawnio, setInstallingPawnio] = useState(false);
   const [pawnioMessage, setPawnioMessage] = useState<string | null>(null);
+  const [profiles, setProfiles] = useState<string[]>([]);
+  const [profileName, setProfileName] = useState("");
+  const [profileMessage, setProfileMessage] = useState<string | null>(null);
+  const [diagnosticsMessage, setDiagnosticsMessage] = useState<string | null>(null);
+  const [influxMessage, setInfluxMessage] = useState<string | null>(null);
+  const [testingInflux, setTestingInflux] = useState(false);
+  const quietHours: QuietHours = settings.quietHours ?? {
+    enabled: false,
+    start: "22:00",
+    end: "07:00",
+    allowCritical: true,
+  };
+  const influxdb: InfluxDbSettings = settings.influxdb ?? {
+    enabled: false,
+    url: "http://localhost:8086",
+    org: "",
+    bucket: "ondo",
+    token: null,
+    flushIntervalSecs: 10,
+    batchSize: 500,
+  };
+  const setInfluxdb = (changes: Partial<InfluxDbSettings>) =>
+    onSettingsChange({ influxdb: { ...influxdb, ...changes } });
+
+  const handleTestInfluxdb = async () => {
+    setTestingInflux(true);
+    setInfluxMessage(null);
+    try {
+      await invoke("test_influxdb_connection", { settings: influxdb });
+      setInfluxMessage("Connected, test point written");
+    } catch (error) {
+      setInfluxMessage(`Error: ${error}`);
+    } finally {
+      setTestingInflux(false);
+    }
+  };
   // Detect Windows using navigator.userAgent (works in Tauri webview)
   const isWindows = typeof navigator !== "undefined" && navigator.userAgent.includes("Windows");
 
@@ -53,11 +97,40 @@ export function SettingsPanel({
     }
   }, [isWindows]);
 
+  useEffect(() => {
+    invoke<string[]>("list_profiles")
+      .then(setProfiles)
+      .catch(() => {});
+  }, []);
+
+  useEffect(() => {
+    const installed = listen<PawnIOInstallResult>("pawnio-installed", (event) => {
+      setPawnioStatus(event.payload.status);
+    });
+    return () => {
+      installed.then((fn) => fn());
+    };
+  }, []);
+
   const handleInstallPawnIO = async () => {
     setInstallingPawnio(true);
     setPawnioMessage("Installing PawnIO driver...");
     try {
-      const result = await invoke<string>("download_and_install_pawnio");
+      const result = await invoke<PawnIOInstallResult>("download_and_install_pawnio");
+      setPawnioStatus(result.status);
+      setPawnioMessage(result.message);
+    } catch (error) {
+      setPawnioMessage(`Error: ${error}`);
+    } finally {
+      setInstallingPawnio(false);
+    }
+  };
+
+  const handleUninstallPawnIO = async () => {
+    setInstallingPawnio(true);
+    setPawnioMessage("Removing PawnIO driver...");
+    try {
+      const result = await invoke<string>("uninstall_pawnio");
       setPawnioMessage(result);
     } catch (error) {
       setPawnioMessage(`Error: ${error}`);
@@ -66,6 +139,54 @@ export function SettingsPanel({
     }
   };
 
+  const handleSaveProfile = async () => {
+    try {
+      setProfiles(await invoke<string[]>("save_profile", { name: profileName }));
+      setProfileMessage(`Saved "${profileName.trim()}"`);
+      setProfileName("");
+    } catch (error) {
+      setProfileMessage(`Error: ${error}`);
+    }
+  };
+
+  // The backend emits settings-changed, which reloads the settings
+  const handleApplyProfile = async (name: string) => {
+    try {
+      await invoke<AppSettings>("apply_profile", { name });
+      setProfileMessage(`Applied "${name}"`);
+    } catch (error) {
+      setProfileMessage(`Error: ${error}`);
+    }
+  };
+
+  const handleCopyDiagnostics = async () => {
+    try {
+      const info = await invoke<SystemInfo>("get_system_info");
+      await navigator.clipboard.writeText(JSON.stringify(info, null, 2));
+      setDiagnosticsMessage("Diagnostics copied to the clipboard");
+    } catch (error) {
+      setDiagnosticsMessage(`Error: ${error}`);
+    }
+  };
+
+  const handleCopyReadings = async (format: "summary" | "markdown") => {
+    try {
+      await invoke<string>("copy_snapshot_to_clipboard", { format });
+      setDiagnosticsMessage("Readings copied to the clipboard");
+    } catch (error) {
+      setDiagnosticsMessage(`Error: ${error}`);
+    }
+  };
+
+  const handleDeleteProfile = async (name: string) => {
+    try {
+      setProfiles(await invoke<string[]>("delete_profile", { name }));
+      setProfileMessage(null);
+    } catch (error) {
+      setProfileMessage(`Error: ${error}`);
+    }
+  };
+
   const handleRefreshPawnioStatus = async () => {
     try {
       const status = await invoke<PawnIOStatus>("check_pawnio_status");
@@ -105,9 +226,27 @@ export function SettingsPanel({
               <option value="top-left">Top Left</option>
               <option value="bottom-right">Bottom Right</option>
               <option value="bottom-left">Bottom Left</option>
+              <option value="custom" disabled>Custom (dragged)</option>
             </select>
           </div>
 
+          <div className="setting-group">
+            <label className="setting-label">
+              Snap to Edges: {(settings.snapThreshold ?? 20) === 0 ? "Off" : `${settings.snapThreshold ?? 20}px`}
+            </label>
+            <input
+              type="range"
+              className="setting-slider"
+              min="0"
+              max="50"
+              step="5"
+              value={settings.snapThreshold ?? 20}
+              onChange={(e) =>
+                onSettingsChange({ snapThreshold: parseInt(e.target.value) })
+              }
+            />
+          </div>
+
           {/* Opacity */}
           <div className="setting-group">
             <label className="setting-label">
@@ -125,10 +264,10 @@ export function SettingsPanel({
             />
           </div>
 
-          {/* Update Interval */}
+          {/* Update Intervals */}
           <div className="setting-group">
             <label className="setting-label">
-              Update Interval: {settings.updateInterval}ms
+              Update Interval (CPU/GPU): {settings.updateIntervalFast}ms
             </label>
             <input
               type="range"
@@ -136,9 +275,66 @@ export function SettingsPanel({
               min="500"
               max="5000"
               step="500"
-              value={settings.updateInterval}
+              value={settings.updateIntervalFast}
+              onChange={(e) =>
+                onSettingsChange({ updateIntervalFast: parseInt(e.target.value) })
+              }
+            />
+          </div>
+
+          <div className="setting-group">
+            <label className="setting-label">
+              Update Interval (Storage/Board): {settings.updateIntervalSlow ?? 5000}ms
+            </label>
+            <input
+              type="range"
+              className="setting-slider"
+              min="1000"
+              max="60000"
+              step="1000"
+              value={settings.updateIntervalSlow ?? 5000}
               onChange={(e) =>
-                onSettingsChange({ updateInterval: parseInt(e.target.value) })
+                onSettingsChange({ updateIntervalSlow: parseInt(e.target.value) })
+              }
+            />
+          </div>
+
+          <div className="setting-group">
+            <label className="setting-label">
+              Performance Mode: 250ms for {settings.performanceModeMinutes ?? 30} min
+            </label>
+            <input
+              type="range"
+              className="setting-slider"
+              min="5"
+              max="240"
+              step="5"
+              value={settings.performanceModeMinutes ?? 30}
+              onChange={(e) =>
+                onSettingsChange({ performanceModeMinutes: parseInt(e.target.value) })
+              }
+            />
+            <button
+              className="setting-button"
+              onClick={() => invoke("set_monitoring_mode", { mode: "performance" }).catch(() => {})}
+            >
+              Start Performance Mode
+            </button>
+          </div>
+
+          <div className="setting-group">
+            <label className="setting-label">
+              Smoothing: {(settings.smoothingFactor ?? 0) === 0 ? "Off" : (settings.smoothingFactor ?? 0).toFixed(2)}
+            </label>
+            <input
+              type="range"
+              className="setting-slider"
+              min="0"
+              max="0.9"
+              step="0.05"
+              value={settings.smoothingFactor ?? 0}
+              onChange={(e) =>
+                onSettingsChange({ smoothingFactor: parseFloat(e.target.value) })
               }
             />
           </div>
@@ -215,7 +411,7 @@ export function SettingsPanel({
 
           <div className="setting-group toggle-group">
             <label className="setting-toggle">
-              <span>Auto Start with Windows</span>
+              <span>Auto Start at Login</span>
               <input
                 type="checkbox"
                 checked={settings.autoStart}
@@ -227,6 +423,34 @@ export function SettingsPanel({
             </label>
           </div>
 
+          <div className="setting-group toggle-group">
+            <label className="setting-toggle">
+              <span>Close to Tray</span>
+              <input
+                type="checkbox"
+                checked={settings.closeToTray ?? true}
+                onChange={(e) =>
+                  onSettingsChange({ closeToTray: e.target.checked })
+                }
+              />
+              <span className="toggle-slider" />
+            </label>
+          </div>
+
+          <div className="setting-group toggle-group">
+            <label className="setting-toggle">
+              <span>Slow Down When Hidden</span>
+              <input
+                type="checkbox"
+                checked={settings.pauseWhenHidden ?? true}
+                onChange={(e) =>
+                  onSettingsChange({ pauseWhenHidden: e.target.checked })
+                }
+              />
+              <span className="toggle-slider" />
+            </label>
+          </div>
+
           <div className="setting-group toggle-group">
             <label className="setting-toggle">
               <span>Compact Mode</span>
@@ -241,6 +465,153 @@ export function SettingsPanel({
             </label>
           </div>
 
+          <div className="setting-group toggle-group">
+            <label className="setting-toggle">
+              <span>Stay Paused After Restart</span>
+              <input
+                type="checkbox"
+                checked={settings.rememberMonitoringPause ?? false}
+                onChange={(e) =>
+                  onSettingsChange({ rememberMonitoringPause: e.target.checked })
+                }
+              />
+              <span className="toggle-slider" />
+            </label>
+          </div>
+
+          {/* Temperature alerts */}
+          <div className="setting-group toggle-group">
+            <label className="setting-toggle">
+              <span>Temperature Alerts</span>
+              <input
+                type="checkbox"
+                checked={settings.alertsEnabled ?? true}
+                onChange={(e) =>
+                  onSettingsChange({ alertsEnabled: e.target.checked })
+                }
+              />
+              <span className="toggle-slider" />
+            </label>
+          </div>
+
+          {(settings.alertsEnabled ?? true) && (
+            <div className="setting-group">
+              <label className="setting-toggle">
+                <span>Quiet Hours</span>
+                <input
+                  type="checkbox"
+                  checked={quietHours.enabled}
+                  onChange={(e) =>
+                    onSettingsChange({ quietHours: { ...quietHours, enabled: e.target.checked } })
+                  }
+                />
+                <span className="toggle-slider" />
+              </label>
+              {quietHours.enabled && (
+                <>
+                  <div className="quiet-hours-times">
+                    <input
+                      type="time"
+                      className="setting-select"
+                      value={quietHours.start}
+                      onChange={(e) =>
+                        onSettingsChange({ quietHours: { ...quietHours, start: e.target.value } })
+                      }
+                    />
+                    <span>to</span>
+                    <input
+                      type="time"
+                      className="setting-select"
+                      value={quietHours.end}
+                      onChange={(e) =>
+                        onSettingsChange({ quietHours: { ...quietHours, end: e.target.value } })
+                      }
+                    />
+                  </div>
+                  <label className="setting-toggle">
+                    <span>Critical Alerts Still Show</span>
+                    <input
+                      type="checkbox"
+                      checked={quietHours.allowCritical}
+                      onChange={(e) =>
+                        onSettingsChange({
+                          quietHours: { ...quietHours, allowCritical: e.target.checked },
+                        })
+                      }
+                    />
+                    <span className="toggle-slider" />
+                  </label>
+                </>
+              )}
+            </div>
+          )}
+
+          {/* InfluxDB push */}
+          <div className="setting-group">
+            <label className="setting-toggle">
+              <span>Push to InfluxDB</span>
+              <input
+                type="checkbox"
+                checked={influxdb.enabled}
+                onChange={(e) => setInfluxdb({ enabled: e.target.checked })}
+              />
+              <span className="toggle-slider" />
+            </label>
+            {influxdb.enabled && (
+              <>
+                <input
+                  type="text"
+                  className="setting-select"
+                  placeholder="http://localhost:8086"
+                  value={influxdb.url}
+                  onChange={(e) => setInfluxdb({ url: e.target.value })}
+                />
+                <input
+                  type="text"
+                  className="setting-select"
+                  placeholder="Organization"
+                  value={influxdb.org}
+                  onChange={(e) => setInfluxdb({ org: e.target.value })}
+                />
+                <input
+                  type="text"
+                  className="setting-select"
+                  placeholder="Bucket"
+                  value={influxdb.bucket}
+                  onChange={(e) => setInfluxdb({ bucket: e.target.value })}
+                />
+                <input
+                  type="password"
+                  className="setting-select"
+                  placeholder="API token"
+                  value={influxdb.token ?? ""}
+                  onChange={(e) => setInfluxdb({ token: e.target.value || null })}
+                />
+                <label className="setting-label">
+                  Write Every: {influxdb.flushIntervalSecs}s
+                </label>
+                <input
+                  type="range"
+                  className="setting-slider"
+                  min="1"
+                  max="60"
+                  value={Math.min(influxdb.flushIntervalSecs, 60)}
+                  onChange={(e) =>
+                    setInfluxdb({ flushIntervalSecs: parseInt(e.target.value) })
+                  }
+                />
+                <button
+                  className="setting-button"
+                  onClick={handleTestInfluxdb}
+                  disabled={testingInflux}
+                >
+                  {testingInflux ? "Testing..." : "Test Connection"}
+                </button>
+                {influxMessage && <p className="update-message">{influxMessage}</p>}
+              </>
+            )}
+          </div>
+
           {/* Update Check Button */}
           {onCheckUpdate && (
             <div className="setting-group">
@@ -283,15 +654,74 @@ export function SettingsPanel({
             </div>
           )}
 
+          {/* Profiles */}
+          <div className="setting-group">
+            <label className="setting-label">Profiles</label>
+            <div className="profile-save">
+              <input
+                type="text"
+                className="setting-select profile-name"
+                placeholder="Profile name"
+                maxLength={32}
+                value={profileName}
+                onChange={(e) => setProfileName(e.target.value)}
+              />
+              <button
+                className="setting-button profile-button"
+                onClick={handleSaveProfile}
+                disabled={profileName.trim() === ""}
+              >
+                Save
+              </button>
+            </div>
+            {profiles.map((name) => (
+              <div className="profile-row" key={name}>
+                <span className="profile-row-name">{name}</span>
+                <button
+                  className="setting-button profile-button"
+                  onClick={() => handleApplyProfile(name)}
+                >
+                  Apply
+                </button>
+                <button
+                  className="setting-button setting-button-debug profile-button"
+                  onClick={() => handleDeleteProfile(name)}
+                >
+                  Delete
+                </button>
+              </div>
+            ))}
+            {profileMessage && <p className="update-message">{profileMessage}</p>}
+          </div>
+
           {/* PawnIO Driver Info (Windows only) */}
           {isWindows && (
             <div className="setting-group pawnio-status">
               <label className="setting-label">Hardware Driver (PawnIO)</label>
               <div className="pawnio-info">
                 {pawnioStatus?.installed ? (
-                  <p className="pawnio-description pawnio-installed">
-                    PawnIO driver is installed and working.
-                  </p>
+                  <>
+                    <p className="pawnio-description pawnio-installed">
+                      PawnIO driver{pawnioStatus.version ? ` ${pawnioStatus.version}` : ""} is
+                      installed and working.
+                    </p>
+                    {pawnioStatus.needs_update && (
+                      <button
+                        className="setting-button setting-button-pawnio"
+                        onClick={handleInstallPawnIO}
+                        disabled={installingPawnio}
+                      >
+                        {installingPawnio ? "Installing..." : "Update PawnIO Driver"}
+                      </button>
+                    )}
+                    <button
+                      className="setting-button setting-button-refresh"
+                      onClick={handleUninstallPawnIO}
+                      disabled={installingPawnio}
+                    >
+                      Uninstall PawnIO Driver
+                    </button>
+                  </>
                 ) : (
                   <>
                     <p className="pawnio-description">
@@ -341,6 +771,54 @@ export function SettingsPanel({
             </label>
           </div>
 
+          <div className="setting-group toggle-group">
+            <label className="setting-toggle">
+              <span>Mock Hardware Data</span>
+              <input
+                type="checkbox"
+                checked={settings.mockData ?? false}
+                onChange={(e) =>
+                  onSettingsChange({ mockData: e.target.checked })
+                }
+              />
+              <span className="toggle-slider" />
+            </label>
+          </div>
+
+          <div className="setting-group">
+            <button
+              className="setting-button setting-button-refresh"
+              onClick={() => invoke("open_config_dir").catch(() => {})}
+            >
+              Open Config Folder
+            </button>
+            <button
+              className="setting-button setting-button-refresh"
+              onClick={() => invoke("open_data_file", { kind: "log" }).catch(() => {})}
+            >
+              Show Log File
+            </button>
+            <button
+              className="setting-button setting-button-refresh"
+              onClick={() => handleCopyReadings("summary")}
+            >
+              Copy Readings
+            </button>
+            <button
+              className="setting-button setting-button-refresh"
+              onClick={() => handleCopyReadings("markdown")}
+            >
+              Copy as Table
+            </button>
+            <button
+              className="setting-button setting-button-refresh"
+              onClick={handleCopyDiagnostics}
+            >
+              Copy Diagnostics
+            </button>
+            {diagnosticsMessage && <p className="update-message">{diagnosticsMessage}</p>}
+          </div>
+
           {import.meta.env.DEV && (
             <div className="setting-group">
               <button
@@ -349,6 +827,14 @@ export function SettingsPanel({
               >
                 Test Sentry Error
               </button>
+              <button
+                className="setting-button setting-button-debug"
+                onClick={() =>
+                  invoke("simulate_reading", { cpuTemp: 98, gpuTemp: 92 }).catch(() => {})
+                }
+              >
+                Simulate Overheating (10s)
+              </button>
             </div>
           )}
         </div>
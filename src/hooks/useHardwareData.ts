@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import type { HardwareData } from "../types";
 import { captureHardwareError } from "../sentry";
 
@@ -26,7 +27,7 @@ interface UseHardwareDataResult {
   hardwareData: HardwareData;
   isLoading: boolean;
   error: string | null;
-  refresh: () => Promise<void>;
+  refresh: () => Promise<boolean>; // false when the backend could not be reached
 }
 
 const INITIAL_DATA: HardwareData = {
@@ -39,6 +40,8 @@ const INITIAL_DATA: HardwareData = {
   timestamp: Date.now(),
 };
 
+// Samples arrive from the backend's sampler as hardware-update events; the
+// interval only paces the mock data used when the backend is unreachable
 export function useHardwareData(intervalMs: number = 1000): UseHardwareDataResult {
   const [hardwareData, setHardwareData] = useState<HardwareData>(INITIAL_DATA);
   const [isLoading, setIsLoading] = useState(true);
@@ -54,80 +57,83 @@ export function useHardwareData(intervalMs: number = 1000): UseHardwareDataResul
   const networkLastActiveRef = useRef<Map<string, number>>(new Map());
   const NETWORK_GRACE_PERIOD_MS = 3000;
 
-  const fetchData = useCallback(async () => {
-    try {
-      const data = await invoke<HardwareData>("get_hardware_data");
-
-      // ネットワーク: 非アクティブなインターフェースを除外し、EMAで平滑化
-      if (data.network) {
-        const now = Date.now();
-        const lastActive = networkLastActiveRef.current;
-        const ema = networkEmaRef.current;
-
-        // rawデータでアクティブ判定を更新
-        for (const iface of data.network) {
-          if (iface.receivedPerSec > 0 || iface.sentPerSec > 0) {
-            lastActive.set(iface.name, now);
-          }
+  const handleData = useCallback((data: HardwareData) => {
+    // ネットワーク: 非アクティブなインターフェースを除外し、EMAで平滑化
+    if (data.network) {
+      const now = Date.now();
+      const lastActive = networkLastActiveRef.current;
+      const ema = networkEmaRef.current;
+
+      // rawデータでアクティブ判定を更新
+      for (const iface of data.network) {
+        if (iface.receivedPerSec > 0 || iface.sentPerSec > 0) {
+          lastActive.set(iface.name, now);
         }
+      }
 
-        // 猶予期間内にアクティブだったインターフェースのみ残す
-        const rawInterfaces = data.network;
-        data.network = data.network.filter((iface) => {
-          const last = lastActive.get(iface.name);
-          return last !== undefined && now - last < NETWORK_GRACE_PERIOD_MS;
-        });
-
-        // すべてフィルタリングされた場合、メインアダプターを1つ保持する
-        // （最後にアクティブだったもの、またはバックエンドから返された最初のもの）
-        if (data.network.length === 0 && rawInterfaces.length > 0) {
-          let mainAdapter = rawInterfaces[0];
-          let latestTime = 0;
-          for (const iface of rawInterfaces) {
-            const last = lastActive.get(iface.name) ?? 0;
-            if (last > latestTime) {
-              latestTime = last;
-              mainAdapter = iface;
-            }
+      // 猶予期間内にアクティブだったインターフェースのみ残す
+      const rawInterfaces = data.network;
+      data.network = data.network.filter((iface) => {
+        const last = lastActive.get(iface.name);
+        return last !== undefined && now - last < NETWORK_GRACE_PERIOD_MS;
+      });
+
+      // すべてフィルタリングされた場合、メインアダプターを1つ保持する
+      // （最後にアクティブだったもの、またはバックエンドから返された最初のもの）
+      if (data.network.length === 0 && rawInterfaces.length > 0) {
+        let mainAdapter = rawInterfaces[0];
+        let latestTime = 0;
+        for (const iface of rawInterfaces) {
+          const last = lastActive.get(iface.name) ?? 0;
+          if (last > latestTime) {
+            latestTime = last;
+            mainAdapter = iface;
           }
-          data.network = [mainAdapter];
         }
+        data.network = [mainAdapter];
+      }
 
-        // EMAで平滑化
-        applyNetworkEma(data.network, ema);
+      // EMAで平滑化
+      applyNetworkEma(data.network, ema);
 
-        // 非アクティブなインターフェースのEMA状態をクリーンアップ
-        const activeNames = new Set(data.network.map((i) => i.name));
-        for (const name of ema.keys()) {
-          if (!activeNames.has(name)) ema.delete(name);
-        }
-        for (const name of lastActive.keys()) {
-          if (now - (lastActive.get(name) ?? 0) > NETWORK_GRACE_PERIOD_MS * 2) {
-            lastActive.delete(name);
-          }
+      // 非アクティブなインターフェースのEMA状態をクリーンアップ
+      const activeNames = new Set(data.network.map((i) => i.name));
+      for (const name of ema.keys()) {
+        if (!activeNames.has(name)) ema.delete(name);
+      }
+      for (const name of lastActive.keys()) {
+        if (now - (lastActive.get(name) ?? 0) > NETWORK_GRACE_PERIOD_MS * 2) {
+          lastActive.delete(name);
         }
       }
+    }
 
-      setHardwareData(data);
-      setError(null);
-
-      // CPU/GPUがnullの場合はエラーとしてSentryに送信（初回のみ）
-      if (!errorReportedRef.current.nullData) {
-        if (data.cpu === null && data.gpu === null) {
-          // 詳細エラーがある場合はそれを送信
-          const errorDetail = data.cpuError || data.gpuError || "Unknown error";
-          captureHardwareError(`Both CPU and GPU data are null: ${errorDetail}`, "both");
-          errorReportedRef.current.nullData = true;
-        } else if (data.cpu === null) {
-          const errorDetail = data.cpuError || "Unknown error";
-          captureHardwareError(`CPU data is null: ${errorDetail}`, "cpu");
-          errorReportedRef.current.nullData = true;
-        } else if (data.gpu === null) {
-          const errorDetail = data.gpuError || "Unknown error";
-          captureHardwareError(`GPU data is null: ${errorDetail}`, "gpu");
-          errorReportedRef.current.nullData = true;
-        }
+    setHardwareData(data);
+    setError(null);
+    setIsLoading(false);
+
+    // CPU/GPUがnullの場合はエラーとしてSentryに送信（初回のみ）
+    if (!errorReportedRef.current.nullData) {
+      if (data.cpu === null && data.gpu === null) {
+        // 詳細エラーがある場合はそれを送信
+        const errorDetail = data.cpuError || data.gpuError || "Unknown error";
+        captureHardwareError(`Both CPU and GPU data are null: ${errorDetail}`, "both");
+        errorReportedRef.current.nullData = true;
+      } else if (data.cpu === null) {
+        const errorDetail = data.cpuError || "Unknown error";
+        captureHardwareError(`CPU data is null: ${errorDetail}`, "cpu");
+        errorReportedRef.current.nullData = true;
+      } else if (data.gpu === null) {
+        const errorDetail = data.gpuError || "Unknown error";
+        captureHardwareError(`GPU data is null: ${errorDetail}`, "gpu");
+        errorReportedRef.current.nullData = true;
       }
+    }
+  }, []);
+
+  const fetchData = useCallback(async () => {
+    try {
+      handleData(await invoke<HardwareData>("get_hardware_data"));
     } catch (err) {
       const errorMessage = err instanceof Error ? err.message : String(err);
       setError(errorMessage);
@@ -146,16 +152,33 @@ export function useHardwareData(intervalMs: number = 1000): UseHardwareDataResul
         }
         setHardwareData(mockData);
       }
-    } finally {
       setIsLoading(false);
+      return false;
     }
-  }, []);
+    return true;
+  }, [handleData]);
 
   useEffect(() => {
-    fetchData();
-    const interval = setInterval(fetchData, intervalMs);
-    return () => clearInterval(interval);
-  }, [fetchData, intervalMs]);
+    let interval: ReturnType<typeof setInterval> | undefined;
+    const unlisten = listen<HardwareData>("hardware-update", (event) => {
+      handleData(event.payload);
+    });
+    const unlistenError = listen<string>("hardware-error", (event) => {
+      setError(event.payload);
+    });
+    // The first sample now rather than at the next tick
+    fetchData().then((ok) => {
+      // Without a backend (browser dev server), keep the mock data moving
+      if (!ok && import.meta.env.DEV) {
+        interval = setInterval(fetchData, intervalMs);
+      }
+    });
+    return () => {
+      clearInterval(interval);
+      unlisten.then((fn) => fn());
+      unlistenError.then((fn) => fn());
+    };
+  }, [fetchData, handleData, intervalMs]);
 
   return {
     hardwareData,
@@ -196,8 +219,10 @@ function generateMockData(): HardwareData {
       {
         name: "Samsung SSD 980 PRO 1TB",
         temperature: 35 + Math.random() * 10,
-        usedSpace: 40 + Math.random() * 30, // percentage (0-100)
+        usedSpace: 450,
+        usedPercent: 45,
         totalSpace: 1000,
+        healthPercent: 98,
       },
     ],
     motherboard: {
@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import type { AppSettings, SectionType } from "../types";
 import { DEFAULT_SETTINGS, SECTION_TYPES } from "../types";
 import { captureSettingsError } from "../sentry";
@@ -44,6 +45,17 @@ export function useSettings(): UseSettingsResult {
     loadSettings();
   }, []);
 
+  // Reload when the backend changes settings (e.g. tray quick toggles)
+  useEffect(() => {
+    const unlisten = listen<string[]>("settings-changed", () => {
+      loadSettings();
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const migrateSettings = (s: AppSettings): AppSettings => {
     // First drop anything that isn't a current SectionType (and dedupe).
     s.sectionOrder = sanitizeSectionList(s.sectionOrder);
@@ -100,33 +112,14 @@ export function useSettings(): UseSettingsResult {
       .catch(() => {})
       .then(async () => {
         try {
-          // Try to save via Tauri
+          // Try to save via Tauri; pinning, position and the debug server
+          // are applied by the save
           await invoke("save_settings", { settings: updated });
 
-          // Apply window settings
-          if (newSettings.alwaysOnTop !== undefined) {
-            // If enabling always on top, disable always on back first
-            if (newSettings.alwaysOnTop) {
-              await invoke("set_always_on_back", { enabled: false });
-            }
-            await invoke("set_always_on_top", { enabled: newSettings.alwaysOnTop });
-          }
-          if (newSettings.alwaysOnBack !== undefined) {
-            // If enabling always on back, disable always on top first
-            if (newSettings.alwaysOnBack) {
-              await invoke("set_always_on_top", { enabled: false });
-            }
-            await invoke("set_always_on_back", { enabled: newSettings.alwaysOnBack });
-          }
-          if (newSettings.position !== undefined) {
-            await invoke("set_window_position", { position: newSettings.position });
-          }
+          // The login entry can fail on its own, so it stays a separate call
           if (newSettings.autoStart !== undefined) {
             await invoke("set_auto_start", { enabled: newSettings.autoStart });
           }
-          if (newSettings.debugServer !== undefined) {
-            await invoke("toggle_debug_server", { enabled: newSettings.debugServer });
-          }
         } catch (err) {
           const errorMessage = err instanceof Error ? err.message : String(err);
           captureSettingsError(errorMessage, "save");
@@ -1,12 +1,14 @@
 import { useState, useEffect, useCallback } from "react";
-import { check } from "@tauri-apps/plugin-updater";
-import { relaunch } from "@tauri-apps/plugin-process";
-import { captureUpdateError } from "../sentry";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { UpdateCheck, UpdateProgress } from "../types";
 
 export interface UpdateInfo {
   available: boolean;
   version?: string;
   currentVersion?: string;
+  releaseNotes?: string | null;
+  downloadSize?: number | null;
 }
 
 export interface UpdaterState {
@@ -17,6 +19,20 @@ export interface UpdaterState {
   error: string | null;
 }
 
+function toUpdateInfo(check: UpdateCheck): UpdateInfo {
+  if (!check.availableVersion) {
+    return { available: false, currentVersion: check.currentVersion };
+  }
+  return {
+    available: true,
+    version: check.availableVersion,
+    currentVersion: check.currentVersion,
+    releaseNotes: check.releaseNotes,
+    downloadSize: check.downloadSize,
+  };
+}
+
+// The backend checks once at startup and reports failures itself
 export function useUpdater() {
   const [state, setState] = useState<UpdaterState>({
     checking: false,
@@ -28,140 +44,60 @@ export function useUpdater() {
 
   const checkForUpdate = useCallback(async () => {
     setState((prev) => ({ ...prev, checking: true, error: null }));
-    console.log("[Updater] Checking for updates...");
-
     try {
-      const update = await check();
-      console.log("[Updater] Check result:", update);
-
-      if (update) {
-        console.log("[Updater] Update available:", update.version);
-        setState((prev) => ({
-          ...prev,
-          checking: false,
-          updateInfo: {
-            available: true,
-            version: update.version,
-            currentVersion: update.currentVersion,
-          },
-        }));
-        return update;
-      } else {
-        console.log("[Updater] No update available");
-        setState((prev) => ({
-          ...prev,
-          checking: false,
-          updateInfo: { available: false },
-        }));
-        return null;
-      }
+      const check = await invoke<UpdateCheck>("check_for_update");
+      setState((prev) => ({ ...prev, checking: false, updateInfo: toUpdateInfo(check) }));
+      return check;
     } catch (error) {
       console.error("[Updater] Error:", error);
-      // Get detailed error information
-      let errorMessage = "Update check failed";
-      if (error instanceof Error) {
-        errorMessage = error.message;
-        // Include stack trace if available
-        if (error.stack) {
-          console.error("[Updater] Stack:", error.stack);
-        }
-      } else if (typeof error === "string") {
-        errorMessage = error;
-      } else if (error && typeof error === "object") {
-        errorMessage = JSON.stringify(error);
-      }
-      captureUpdateError(`check: ${errorMessage}`);
-      setState((prev) => ({
-        ...prev,
-        checking: false,
-        error: errorMessage,
-      }));
+      setState((prev) => ({ ...prev, checking: false, error: String(error) }));
       return null;
     }
   }, []);
 
   const downloadAndInstall = useCallback(async () => {
     setState((prev) => ({ ...prev, downloading: true, progress: 0, error: null }));
-
     try {
-      const update = await check();
-      if (!update) {
-        setState((prev) => ({
-          ...prev,
-          downloading: false,
-          error: "No update available",
-        }));
-        return;
-      }
-
-      console.log("[Updater] Downloading update...");
-      let downloaded = 0;
-      let contentLength = 0;
-      await update.download((event) => {
-        switch (event.event) {
-          case "Started":
-            contentLength = event.data.contentLength ?? 0;
-            break;
-          case "Progress":
-            downloaded += event.data.chunkLength;
-            if (contentLength > 0) {
-              const progress = Math.min(Math.round((downloaded / contentLength) * 100), 99);
-              setState((prev) => ({ ...prev, progress }));
-            } else {
-              // Content-Length unknown: estimate based on ~50MB typical update size
-              const estimatedTotal = 50 * 1024 * 1024;
-              const progress = Math.min(Math.round((downloaded / estimatedTotal) * 100), 95);
-              setState((prev) => ({ ...prev, progress }));
-            }
-            break;
-          case "Finished":
-            setState((prev) => ({ ...prev, progress: 100 }));
-            break;
-        }
-      });
-      console.log("[Updater] Download complete, installing...");
-      await update.install();
-      console.log("[Updater] Install complete, relaunching...");
-      await relaunch();
+      // Restarts into the new version on success
+      await invoke("install_update");
     } catch (error) {
       console.error("[Updater] Download error:", error);
-      // Get detailed error information
-      let errorMessage = "Update failed";
-      if (error instanceof Error) {
-        errorMessage = error.message;
-        if (error.stack) {
-          console.error("[Updater] Stack:", error.stack);
-        }
-      } else if (typeof error === "string") {
-        errorMessage = error;
-      } else if (error && typeof error === "object") {
-        errorMessage = JSON.stringify(error);
-      }
-      captureUpdateError(`download: ${errorMessage}`);
-      setState((prev) => ({
-        ...prev,
-        downloading: false,
-        error: errorMessage,
-      }));
+      setState((prev) => ({ ...prev, downloading: false, error: String(error) }));
     }
   }, []);
 
-  // Check for updates on mount (with delay to not interfere with boot),
-  // then re-check once every 24 hours while the app is running.
-  useEffect(() => {
-    const DAILY_MS = 24 * 60 * 60 * 1000;
+  // "Remind me later": the startup check stays quiet about this version
+  const skipVersion = useCallback(async (version: string) => {
+    try {
+      await invoke("update_settings", { patch: { skipVersion: version } });
+    } catch (error) {
+      console.error("[Updater] Failed to skip version:", error);
+    }
+  }, []);
 
-    const timer = setTimeout(() => {
-      checkForUpdate();
-    }, 10000); // Check 10 seconds after launch
+  useEffect(() => {
+    const available = listen<UpdateCheck>("update-available", (event) => {
+      setState((prev) => ({ ...prev, updateInfo: toUpdateInfo(event.payload) }));
+    });
+    const progress = listen<UpdateProgress>("update-progress", (event) => {
+      const { downloaded, total } = event.payload;
+      // Content-Length unknown: estimate based on ~50MB typical update size
+      const estimatedTotal = total ?? 50 * 1024 * 1024;
+      const cap = total ? 99 : 95;
+      setState((prev) => ({
+        ...prev,
+        progress: Math.min(Math.round((downloaded / estimatedTotal) * 100), cap),
+      }));
+    });
 
+    // Re-check once every 24 hours while the app is running
     const interval = setInterval(() => {
-      console.log("[Updater] Daily update check triggered");
       checkForUpdate();
-    }, DAILY_MS);
+    }, 24 * 60 * 60 * 1000);
 
     return () => {
-      clearTimeout(timer);
+      available.then((fn) => fn());
+      progress.then((fn) => fn());
       clearInterval(interval);
     };
   }, [checkForUpdate]);
@@ -170,5 +106,6 @@ export function useUpdater() {
     ...state,
     checkForUpdate,
     downloadAndInstall,
+    skipVersion,
   };
 }
@@ -95,13 +95,4 @@ export function captureWindowError(error: string, operation: string) {
 /**
  * アップデート関連エラーをSentryに送信
  */
-export function captureUpdateError(error: string) {
-  Sentry.captureException(new Error(`[Update] ${error}`), {
-    tags: {
-      source: "update",
-      platform: getPlatform(),
-    },
-  });
-}
-
 export { Sentry };
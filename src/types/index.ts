@@ -12,6 +12,17 @@ export interface HardwareInfo {
   sensors: SensorData[];
 }
 
+// When each section of a sample was read (ms since the epoch)
+export interface SectionTimestamps {
+  cpu?: number | null;
+  gpu?: number | null;
+  storage?: number | null;
+  motherboard?: number | null;
+}
+
+// A section's reading against the thresholds setting
+export type SectionStatus = "ok" | "warning" | "critical";
+
 export interface HardwareData {
   cpu: {
     name: string;
@@ -23,6 +34,23 @@ export interface HardwareData {
       index: number;
       temperature: number;
       load: number;
+      frequency?: number | null; // Per-core clock in GHz, when reported
+      estimated?: boolean; // Temperature is the package reading
+      package?: number; // Socket index, on multi-socket machines
+    }>;
+    power?: number; // Package power in W; absent without a sensor
+    voltage?: number; // Vcore in V; absent without a sensor
+    isThrottling?: boolean; // Hot, loaded and clocked well below its peak
+    baseFrequency?: number; // Rated base clock in GHz; 0 when unknown
+    maxFrequency?: number; // Highest clock in GHz, rated or seen; 0 when unknown
+    status?: SectionStatus; // Temperature or load against the thresholds setting
+    // One per socket on multi-socket machines; the fields above sum them up
+    packages?: Array<{
+      index: number;
+      name: string;
+      temperature: number;
+      load: number;
+      power?: number;
     }>;
   } | null;
   gpu: {
@@ -33,20 +61,54 @@ export interface HardwareData {
     frequency: number; // Current frequency in GHz
     memoryUsed: number;
     memoryTotal: number;
+    power?: number; // Board power in W; absent without a sensor
+    powerLimit?: number; // Board power limit in W
+    hotspotTemperature?: number;
+    memoryTemperature?: number;
+    fans?: Array<{
+      name: string;
+      speed: number; // See unit
+      unit?: "rpm" | "percent"; // "percent" for a duty cycle without a tachometer
+      stopped?: boolean; // Reported at 0, e.g. a dead fan or fan-stop
+    }>;
+    status?: SectionStatus;
   } | null;
   storage: Array<{
     name: string;
     temperature: number;
     usedSpace: number; // in GB
+    usedPercent: number;
     totalSpace: number; // in GB
+    healthPercent?: number; // SMART remaining life
+    dataWrittenTb?: number;
+    readRate?: number; // bytes/sec
+    writeRate?: number; // bytes/sec
+    // Volumes on the drive, when known; empty when none is mounted
+    partitions?: Array<{
+      mountPoint: string; // "C:"
+      usedSpace: number; // in GB
+      totalSpace: number; // in GB
+    }>;
+    status?: SectionStatus;
   }> | null;
   motherboard: {
     name: string;
     temperature: number;
     fans: Array<{
       name: string;
-      speed: number; // RPM
+      speed: number; // See unit
+      unit?: "rpm" | "percent"; // "percent" for a duty cycle without a tachometer
+      stopped?: boolean; // Reported at 0, e.g. a dead fan or fan-stop
     }>;
+    temperatures?: Array<{
+      name: string;
+      temperature: number;
+    }>; // Chipset, VRM, PCH, ...
+    voltages?: Array<{
+      name: string;
+      volts: number;
+    }>; // PSU rails first
+    status?: SectionStatus;
   } | null;
   network: Array<{
     name: string;
@@ -60,8 +122,40 @@ export interface HardwareData {
     fpsProcessName: string | null;
   } | null;
   timestamp: number;
-  cpuError?: string;
+  cpuError?: string; // cpuErrorDetail.message
   gpuError?: string;
+  cpuErrorDetail?: SensorError;
+  gpuErrorDetail?: SensorError;
+  dataAgeMs?: number | null; // Age of the sensor readings (Windows/LHM only)
+  limitedAccess?: boolean; // Some sensors need administrator rights
+  dataSource?: "lhm" | "sysinfo" | "mock" | string; // What produced the readings
+  degraded?: boolean; // A fallback missing sensors, e.g. sysinfo on Windows
+  sectionTimestamps?: SectionTimestamps;
+  rawReadings?: RawReadings; // Unsmoothed values, while smoothingFactor > 0
+}
+
+// Why a section is missing or reads worse than usual
+export interface SensorError {
+  code: string; // e.g. "lhm_no_gpu", "sysinfo_no_temperature"
+  message: string;
+  source: string; // As in dataSource
+}
+
+// Payload of monitoring-mode-changed and get_monitoring_mode
+export interface MonitoringModeStatus {
+  mode: "normal" | "performance";
+  intervalMs: number; // How often to poll
+  until: number | null; // When performance mode ends, ms since epoch
+}
+
+// Headline readings before smoothing
+export interface RawReadings {
+  cpuTemperature: number | null;
+  cpuLoad: number | null;
+  cpuFrequency: number | null;
+  gpuTemperature: number | null;
+  gpuLoad: number | null;
+  gpuFrequency: number | null;
 }
 
 export interface WindowState {
@@ -69,22 +163,182 @@ export interface WindowState {
   y: number;
   width: number;
   height: number;
+  monitorName?: string | null; // Monitor the window was on when saved
+}
+
+// Alerts are recorded but not shown in this window of local time
+export interface QuietHours {
+  enabled: boolean;
+  start: string; // "HH:MM"; an end before the start runs past midnight
+  end: string;
+  allowCritical: boolean; // Critical alerts are still shown
+}
+
+// Pushing samples to InfluxDB 2.x; check with test_influxdb_connection
+export interface InfluxDbSettings {
+  enabled: boolean;
+  url: string; // Server root, e.g. http://localhost:8086
+  org: string;
+  bucket: string;
+  token?: string | null; // Needs write access to the bucket
+  flushIntervalSecs: number; // Seconds between writes
+  batchSize: number; // Points that trigger a write before the interval is up
+}
+
+// Warning and critical levels for one kind of device. An unset temperature
+// follows the device's maxTemperature; an unset load is never flagged.
+export interface ComponentThresholds {
+  warningTemperature?: number | null; // °C
+  criticalTemperature?: number | null;
+  warningLoad?: number | null; // %
+  criticalLoad?: number | null;
+}
+
+export interface Thresholds {
+  cpu: ComponentThresholds;
+  gpu: ComponentThresholds;
+  storage: ComponentThresholds;
+  motherboard: ComponentThresholds;
+}
+
+// temperature-alert and alert-resolved payloads, get_alert_history entries
+export interface Alert {
+  id: number;
+  component: "cpu" | "gpu";
+  metric: "temperature";
+  severity: "warning" | "critical";
+  value: number; // °C when it fired
+  threshold: number; // °C
+  firedAt: number;
+  resolvedAt: number | null; // null while active
+  acknowledged: boolean;
+  notified: boolean; // false when quiet hours kept it from being shown
 }
 
 export interface AppSettings {
-  position: "right" | "left" | "top-right" | "top-left" | "bottom-right" | "bottom-left";
+  /** "custom" once the widget has been dragged off a preset. */
+  position: "right" | "left" | "top-right" | "top-left" | "bottom-right" | "bottom-left" | "custom";
+  snapThreshold?: number; // px from a screen edge to snap; 0 turns snapping off
   opacity: number;
   alwaysOnTop: boolean;
   alwaysOnBack: boolean;
+  clickThrough?: boolean; // Changed via set_click_through or the tray only
   autoStart: boolean;
-  updateInterval: number;
+  closeToTray?: boolean; // Close button hides to the tray instead of quitting
+  updateIntervalFast: number; // CPU/GPU, and how often the UI polls
+  updateIntervalSlow?: number; // Storage and motherboard
+  smoothingFactor?: number; // 0 = raw, 0.9 = heavy
+  performanceModeMinutes?: number; // How long performance mode lasts
+  // Disabled sections are not sampled and are missing from HardwareData
+  monitorCpu?: boolean;
+  monitorGpu?: boolean;
+  monitorStorage?: boolean;
+  monitorMotherboard?: boolean;
+  mockData?: boolean; // Synthetic samples instead of the sensors, for UI work
+  pauseWhenHidden?: boolean; // Sample every 30s while hidden to the tray
+  monitoringPaused?: boolean; // Changed via set_monitoring_paused or the tray only
+  rememberMonitoringPause?: boolean; // Start paused if paused at exit
+  alertsEnabled?: boolean; // Toast when the CPU or GPU nears its maximum temperature
+  quietHours?: QuietHours;
+  thresholds?: Thresholds; // Behind each section's status and the alerts
+  influxdb?: InfluxDbSettings;
   theme: "auto" | "dark" | "light";
   temperatureUnit: "celsius" | "fahrenheit";
   compactMode: boolean;
   debugServer: boolean;
   sectionOrder: SectionType[];
   hiddenSections: SectionType[];
+  // Keyed by the name the sensors report, see list_detected_sensors
+  sensorOverrides?: Record<string, SensorOverride>;
   windowState?: WindowState;
+  updateChannel?: "stable" | "beta";
+  skipVersion?: string | null; // Left out of the startup update check
+}
+
+// From check_for_update and the update-available event
+export interface UpdateCheck {
+  currentVersion: string;
+  availableVersion: string | null;
+  releaseNotes: string | null;
+  downloadSize: number | null; // Bytes
+  skipped: boolean;
+}
+
+// Payload of the update-progress event
+export interface UpdateProgress {
+  downloaded: number;
+  total: number | null;
+}
+
+// From get_privilege_status
+export interface PrivilegeStatus {
+  elevated: boolean;
+  canRestartElevated: boolean;
+}
+
+// Readings with a non-zero value in the get_capabilities probe sample
+export interface SensorAvailability {
+  cpuTemperature: boolean;
+  cpuLoad: boolean;
+  cpuPower: boolean;
+  gpuTemperature: boolean;
+  gpuLoad: boolean;
+  gpuPower: boolean;
+  storageTemperature: boolean;
+  motherboardTemperature: boolean;
+  fanSpeeds: boolean;
+}
+
+// From get_capabilities
+export interface Capabilities {
+  platform: string;
+  elevated: boolean;
+  pawnioInstalled: boolean;
+  lhmDaemonAvailable: boolean; // Windows only
+  nvidiaSmiAvailable: boolean; // Windows only
+  rocmSmiAvailable: boolean; // Windows only
+  dataSource: string;
+  sensors: SensorAvailability;
+  notes: string[]; // Why readings are missing
+}
+
+// Payload of the data-source-changed event
+export interface DataSourceChange {
+  previous: string | null; // null for the first sample
+  source: string;
+  degraded: boolean;
+}
+
+// From get_system_info, for bug reports
+export interface SystemInfo {
+  os: string | null;
+  osBuild: string | null;
+  appVersion: string;
+  pawnioInstalled: boolean;
+  lhmDaemonRunning: boolean;
+  lhmDaemonUptimeSecs: number | null;
+  dataSource: string | null; // lhm | sysinfo | mock
+  elevated: boolean;
+  settingsPath: string;
+  recentErrors: string[];
+}
+
+// Identity that doesn't change between samples, from get_hardware_static
+export interface HardwareStatic {
+  cpu: { name: string; coreCount: number } | null;
+  gpu: { name: string; memoryTotal: number } | null;
+  storage: Array<{ name: string; totalSpace: number }>;
+  motherboard: string | null;
+}
+
+export interface SensorOverride {
+  hidden: boolean;
+  alias?: string | null;
+}
+
+export interface DetectedSensor {
+  kind: "cpu" | "gpu" | "storage" | "motherboard" | "fan" | "network";
+  name: string;
 }
 
 export const DEFAULT_SETTINGS: AppSettings = {
@@ -93,7 +347,8 @@ export const DEFAULT_SETTINGS: AppSettings = {
   alwaysOnTop: false,
   alwaysOnBack: false,
   autoStart: false,
-  updateInterval: 1000,
+  updateIntervalFast: 1000,
+  updateIntervalSlow: 5000,
   theme: "auto",
   temperatureUnit: "celsius",
   compactMode: false,
@@ -119,4 +374,16 @@ export interface AudioDevice {
 export interface PawnIOStatus {
   installed: boolean;
   checking: boolean;
+  version?: string;
+  driver_path?: string;
+  needs_update?: boolean; // The bundled installer is newer
+}
+
+// From download_and_install_pawnio and the pawnio-installed event
+export interface PawnIOInstallResult {
+  success: boolean;
+  exit_code: number;
+  status: PawnIOStatus;
+  daemon_restarted: boolean; // Sensors were reloaded without restarting Ondo
+  message: string;
 }